@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use rust_extensions::SliceOrVec;
+
+use crate::{blob::BlobAccessConditions, types::AzureStorageError};
+
+use super::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions};
+
+/// Blobs which only grow: every block is appended at the end, which makes them a fit for logs
+#[async_trait]
+pub trait AppendBlobApi {
+    /// Creates an empty append blob. An existing blob of any type is replaced.
+    /// Returns ETag of the blob
+    async fn create_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Creates an empty append blob with HTTP headers, metadata and access conditions.
+    /// If-None-Match: * creates it only if it does not exist yet
+    async fn create_append_blob_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        options: CreateAppendBlobOptions,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Appends the block at the end of the blob. Blocks of up to 4 MiB, up to 50,000 blocks per blob.
+    /// Appending to a blob of another type fails with InvalidBlobType, to a sealed one - with BlobIsSealed
+    async fn append_block<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+    ) -> Result<AppendBlockResult, AzureStorageError>;
+
+    /// A not met append position fails with AppendPositionConditionNotMet,
+    /// a not met max size - with MaxBlobSizeConditionNotMet
+    async fn append_block_with_conditions<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+        conditions: AppendBlockConditions,
+    ) -> Result<AppendBlockResult, AzureStorageError>;
+
+    /// The blob becomes read-only: it can still be read, copied or deleted, but takes no more blocks.
+    /// Sealing a sealed blob succeeds. Returns ETag of the blob
+    async fn seal_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError>;
+
+    async fn seal_append_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError>;
+}
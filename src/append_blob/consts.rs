@@ -0,0 +1,5 @@
+/// Committed blocks an append blob takes. Append Block past it fails with BlockCountExceedsLimit
+pub const MAX_APPEND_BLOCKS: u64 = 50_000;
+
+/// Largest block Append Block takes with the REST version the crate speaks (2019-12-12)
+pub const MAX_APPEND_BLOCK_SIZE: usize = 4 * 1024 * 1024;
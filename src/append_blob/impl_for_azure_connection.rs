@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use rust_extensions::SliceOrVec;
+
+use crate::{
+    blob::BlobAccessConditions, connection::AzureStorageConnection,
+    storage_backend::AppendBlobBackend, AzureStorageError,
+};
+
+use super::{
+    api::AppendBlobApi, AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions,
+};
+
+#[async_trait]
+impl AppendBlobApi for AzureStorageConnection {
+    async fn create_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError> {
+        self.create_append_blob_with_options(
+            container_name,
+            blob_name,
+            CreateAppendBlobOptions::new(),
+        )
+        .await
+    }
+
+    async fn create_append_blob_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        options: CreateAppendBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        self.get_storage_backend()
+            .create_append_blob(container_name, blob_name, options)
+            .await
+    }
+
+    async fn append_block<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+    ) -> Result<AppendBlockResult, AzureStorageError> {
+        self.append_block_with_conditions(
+            container_name,
+            blob_name,
+            content,
+            AppendBlockConditions::new(),
+        )
+        .await
+    }
+
+    async fn append_block_with_conditions<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+        conditions: AppendBlockConditions,
+    ) -> Result<AppendBlockResult, AzureStorageError> {
+        self.get_storage_backend()
+            .append_block(
+                container_name,
+                blob_name,
+                content.into().into_vec(),
+                conditions,
+            )
+            .await
+    }
+
+    async fn seal_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError> {
+        self.seal_append_blob_with_conditions(
+            container_name,
+            blob_name,
+            BlobAccessConditions::new(),
+        )
+        .await
+    }
+
+    async fn seal_append_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        self.get_storage_backend()
+            .seal_append_blob(container_name, blob_name, conditions)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        append_blob::MAX_APPEND_BLOCKS,
+        blob::{BlobApi, BlobType},
+        blob_container::BlobContainersApi,
+        block_blob::BlockBlobApi,
+        sdk_files::blob_sidecar::BlobSidecar,
+    };
+
+    #[tokio::test]
+    async fn test_append_blob_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-append-blob";
+        const BLOB_NAME: &str = "audit-log";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        connection
+            .create_append_blob(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+
+        let result = connection
+            .append_block(CONTAINER_NAME, BLOB_NAME, b"abc".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(0, result.append_offset);
+        assert_eq!(1, result.committed_block_count);
+
+        let result = connection
+            .append_block_with_conditions(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                b"de".to_vec(),
+                AppendBlockConditions::new().with_append_position(3),
+            )
+            .await
+            .unwrap();
+        assert_eq!(3, result.append_offset);
+        assert_eq!(2, result.committed_block_count);
+
+        let result = connection
+            .append_block_with_conditions(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                b"f".to_vec(),
+                AppendBlockConditions::new().with_append_position(3),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::AppendPositionConditionNotMet(_))
+        ));
+
+        let result = connection
+            .append_block_with_conditions(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                b"f".to_vec(),
+                AppendBlockConditions::new().with_max_size(5),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::MaxBlobSizeConditionNotMet(_))
+        ));
+
+        assert_eq!(
+            b"abcde".to_vec(),
+            connection
+                .download_blob(CONTAINER_NAME, BLOB_NAME)
+                .await
+                .unwrap()
+        );
+
+        let props = connection
+            .get_blob_properties(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+        assert_eq!(BlobType::AppendBlob, props.blob_type);
+        assert_eq!(Some(2), props.committed_block_count);
+        assert_eq!(Some(false), props.is_sealed);
+
+        // Appending 50,000 blocks takes too long on disk - the limit is reached by editing the sidecar
+        let file_data = match &connection {
+            AzureStorageConnection::File(data) => data,
+            _ => panic!("File connection is expected"),
+        };
+
+        let mut sidecar = BlobSidecar::read(file_data, CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+        sidecar.committed_block_count = MAX_APPEND_BLOCKS;
+        sidecar
+            .write(file_data, CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+
+        let result = connection
+            .append_block(CONTAINER_NAME, BLOB_NAME, b"f".to_vec())
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::BlockCountExceedsLimit(_))
+        ));
+
+        connection
+            .seal_append_blob(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+
+        let result = connection
+            .append_block(CONTAINER_NAME, BLOB_NAME, b"f".to_vec())
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobIsSealed(_))));
+
+        let props = connection
+            .get_blob_properties(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+        assert_eq!(Some(true), props.is_sealed);
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, "block-blob", b"abc".to_vec())
+            .await
+            .unwrap();
+
+        let result = connection
+            .append_block(CONTAINER_NAME, "block-blob", b"f".to_vec())
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::InvalidBlobType(_))));
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
+}
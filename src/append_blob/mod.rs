@@ -0,0 +1,9 @@
+mod api;
+mod consts;
+pub mod impl_for_azure_connection;
+mod models;
+pub mod sdk;
+
+pub use api::AppendBlobApi;
+pub use consts::{MAX_APPEND_BLOCKS, MAX_APPEND_BLOCK_SIZE};
+pub use models::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions};
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use crate::{
+    blob::{BlobAccessConditions, BlobHttpHeaders},
+    block_blob::UploadBlockBlobOptions,
+    AzureStorageError,
+};
+
+use super::{MAX_APPEND_BLOCKS, MAX_APPEND_BLOCK_SIZE};
+
+/// HTTP headers, metadata and access conditions an append blob is created with
+#[derive(Debug, Clone, Default)]
+pub struct CreateAppendBlobOptions {
+    pub http_headers: BlobHttpHeaders,
+    pub metadata: HashMap<String, String>,
+    pub conditions: BlobAccessConditions,
+}
+
+impl CreateAppendBlobOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_http_headers(mut self, http_headers: BlobHttpHeaders) -> Self {
+        self.http_headers = http_headers;
+        self
+    }
+
+    pub fn with_metadata(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_conditions(mut self, conditions: BlobAccessConditions) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    /// Local backends create the blob the way they write any other blob
+    pub(crate) fn into_upload_options(self) -> UploadBlockBlobOptions {
+        UploadBlockBlobOptions {
+            http_headers: self.http_headers,
+            metadata: self.metadata,
+            conditions: self.conditions,
+        }
+    }
+}
+
+/// Conditions of Append Block on top of the access conditions of the blob
+#[derive(Debug, Clone, Default)]
+pub struct AppendBlockConditions {
+    /// x-ms-blob-condition-appendpos: the block is appended only if the blob is exactly that long.
+    /// Makes a retried append safe - the retry of an append which went through fails instead of appending twice
+    pub append_position: Option<u64>,
+    /// x-ms-blob-condition-maxsize: the block is appended only if the blob does not grow past it
+    pub max_size: Option<u64>,
+    pub access: BlobAccessConditions,
+}
+
+impl AppendBlockConditions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_append_position(mut self, append_position: u64) -> Self {
+        self.append_position = Some(append_position);
+        self
+    }
+
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    pub fn with_access_conditions(mut self, access: BlobAccessConditions) -> Self {
+        self.access = access;
+        self
+    }
+
+    /// Emulation for local backends, once the access conditions are met. `blob_size` and
+    /// `committed_block_count` are of the blob before the append
+    pub(crate) fn check(
+        &self,
+        blob_size: u64,
+        committed_block_count: u64,
+        block_size: usize,
+    ) -> Result<(), AzureStorageError> {
+        if block_size > MAX_APPEND_BLOCK_SIZE {
+            return Err(AzureStorageError::RequestBodyTooLarge(None));
+        }
+
+        if let Some(max_size) = self.max_size {
+            if blob_size + block_size as u64 > max_size {
+                return Err(AzureStorageError::MaxBlobSizeConditionNotMet(None));
+            }
+        }
+
+        if let Some(append_position) = self.append_position {
+            if blob_size != append_position {
+                return Err(AzureStorageError::AppendPositionConditionNotMet(None));
+            }
+        }
+
+        if committed_block_count >= MAX_APPEND_BLOCKS {
+            return Err(AzureStorageError::BlockCountExceedsLimit(None));
+        }
+
+        Ok(())
+    }
+}
+
+/// Response of Append Block
+#[derive(Debug, Clone)]
+pub struct AppendBlockResult {
+    /// ETag of the blob after the append
+    pub etag: String,
+    /// Offset the block was appended at (x-ms-blob-append-offset)
+    pub append_offset: u64,
+    /// Blocks of the blob including the appended one (x-ms-blob-committed-block-count)
+    pub committed_block_count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_block_conditions() {
+        let conditions = AppendBlockConditions::new()
+            .with_append_position(10)
+            .with_max_size(15);
+
+        assert!(conditions.check(10, 0, 5).is_ok());
+
+        assert!(matches!(
+            conditions.check(10, 0, 6),
+            Err(AzureStorageError::MaxBlobSizeConditionNotMet(_))
+        ));
+
+        assert!(matches!(
+            conditions.check(9, 0, 1),
+            Err(AzureStorageError::AppendPositionConditionNotMet(_))
+        ));
+
+        assert!(matches!(
+            AppendBlockConditions::new().check(0, MAX_APPEND_BLOCKS, 1),
+            Err(AzureStorageError::BlockCountExceedsLimit(_))
+        ));
+
+        assert!(matches!(
+            AppendBlockConditions::new().check(0, 0, MAX_APPEND_BLOCK_SIZE + 1),
+            Err(AzureStorageError::RequestBodyTooLarge(_))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::blob::BlobAccessConditions;
+use crate::sdk_azure::access_conditions::with_access_conditions;
+use crate::sdk_azure::azure_response_handler::AzureResponseHandler;
+use crate::sdk_azure::blob_http_headers::with_blob_http_headers;
+use crate::sdk_azure::blob_metadata::with_metadata_headers;
+use crate::sdk_azure::flurl_ext::FlUrlAzureExtensions;
+use crate::sdk_azure::http_transport::AzureHttpRequest;
+use crate::sdk_azure::retry_policy::execute_request;
+use crate::sdk_azure::sign_utils::SignVerb;
+use crate::{connection::AzureStorageConnectionData, types::AzureStorageError};
+
+use super::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions};
+
+use crate::sdk_azure::consts::AZURE_REST_VERSION;
+
+use flurl::FlUrl;
+
+/// Put Blob with x-ms-blob-type: AppendBlob and no content
+pub async fn create(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    options: &CreateAppendBlobOptions,
+) -> Result<String, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .with_header("x-ms-blob-type", "AppendBlob");
+
+        let fl_url = with_blob_http_headers(fl_url, &options.http_headers);
+        let fl_url = with_access_conditions(fl_url, &options.conditions);
+
+        let fl_url = with_metadata_headers(fl_url, &options.metadata)
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    response.get_etag()
+}
+
+/// Append Block (comp=appendblock)
+pub async fn append_block(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    content: Vec<u8>,
+    conditions: &AppendBlockConditions,
+) -> Result<AppendBlockResult, AzureStorageError> {
+    // A retry of an append which went through would append the block twice, unless the position is pinned
+    let idempotent = conditions.append_position.is_some();
+
+    let response = execute_request(connection, idempotent, || {
+        let content = content.clone();
+
+        async move {
+            let fl_url: FlUrl = connection.into();
+
+            let mut fl_url = fl_url
+                .append_path_segment(container_name)
+                .append_path_segment(blob_name)
+                .append_query_param("comp", Some("appendblock"));
+
+            if let Some(append_position) = conditions.append_position {
+                fl_url = fl_url
+                    .with_header("x-ms-blob-condition-appendpos", append_position.to_string());
+            }
+
+            if let Some(max_size) = conditions.max_size {
+                fl_url = fl_url.with_header("x-ms-blob-condition-maxsize", max_size.to_string());
+            }
+
+            let fl_url = with_access_conditions(fl_url, &conditions.access)
+                .add_azure_headers(
+                    SignVerb::PUT,
+                    connection,
+                    Some(content.len()),
+                    None,
+                    AZURE_REST_VERSION,
+                )
+                .await?;
+
+            Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url).with_body(content))
+        }
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(AppendBlockResult {
+        etag: response.get_etag()?,
+        append_offset: get_number_header(&response, "x-ms-blob-append-offset")?,
+        committed_block_count: get_number_header(&response, "x-ms-blob-committed-block-count")?,
+    })
+}
+
+/// Seal (comp=seal)
+pub async fn seal(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    conditions: &BlobAccessConditions,
+) -> Result<String, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("seal"));
+
+        let fl_url = with_access_conditions(fl_url, conditions)
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    response.get_etag()
+}
+
+fn get_number_header(
+    response: &AzureResponseHandler,
+    header_name: &str,
+) -> Result<u64, AzureStorageError> {
+    let value = response.get_header(header_name)?;
+
+    value.parse().map_err(|_| AzureStorageError::UnknownError {
+        msg: format!("Invalid {} header: {}", header_name, value),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::sdk_azure::http_transport::{
+        get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+    };
+
+    #[tokio::test]
+    async fn test_append_blob_requests() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(201).with_header("etag", "\"0x1\""));
+        transport.push_response(
+            AzureHttpResponse::new(201)
+                .with_header("etag", "\"0x2\"")
+                .with_header("x-ms-blob-append-offset", "0")
+                .with_header("x-ms-blob-committed-block-count", "1"),
+        );
+        transport.push_response(AzureHttpResponse::new(200).with_header("etag", "\"0x3\""));
+
+        let connection = get_scripted_connection(&transport);
+
+        let etag = create(
+            &connection,
+            "container",
+            "blob",
+            &CreateAppendBlobOptions::new().with_metadata("tenant", "acme"),
+        )
+        .await
+        .unwrap();
+        assert_eq!("\"0x1\"", etag);
+
+        let result = append_block(
+            &connection,
+            "container",
+            "blob",
+            b"abc".to_vec(),
+            &AppendBlockConditions::new()
+                .with_append_position(0)
+                .with_max_size(1024),
+        )
+        .await
+        .unwrap();
+        assert_eq!("\"0x2\"", result.etag);
+        assert_eq!(0, result.append_offset);
+        assert_eq!(1, result.committed_block_count);
+
+        let etag = seal(
+            &connection,
+            "container",
+            "blob",
+            &BlobAccessConditions::new().with_if_match("\"0x2\""),
+        )
+        .await
+        .unwrap();
+        assert_eq!("\"0x3\"", etag);
+
+        let requests = transport.get_requests();
+        assert_eq!(Some("AppendBlob"), requests[0].get_header("x-ms-blob-type"));
+        assert_eq!(Some("acme"), requests[0].get_header("x-ms-meta-tenant"));
+
+        assert_eq!(Some("appendblock"), requests[1].get_query_param("comp"));
+        assert_eq!(
+            Some("0"),
+            requests[1].get_header("x-ms-blob-condition-appendpos")
+        );
+        assert_eq!(
+            Some("1024"),
+            requests[1].get_header("x-ms-blob-condition-maxsize")
+        );
+        assert_eq!(Some(b"abc".to_vec()), requests[1].body);
+
+        assert_eq!(Some("seal"), requests[2].get_query_param("comp"));
+        assert_eq!(Some("\"0x2\""), requests[2].get_header("If-Match"));
+    }
+}
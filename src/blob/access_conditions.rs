@@ -0,0 +1,155 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::AzureStorageError;
+
+/// If-Match, If-None-Match, If-Modified-Since and the lease id of a blob request.
+/// Azure answers 412 (304 on reads) when a condition is not met, which comes back as ConditionNotMet
+#[derive(Debug, Clone, Default)]
+pub struct BlobAccessConditions {
+    pub if_match: Option<String>,
+    /// "*" - the request succeeds only if the blob does not exist
+    pub if_none_match: Option<String>,
+    pub if_modified_since: Option<DateTimeAsMicroseconds>,
+    /// Goes as x-ms-lease-id. Writes and deletes of a leased blob require it
+    pub lease_id: Option<String>,
+}
+
+impl BlobAccessConditions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_if_match(mut self, etag: impl Into<String>) -> Self {
+        self.if_match = Some(etag.into());
+        self
+    }
+
+    pub fn with_if_none_match(mut self, etag: impl Into<String>) -> Self {
+        self.if_none_match = Some(etag.into());
+        self
+    }
+
+    /// Shortcut for If-None-Match: *
+    pub fn if_not_exists() -> Self {
+        Self::new().with_if_none_match("*")
+    }
+
+    pub fn with_if_modified_since(mut self, moment: DateTimeAsMicroseconds) -> Self {
+        self.if_modified_since = Some(moment);
+        self
+    }
+
+    pub fn with_lease_id(mut self, lease_id: impl Into<String>) -> Self {
+        self.lease_id = Some(lease_id.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.if_match.is_none()
+            && self.if_none_match.is_none()
+            && self.if_modified_since.is_none()
+            && self.lease_id.is_none()
+    }
+
+    /// Emulation for local backends. `current` is ETag and Last-Modified of the blob, None if the blob does not exist
+    pub(crate) fn check(
+        &self,
+        current: Option<(&str, DateTimeAsMicroseconds)>,
+    ) -> Result<(), AzureStorageError> {
+        let (etag, last_modified) = match current {
+            Some(current) => current,
+            None => {
+                if self.if_match.is_some() {
+                    return Err(AzureStorageError::ConditionNotMet(None));
+                }
+
+                return Ok(());
+            }
+        };
+
+        if let Some(if_match) = self.if_match.as_ref() {
+            if if_match != "*" && if_match != etag {
+                return Err(AzureStorageError::ConditionNotMet(None));
+            }
+        }
+
+        if let Some(if_none_match) = self.if_none_match.as_ref() {
+            if if_none_match == "*" || if_none_match == etag {
+                return Err(AzureStorageError::ConditionNotMet(None));
+            }
+        }
+
+        if let Some(if_modified_since) = self.if_modified_since {
+            // HTTP dates carry seconds only, so Azure compares with that precision
+            if last_modified.unix_microseconds / 1_000_000
+                <= if_modified_since.unix_microseconds / 1_000_000
+            {
+                return Err(AzureStorageError::ConditionNotMet(None));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Same as `check`, but If-None-Match: * on an existing blob fails the way Put Blob does - with BlobAlreadyExists
+    pub(crate) fn check_before_write(
+        &self,
+        current: Option<(&str, DateTimeAsMicroseconds)>,
+    ) -> Result<(), AzureStorageError> {
+        if current.is_some() && self.if_none_match.as_deref() == Some("*") {
+            return Err(AzureStorageError::BlobAlreadyExists(None));
+        }
+
+        self.check(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_if_match() {
+        let now = DateTimeAsMicroseconds::now();
+        let conditions = BlobAccessConditions::new().with_if_match("\"0x1\"");
+
+        assert!(conditions.check(Some(("\"0x1\"", now))).is_ok());
+        assert!(matches!(
+            conditions.check(Some(("\"0x2\"", now))),
+            Err(AzureStorageError::ConditionNotMet(_))
+        ));
+        assert!(matches!(
+            conditions.check(None),
+            Err(AzureStorageError::ConditionNotMet(_))
+        ));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let now = DateTimeAsMicroseconds::now();
+
+        let conditions = BlobAccessConditions::new().with_if_none_match("\"0x1\"");
+        assert!(conditions.check(Some(("\"0x2\"", now))).is_ok());
+        assert!(conditions.check(Some(("\"0x1\"", now))).is_err());
+
+        let conditions = BlobAccessConditions::if_not_exists();
+        assert!(conditions.check_before_write(None).is_ok());
+        assert!(matches!(
+            conditions.check_before_write(Some(("\"0x1\"", now))),
+            Err(AzureStorageError::BlobAlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_if_modified_since() {
+        let last_modified = DateTimeAsMicroseconds::new(10_500_000);
+
+        let conditions = BlobAccessConditions::new()
+            .with_if_modified_since(DateTimeAsMicroseconds::new(9_000_000));
+        assert!(conditions.check(Some(("\"0x1\"", last_modified))).is_ok());
+
+        let conditions = BlobAccessConditions::new()
+            .with_if_modified_since(DateTimeAsMicroseconds::new(10_000_000));
+        assert!(conditions.check(Some(("\"0x1\"", last_modified))).is_err());
+    }
+}
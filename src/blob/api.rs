@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 
 use crate::types::AzureStorageError;
 
-use super::BlobProperties;
+use super::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, DownloadedBlob};
 
 #[async_trait]
 pub trait BlobApi {
@@ -18,15 +20,77 @@ pub trait BlobApi {
         blob_name: &str,
     ) -> Result<Vec<u8>, AzureStorageError>;
 
+    /// Downloads `length` bytes starting at `offset` (x-ms-range), for blobs of any type.
+    /// Without length the range is open-ended and reads up to the end of the blob.
+    /// A range running past the end is cut at the end, a range starting past the end is InvalidRange (416)
+    async fn download_range(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Vec<u8>, AzureStorageError>;
+
+    /// Downloads the blob if the conditions are met. Properties of the downloaded version come along,
+    /// their ETag is what a following conditional write passes as If-Match
+    async fn download_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<DownloadedBlob, AzureStorageError>;
+
     async fn delete_blob(
         &self,
         container_name: &str,
         blob_name: &str,
     ) -> Result<(), AzureStorageError>;
 
+    async fn delete_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError>;
+
     async fn delete_blob_if_exists(
         &self,
         container_name: &str,
         blob_name: &str,
     ) -> Result<(), AzureStorageError>;
+
+    /// delete_blob_if_exists of a leased blob by the lease holder
+    async fn delete_blob_if_exists_with_lease_id(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError>;
+
+    /// Replaces all user-defined metadata of the blob (x-ms-meta-*).
+    /// A leased blob is written with the lease id of the conditions
+    async fn set_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        metadata: HashMap<String, String>,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError>;
+
+    /// Set Blob Properties (comp=properties): replaces content-type, content-encoding,
+    /// content-language, content-disposition and cache-control. Headers which are None are cleared
+    async fn set_blob_http_headers(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        http_headers: BlobHttpHeaders,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError>;
+
+    /// Metadata names come back lowercased
+    async fn get_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<HashMap<String, String>, AzureStorageError>;
 }
@@ -0,0 +1,64 @@
+use std::ops::Range;
+
+use crate::AzureStorageError;
+
+/// x-ms-range of a download. A range without length is open-ended: it reads up to the end of the blob
+pub(crate) fn get_range_header(offset: u64, length: Option<u64>) -> String {
+    match length {
+        Some(length) => format!("bytes={}-{}", offset, offset + length - 1),
+        None => format!("bytes={}-", offset),
+    }
+}
+
+/// Bytes of a blob of `blob_size` the range reads, by the rules Azure applies:
+/// a range past the end is cut at the end, a range starting at or past the end is InvalidRange (416).
+/// The range of an empty blob starting at 0 reads nothing
+pub(crate) fn resolve_range(
+    offset: u64,
+    length: Option<u64>,
+    blob_size: u64,
+) -> Result<Range<u64>, AzureStorageError> {
+    if length == Some(0) || (offset == 0 && blob_size == 0) {
+        return Ok(offset..offset);
+    }
+
+    if offset >= blob_size {
+        return Err(AzureStorageError::InvalidRange(None));
+    }
+
+    let end = match length {
+        Some(length) => blob_size.min(offset.saturating_add(length)),
+        None => blob_size,
+    };
+
+    Ok(offset..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_header() {
+        assert_eq!("bytes=0-511", get_range_header(0, Some(512)));
+        assert_eq!("bytes=100-", get_range_header(100, None));
+    }
+
+    #[test]
+    fn test_resolve_range() {
+        assert_eq!(2..5, resolve_range(2, Some(3), 10).unwrap());
+        assert_eq!(8..10, resolve_range(8, Some(100), 10).unwrap());
+        assert_eq!(4..10, resolve_range(4, None, 10).unwrap());
+        assert_eq!(0..0, resolve_range(0, None, 0).unwrap());
+        assert_eq!(3..3, resolve_range(3, Some(0), 10).unwrap());
+
+        assert!(matches!(
+            resolve_range(10, None, 10),
+            Err(AzureStorageError::InvalidRange(_))
+        ));
+        assert!(matches!(
+            resolve_range(1, Some(1), 0),
+            Err(AzureStorageError::InvalidRange(_))
+        ));
+    }
+}
@@ -1,7 +1,11 @@
-use crate::{connection::AzureStorageConnection, types::AzureStorageError};
+use std::collections::HashMap;
+
+use crate::{
+    connection::AzureStorageConnection, storage_backend::BlobBackend, types::AzureStorageError,
+};
 use async_trait::async_trait;
 
-use super::{api::BlobApi, BlobProperties};
+use super::{api::BlobApi, BlobAccessConditions, BlobHttpHeaders, BlobProperties, DownloadedBlob};
 
 #[async_trait]
 impl BlobApi for AzureStorageConnection {
@@ -10,33 +14,9 @@ impl BlobApi for AzureStorageConnection {
         container_name: &str,
         blob_name: &str,
     ) -> Result<BlobProperties, AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::get_blob_properties(
-                    connection_data,
-                    container_name,
-                    blob_name,
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::blobs::get_blob_properties(
-                    connection_data,
-                    container_name,
-                    blob_name,
-                )
-                .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    container_name,
-                )
-                .await?;
-
-                container.get_blob_properties(blob_name).await
-            }
-        }
+        self.get_storage_backend()
+            .get_blob_properties(container_name, blob_name)
+            .await
     }
 
     async fn download_blob(
@@ -44,23 +24,32 @@ impl BlobApi for AzureStorageConnection {
         container_name: &str,
         blob_name: &str,
     ) -> Result<Vec<u8>, AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::download(connection_data, container_name, blob_name).await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::blobs::download(connection_data, container_name, blob_name).await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    container_name,
-                )
-                .await?;
-
-                container.download(blob_name).await
-            }
-        }
+        self.get_storage_backend()
+            .download_blob(container_name, blob_name)
+            .await
+    }
+
+    async fn download_range(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        self.get_storage_backend()
+            .download_range(container_name, blob_name, offset, length)
+            .await
+    }
+
+    async fn download_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<DownloadedBlob, AzureStorageError> {
+        self.get_storage_backend()
+            .download_blob_with_conditions(container_name, blob_name, conditions)
+            .await
     }
 
     async fn delete_blob(
@@ -68,27 +57,20 @@ impl BlobApi for AzureStorageConnection {
         container_name: &str,
         blob_name: &str,
     ) -> Result<(), AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::delete(connection_data, container_name, blob_name).await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::blobs::delete(connection_data, container_name, blob_name).await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    container_name,
-                )
-                .await?;
-
-                if container.delete(blob_name).await {
-                    return Ok(());
-                }
-
-                return Err(AzureStorageError::BlobNotFound);
-            }
-        }
+        self.get_storage_backend()
+            .delete_blob(container_name, blob_name, BlobAccessConditions::new())
+            .await
+    }
+
+    async fn delete_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .delete_blob(container_name, blob_name, conditions)
+            .await
     }
 
     async fn delete_blob_if_exists(
@@ -96,34 +78,127 @@ impl BlobApi for AzureStorageConnection {
         container_name: &str,
         blob_name: &str,
     ) -> Result<(), AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::delete_if_exists(
-                    connection_data,
-                    container_name,
-                    blob_name,
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::blobs::delete_if_exists(
-                    connection_data,
-                    container_name,
-                    blob_name,
-                )
-                .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    container_name,
-                )
-                .await?;
-
-                container.delete(blob_name).await;
-
-                Ok(())
-            }
-        }
+        self.get_storage_backend()
+            .delete_blob_if_exists(container_name, blob_name, None)
+            .await
+    }
+
+    async fn delete_blob_if_exists_with_lease_id(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .delete_blob_if_exists(container_name, blob_name, Some(lease_id))
+            .await
+    }
+
+    async fn set_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        metadata: HashMap<String, String>,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .set_blob_metadata(container_name, blob_name, metadata, conditions)
+            .await
+    }
+
+    async fn set_blob_http_headers(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        http_headers: BlobHttpHeaders,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .set_blob_http_headers(container_name, blob_name, http_headers, conditions)
+            .await
+    }
+
+    async fn get_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<HashMap<String, String>, AzureStorageError> {
+        self.get_storage_backend()
+            .get_blob_metadata(container_name, blob_name)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        blob_container::BlobContainersApi, block_blob::BlockBlobApi,
+        page_blob::AzurePageBlobStorage,
+    };
+
+    #[tokio::test]
+    async fn test_download_range_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-download-range";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = Arc::new(AzureStorageConnection::from_conn_string(folder.as_str()));
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![0u8, 1u8, 2u8, 3u8, 4u8])
+            .await
+            .unwrap();
+
+        let result = connection
+            .download_range(CONTAINER_NAME, BLOB_NAME, 1, Some(2))
+            .await
+            .unwrap();
+        assert_eq!(vec![1u8, 2u8], result);
+
+        let result = connection
+            .download_range(CONTAINER_NAME, BLOB_NAME, 3, None)
+            .await
+            .unwrap();
+        assert_eq!(vec![3u8, 4u8], result);
+
+        let result = connection
+            .download_range(CONTAINER_NAME, BLOB_NAME, 4, Some(100))
+            .await
+            .unwrap();
+        assert_eq!(vec![4u8], result);
+
+        let result = connection
+            .download_range(CONTAINER_NAME, BLOB_NAME, 5, None)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::InvalidRange(_))));
+
+        let result = connection
+            .download_range(CONTAINER_NAME, "not-exists", 0, None)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        let page_blob =
+            AzurePageBlobStorage::new(connection.clone(), CONTAINER_NAME, "page-blob").await;
+        page_blob.create(2).await.unwrap();
+        page_blob.save_pages(1, vec![7u8; 512]).await.unwrap();
+
+        // The range is not page-aligned
+        let result = connection
+            .download_range(CONTAINER_NAME, "page-blob", 510, Some(4))
+            .await
+            .unwrap();
+        assert_eq!(vec![0u8, 0u8, 7u8, 7u8], result);
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
     }
 }
@@ -1,6 +1,14 @@
+mod access_conditions;
 mod api;
+mod blob_range;
 pub mod impl_for_azure_connection;
 mod models;
+mod write_moment;
 
+pub use access_conditions::BlobAccessConditions;
 pub use api::BlobApi;
-pub use models::BlobProperties;
+pub use models::{
+    BlobHttpHeaders, BlobProperties, BlobType, DownloadedBlob, LeaseState, LeaseStatus,
+};
+pub(crate) use blob_range::{get_range_header, resolve_range};
+pub(crate) use write_moment::next_write_moment;
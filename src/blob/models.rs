@@ -1,4 +1,214 @@
+use std::collections::HashMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobType {
+    BlockBlob,
+    PageBlob,
+    AppendBlob,
+}
+
+impl BlobType {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src {
+            "BlockBlob" => Some(Self::BlockBlob),
+            "PageBlob" => Some(Self::PageBlob),
+            "AppendBlob" => Some(Self::AppendBlob),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BlockBlob => "BlockBlob",
+            Self::PageBlob => "PageBlob",
+            Self::AppendBlob => "AppendBlob",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaseState {
+    Available,
+    Leased,
+    Expired,
+    Breaking,
+    Broken,
+}
+
+impl LeaseState {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src {
+            "available" => Some(Self::Available),
+            "leased" => Some(Self::Leased),
+            "expired" => Some(Self::Expired),
+            "breaking" => Some(Self::Breaking),
+            "broken" => Some(Self::Broken),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Available => "available",
+            Self::Leased => "leased",
+            Self::Expired => "expired",
+            Self::Breaking => "breaking",
+            Self::Broken => "broken",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaseStatus {
+    Locked,
+    Unlocked,
+}
+
+impl LeaseStatus {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src {
+            "locked" => Some(Self::Locked),
+            "unlocked" => Some(Self::Unlocked),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Locked => "locked",
+            Self::Unlocked => "unlocked",
+        }
+    }
+}
+
+/// Standard HTTP headers Azure stores with the blob and returns on download.
+/// Set on upload or with Set Blob Properties (comp=properties)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlobHttpHeaders {
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_language: Option<String>,
+    pub content_disposition: Option<String>,
+    pub cache_control: Option<String>,
+    /// Base64 MD5 of the whole content. Stored as is - nothing checks it against the content
+    pub content_md5: Option<String>,
+}
+
+impl BlobHttpHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_content_type(mut self, value: impl Into<String>) -> Self {
+        self.content_type = Some(value.into());
+        self
+    }
+
+    pub fn with_content_encoding(mut self, value: impl Into<String>) -> Self {
+        self.content_encoding = Some(value.into());
+        self
+    }
+
+    pub fn with_content_language(mut self, value: impl Into<String>) -> Self {
+        self.content_language = Some(value.into());
+        self
+    }
+
+    pub fn with_content_disposition(mut self, value: impl Into<String>) -> Self {
+        self.content_disposition = Some(value.into());
+        self
+    }
+
+    pub fn with_cache_control(mut self, value: impl Into<String>) -> Self {
+        self.cache_control = Some(value.into());
+        self
+    }
+
+    pub fn with_content_md5(mut self, value: impl Into<String>) -> Self {
+        self.content_md5 = Some(value.into());
+        self
+    }
+}
+
+/// Blob properties as returned by Get Blob Properties (HEAD).
+/// File and in-memory backends fill the same fields; values they can not emulate stay None
 #[derive(Debug, Clone)]
 pub struct BlobProperties {
     pub blob_size: usize,
+    pub blob_type: BlobType,
+    pub etag: Option<String>,
+    pub last_modified: Option<DateTimeAsMicroseconds>,
+    pub creation_time: Option<DateTimeAsMicroseconds>,
+    pub content_type: Option<String>,
+    pub content_encoding: Option<String>,
+    pub content_language: Option<String>,
+    pub content_disposition: Option<String>,
+    pub content_md5: Option<String>,
+    pub cache_control: Option<String>,
+    pub lease_state: LeaseState,
+    pub lease_status: LeaseStatus,
+    pub access_tier: Option<String>,
+    /// x-ms-meta-* values. Keys are without the prefix
+    pub metadata: HashMap<String, String>,
+    /// Page blobs only
+    pub sequence_number: Option<u64>,
+    /// Append blobs only
+    pub committed_block_count: Option<u64>,
+    /// Append blobs only. A sealed blob takes no more blocks
+    pub is_sealed: Option<bool>,
+}
+
+impl BlobProperties {
+    pub fn new(blob_type: BlobType, blob_size: usize) -> Self {
+        Self {
+            blob_size,
+            blob_type,
+            etag: None,
+            last_modified: None,
+            creation_time: None,
+            content_type: None,
+            content_encoding: None,
+            content_language: None,
+            content_disposition: None,
+            content_md5: None,
+            cache_control: None,
+            lease_state: LeaseState::Available,
+            lease_status: LeaseStatus::Unlocked,
+            access_tier: None,
+            metadata: HashMap::new(),
+            sequence_number: None,
+            committed_block_count: None,
+            is_sealed: None,
+        }
+    }
+
+    pub fn get_http_headers(&self) -> BlobHttpHeaders {
+        BlobHttpHeaders {
+            content_type: self.content_type.clone(),
+            content_encoding: self.content_encoding.clone(),
+            content_language: self.content_language.clone(),
+            content_disposition: self.content_disposition.clone(),
+            cache_control: self.cache_control.clone(),
+            content_md5: self.content_md5.clone(),
+        }
+    }
+
+    pub(crate) fn set_http_headers(&mut self, http_headers: &BlobHttpHeaders) {
+        self.content_type = http_headers.content_type.clone();
+        self.content_encoding = http_headers.content_encoding.clone();
+        self.content_language = http_headers.content_language.clone();
+        self.content_disposition = http_headers.content_disposition.clone();
+        self.cache_control = http_headers.cache_control.clone();
+        self.content_md5 = http_headers.content_md5.clone();
+    }
+}
+
+/// Content of the blob together with the properties of the version which was read.
+/// The ETag of `properties` is what an optimistic update passes back as If-Match
+#[derive(Debug, Clone)]
+pub struct DownloadedBlob {
+    pub content: Vec<u8>,
+    pub properties: BlobProperties,
 }
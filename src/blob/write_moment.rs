@@ -0,0 +1,22 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+static LAST_WRITE_MOMENT: AtomicI64 = AtomicI64::new(0);
+
+/// Strictly increasing moment local backends stamp on every write,
+/// so two writes within the same microsecond (or file system timestamp tick) still get different ETags
+pub fn next_write_moment() -> DateTimeAsMicroseconds {
+    let now = DateTimeAsMicroseconds::now().unix_microseconds;
+
+    let mut last = LAST_WRITE_MOMENT.load(Ordering::SeqCst);
+
+    loop {
+        let next = if now > last { now } else { last + 1 };
+
+        match LAST_WRITE_MOMENT.compare_exchange(last, next, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => return DateTimeAsMicroseconds::new(next),
+            Err(current) => last = current,
+        }
+    }
+}
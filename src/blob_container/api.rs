@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use flurl::FlUrlError;
 
 use crate::AzureStorageError;
 
@@ -17,7 +16,7 @@ pub trait BlobContainersApi {
         container_name: &str,
     ) -> Result<(), AzureStorageError>;
 
-    async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, FlUrlError>;
+    async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, AzureStorageError>;
 
     async fn get_list_of_blobs(
         &self,
@@ -1,9 +1,10 @@
-use crate::{connection::AzureStorageConnection, AzureStorageError};
+use crate::{
+    connection::AzureStorageConnection, storage_backend::BlobContainersBackend, AzureStorageError,
+};
 
 use super::api::BlobContainersApi;
 
 use async_trait::async_trait;
-use flurl::FlUrlError;
 
 #[async_trait]
 impl BlobContainersApi for AzureStorageConnection {
@@ -11,94 +12,38 @@ impl BlobContainersApi for AzureStorageConnection {
         &self,
         container_name: &str,
     ) -> Result<(), AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::containers::create_if_not_exists(connection_data, container_name)
-                    .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::containers::create_if_not_exists(connection_data, container_name)
-                    .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                connection_data.create(container_name).await;
-                Ok(())
-            }
-        }
+        self.get_storage_backend()
+            .create_container_if_not_exists(container_name)
+            .await
     }
 
     async fn delete_container(&self, container_name: &str) -> Result<(), AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::containers::delete(connection_data, container_name).await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::containers::delete(connection_data, container_name).await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                if connection_data.delete(container_name).await {
-                    Ok(())
-                } else {
-                    Err(AzureStorageError::ContainerNotFound)
-                }
-            }
-        }
+        self.get_storage_backend()
+            .delete_container(container_name)
+            .await
     }
 
     async fn delete_container_if_exists(
         &self,
         container_name: &str,
     ) -> Result<(), AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::containers::delete_if_exists(connection_data, container_name)
-                    .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::containers::delete_if_exists(connection_data, container_name)
-                    .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                connection_data.delete(container_name).await;
-                Ok(())
-            }
-        }
+        self.get_storage_backend()
+            .delete_container_if_exists(container_name)
+            .await
     }
 
-    async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, FlUrlError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::containers::get_list(connection_data).await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::containers::get_list(connection_data).await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                Ok(connection_data.get_list().await)
-            }
-        }
+    async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, AzureStorageError> {
+        self.get_storage_backend()
+            .get_list_of_blob_containers()
+            .await
     }
 
     async fn get_list_of_blobs(
         &self,
         container_name: &str,
     ) -> Result<Vec<String>, AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::get_list(connection_data, container_name).await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                crate::sdk_files::blobs::get_list(connection_data, container_name).await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    container_name,
-                )
-                .await?;
-
-                Ok(container.get_list_of_blobs().await)
-            }
-        }
+        self.get_storage_backend()
+            .get_list_of_blobs(container_name)
+            .await
     }
 }
@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+
+use crate::AzureStorageError;
+
+use super::{BlobCopyState, CopySource};
+
+/// Server-side copy - the content does not go through the client.
+/// Local backends copy before returning, so the copy is always completed there
+#[async_trait]
+pub trait BlobCopyApi {
+    /// Copy Blob. Azure may still be copying when it returns (`CopyStatus::Pending`) - poll `get_copy_status`.
+    /// A leased destination is copied to with its lease id
+    async fn start_copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError>;
+
+    /// Copy Blob From URL: completes before returning. Azure copies this way block blobs up to 256 MiB,
+    /// and reads the source with no credentials of the connection - a public blob or a SAS url
+    async fn copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError>;
+
+    /// State of the last copy the blob was the destination of. None if it was never copied to
+    async fn get_copy_status(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Option<BlobCopyState>, AzureStorageError>;
+
+    /// Leaves the destination blob empty. Fails with NoPendingCopyOperation once the copy is completed
+    async fn abort_copy(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        copy_id: &str,
+    ) -> Result<(), AzureStorageError>;
+}
@@ -0,0 +1,143 @@
+use crate::{
+    connection::AzureStorageConnection, storage_backend::BlobCopyBackend, AzureStorageError,
+};
+
+use super::{api::BlobCopyApi, BlobCopyState, CopySource};
+
+use async_trait::async_trait;
+
+#[async_trait]
+impl BlobCopyApi for AzureStorageConnection {
+    async fn start_copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        self.get_storage_backend()
+            .start_copy_from_url(container_name, blob_name, source, lease_id)
+            .await
+    }
+
+    async fn copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        self.get_storage_backend()
+            .copy_from_url(container_name, blob_name, source, lease_id)
+            .await
+    }
+
+    async fn get_copy_status(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Option<BlobCopyState>, AzureStorageError> {
+        self.get_storage_backend()
+            .get_copy_status(container_name, blob_name)
+            .await
+    }
+
+    async fn abort_copy(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        copy_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .abort_copy(container_name, blob_name, copy_id)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blob::BlobApi, blob_container::BlobContainersApi, blob_copy::CopyStatus,
+        block_blob::BlockBlobApi,
+    };
+
+    #[tokio::test]
+    async fn test_copy_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-blob-copy";
+        const SOURCE_BLOB_NAME: &str = "source-blob";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, SOURCE_BLOB_NAME, vec![1u8, 2u8])
+            .await
+            .unwrap();
+
+        let copy_state = connection
+            .copy_from_url(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                &CopySource::from_blob(CONTAINER_NAME, SOURCE_BLOB_NAME),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(CopyStatus::Success, copy_state.status);
+
+        assert_eq!(
+            vec![1u8, 2u8],
+            connection
+                .download_blob(CONTAINER_NAME, BLOB_NAME)
+                .await
+                .unwrap()
+        );
+
+        // The copy state survives in the sidecar of the blob
+        assert_eq!(
+            Some(copy_state.clone()),
+            connection
+                .get_copy_status(CONTAINER_NAME, BLOB_NAME)
+                .await
+                .unwrap()
+        );
+
+        let result = connection
+            .abort_copy(CONTAINER_NAME, BLOB_NAME, copy_state.copy_id.as_str())
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::NoPendingCopyOperation(_))
+        ));
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![3u8])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            None,
+            connection
+                .get_copy_status(CONTAINER_NAME, BLOB_NAME)
+                .await
+                .unwrap()
+        );
+
+        let result = connection
+            .abort_copy(CONTAINER_NAME, "not-exists", copy_state.copy_id.as_str())
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,133 @@
+use crate::AzureStorageError;
+
+/// Blob a copy reads from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopySource {
+    /// Blob of the same storage (account, folder or in-memory storage) as the destination
+    Blob {
+        container_name: String,
+        blob_name: String,
+    },
+    /// Any url Azure can read: a public blob, a blob of another account with a SAS, ...
+    /// Local backends copy only blobs of their own storage
+    Url(String),
+}
+
+impl CopySource {
+    pub fn from_blob(container_name: &str, blob_name: &str) -> Self {
+        Self::Blob {
+            container_name: container_name.to_string(),
+            blob_name: blob_name.to_string(),
+        }
+    }
+
+    pub fn from_url(url: &str) -> Self {
+        Self::Url(url.to_string())
+    }
+
+    /// Container and blob name a local backend copies from
+    pub(crate) fn get_local_blob(&self) -> Result<(&str, &str), AzureStorageError> {
+        match self {
+            Self::Blob {
+                container_name,
+                blob_name,
+            } => Ok((container_name.as_str(), blob_name.as_str())),
+            Self::Url(_) => Err(AzureStorageError::CannotVerifyCopySource(None)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyStatus {
+    Pending,
+    Success,
+    Aborted,
+    Failed,
+}
+
+impl CopyStatus {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src {
+            "pending" => Some(Self::Pending),
+            "success" => Some(Self::Success),
+            "aborted" => Some(Self::Aborted),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Success => "success",
+            Self::Aborted => "aborted",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// x-ms-copy-progress: bytes copied out of the total, e.g. 1024/4096
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyProgress {
+    pub bytes_copied: u64,
+    pub bytes_total: u64,
+}
+
+impl CopyProgress {
+    pub fn parse(src: &str) -> Option<Self> {
+        let (bytes_copied, bytes_total) = src.split_once('/')?;
+
+        Some(Self {
+            bytes_copied: bytes_copied.trim().parse().ok()?,
+            bytes_total: bytes_total.trim().parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for CopyProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.bytes_copied, self.bytes_total)
+    }
+}
+
+/// State of the last copy the blob was the destination of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobCopyState {
+    /// Id `abort_copy` takes
+    pub copy_id: String,
+    pub status: CopyStatus,
+    pub progress: Option<CopyProgress>,
+    /// Why the copy failed or was aborted
+    pub status_description: Option<String>,
+}
+
+impl BlobCopyState {
+    /// Copy which completed at once - the way local backends copy
+    pub(crate) fn completed(bytes_total: u64) -> Self {
+        Self {
+            copy_id: uuid::Uuid::new_v4().to_string(),
+            status: CopyStatus::Success,
+            progress: Some(CopyProgress {
+                bytes_copied: bytes_total,
+                bytes_total,
+            }),
+            status_description: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_copy_progress() {
+        let result = CopyProgress::parse("1024/4096").unwrap();
+
+        assert_eq!(1024, result.bytes_copied);
+        assert_eq!(4096, result.bytes_total);
+        assert_eq!("1024/4096", result.to_string());
+
+        assert!(CopyProgress::parse("1024").is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::AzureStorageError;
+
+use super::LeaseDuration;
+
+/// Lease Blob (comp=lease). Writes and deletes of a leased blob have to pass the lease id
+/// with `BlobAccessConditions::with_lease_id`
+#[async_trait]
+pub trait BlobLeaseApi {
+    /// Returns the lease id. Azure generates one unless `proposed_lease_id` is given
+    async fn acquire_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        duration: LeaseDuration,
+        proposed_lease_id: Option<&str>,
+    ) -> Result<String, AzureStorageError>;
+
+    async fn renew_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError>;
+
+    async fn release_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError>;
+
+    /// Returns the time left until the lease is broken.
+    /// No `break_period` - a fixed lease breaks when it expires, an infinite one breaks immediately
+    async fn break_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        break_period: Option<Duration>,
+    ) -> Result<Duration, AzureStorageError>;
+
+    /// Returns the new lease id
+    async fn change_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+        proposed_lease_id: &str,
+    ) -> Result<String, AzureStorageError>;
+}
@@ -0,0 +1,673 @@
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use rust_extensions::StrOrString;
+use tokio::{
+    sync::{oneshot, watch},
+    task::JoinHandle,
+};
+
+use crate::{
+    blob::BlobAccessConditions,
+    block_blob::UploadBlockBlobOptions,
+    storage_backend::{BlobContainersBackend, BlobLeaseBackend, BlockBlobBackend},
+    AzureStorageConnection, AzureStorageError, StorageClock, StorageError, SystemClock,
+};
+
+use super::LeaseDuration;
+
+const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(30);
+const DEFAULT_INFINITE_LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(15);
+
+// The clock may be moved by hand, so the renewal does not sleep longer than this between looks at it
+const CLOCK_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Distributed lock (leader election) on a well-known blob.
+/// Whoever holds the lease of the blob is the leader; the lease is renewed in the background
+/// while the returned `BlobLeaseGuard` is alive. The blob is created empty if it does not exist.
+///
+/// Leadership of a fixed lease ends a safety margin before the lease could expire, counting from
+/// when the last successful renewal was sent - a renewal still in flight does not extend it
+pub struct BlobLeaseLock {
+    connection: Arc<AzureStorageConnection>,
+    container_name: String,
+    blob_name: String,
+    lease_duration: LeaseDuration,
+    renew_interval: Option<Duration>,
+    safety_margin: Option<Duration>,
+    lease_id: String,
+    clock: Arc<dyn StorageClock>,
+}
+
+impl BlobLeaseLock {
+    pub fn new(
+        connection: Arc<AzureStorageConnection>,
+        container_name: impl Into<StrOrString<'static>>,
+        blob_name: impl Into<StrOrString<'static>>,
+    ) -> Self {
+        let container_name: StrOrString<'static> = container_name.into();
+        let blob_name: StrOrString<'static> = blob_name.into();
+
+        Self {
+            connection,
+            container_name: container_name.to_string(),
+            blob_name: blob_name.to_string(),
+            lease_duration: LeaseDuration::Fixed(DEFAULT_LEASE_DURATION),
+            renew_interval: None,
+            safety_margin: None,
+            lease_id: uuid::Uuid::new_v4().to_string(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Azure accepts 15..=60 seconds or an infinite lease. Default is 30 seconds
+    pub fn with_lease_duration(mut self, lease_duration: LeaseDuration) -> Self {
+        self.lease_duration = lease_duration;
+        self
+    }
+
+    /// Default is a third of a fixed lease duration, so two renewals in a row may fail before the lease is lost
+    pub fn with_renew_interval(mut self, renew_interval: Duration) -> Self {
+        self.renew_interval = Some(renew_interval);
+        self
+    }
+
+    /// How long before the lease could expire the guard stops being the leader.
+    /// Covers the drift between the local clock and the one of the storage. Default is a sixth of a fixed lease duration
+    pub fn with_safety_margin(mut self, safety_margin: Duration) -> Self {
+        self.safety_margin = Some(safety_margin);
+        self
+    }
+
+    /// Clock the leadership deadline and the renewals are timed by. Default is the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn StorageClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Lease id the lock proposes. Default is a random one, generated once per lock
+    pub fn with_lease_id(mut self, lease_id: impl Into<String>) -> Self {
+        self.lease_id = lease_id.into();
+        self
+    }
+
+    pub fn get_lease_id(&self) -> &str {
+        self.lease_id.as_str()
+    }
+
+    fn get_renew_interval(&self) -> Duration {
+        if let Some(renew_interval) = self.renew_interval {
+            return renew_interval;
+        }
+
+        match self.lease_duration {
+            LeaseDuration::Fixed(duration) => duration / 3,
+            LeaseDuration::Infinite => DEFAULT_INFINITE_LEASE_RENEW_INTERVAL,
+        }
+    }
+
+    fn get_safety_margin(&self) -> Duration {
+        if let Some(safety_margin) = self.safety_margin {
+            return safety_margin;
+        }
+
+        match self.lease_duration {
+            LeaseDuration::Fixed(duration) => duration / 6,
+            LeaseDuration::Infinite => Duration::ZERO,
+        }
+    }
+
+    /// Returns None if somebody else holds the lease
+    pub async fn try_lock(&self) -> Result<Option<BlobLeaseGuard>, AzureStorageError> {
+        let mut sent_at = self.clock.now().unix_microseconds;
+        let result = self.acquire().await;
+
+        let result = match result {
+            Err(err) if err.is_not_found() => {
+                self.create_blob().await?;
+                sent_at = self.clock.now().unix_microseconds;
+                self.acquire().await
+            }
+            result => result,
+        };
+
+        match result {
+            Ok(lease_id) => Ok(Some(self.start_renewal(lease_id, sent_at))),
+            Err(err) if err.is_conflict() => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Waits until the lease is acquired, trying every renew interval
+    pub async fn lock(&self) -> Result<BlobLeaseGuard, AzureStorageError> {
+        loop {
+            if let Some(guard) = self.try_lock().await? {
+                return Ok(guard);
+            }
+
+            tokio::time::sleep(self.get_renew_interval()).await;
+        }
+    }
+
+    async fn acquire(&self) -> Result<String, AzureStorageError> {
+        self.connection
+            .get_storage_backend()
+            .acquire_blob_lease(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                self.lease_duration,
+                Some(self.lease_id.as_str()),
+            )
+            .await
+    }
+
+    async fn create_blob(&self) -> Result<(), AzureStorageError> {
+        let backend = self.connection.get_storage_backend();
+
+        backend
+            .create_container_if_not_exists(self.container_name.as_str())
+            .await?;
+
+        let result = backend
+            .upload_block_blob(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                vec![],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::if_not_exists()),
+            )
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            // Another contender has just created it
+            Err(err) if err.is_conflict() || err.is_condition_not_met() => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn start_renewal(&self, lease_id: String, acquired_at: i64) -> BlobLeaseGuard {
+        let (leader_sender, leader_receiver) = watch::channel(true);
+        let (stop_sender, stop_receiver) = oneshot::channel();
+
+        let renewal = LeaseRenewal {
+            connection: self.connection.clone(),
+            container_name: self.container_name.to_string(),
+            blob_name: self.blob_name.to_string(),
+            lease_id: lease_id.to_string(),
+            lease_duration: self.lease_duration,
+            renew_interval: self.get_renew_interval(),
+            safety_margin: self.get_safety_margin(),
+            clock: self.clock.clone(),
+            acquired_at,
+        };
+
+        let renewal = tokio::spawn(renewal.run(leader_sender, stop_receiver));
+
+        BlobLeaseGuard {
+            connection: self.connection.clone(),
+            container_name: self.container_name.to_string(),
+            blob_name: self.blob_name.to_string(),
+            lease_id,
+            is_leader: leader_receiver,
+            stop: Some(stop_sender),
+            renewal: Some(renewal),
+        }
+    }
+}
+
+/// Held lease of a `BlobLeaseLock`. The lease is released by `release` or, in the background, on drop
+pub struct BlobLeaseGuard {
+    connection: Arc<AzureStorageConnection>,
+    container_name: String,
+    blob_name: String,
+    lease_id: String,
+    is_leader: watch::Receiver<bool>,
+    stop: Option<oneshot::Sender<()>>,
+    renewal: Option<JoinHandle<()>>,
+}
+
+impl BlobLeaseGuard {
+    pub fn get_lease_id(&self) -> &str {
+        self.lease_id.as_str()
+    }
+
+    /// False once a renewal has failed for good (the lease was broken, taken over or expired)
+    /// or no renewal has succeeded in time to be sure the lease is still held.
+    /// It never becomes true again - acquire a new guard instead
+    pub fn is_leader(&self) -> bool {
+        *self.is_leader.borrow()
+    }
+
+    /// "Still leader" channel. Changes to false when the lease is lost
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.is_leader.clone()
+    }
+
+    /// Conditions for writes to the leased blob itself (e.g. the page blob the lock guards)
+    pub fn get_access_conditions(&self) -> BlobAccessConditions {
+        BlobAccessConditions::new().with_lease_id(self.lease_id.as_str())
+    }
+
+    /// Stops the renewal and releases the lease. A lease which is already lost is not released
+    pub async fn release(mut self) -> Result<(), AzureStorageError> {
+        self.stop_renewal().await;
+
+        if !self.is_leader() {
+            return Ok(());
+        }
+
+        self.connection
+            .get_storage_backend()
+            .release_blob_lease(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                self.lease_id.as_str(),
+            )
+            .await
+    }
+
+    async fn stop_renewal(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+
+        if let Some(renewal) = self.renewal.take() {
+            let _ = renewal.await;
+        }
+    }
+}
+
+impl Drop for BlobLeaseGuard {
+    fn drop(&mut self) {
+        // Already released
+        if self.renewal.is_none() {
+            return;
+        }
+
+        // Dropping the sender stops the renewal
+        self.stop.take();
+
+        if !self.is_leader() {
+            return;
+        }
+
+        let connection = self.connection.clone();
+        let container_name = self.container_name.to_string();
+        let blob_name = self.blob_name.to_string();
+        let lease_id = self.lease_id.to_string();
+
+        // Outside of a runtime the lease is left to expire
+        let runtime = match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => runtime,
+            Err(_) => return,
+        };
+
+        runtime.spawn(async move {
+            let _ = connection
+                .get_storage_backend()
+                .release_blob_lease(
+                    container_name.as_str(),
+                    blob_name.as_str(),
+                    lease_id.as_str(),
+                )
+                .await;
+        });
+    }
+}
+
+type RenewalFuture<'s> = Pin<Box<dyn Future<Output = Result<(), AzureStorageError>> + Send + 's>>;
+
+struct LeaseRenewal {
+    connection: Arc<AzureStorageConnection>,
+    container_name: String,
+    blob_name: String,
+    lease_id: String,
+    lease_duration: LeaseDuration,
+    renew_interval: Duration,
+    safety_margin: Duration,
+    clock: Arc<dyn StorageClock>,
+    // Moment the acquire request was sent, in microseconds of the clock
+    acquired_at: i64,
+}
+
+impl LeaseRenewal {
+    /// Azure counts the lease duration from when it receives a request, which is never before the request was sent.
+    /// So the lease renewed by a request sent at `renewed_at` is held at least until the deadline
+    fn get_deadline(&self, renewed_at: i64) -> Option<i64> {
+        match self.lease_duration {
+            LeaseDuration::Fixed(duration) => {
+                let duration = duration.saturating_sub(self.safety_margin);
+                Some(renewed_at + duration.as_micros() as i64)
+            }
+            LeaseDuration::Infinite => None,
+        }
+    }
+
+    async fn renew(&self) -> Result<(), AzureStorageError> {
+        self.connection
+            .get_storage_backend()
+            .renew_blob_lease(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                self.lease_id.as_str(),
+            )
+            .await
+    }
+
+    async fn run(self, is_leader: watch::Sender<bool>, mut stop: oneshot::Receiver<()>) {
+        let renew_interval = self.renew_interval.as_micros() as i64;
+
+        // Send moment of the last acquire or renewal which succeeded
+        let mut renewed_at = self.acquired_at;
+        let mut next_renewal_at = renewed_at + renew_interval;
+        let mut renewal: Option<(i64, RenewalFuture<'_>)> = None;
+
+        loop {
+            let now = self.clock.now().unix_microseconds;
+            let deadline = self.get_deadline(renewed_at);
+
+            // A renewal in flight does not help: the lease may be gone before it reaches Azure
+            if let Some(deadline) = deadline {
+                if now >= deadline {
+                    let _ = is_leader.send(false);
+                    return;
+                }
+            }
+
+            if renewal.is_none() && now >= next_renewal_at {
+                let future: RenewalFuture<'_> = Box::pin(self.renew());
+                renewal = Some((now, future));
+            }
+
+            let mut wake_at = deadline.unwrap_or(i64::MAX);
+
+            if renewal.is_none() {
+                wake_at = wake_at.min(next_renewal_at);
+            }
+
+            let wait = Duration::from_micros((wake_at - now) as u64).min(CLOCK_CHECK_INTERVAL);
+
+            tokio::select! {
+                biased;
+                _ = &mut stop => return,
+                (sent_at, result) = wait_for_renewal(&mut renewal) => {
+                    renewal = None;
+
+                    match result {
+                        Ok(()) => {
+                            renewed_at = sent_at;
+                            next_renewal_at = sent_at + renew_interval;
+                        }
+                        Err(err) => {
+                            // Broken, taken over, or the blob is gone - retrying does not bring the lease back
+                            if err.is_condition_not_met() || err.is_conflict() || err.is_not_found() {
+                                let _ = is_leader.send(false);
+                                return;
+                            }
+
+                            next_renewal_at = self.clock.now().unix_microseconds + renew_interval;
+                        }
+                    }
+                }
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+    }
+}
+
+async fn wait_for_renewal(
+    renewal: &mut Option<(i64, RenewalFuture<'_>)>,
+) -> (i64, Result<(), AzureStorageError>) {
+    match renewal {
+        Some((sent_at, future)) => (*sent_at, future.await),
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+    use tokio::sync::Notify;
+
+    use super::*;
+    use crate::{
+        blob_lease::BlobLeaseApi,
+        block_blob::BlockBlobApi,
+        connection::AzureStorageConnectionData,
+        sdk_azure::http_transport::{AzureHttpRequest, AzureHttpResponse, AzureHttpTransport},
+        ManualClock, RetryPolicy,
+    };
+
+    /// Grants the acquire, then holds every renewal until it is told to respond
+    struct HeldRenewalTransport {
+        requests: AtomicUsize,
+        renewal_sent: Notify,
+        respond: Notify,
+    }
+
+    impl HeldRenewalTransport {
+        fn new() -> Self {
+            Self {
+                requests: AtomicUsize::new(0),
+                renewal_sent: Notify::new(),
+                respond: Notify::new(),
+            }
+        }
+
+        async fn wait_for_renewal(&self) {
+            tokio::time::timeout(Duration::from_secs(1), self.renewal_sent.notified())
+                .await
+                .unwrap();
+        }
+    }
+
+    #[async_trait]
+    impl AzureHttpTransport for HeldRenewalTransport {
+        async fn send(
+            &self,
+            _request: AzureHttpRequest,
+        ) -> Result<AzureHttpResponse, AzureStorageError> {
+            if self.requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Ok(AzureHttpResponse::new(201).with_header("x-ms-lease-id", "lease-1"));
+            }
+
+            self.renewal_sent.notify_one();
+            self.respond.notified().await;
+
+            Ok(AzureHttpResponse::new(200))
+        }
+    }
+
+    // 15 seconds lease, renewed every 5 seconds. Leadership ends 2.5 seconds before the lease could expire
+    async fn get_held_renewal_guard(
+        transport: &Arc<HeldRenewalTransport>,
+        clock: &Arc<ManualClock>,
+    ) -> BlobLeaseGuard {
+        let connection = AzureStorageConnectionData::from_conn_string(
+            "AccountName=test;AccountKey=YWJj;BlobEndpoint=http://127.0.0.1:10000/test",
+        )
+        .with_retry_policy(RetryPolicy::no_retry())
+        .with_transport(transport.clone());
+
+        let connection = Arc::new(AzureStorageConnection::AzureStorage(connection));
+
+        BlobLeaseLock::new(connection, "locks", "writer")
+            .with_lease_duration(LeaseDuration::from_secs(15))
+            .with_renew_interval(Duration::from_secs(5))
+            .with_clock(clock.clone())
+            .try_lock()
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    fn get_in_mem_connection(clock: &Arc<ManualClock>) -> Arc<AzureStorageConnection> {
+        Arc::new(AzureStorageConnection::new_in_memory().with_clock(clock.clone()))
+    }
+
+    // 15 seconds lease, renewed as soon as the clock is 50ms past the last renewal
+    fn get_lock(
+        connection: &Arc<AzureStorageConnection>,
+        clock: &Arc<ManualClock>,
+    ) -> BlobLeaseLock {
+        BlobLeaseLock::new(connection.clone(), "locks", "writer")
+            .with_lease_duration(LeaseDuration::from_secs(15))
+            .with_renew_interval(Duration::from_millis(50))
+            .with_clock(clock.clone())
+    }
+
+    #[tokio::test]
+    async fn test_only_one_leader() {
+        let clock = Arc::new(ManualClock::new(DateTimeAsMicroseconds::now()));
+        let connection = get_in_mem_connection(&clock);
+
+        let first = get_lock(&connection, &clock);
+        let second = get_lock(&connection, &clock);
+
+        let guard = first.try_lock().await.unwrap().unwrap();
+        assert!(guard.is_leader());
+        assert_eq!(first.get_lease_id(), guard.get_lease_id());
+
+        assert!(second.try_lock().await.unwrap().is_none());
+
+        // The background renewal keeps the lease past its duration
+        for _ in 0..4 {
+            clock.advance(Duration::from_secs(5));
+            tokio::time::sleep(CLOCK_CHECK_INTERVAL * 3).await;
+        }
+        assert!(guard.is_leader());
+        assert!(second.try_lock().await.unwrap().is_none());
+
+        guard.release().await.unwrap();
+
+        let guard = second.try_lock().await.unwrap().unwrap();
+        assert!(guard.is_leader());
+
+        drop(guard);
+
+        let guard = tokio::time::timeout(Duration::from_secs(1), first.lock())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(guard.is_leader());
+    }
+
+    #[test]
+    fn test_guard_dropped_outside_of_runtime_does_not_panic() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let clock = Arc::new(ManualClock::new(DateTimeAsMicroseconds::now()));
+        let connection = get_in_mem_connection(&clock);
+
+        let guard = runtime
+            .block_on(get_lock(&connection, &clock).try_lock())
+            .unwrap()
+            .unwrap();
+        assert!(guard.is_leader());
+
+        drop(runtime);
+        drop(guard);
+    }
+
+    #[tokio::test]
+    async fn test_leader_is_notified_when_lease_is_broken() {
+        let clock = Arc::new(ManualClock::new(DateTimeAsMicroseconds::now()));
+        let connection = get_in_mem_connection(&clock);
+
+        let guard = get_lock(&connection, &clock)
+            .try_lock()
+            .await
+            .unwrap()
+            .unwrap();
+        let mut is_leader = guard.subscribe();
+
+        connection
+            .break_blob_lease("locks", "writer", Some(Duration::ZERO))
+            .await
+            .unwrap();
+
+        // The next renewal finds the lease broken
+        clock.advance(Duration::from_secs(1));
+
+        tokio::time::timeout(Duration::from_secs(1), is_leader.changed())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(!*is_leader.borrow());
+        assert!(!guard.is_leader());
+
+        let result = connection
+            .upload_block_blob_with_options(
+                "locks",
+                "writer",
+                vec![1u8],
+                UploadBlockBlobOptions::new().with_conditions(guard.get_access_conditions()),
+            )
+            .await;
+        assert!(result.unwrap_err().is_condition_not_met());
+
+        guard.release().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_leadership_ends_while_renewal_is_in_flight() {
+        let transport = Arc::new(HeldRenewalTransport::new());
+        let clock = Arc::new(ManualClock::new(DateTimeAsMicroseconds::now()));
+
+        let guard = get_held_renewal_guard(&transport, &clock).await;
+        let mut is_leader = guard.subscribe();
+
+        clock.advance(Duration::from_secs(5));
+        transport.wait_for_renewal().await;
+        assert!(guard.is_leader());
+
+        // The renewal never gets an answer, so the lease may be gone 15 seconds after the acquire
+        clock.advance(Duration::from_secs(8));
+
+        tokio::time::timeout(Duration::from_secs(1), is_leader.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!guard.is_leader());
+
+        guard.release().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_leadership_deadline_counts_from_when_renewal_was_sent() {
+        let transport = Arc::new(HeldRenewalTransport::new());
+        let clock = Arc::new(ManualClock::new(DateTimeAsMicroseconds::now()));
+
+        let guard = get_held_renewal_guard(&transport, &clock).await;
+        let mut is_leader = guard.subscribe();
+
+        // Sent at 5s, answered at 9s: the lease is held until 20s at least
+        clock.advance(Duration::from_secs(5));
+        transport.wait_for_renewal().await;
+        clock.advance(Duration::from_secs(4));
+        transport.respond.notify_one();
+
+        // Past the deadline of the acquire, before the one of the renewal
+        clock.advance(Duration::from_secs(7));
+        transport.wait_for_renewal().await;
+        assert!(guard.is_leader());
+
+        // Past the deadline of the renewal sent at 5s, which it would not be if counted from the answer at 9s
+        clock.advance(Duration::from_secs(2));
+
+        tokio::time::timeout(Duration::from_secs(1), is_leader.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!guard.is_leader());
+
+        guard.release().await.unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+use std::time::Duration;
+
+use crate::{
+    connection::AzureStorageConnection, storage_backend::BlobLeaseBackend, AzureStorageError,
+};
+
+use super::{api::BlobLeaseApi, LeaseDuration};
+
+use async_trait::async_trait;
+
+#[async_trait]
+impl BlobLeaseApi for AzureStorageConnection {
+    async fn acquire_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        duration: LeaseDuration,
+        proposed_lease_id: Option<&str>,
+    ) -> Result<String, AzureStorageError> {
+        self.get_storage_backend()
+            .acquire_blob_lease(container_name, blob_name, duration, proposed_lease_id)
+            .await
+    }
+
+    async fn renew_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .renew_blob_lease(container_name, blob_name, lease_id)
+            .await
+    }
+
+    async fn release_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .release_blob_lease(container_name, blob_name, lease_id)
+            .await
+    }
+
+    async fn break_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        break_period: Option<Duration>,
+    ) -> Result<Duration, AzureStorageError> {
+        self.get_storage_backend()
+            .break_blob_lease(container_name, blob_name, break_period)
+            .await
+    }
+
+    async fn change_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+        proposed_lease_id: &str,
+    ) -> Result<String, AzureStorageError> {
+        self.get_storage_backend()
+            .change_blob_lease(container_name, blob_name, lease_id, proposed_lease_id)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+    use crate::{
+        blob::{BlobAccessConditions, BlobApi, BlobHttpHeaders, LeaseState},
+        blob_container::BlobContainersApi,
+        block_blob::{BlockBlobApi, UploadBlockBlobOptions},
+        ManualClock,
+    };
+
+    #[tokio::test]
+    async fn test_leases_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-blob-leases";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let clock = Arc::new(ManualClock::new(DateTimeAsMicroseconds::now()));
+
+        let connection =
+            AzureStorageConnection::from_conn_string(folder.as_str()).with_clock(clock.clone());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        let result = connection
+            .acquire_blob_lease(CONTAINER_NAME, BLOB_NAME, LeaseDuration::Infinite, None)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![0u8])
+            .await
+            .unwrap();
+
+        let lease_id = connection
+            .acquire_blob_lease(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                LeaseDuration::from_secs(15),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let props = connection
+            .get_blob_properties(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+        assert_eq!(LeaseState::Leased, props.lease_state);
+
+        let result = connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![1u8])
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        connection
+            .upload_block_blob_with_options(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                vec![1u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::new().with_lease_id(lease_id.as_str())),
+            )
+            .await
+            .unwrap();
+
+        let result = connection.delete_blob(CONTAINER_NAME, BLOB_NAME).await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        let result = connection
+            .set_blob_metadata(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                HashMap::new(),
+                BlobAccessConditions::new(),
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        let result = connection
+            .set_blob_http_headers(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                BlobHttpHeaders::new(),
+                BlobAccessConditions::new(),
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        let result = connection
+            .put_block(CONTAINER_NAME, BLOB_NAME, "QUFB", vec![2u8], None)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        let result = connection
+            .delete_blob_if_exists(CONTAINER_NAME, BLOB_NAME)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        // The lease holder writes with its lease id
+        connection
+            .set_blob_metadata(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                HashMap::new(),
+                BlobAccessConditions::new().with_lease_id(lease_id.as_str()),
+            )
+            .await
+            .unwrap();
+
+        connection
+            .set_blob_http_headers(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                BlobHttpHeaders::new(),
+                BlobAccessConditions::new().with_lease_id(lease_id.as_str()),
+            )
+            .await
+            .unwrap();
+
+        connection
+            .put_block(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                "QUFB",
+                vec![2u8],
+                Some(lease_id.as_str()),
+            )
+            .await
+            .unwrap();
+
+        clock.advance(std::time::Duration::from_secs(16));
+
+        let props = connection
+            .get_blob_properties(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+        assert_eq!(LeaseState::Expired, props.lease_state);
+
+        connection
+            .delete_blob(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
+}
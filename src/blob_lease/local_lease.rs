@@ -0,0 +1,462 @@
+use std::time::Duration;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    blob::{BlobProperties, LeaseState, LeaseStatus},
+    AzureStorageError,
+};
+
+use super::LeaseDuration;
+
+#[derive(Debug, Clone)]
+enum LocalLeaseState {
+    Available,
+    Leased {
+        lease_id: String,
+        duration: LeaseDuration,
+        expires_at: Option<i64>,
+    },
+    Expired {
+        lease_id: String,
+        duration: LeaseDuration,
+    },
+    Breaking {
+        lease_id: String,
+        broken_at: i64,
+    },
+    Broken {
+        lease_id: String,
+    },
+}
+
+/// Lease state machine of the in-memory and file backends.
+/// Expiry and breaking are resolved lazily against the `now` of the backend clock
+#[derive(Debug, Clone)]
+pub struct LocalLease {
+    state: LocalLeaseState,
+}
+
+impl LocalLease {
+    pub fn new() -> Self {
+        Self {
+            state: LocalLeaseState::Available,
+        }
+    }
+
+    /// State at `now`: a fixed lease past its expiry is expired, a breaking one past its break moment is broken
+    fn resolved(&self, now: DateTimeAsMicroseconds) -> LocalLeaseState {
+        match &self.state {
+            LocalLeaseState::Leased {
+                lease_id,
+                duration,
+                expires_at: Some(expires_at),
+            } if *expires_at <= now.unix_microseconds => LocalLeaseState::Expired {
+                lease_id: lease_id.to_string(),
+                duration: *duration,
+            },
+            LocalLeaseState::Breaking {
+                lease_id,
+                broken_at,
+            } if *broken_at <= now.unix_microseconds => LocalLeaseState::Broken {
+                lease_id: lease_id.to_string(),
+            },
+            state => state.clone(),
+        }
+    }
+
+    fn resolve(&mut self, now: DateTimeAsMicroseconds) {
+        self.state = self.resolved(now);
+    }
+
+    fn lease(&mut self, lease_id: String, duration: LeaseDuration, now: DateTimeAsMicroseconds) {
+        let expires_at = match duration {
+            LeaseDuration::Fixed(duration) => {
+                Some(now.unix_microseconds + duration.as_micros() as i64)
+            }
+            LeaseDuration::Infinite => None,
+        };
+
+        self.state = LocalLeaseState::Leased {
+            lease_id,
+            duration,
+            expires_at,
+        };
+    }
+
+    pub fn acquire(
+        &mut self,
+        duration: LeaseDuration,
+        proposed_lease_id: Option<&str>,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<String, AzureStorageError> {
+        if !duration.is_valid() {
+            return Err(AzureStorageError::InvalidHeaderValue(None));
+        }
+
+        self.resolve(now);
+
+        match &self.state {
+            LocalLeaseState::Leased { lease_id, .. } => {
+                if proposed_lease_id != Some(lease_id.as_str()) {
+                    return Err(AzureStorageError::LeaseAlreadyPresent(None));
+                }
+            }
+            LocalLeaseState::Breaking { .. } => {
+                return Err(AzureStorageError::LeaseAlreadyPresent(None));
+            }
+            _ => {}
+        }
+
+        let lease_id = match proposed_lease_id {
+            Some(lease_id) => lease_id.to_string(),
+            None => uuid::Uuid::new_v4().to_string(),
+        };
+
+        self.lease(lease_id.to_string(), duration, now);
+
+        Ok(lease_id)
+    }
+
+    pub fn renew(
+        &mut self,
+        lease_id: &str,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<(), AzureStorageError> {
+        self.resolve(now);
+
+        match &self.state {
+            LocalLeaseState::Leased {
+                lease_id: current,
+                duration,
+                ..
+            }
+            | LocalLeaseState::Expired {
+                lease_id: current,
+                duration,
+            } => {
+                if current != lease_id {
+                    return Err(AzureStorageError::LeaseIdMismatch(None));
+                }
+
+                let duration = *duration;
+                self.lease(lease_id.to_string(), duration, now);
+                Ok(())
+            }
+            LocalLeaseState::Breaking {
+                lease_id: current, ..
+            }
+            | LocalLeaseState::Broken { lease_id: current } => {
+                if current != lease_id {
+                    return Err(AzureStorageError::LeaseIdMismatch(None));
+                }
+
+                Err(AzureStorageError::LeaseNotPresent(None))
+            }
+            LocalLeaseState::Available => Err(AzureStorageError::LeaseNotPresent(None)),
+        }
+    }
+
+    pub fn release(
+        &mut self,
+        lease_id: &str,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<(), AzureStorageError> {
+        self.resolve(now);
+
+        match &self.state {
+            LocalLeaseState::Leased {
+                lease_id: current, ..
+            }
+            | LocalLeaseState::Expired {
+                lease_id: current, ..
+            }
+            | LocalLeaseState::Breaking {
+                lease_id: current, ..
+            }
+            | LocalLeaseState::Broken { lease_id: current } => {
+                if current != lease_id {
+                    return Err(AzureStorageError::LeaseIdMismatch(None));
+                }
+
+                self.state = LocalLeaseState::Available;
+                Ok(())
+            }
+            LocalLeaseState::Available => Err(AzureStorageError::LeaseNotPresent(None)),
+        }
+    }
+
+    pub fn change(
+        &mut self,
+        lease_id: &str,
+        proposed_lease_id: &str,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<String, AzureStorageError> {
+        self.resolve(now);
+
+        match &mut self.state {
+            LocalLeaseState::Leased {
+                lease_id: current, ..
+            } => {
+                if current.as_str() != lease_id && current.as_str() != proposed_lease_id {
+                    return Err(AzureStorageError::LeaseIdMismatch(None));
+                }
+
+                *current = proposed_lease_id.to_string();
+                Ok(proposed_lease_id.to_string())
+            }
+            _ => Err(AzureStorageError::LeaseNotPresent(None)),
+        }
+    }
+
+    /// Returns the time left until the lease is broken
+    pub fn break_lease(
+        &mut self,
+        break_period: Option<Duration>,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<Duration, AzureStorageError> {
+        if let Some(break_period) = break_period {
+            if break_period > Duration::from_secs(60) {
+                return Err(AzureStorageError::InvalidHeaderValue(None));
+            }
+        }
+
+        self.resolve(now);
+
+        let (lease_id, remaining) = match &self.state {
+            LocalLeaseState::Available => return Err(AzureStorageError::LeaseNotPresent(None)),
+            LocalLeaseState::Leased {
+                lease_id,
+                expires_at,
+                ..
+            } => {
+                let remaining = expires_at.map(|expires_at| {
+                    Duration::from_micros((expires_at - now.unix_microseconds) as u64)
+                });
+
+                let remaining = match (break_period, remaining) {
+                    (Some(break_period), Some(remaining)) => break_period.min(remaining),
+                    (Some(break_period), None) => break_period,
+                    (None, Some(remaining)) => remaining,
+                    (None, None) => Duration::ZERO,
+                };
+
+                (lease_id.to_string(), remaining)
+            }
+            LocalLeaseState::Breaking {
+                lease_id,
+                broken_at,
+            } => {
+                let remaining = Duration::from_micros((broken_at - now.unix_microseconds) as u64);
+
+                let remaining = match break_period {
+                    Some(break_period) => break_period.min(remaining),
+                    None => remaining,
+                };
+
+                (lease_id.to_string(), remaining)
+            }
+            LocalLeaseState::Expired { lease_id, .. } | LocalLeaseState::Broken { lease_id } => {
+                (lease_id.to_string(), Duration::ZERO)
+            }
+        };
+
+        if remaining.is_zero() {
+            self.state = LocalLeaseState::Broken { lease_id };
+        } else {
+            self.state = LocalLeaseState::Breaking {
+                lease_id,
+                broken_at: now.unix_microseconds + remaining.as_micros() as i64,
+            };
+        }
+
+        Ok(remaining)
+    }
+
+    /// Leased and breaking blobs are locked: writes need the lease id, and a passed lease id has to match.
+    /// A lease id on an unlocked blob fails the way Azure does - with LeaseNotPresent
+    pub fn check_access(
+        &self,
+        lease_id: Option<&str>,
+        is_write: bool,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<(), AzureStorageError> {
+        match &self.resolved(now) {
+            LocalLeaseState::Leased {
+                lease_id: current, ..
+            }
+            | LocalLeaseState::Breaking {
+                lease_id: current, ..
+            } => match lease_id {
+                Some(lease_id) => {
+                    if lease_id != current {
+                        return Err(AzureStorageError::LeaseIdMismatch(None));
+                    }
+
+                    Ok(())
+                }
+                None => {
+                    if is_write {
+                        return Err(AzureStorageError::LeaseIdMissing(None));
+                    }
+
+                    Ok(())
+                }
+            },
+            _ => {
+                if lease_id.is_some() {
+                    return Err(AzureStorageError::LeaseNotPresent(None));
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    pub fn apply_to_properties(
+        &mut self,
+        properties: &mut BlobProperties,
+        now: DateTimeAsMicroseconds,
+    ) {
+        self.resolve(now);
+
+        let (lease_state, lease_status) = match &self.state {
+            LocalLeaseState::Available => (LeaseState::Available, LeaseStatus::Unlocked),
+            LocalLeaseState::Leased { .. } => (LeaseState::Leased, LeaseStatus::Locked),
+            LocalLeaseState::Expired { .. } => (LeaseState::Expired, LeaseStatus::Unlocked),
+            LocalLeaseState::Breaking { .. } => (LeaseState::Breaking, LeaseStatus::Locked),
+            LocalLeaseState::Broken { .. } => (LeaseState::Broken, LeaseStatus::Unlocked),
+        };
+
+        properties.lease_state = lease_state;
+        properties.lease_status = lease_status;
+    }
+
+    pub fn is_available(&self) -> bool {
+        matches!(self.state, LocalLeaseState::Available)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTimeAsMicroseconds {
+        DateTimeAsMicroseconds::new(secs * 1_000_000)
+    }
+
+    #[test]
+    fn test_fixed_lease_expires() {
+        let mut lease = LocalLease::new();
+
+        let lease_id = lease
+            .acquire(LeaseDuration::from_secs(15), None, at(0))
+            .unwrap();
+
+        assert!(matches!(
+            lease.acquire(LeaseDuration::from_secs(15), None, at(10)),
+            Err(AzureStorageError::LeaseAlreadyPresent(_))
+        ));
+        assert!(matches!(
+            lease.check_access(None, true, at(10)),
+            Err(AzureStorageError::LeaseIdMissing(_))
+        ));
+        assert!(lease.check_access(None, false, at(10)).is_ok());
+
+        // Renewal restarts the 15 seconds
+        lease.renew(&lease_id, at(10)).unwrap();
+        assert!(lease.check_access(None, true, at(20)).is_err());
+
+        assert!(lease.check_access(None, true, at(25)).is_ok());
+        assert!(matches!(
+            lease.check_access(Some(&lease_id), true, at(25)),
+            Err(AzureStorageError::LeaseNotPresent(_))
+        ));
+
+        // An expired lease can still be renewed by its holder
+        lease.renew(&lease_id, at(30)).unwrap();
+        assert!(lease.check_access(Some(&lease_id), true, at(31)).is_ok());
+        assert!(matches!(
+            lease.check_access(Some("other"), true, at(31)),
+            Err(AzureStorageError::LeaseIdMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_lease_duration_out_of_range() {
+        let mut lease = LocalLease::new();
+
+        assert!(matches!(
+            lease.acquire(LeaseDuration::from_secs(10), None, at(0)),
+            Err(AzureStorageError::InvalidHeaderValue(_))
+        ));
+        assert!(matches!(
+            lease.acquire(LeaseDuration::from_secs(61), None, at(0)),
+            Err(AzureStorageError::InvalidHeaderValue(_))
+        ));
+        assert!(lease.is_available());
+
+        lease
+            .acquire(LeaseDuration::from_secs(60), None, at(0))
+            .unwrap();
+
+        assert!(matches!(
+            lease.break_lease(Some(Duration::from_secs(61)), at(0)),
+            Err(AzureStorageError::InvalidHeaderValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_break_lease() {
+        let mut lease = LocalLease::new();
+
+        assert!(matches!(
+            lease.break_lease(None, at(0)),
+            Err(AzureStorageError::LeaseNotPresent(_))
+        ));
+
+        let lease_id = lease.acquire(LeaseDuration::Infinite, None, at(0)).unwrap();
+
+        let remaining = lease
+            .break_lease(Some(Duration::from_secs(10)), at(0))
+            .unwrap();
+        assert_eq!(Duration::from_secs(10), remaining);
+
+        assert!(lease.check_access(None, true, at(5)).is_err());
+        assert!(matches!(
+            lease.renew(&lease_id, at(5)),
+            Err(AzureStorageError::LeaseNotPresent(_))
+        ));
+
+        assert!(lease.check_access(None, true, at(10)).is_ok());
+
+        let new_lease_id = lease
+            .acquire(LeaseDuration::Infinite, Some("proposed"), at(11))
+            .unwrap();
+        assert_eq!("proposed", new_lease_id);
+
+        // An infinite lease without a break period is broken immediately
+        assert_eq!(Duration::ZERO, lease.break_lease(None, at(12)).unwrap());
+        assert!(lease.check_access(None, true, at(12)).is_ok());
+    }
+
+    #[test]
+    fn test_change_and_release() {
+        let mut lease = LocalLease::new();
+
+        let lease_id = lease.acquire(LeaseDuration::Infinite, None, at(0)).unwrap();
+
+        assert!(matches!(
+            lease.change("wrong", "new", at(1)),
+            Err(AzureStorageError::LeaseIdMismatch(_))
+        ));
+        assert_eq!("new", lease.change(&lease_id, "new", at(1)).unwrap());
+
+        assert!(matches!(
+            lease.release(&lease_id, at(2)),
+            Err(AzureStorageError::LeaseIdMismatch(_))
+        ));
+        lease.release("new", at(2)).unwrap();
+        assert!(lease.is_available());
+    }
+}
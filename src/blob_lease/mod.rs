@@ -0,0 +1,9 @@
+mod api;
+mod blob_lease_lock;
+pub mod impl_for_azure_connection;
+pub(crate) mod local_lease;
+mod models;
+
+pub use api::BlobLeaseApi;
+pub use blob_lease_lock::{BlobLeaseGuard, BlobLeaseLock};
+pub use models::LeaseDuration;
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+/// Duration of an acquired lease. Azure accepts fixed leases of 15..=60 seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaseDuration {
+    Fixed(Duration),
+    Infinite,
+}
+
+impl LeaseDuration {
+    pub fn from_secs(secs: u64) -> Self {
+        Self::Fixed(Duration::from_secs(secs))
+    }
+
+    /// Azure takes infinite leases and fixed ones of 15..=60 seconds
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Fixed(duration) => (15..=60).contains(&duration.as_secs()),
+            Self::Infinite => true,
+        }
+    }
+
+    /// Value of the x-ms-lease-duration header
+    pub fn as_header_value(&self) -> String {
+        match self {
+            Self::Fixed(duration) => duration.as_secs().to_string(),
+            Self::Infinite => "-1".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_value() {
+        assert_eq!("15", LeaseDuration::from_secs(15).as_header_value());
+        assert_eq!("-1", LeaseDuration::Infinite.as_header_value());
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(LeaseDuration::from_secs(15).is_valid());
+        assert!(LeaseDuration::from_secs(60).is_valid());
+        assert!(LeaseDuration::Infinite.is_valid());
+        assert!(!LeaseDuration::from_secs(14).is_valid());
+        assert!(!LeaseDuration::from_secs(61).is_valid());
+    }
+}
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+
+use crate::{blob::BlobProperties, AzureStorageError};
+
+use super::DeleteSnapshotsOption;
+
+/// Read-only point-in-time copies of a blob. A snapshot is addressed by the id `create_blob_snapshot` returns
+#[async_trait]
+pub trait BlobSnapshotApi {
+    /// Returns the snapshot id (x-ms-snapshot), e.g. 2011-03-09T01:42:34.9360000Z
+    async fn create_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Snapshot ids of the blob, oldest first
+    async fn get_list_of_blob_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError>;
+
+    async fn get_blob_snapshot_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<BlobProperties, AzureStorageError>;
+
+    async fn download_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<Vec<u8>, AzureStorageError>;
+
+    /// Pages of a page blob snapshot
+    async fn get_blob_snapshot_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError>;
+
+    async fn delete_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<(), AzureStorageError>;
+
+    /// `delete_blob` of a blob which has snapshots fails with SnapshotsPresent - this is the way to delete it.
+    /// A leased blob is deleted with its lease id
+    async fn delete_blob_with_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        option: DeleteSnapshotsOption,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError>;
+}
@@ -0,0 +1,227 @@
+use crate::{
+    blob::BlobProperties, connection::AzureStorageConnection, storage_backend::BlobSnapshotBackend,
+    AzureStorageError,
+};
+
+use super::{api::BlobSnapshotApi, DeleteSnapshotsOption};
+
+use async_trait::async_trait;
+
+#[async_trait]
+impl BlobSnapshotApi for AzureStorageConnection {
+    async fn create_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError> {
+        self.get_storage_backend()
+            .create_blob_snapshot(container_name, blob_name)
+            .await
+    }
+
+    async fn get_list_of_blob_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError> {
+        self.get_storage_backend()
+            .get_list_of_blob_snapshots(container_name, blob_name)
+            .await
+    }
+
+    async fn get_blob_snapshot_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        self.get_storage_backend()
+            .get_blob_snapshot_properties(container_name, blob_name, snapshot)
+            .await
+    }
+
+    async fn download_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        self.get_storage_backend()
+            .download_blob_snapshot(container_name, blob_name, snapshot)
+            .await
+    }
+
+    async fn get_blob_snapshot_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        self.get_storage_backend()
+            .get_blob_snapshot_pages(
+                container_name,
+                blob_name,
+                snapshot,
+                start_page_no,
+                pages_amount,
+            )
+            .await
+    }
+
+    async fn delete_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .delete_blob_snapshot(container_name, blob_name, snapshot)
+            .await
+    }
+
+    async fn delete_blob_with_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        option: DeleteSnapshotsOption,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .delete_blob_with_snapshots(container_name, blob_name, option, lease_id)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blob::BlobApi, blob_container::BlobContainersApi, block_blob::BlockBlobApi,
+        page_blob::AzurePageBlobStorage,
+    };
+
+    #[tokio::test]
+    async fn test_snapshots_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-blob-snapshots";
+        const BLOB_NAME: &str = "test-blob-name";
+        const PAGE_BLOB_NAME: &str = "test-page-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        let result = connection
+            .create_blob_snapshot(CONTAINER_NAME, BLOB_NAME)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![1u8])
+            .await
+            .unwrap();
+
+        let props_before = connection
+            .get_blob_properties(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+
+        let snapshot = connection
+            .create_blob_snapshot(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![2u8])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![snapshot.clone()],
+            connection
+                .get_list_of_blob_snapshots(CONTAINER_NAME, BLOB_NAME)
+                .await
+                .unwrap()
+        );
+
+        assert_eq!(
+            vec![1u8],
+            connection
+                .download_blob_snapshot(CONTAINER_NAME, BLOB_NAME, snapshot.as_str())
+                .await
+                .unwrap()
+        );
+
+        let snapshot_props = connection
+            .get_blob_snapshot_properties(CONTAINER_NAME, BLOB_NAME, snapshot.as_str())
+            .await
+            .unwrap();
+        assert_eq!(props_before.etag, snapshot_props.etag);
+
+        // The snapshot folder is not a blob of the container
+        assert_eq!(
+            vec![BLOB_NAME.to_string()],
+            connection.get_list_of_blobs(CONTAINER_NAME).await.unwrap()
+        );
+
+        let result = connection.delete_blob(CONTAINER_NAME, BLOB_NAME).await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::SnapshotsPresent(_))
+        ));
+
+        connection
+            .delete_blob_with_snapshots(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                DeleteSnapshotsOption::Include,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(connection
+            .get_list_of_blob_snapshots(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let page_blob = AzurePageBlobStorage::new(
+            std::sync::Arc::new(AzureStorageConnection::from_conn_string(folder.as_str())),
+            CONTAINER_NAME,
+            PAGE_BLOB_NAME,
+        )
+        .await;
+
+        page_blob.create(2).await.unwrap();
+        page_blob.save_pages(1, vec![1u8; 512]).await.unwrap();
+
+        let snapshot = page_blob.create_snapshot().await.unwrap();
+
+        page_blob.save_pages(1, vec![2u8; 512]).await.unwrap();
+
+        assert_eq!(
+            vec![1u8; 512],
+            page_blob
+                .get_pages_at_snapshot(snapshot.as_str(), 1, 1)
+                .await
+                .unwrap()
+        );
+
+        connection
+            .delete_blob_snapshot(CONTAINER_NAME, PAGE_BLOB_NAME, snapshot.as_str())
+            .await
+            .unwrap();
+
+        page_blob.delete().await.unwrap();
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,6 @@
+mod api;
+pub mod impl_for_azure_connection;
+mod models;
+
+pub use api::BlobSnapshotApi;
+pub use models::DeleteSnapshotsOption;
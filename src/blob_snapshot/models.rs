@@ -0,0 +1,17 @@
+/// x-ms-delete-snapshots of Delete Blob. A blob which has snapshots can not be deleted without it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteSnapshotsOption {
+    /// The blob and all its snapshots
+    Include,
+    /// Snapshots only, the blob stays
+    Only,
+}
+
+impl DeleteSnapshotsOption {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Include => "include",
+            Self::Only => "only",
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+
+use crate::AzureStorageError;
+
+use super::BlobDownloadStream;
+
+/// Downloads which do not keep the whole blob in memory
+#[async_trait]
+pub trait BlobStreamApi {
+    /// Streams the blob in chunks of `DEFAULT_DOWNLOAD_CHUNK_SIZE`
+    async fn download_stream(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<BlobDownloadStream, AzureStorageError>;
+
+    /// Azure reads each chunk by a ranged request, pinned to the ETag the stream was opened at:
+    /// if the blob is overwritten in the middle of the download the stream fails with ConditionNotMet.
+    /// Fails with InvalidOptions if the chunk size is 0
+    async fn download_stream_with_chunk_size(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        chunk_size: usize,
+    ) -> Result<BlobDownloadStream, AzureStorageError>;
+}
@@ -0,0 +1,179 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use async_trait::async_trait;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::AzureStorageError;
+
+/// Chunk size of `BlobStreamApi::download_stream`
+pub const DEFAULT_DOWNLOAD_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Source of the chunks of a `BlobDownloadStream`. Backends implement it to stream the content of a blob
+#[async_trait]
+pub trait BlobChunkReader: Send {
+    /// None once the whole blob is read
+    async fn read_chunk(&mut self) -> Result<Option<Vec<u8>>, AzureStorageError>;
+}
+
+type PendingChunk = Pin<
+    Box<
+        dyn Future<
+                Output = (
+                    Box<dyn BlobChunkReader>,
+                    Result<Option<Vec<u8>>, AzureStorageError>,
+                ),
+            > + Send,
+    >,
+>;
+
+/// Content of a blob chunk by chunk. The next chunk is read only when the previous one is taken,
+/// so at most one chunk is kept in memory regardless of the blob size.
+/// Implements `tokio::io::AsyncRead` as well
+pub struct BlobDownloadStream {
+    blob_size: u64,
+    reader: Option<Box<dyn BlobChunkReader>>,
+    pending: Option<PendingChunk>,
+    chunk: Vec<u8>,
+    chunk_position: usize,
+}
+
+impl BlobDownloadStream {
+    pub fn new(blob_size: u64, reader: Box<dyn BlobChunkReader>) -> Self {
+        Self {
+            blob_size,
+            reader: Some(reader),
+            pending: None,
+            chunk: Vec::new(),
+            chunk_position: 0,
+        }
+    }
+
+    /// Size of the blob at the moment the stream was opened
+    pub fn get_blob_size(&self) -> u64 {
+        self.blob_size
+    }
+
+    /// None at the end of the blob. After an error the stream is over
+    pub async fn get_next(&mut self) -> Result<Option<Vec<u8>>, AzureStorageError> {
+        // What is left of a chunk partly read through AsyncRead
+        if self.chunk_position < self.chunk.len() {
+            let mut chunk = std::mem::take(&mut self.chunk);
+            let result = chunk.split_off(self.chunk_position);
+            self.chunk_position = 0;
+            return Ok(Some(result));
+        }
+
+        std::future::poll_fn(|cx| self.poll_next_chunk(cx)).await
+    }
+
+    /// Reads the rest of the blob into memory
+    pub async fn read_to_end(mut self) -> Result<Vec<u8>, AzureStorageError> {
+        let mut result = Vec::new();
+
+        while let Some(chunk) = self.get_next().await? {
+            result.extend_from_slice(chunk.as_slice());
+        }
+
+        Ok(result)
+    }
+
+    fn poll_next_chunk(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<Vec<u8>>, AzureStorageError>> {
+        if self.pending.is_none() {
+            let mut reader = match self.reader.take() {
+                Some(reader) => reader,
+                None => return Poll::Ready(Ok(None)),
+            };
+
+            self.pending = Some(Box::pin(async move {
+                let result = reader.read_chunk().await;
+                (reader, result)
+            }));
+        }
+
+        let (reader, result) = ready!(self.pending.as_mut().unwrap().as_mut().poll(cx));
+        self.pending = None;
+
+        if let Ok(Some(_)) = &result {
+            self.reader = Some(reader);
+        }
+
+        Poll::Ready(result)
+    }
+}
+
+impl AsyncRead for BlobDownloadStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let stream = self.get_mut();
+
+        while stream.chunk_position >= stream.chunk.len() {
+            match ready!(stream.poll_next_chunk(cx)) {
+                Ok(Some(chunk)) => {
+                    stream.chunk = chunk;
+                    stream.chunk_position = 0;
+                }
+                Ok(None) => return Poll::Ready(Ok(())),
+                Err(err) => return Poll::Ready(Err(std::io::Error::other(err))),
+            }
+        }
+
+        let to_copy = buf
+            .remaining()
+            .min(stream.chunk.len() - stream.chunk_position);
+
+        buf.put_slice(&stream.chunk[stream.chunk_position..stream.chunk_position + to_copy]);
+        stream.chunk_position += to_copy;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestChunkReader {
+        chunks: Vec<Vec<u8>>,
+    }
+
+    #[async_trait]
+    impl BlobChunkReader for TestChunkReader {
+        async fn read_chunk(&mut self) -> Result<Option<Vec<u8>>, AzureStorageError> {
+            if self.chunks.is_empty() {
+                return Ok(None);
+            }
+
+            Ok(Some(self.chunks.remove(0)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_read_and_chunks_can_be_mixed() {
+        let mut stream = BlobDownloadStream::new(
+            5,
+            Box::new(TestChunkReader {
+                chunks: vec![vec![1u8, 2u8, 3u8], vec![4u8, 5u8]],
+            }),
+        );
+
+        let mut buf = [0u8; 2];
+        let read = tokio::io::AsyncReadExt::read(&mut stream, &mut buf)
+            .await
+            .unwrap();
+        assert_eq!(2, read);
+        assert_eq!([1u8, 2u8], buf);
+
+        assert_eq!(Some(vec![3u8]), stream.get_next().await.unwrap());
+        assert_eq!(vec![4u8, 5u8], stream.read_to_end().await.unwrap());
+    }
+}
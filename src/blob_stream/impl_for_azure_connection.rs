@@ -0,0 +1,147 @@
+use crate::{
+    connection::AzureStorageConnection, storage_backend::BlobStreamBackend, AzureStorageError,
+};
+
+use super::{api::BlobStreamApi, BlobDownloadStream, DEFAULT_DOWNLOAD_CHUNK_SIZE};
+
+use async_trait::async_trait;
+
+#[async_trait]
+impl BlobStreamApi for AzureStorageConnection {
+    async fn download_stream(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<BlobDownloadStream, AzureStorageError> {
+        self.download_stream_with_chunk_size(container_name, blob_name, DEFAULT_DOWNLOAD_CHUNK_SIZE)
+            .await
+    }
+
+    async fn download_stream_with_chunk_size(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        chunk_size: usize,
+    ) -> Result<BlobDownloadStream, AzureStorageError> {
+        if chunk_size == 0 {
+            return Err(AzureStorageError::InvalidOptions {
+                msg: "Chunk size of a download stream must be greater than 0".to_string(),
+            });
+        }
+
+        self.get_storage_backend()
+            .open_blob_stream(container_name, blob_name, chunk_size)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{blob_container::BlobContainersApi, block_blob::BlockBlobApi};
+
+    #[tokio::test]
+    async fn test_download_stream_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-download-stream";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        let content: Vec<u8> = (0..100u8).collect();
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, content.clone())
+            .await
+            .unwrap();
+
+        let mut stream = connection
+            .download_stream_with_chunk_size(CONTAINER_NAME, BLOB_NAME, 30)
+            .await
+            .unwrap();
+
+        assert_eq!(100, stream.get_blob_size());
+
+        let mut chunks = Vec::new();
+        while let Some(chunk) = stream.get_next().await.unwrap() {
+            chunks.push(chunk);
+        }
+
+        assert_eq!(
+            vec![30, 30, 30, 10],
+            chunks.iter().map(|itm| itm.len()).collect::<Vec<_>>()
+        );
+        assert_eq!(content, chunks.concat());
+
+        let mut stream = connection
+            .download_stream(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+
+        let mut result = Vec::new();
+        tokio::io::AsyncReadExt::read_to_end(&mut stream, &mut result)
+            .await
+            .unwrap();
+        assert_eq!(content, result);
+
+        // A blob overwritten in the middle of the download fails the rest of it
+        let mut stream = connection
+            .download_stream_with_chunk_size(CONTAINER_NAME, BLOB_NAME, 30)
+            .await
+            .unwrap();
+
+        let mut buf = [0u8; 30];
+        tokio::io::AsyncReadExt::read_exact(&mut stream, &mut buf)
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![1u8; 100])
+            .await
+            .unwrap();
+
+        let err = tokio::io::AsyncReadExt::read_exact(&mut stream, &mut buf)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.into_inner()
+                .unwrap()
+                .downcast::<AzureStorageError>()
+                .as_deref(),
+            Ok(AzureStorageError::ConditionNotMet(_))
+        ));
+
+        let result = connection
+            .download_stream(CONTAINER_NAME, "not-exists")
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        let result = connection.download_stream("not-exists", BLOB_NAME).await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::ContainerNotFound(_))
+        ));
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zero_chunk_size_is_an_error() {
+        let connection = AzureStorageConnection::new_in_memory();
+
+        let result = connection
+            .download_stream_with_chunk_size("test", "blob", 0)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::InvalidOptions { .. })
+        ));
+    }
+}
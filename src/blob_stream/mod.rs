@@ -0,0 +1,6 @@
+mod api;
+mod blob_download_stream;
+pub mod impl_for_azure_connection;
+
+pub use api::BlobStreamApi;
+pub use blob_download_stream::{BlobChunkReader, BlobDownloadStream, DEFAULT_DOWNLOAD_CHUNK_SIZE};
@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+
+use crate::AzureStorageError;
+
+use super::TransferOptions;
+
+/// Transfers of large blobs by several requests at a time: uploads are put as blocks, downloads are read
+/// as ranges. Work the same way on every backend
+#[async_trait]
+pub trait BlobTransferApi {
+    /// Uploads the local file as a block blob. Returns ETag of the blob
+    async fn upload_file(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Fails with InvalidOptions if the chunk size or the concurrency is 0
+    async fn upload_file_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+        options: TransferOptions,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Downloads the blob into the local file, which is created or overwritten. Returns the size of the blob.
+    /// The blob is downloaded into `{file_path}.download`, which replaces the file once the download is
+    /// complete - a failed download leaves the file as it was
+    async fn download_to_file(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+    ) -> Result<u64, AzureStorageError>;
+
+    /// Fails with InvalidOptions if the chunk size or the concurrency is 0
+    async fn download_to_file_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+        options: TransferOptions,
+    ) -> Result<u64, AzureStorageError>;
+
+    /// Ranges are read in parallel and put together in order. A blob changed in the middle of the download
+    /// fails it with ConditionNotMet. Fails with InvalidOptions if the chunk size or the concurrency is 0
+    async fn download_in_parallel(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        options: TransferOptions,
+    ) -> Result<Vec<u8>, AzureStorageError>;
+}
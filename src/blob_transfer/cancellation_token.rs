@@ -0,0 +1,44 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use tokio::sync::Notify;
+
+/// Stops the transfers it is given to. Clones share the state, so one clone cancels them all
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationTokenInner>,
+}
+
+#[derive(Default)]
+struct CancellationTokenInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the token is cancelled
+    pub async fn cancelled(&self) {
+        let notified = self.inner.notify.notified();
+
+        if self.is_cancelled() {
+            return;
+        }
+
+        notified.await;
+    }
+}
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+
+use crate::{block_blob::BlockBlobApi, connection::AzureStorageConnection, AzureStorageError};
+
+use super::{api::BlobTransferApi, parallel_download, TransferOptions};
+
+#[async_trait]
+impl BlobTransferApi for AzureStorageConnection {
+    async fn upload_file(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+    ) -> Result<String, AzureStorageError> {
+        self.upload_file_with_options(container_name, blob_name, file_path, TransferOptions::new())
+            .await
+    }
+
+    async fn upload_file_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+        options: TransferOptions,
+    ) -> Result<String, AzureStorageError> {
+        options.check()?;
+
+        let file = tokio::fs::File::open(file_path).await?;
+
+        // The file is read block by block while the blocks read before are put
+        self.upload_from_reader_with_options(
+            container_name,
+            blob_name,
+            file,
+            options.to_upload_from_reader_options(),
+        )
+        .await
+    }
+
+    async fn download_to_file(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+    ) -> Result<u64, AzureStorageError> {
+        self.download_to_file_with_options(
+            container_name,
+            blob_name,
+            file_path,
+            TransferOptions::new(),
+        )
+        .await
+    }
+
+    async fn download_to_file_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+        options: TransferOptions,
+    ) -> Result<u64, AzureStorageError> {
+        options.check()?;
+
+        // The blob is downloaded next to the file and replaces it once it is complete,
+        // so a failed download leaves the file as it was
+        let download_path = get_download_path(file_path);
+
+        let mut file = tokio::fs::File::create(download_path.as_str()).await?;
+
+        let result = parallel_download::download(
+            self.get_storage_backend(),
+            container_name,
+            blob_name,
+            &mut file,
+            &options,
+        )
+        .await;
+
+        drop(file);
+
+        let result = match result {
+            Ok(size) => tokio::fs::rename(download_path.as_str(), file_path)
+                .await
+                .map(|_| size)
+                .map_err(AzureStorageError::IoError),
+            Err(err) => Err(err),
+        };
+
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(download_path.as_str()).await;
+        }
+
+        result
+    }
+
+    async fn download_in_parallel(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        options: TransferOptions,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let mut content = Vec::new();
+
+        parallel_download::download(
+            self.get_storage_backend(),
+            container_name,
+            blob_name,
+            &mut content,
+            &options,
+        )
+        .await?;
+
+        Ok(content)
+    }
+}
+
+fn get_download_path(file_path: &str) -> String {
+    format!("{}.download", file_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob_container::BlobContainersApi;
+
+    #[tokio::test]
+    async fn test_transfer_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-parallel-transfer";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        let content: Vec<u8> = (0..1000).map(|itm| (itm % 251) as u8).collect();
+
+        let src_file = format!("{}{}.src", folder, CONTAINER_NAME);
+        let dest_file = format!("{}{}.dest", folder, CONTAINER_NAME);
+
+        tokio::fs::write(src_file.as_str(), content.as_slice())
+            .await
+            .unwrap();
+
+        let options = TransferOptions::new()
+            .with_chunk_size(64)
+            .with_max_concurrency(4);
+
+        connection
+            .upload_file_with_options(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                src_file.as_str(),
+                options.clone(),
+            )
+            .await
+            .unwrap();
+
+        let size = connection
+            .download_to_file_with_options(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                dest_file.as_str(),
+                options.clone(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(1000, size);
+        assert_eq!(content, tokio::fs::read(dest_file.as_str()).await.unwrap());
+
+        let downloaded = connection
+            .download_in_parallel(CONTAINER_NAME, BLOB_NAME, options.clone())
+            .await
+            .unwrap();
+        assert_eq!(content, downloaded);
+
+        let result = connection
+            .download_to_file_with_options(
+                CONTAINER_NAME,
+                "not-exists",
+                dest_file.as_str(),
+                options,
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        // A failed download leaves the file as it was, and nothing next to it
+        assert_eq!(content, tokio::fs::read(dest_file.as_str()).await.unwrap());
+        assert!(
+            tokio::fs::metadata(get_download_path(dest_file.as_str()).as_str())
+                .await
+                .is_err()
+        );
+
+        tokio::fs::remove_file(src_file.as_str()).await.unwrap();
+        tokio::fs::remove_file(dest_file.as_str()).await.unwrap();
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zero_chunk_size_or_concurrency_is_an_error() {
+        let connection = AzureStorageConnection::new_in_memory();
+
+        for options in [
+            TransferOptions::new().with_chunk_size(0),
+            TransferOptions::new().with_max_concurrency(0),
+        ] {
+            let result = connection
+                .upload_file_with_options("test", "blob", "not-exists.src", options.clone())
+                .await;
+            assert!(matches!(
+                result,
+                Err(AzureStorageError::InvalidOptions { .. })
+            ));
+
+            let result = connection
+                .download_to_file_with_options("test", "blob", "not-exists.dest", options.clone())
+                .await;
+            assert!(matches!(
+                result,
+                Err(AzureStorageError::InvalidOptions { .. })
+            ));
+
+            let result = connection
+                .download_in_parallel("test", "blob", options)
+                .await;
+            assert!(matches!(
+                result,
+                Err(AzureStorageError::InvalidOptions { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,14 @@
+mod api;
+mod cancellation_token;
+pub mod impl_for_azure_connection;
+mod parallel_download;
+mod transfer_journal;
+mod transfer_manager;
+mod transfer_options;
+mod transfer_progress;
+
+pub use api::BlobTransferApi;
+pub use cancellation_token::CancellationToken;
+pub use transfer_manager::TransferManager;
+pub use transfer_options::TransferOptions;
+pub use transfer_progress::TransferProgress;
@@ -0,0 +1,122 @@
+use std::io::SeekFrom;
+
+use async_trait::async_trait;
+
+use crate::{in_flight::InFlight, storage_backend::StorageBackend, AzureStorageError};
+
+use super::TransferOptions;
+
+/// Where the ranges of a parallel download are written to, each at its offset
+#[async_trait]
+pub(crate) trait DownloadTarget: Send {
+    async fn set_len(&mut self, len: u64) -> Result<(), AzureStorageError>;
+
+    async fn write_chunk(&mut self, offset: u64, chunk: Vec<u8>) -> Result<(), AzureStorageError>;
+
+    async fn flush(&mut self) -> Result<(), AzureStorageError>;
+}
+
+#[async_trait]
+impl DownloadTarget for Vec<u8> {
+    async fn set_len(&mut self, len: u64) -> Result<(), AzureStorageError> {
+        self.resize(len as usize, 0);
+        Ok(())
+    }
+
+    async fn write_chunk(&mut self, offset: u64, chunk: Vec<u8>) -> Result<(), AzureStorageError> {
+        let offset = offset as usize;
+        self[offset..offset + chunk.len()].copy_from_slice(chunk.as_slice());
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), AzureStorageError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DownloadTarget for tokio::fs::File {
+    async fn set_len(&mut self, len: u64) -> Result<(), AzureStorageError> {
+        tokio::fs::File::set_len(self, len).await?;
+        Ok(())
+    }
+
+    async fn write_chunk(&mut self, offset: u64, chunk: Vec<u8>) -> Result<(), AzureStorageError> {
+        tokio::io::AsyncSeekExt::seek(self, SeekFrom::Start(offset)).await?;
+        tokio::io::AsyncWriteExt::write_all(self, chunk.as_slice()).await?;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), AzureStorageError> {
+        tokio::io::AsyncWriteExt::flush(self).await?;
+        Ok(())
+    }
+}
+
+/// The blob is read as ranges of `chunk_size`, `max_concurrency` at a time, and every range is written
+/// to the target as soon as it arrives. Returns the size of the blob.
+/// A blob changed in the middle of the download fails it with ConditionNotMet
+pub(crate) async fn download(
+    backend: &dyn StorageBackend,
+    container_name: &str,
+    blob_name: &str,
+    target: &mut impl DownloadTarget,
+    options: &TransferOptions,
+) -> Result<u64, AzureStorageError> {
+    options.check()?;
+
+    let chunk_size = options.chunk_size;
+    let max_concurrency = options.max_concurrency;
+
+    let properties = backend
+        .get_blob_properties(container_name, blob_name)
+        .await?;
+
+    let blob_size = properties.blob_size as u64;
+
+    target.set_len(blob_size).await?;
+
+    let mut next_offset = 0;
+    let mut in_flight = InFlight::new();
+
+    loop {
+        while next_offset < blob_size && in_flight.len() < max_concurrency {
+            let offset = next_offset;
+            let length = (chunk_size as u64).min(blob_size - offset);
+            next_offset += length;
+
+            in_flight.push(async move {
+                let chunk = backend
+                    .download_range(container_name, blob_name, offset, Some(length))
+                    .await?;
+
+                // The blob was cut after the download started
+                if chunk.len() as u64 != length {
+                    return Err(AzureStorageError::ConditionNotMet(None));
+                }
+
+                Ok((offset, chunk))
+            });
+        }
+
+        let (offset, chunk) = match std::future::poll_fn(|cx| in_flight.poll_next(cx)).await {
+            Some(result) => result?,
+            None => break,
+        };
+
+        target.write_chunk(offset, chunk).await?;
+    }
+
+    target.flush().await?;
+
+    // Ranges are read without conditions, so the ETag is checked once all of them are read
+    let properties_after = backend
+        .get_blob_properties(container_name, blob_name)
+        .await?;
+
+    if properties_after.etag != properties.etag {
+        return Err(AzureStorageError::ConditionNotMet(None));
+    }
+
+    Ok(blob_size)
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeSet;
+
+use crate::AzureStorageError;
+
+/// Chunks a transfer has completed, kept in a small file so a restarted transfer skips them.
+/// The first line identifies the transfer, the second is its id, then every completed chunk appends
+/// a line with its number. A journal of another transfer is started over
+pub(crate) struct TransferJournal {
+    file_path: String,
+    key: String,
+    transfer_id: String,
+    completed: BTreeSet<u64>,
+    file: tokio::fs::File,
+}
+
+impl TransferJournal {
+    pub async fn open(file_path: &str, key: &str) -> Result<Self, AzureStorageError> {
+        if let Ok(content) = tokio::fs::read_to_string(file_path).await {
+            let mut lines = content.lines();
+
+            if lines.next() == Some(key) {
+                if let Some(transfer_id) = lines.next() {
+                    // A line cut by a crash does not parse and is skipped
+                    let completed = lines.filter_map(|line| line.parse().ok()).collect();
+
+                    let file = tokio::fs::OpenOptions::new()
+                        .append(true)
+                        .open(file_path)
+                        .await?;
+
+                    return Ok(Self {
+                        file_path: file_path.to_string(),
+                        key: key.to_string(),
+                        transfer_id: transfer_id.to_string(),
+                        completed,
+                        file,
+                    });
+                }
+            }
+        }
+
+        Self::create(file_path, key).await
+    }
+
+    async fn create(file_path: &str, key: &str) -> Result<Self, AzureStorageError> {
+        let transfer_id = uuid::Uuid::new_v4().simple().to_string();
+
+        let mut file = tokio::fs::File::create(file_path).await?;
+
+        tokio::io::AsyncWriteExt::write_all(
+            &mut file,
+            format!("{}\n{}\n", key, transfer_id).as_bytes(),
+        )
+        .await?;
+        tokio::io::AsyncWriteExt::flush(&mut file).await?;
+
+        Ok(Self {
+            file_path: file_path.to_string(),
+            key: key.to_string(),
+            transfer_id,
+            completed: BTreeSet::new(),
+            file,
+        })
+    }
+
+    /// Forgets the completed chunks. The transfer gets a new id
+    pub async fn reset(&mut self) -> Result<(), AzureStorageError> {
+        *self = Self::create(self.file_path.as_str(), self.key.as_str()).await?;
+        Ok(())
+    }
+
+    /// Stays the same while the transfer is resumed
+    pub fn get_transfer_id(&self) -> &str {
+        self.transfer_id.as_str()
+    }
+
+    pub fn get_completed(&self) -> &BTreeSet<u64> {
+        &self.completed
+    }
+
+    pub fn is_completed(&self, chunk_no: u64) -> bool {
+        self.completed.contains(&chunk_no)
+    }
+
+    /// Chunks which turn out to be lost are transferred again
+    pub fn retain(&mut self, f: impl FnMut(&u64) -> bool) {
+        self.completed.retain(f);
+    }
+
+    pub async fn complete(&mut self, chunk_no: u64) -> Result<(), AzureStorageError> {
+        tokio::io::AsyncWriteExt::write_all(&mut self.file, format!("{}\n", chunk_no).as_bytes())
+            .await?;
+        tokio::io::AsyncWriteExt::flush(&mut self.file).await?;
+
+        self.completed.insert(chunk_no);
+
+        Ok(())
+    }
+
+    /// The transfer is over - nothing to resume
+    pub async fn delete(self) {
+        drop(self.file);
+        let _ = tokio::fs::remove_file(self.file_path.as_str()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_journal_is_resumed_for_the_same_transfer_only() {
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+        tokio::fs::create_dir_all(folder.as_str()).await.unwrap();
+
+        let file_path = format!("{}test-transfer-journal", folder);
+
+        let mut journal = TransferJournal::open(file_path.as_str(), "upload a/b 10 4")
+            .await
+            .unwrap();
+        journal.complete(0).await.unwrap();
+        journal.complete(2).await.unwrap();
+
+        let transfer_id = journal.get_transfer_id().to_string();
+        drop(journal);
+
+        let journal = TransferJournal::open(file_path.as_str(), "upload a/b 10 4")
+            .await
+            .unwrap();
+        assert_eq!(transfer_id, journal.get_transfer_id());
+        assert!(journal.is_completed(0));
+        assert!(!journal.is_completed(1));
+        assert!(journal.is_completed(2));
+        drop(journal);
+
+        let journal = TransferJournal::open(file_path.as_str(), "upload a/b 12 4")
+            .await
+            .unwrap();
+        assert_ne!(transfer_id, journal.get_transfer_id());
+        assert!(journal.get_completed().is_empty());
+
+        journal.delete().await;
+        assert!(tokio::fs::metadata(file_path.as_str()).await.is_err());
+    }
+}
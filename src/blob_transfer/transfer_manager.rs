@@ -0,0 +1,881 @@
+use std::{
+    collections::HashSet,
+    future::Future,
+    io::SeekFrom,
+    pin::{pin, Pin},
+    sync::Arc,
+    task::{Context, Poll},
+    time::UNIX_EPOCH,
+};
+
+use base64::Engine;
+use md5::{Digest, Md5};
+use tokio::sync::watch;
+
+use crate::{
+    block_blob::{generate_block_id, BlockListItem, BlockListType, MAX_COMMITTED_BLOCKS},
+    connection::AzureStorageConnection,
+    in_flight::InFlight,
+    AzureStorageError, StorageError,
+};
+
+use super::{
+    transfer_journal::TransferJournal, CancellationToken, TransferOptions, TransferProgress,
+};
+
+/// Uploads and downloads local files chunk by chunk, and keeps the chunks it has completed in a journal
+/// next to the file (`{file_path}.transfer-journal`). A transfer stopped by an error, a cancellation or
+/// a restart of the process resumes where it stopped once it is started again with the same chunk size.
+/// Uploads put every block with its MD5, which the service checks, then check the committed block list
+/// and commit the blob with the MD5 of the file. Downloads check the file against the MD5 of the blob
+pub struct TransferManager {
+    connection: Arc<AzureStorageConnection>,
+    options: TransferOptions,
+    cancellation_token: CancellationToken,
+    progress: watch::Sender<TransferProgress>,
+}
+
+impl TransferManager {
+    pub fn new(connection: Arc<AzureStorageConnection>) -> Self {
+        let (progress, _) = watch::channel(TransferProgress::default());
+
+        Self {
+            connection,
+            options: TransferOptions::new(),
+            cancellation_token: CancellationToken::new(),
+            progress,
+        }
+    }
+
+    pub fn with_options(mut self, options: TransferOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Cancelled transfers fail with TransferCancelled and keep their journal
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    /// Progress of the current transfer, updated after every chunk
+    pub fn subscribe(&self) -> watch::Receiver<TransferProgress> {
+        self.progress.subscribe()
+    }
+
+    /// Returns the ETag of the committed blob. The blocks are put under ids derived from the id
+    /// of the journal, so the blocks a restarted upload finds staged are not put again.
+    /// A file changed since the journal was started is uploaded from the start
+    pub async fn upload_file(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+    ) -> Result<String, AzureStorageError> {
+        let (chunk_size, max_concurrency) = self.get_chunk_size_and_concurrency()?;
+        let backend = self.connection.get_storage_backend();
+
+        let mut file = tokio::fs::File::open(file_path).await?;
+        let file_metadata = file.metadata().await?;
+        let file_size = file_metadata.len();
+
+        let chunk_count = file_size.div_ceil(chunk_size);
+
+        if chunk_count > MAX_COMMITTED_BLOCKS as u64 {
+            return Err(AzureStorageError::BlockListTooLong(None));
+        }
+
+        let key = get_upload_key(container_name, blob_name, &file_metadata, chunk_size);
+
+        let mut journal =
+            TransferJournal::open(get_journal_path(file_path).as_str(), key.as_str()).await?;
+
+        let upload_id = journal.get_transfer_id().to_string();
+
+        // Blocks put before the restart are gone if the blob was committed or written over since
+        if !journal.get_completed().is_empty() {
+            let staged: HashSet<String> = match backend
+                .get_block_list(container_name, blob_name, BlockListType::Uncommitted)
+                .await
+            {
+                Ok(block_list) => block_list
+                    .uncommitted
+                    .into_iter()
+                    .map(|itm| itm.block_id)
+                    .collect(),
+                Err(err) if err.is_not_found() => HashSet::new(),
+                Err(err) => return Err(err),
+            };
+
+            journal.retain(|chunk_no| {
+                staged.contains(&generate_block_id(upload_id.as_str(), *chunk_no as usize))
+            });
+        }
+
+        let mut progress = get_initial_progress(&journal, chunk_size, file_size);
+        self.progress.send_replace(progress);
+
+        let lease_id = self.options.upload_options.conditions.lease_id.as_deref();
+
+        let mut md5 = Md5::new();
+        let mut in_flight = InFlight::new();
+        let mut completed = Vec::new();
+        let mut cancelled = pin!(self.cancellation_token.cancelled());
+
+        for chunk_no in 0..chunk_count {
+            std::future::poll_fn(|cx| {
+                if let Err(err) =
+                    poll_in_flight(cancelled.as_mut(), &mut in_flight, &mut completed, cx)
+                {
+                    return Poll::Ready(Err(err));
+                }
+
+                if in_flight.len() < max_concurrency {
+                    Poll::Ready(Ok(()))
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await?;
+
+            // The puts in flight go on while the chunk is read
+            let mut read = pin!(read_chunk(
+                &mut file,
+                get_chunk_len(chunk_no, chunk_size, file_size)
+            ));
+
+            let chunk = std::future::poll_fn(|cx| {
+                if let Err(err) =
+                    poll_in_flight(cancelled.as_mut(), &mut in_flight, &mut completed, cx)
+                {
+                    return Poll::Ready(Err(err));
+                }
+
+                read.as_mut().poll(cx)
+            })
+            .await?;
+
+            self.complete_chunks(&mut journal, &mut completed, chunk_size, &mut progress)
+                .await?;
+
+            // Every chunk is read for the MD5, but the ones put before the restart are not put again
+            md5.update(chunk.as_slice());
+
+            if journal.is_completed(chunk_no) {
+                continue;
+            }
+
+            let block_id = generate_block_id(upload_id.as_str(), chunk_no as usize);
+
+            in_flight.push(async move {
+                backend
+                    .put_block(
+                        container_name,
+                        blob_name,
+                        block_id.as_str(),
+                        chunk,
+                        lease_id,
+                    )
+                    .await?;
+
+                Ok(chunk_no)
+            });
+        }
+
+        std::future::poll_fn(|cx| {
+            if let Err(err) = poll_in_flight(cancelled.as_mut(), &mut in_flight, &mut completed, cx)
+            {
+                return Poll::Ready(Err(err));
+            }
+
+            if in_flight.is_empty() {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await?;
+
+        self.complete_chunks(&mut journal, &mut completed, chunk_size, &mut progress)
+            .await?;
+
+        let content_md5 = base64::engine::general_purpose::STANDARD.encode(md5.finalize());
+
+        let block_ids: Vec<String> = (0..chunk_count)
+            .map(|chunk_no| generate_block_id(upload_id.as_str(), chunk_no as usize))
+            .collect();
+
+        let blocks = block_ids
+            .iter()
+            .map(|block_id| BlockListItem::Uncommitted(block_id.clone()))
+            .collect();
+
+        // The service stores x-ms-blob-content-md5 as it is sent, without checking it against the blocks
+        let mut upload_options = self.options.upload_options.clone();
+        upload_options.http_headers.content_md5 = Some(content_md5);
+
+        let etag = backend
+            .put_block_list(container_name, blob_name, blocks, upload_options)
+            .await?;
+
+        // The blocks are committed - there is nothing to resume
+        journal.delete().await;
+
+        // Every block was checked against its MD5 when it was put; the blob must be made of them in the order of the file
+        let block_list = backend
+            .get_block_list(container_name, blob_name, BlockListType::Committed)
+            .await?;
+
+        let is_made_of_the_file = block_list.committed.len() == block_ids.len()
+            && block_list
+                .committed
+                .iter()
+                .zip(block_ids.iter())
+                .enumerate()
+                .all(|(chunk_no, (block, block_id))| {
+                    block.block_id == *block_id
+                        && block.size as u64
+                            == get_chunk_len(chunk_no as u64, chunk_size, file_size)
+                });
+
+        if !is_made_of_the_file {
+            return Err(AzureStorageError::TransferVerificationFailed);
+        }
+
+        Ok(etag)
+    }
+
+    /// Returns the size of the blob. A download is resumed only while the blob keeps its ETag,
+    /// and the MD5 of the file is checked if the blob has one
+    pub async fn download_file(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        file_path: &str,
+    ) -> Result<u64, AzureStorageError> {
+        let (chunk_size, max_concurrency) = self.get_chunk_size_and_concurrency()?;
+        let backend = self.connection.get_storage_backend();
+
+        let properties = backend
+            .get_blob_properties(container_name, blob_name)
+            .await?;
+
+        let blob_size = properties.blob_size as u64;
+        let chunk_count = blob_size.div_ceil(chunk_size);
+
+        let key = format!(
+            "download {}/{} {} {} {}",
+            container_name,
+            blob_name,
+            blob_size,
+            chunk_size,
+            properties.etag.as_deref().unwrap_or_default()
+        );
+
+        let mut journal =
+            TransferJournal::open(get_journal_path(file_path).as_str(), key.as_str()).await?;
+
+        let mut file = if journal.get_completed().is_empty() {
+            tokio::fs::File::create(file_path).await?
+        } else {
+            match tokio::fs::OpenOptions::new()
+                .write(true)
+                .open(file_path)
+                .await
+            {
+                Ok(file) => file,
+                // The chunks downloaded before the restart are gone together with the file
+                Err(_) => {
+                    journal.reset().await?;
+                    tokio::fs::File::create(file_path).await?
+                }
+            }
+        };
+
+        file.set_len(blob_size).await?;
+
+        let mut progress = get_initial_progress(&journal, chunk_size, blob_size);
+        self.progress.send_replace(progress);
+
+        let chunks_to_download: Vec<u64> = (0..chunk_count)
+            .filter(|chunk_no| !journal.is_completed(*chunk_no))
+            .collect();
+
+        let mut in_flight = InFlight::new();
+        let mut completed = Vec::new();
+        let mut cancelled = pin!(self.cancellation_token.cancelled());
+
+        for chunk_no in chunks_to_download {
+            std::future::poll_fn(|cx| {
+                if let Err(err) =
+                    poll_in_flight(cancelled.as_mut(), &mut in_flight, &mut completed, cx)
+                {
+                    return Poll::Ready(Err(err));
+                }
+
+                if in_flight.len() < max_concurrency {
+                    Poll::Ready(Ok(()))
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await?;
+
+            self.write_chunks(
+                &mut file,
+                &mut journal,
+                &mut completed,
+                chunk_size,
+                &mut progress,
+            )
+            .await?;
+
+            let offset = chunk_no * chunk_size;
+            let length = get_chunk_len(chunk_no, chunk_size, blob_size);
+
+            in_flight.push(async move {
+                let chunk = backend
+                    .download_range(container_name, blob_name, offset, Some(length))
+                    .await?;
+
+                // The blob was cut after the download started
+                if chunk.len() as u64 != length {
+                    return Err(AzureStorageError::ConditionNotMet(None));
+                }
+
+                Ok((chunk_no, chunk))
+            });
+        }
+
+        std::future::poll_fn(|cx| {
+            if let Err(err) = poll_in_flight(cancelled.as_mut(), &mut in_flight, &mut completed, cx)
+            {
+                return Poll::Ready(Err(err));
+            }
+
+            if in_flight.is_empty() {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await?;
+
+        self.write_chunks(
+            &mut file,
+            &mut journal,
+            &mut completed,
+            chunk_size,
+            &mut progress,
+        )
+        .await?;
+
+        drop(file);
+
+        let properties_after = backend
+            .get_blob_properties(container_name, blob_name)
+            .await?;
+
+        // Chunks of two versions of the blob are not worth resuming
+        journal.delete().await;
+
+        if properties_after.etag != properties.etag {
+            return Err(AzureStorageError::ConditionNotMet(None));
+        }
+
+        if tokio::fs::metadata(file_path).await?.len() != blob_size {
+            return Err(AzureStorageError::TransferVerificationFailed);
+        }
+
+        if let Some(content_md5) = properties.content_md5 {
+            if get_file_md5(file_path).await? != content_md5 {
+                return Err(AzureStorageError::TransferVerificationFailed);
+            }
+        }
+
+        Ok(blob_size)
+    }
+
+    fn get_chunk_size_and_concurrency(&self) -> Result<(u64, usize), AzureStorageError> {
+        self.options.check()?;
+        Ok((self.options.chunk_size as u64, self.options.max_concurrency))
+    }
+
+    async fn complete_chunks(
+        &self,
+        journal: &mut TransferJournal,
+        completed: &mut Vec<u64>,
+        chunk_size: u64,
+        progress: &mut TransferProgress,
+    ) -> Result<(), AzureStorageError> {
+        for chunk_no in completed.drain(..) {
+            journal.complete(chunk_no).await?;
+
+            progress.bytes_transferred += get_chunk_len(chunk_no, chunk_size, progress.total_bytes);
+            self.progress.send_replace(*progress);
+        }
+
+        Ok(())
+    }
+
+    /// A chunk is in the journal only once it is written to the file
+    async fn write_chunks(
+        &self,
+        file: &mut tokio::fs::File,
+        journal: &mut TransferJournal,
+        completed: &mut Vec<(u64, Vec<u8>)>,
+        chunk_size: u64,
+        progress: &mut TransferProgress,
+    ) -> Result<(), AzureStorageError> {
+        for (chunk_no, chunk) in completed.drain(..) {
+            tokio::io::AsyncSeekExt::seek(file, SeekFrom::Start(chunk_no * chunk_size)).await?;
+            tokio::io::AsyncWriteExt::write_all(file, chunk.as_slice()).await?;
+            tokio::io::AsyncWriteExt::flush(file).await?;
+
+            journal.complete(chunk_no).await?;
+
+            progress.bytes_transferred += chunk.len() as u64;
+            self.progress.send_replace(*progress);
+        }
+
+        Ok(())
+    }
+}
+
+fn get_journal_path(file_path: &str) -> String {
+    format!("{}.transfer-journal", file_path)
+}
+
+/// A file modified since the journal was started gets another key, so its upload starts over
+fn get_upload_key(
+    container_name: &str,
+    blob_name: &str,
+    file_metadata: &std::fs::Metadata,
+    chunk_size: u64,
+) -> String {
+    let modified = file_metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|modified| modified.as_nanos())
+        .unwrap_or_default();
+
+    format!(
+        "upload {}/{} {} {} {}",
+        container_name,
+        blob_name,
+        file_metadata.len(),
+        modified,
+        chunk_size
+    )
+}
+
+fn get_chunk_len(chunk_no: u64, chunk_size: u64, total_size: u64) -> u64 {
+    chunk_size.min(total_size - chunk_no * chunk_size)
+}
+
+/// Chunks completed before the restart count as transferred
+fn get_initial_progress(
+    journal: &TransferJournal,
+    chunk_size: u64,
+    total_bytes: u64,
+) -> TransferProgress {
+    TransferProgress {
+        bytes_transferred: journal
+            .get_completed()
+            .iter()
+            .map(|chunk_no| get_chunk_len(*chunk_no, chunk_size, total_bytes))
+            .sum(),
+        total_bytes,
+    }
+}
+
+/// Drives every request in flight and collects the completed ones.
+/// Fails with the first request which fails, or once the transfer is cancelled
+fn poll_in_flight<T>(
+    cancelled: Pin<&mut impl Future<Output = ()>>,
+    in_flight: &mut InFlight<T>,
+    completed: &mut Vec<T>,
+    cx: &mut Context<'_>,
+) -> Result<(), AzureStorageError> {
+    if cancelled.poll(cx).is_ready() {
+        return Err(AzureStorageError::TransferCancelled);
+    }
+
+    match in_flight.poll_all(cx, |value| completed.push(value)) {
+        Poll::Ready(Err(err)) => Err(err),
+        _ => Ok(()),
+    }
+}
+
+async fn read_chunk(file: &mut tokio::fs::File, len: u64) -> Result<Vec<u8>, AzureStorageError> {
+    let mut chunk = vec![0; len as usize];
+    tokio::io::AsyncReadExt::read_exact(file, chunk.as_mut_slice()).await?;
+    Ok(chunk)
+}
+
+async fn get_file_md5(file_path: &str) -> Result<String, AzureStorageError> {
+    let mut file = tokio::fs::File::open(file_path).await?;
+
+    let mut md5 = Md5::new();
+    let mut buffer = vec![0; 1024 * 1024];
+
+    loop {
+        let read = tokio::io::AsyncReadExt::read(&mut file, buffer.as_mut_slice()).await?;
+
+        if read == 0 {
+            break;
+        }
+
+        md5.update(&buffer[..read]);
+    }
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(md5.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        blob::BlobHttpHeaders,
+        blob_container::BlobContainersApi,
+        block_blob::{BlockBlobApi, UploadBlockBlobOptions},
+        sdk_azure::http_transport::{
+            get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+        },
+    };
+
+    fn compile_committed_blocks(blocks: &[(&str, usize)]) -> String {
+        let mut result =
+            String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?><BlockList><CommittedBlocks>");
+
+        for (block_id, size) in blocks {
+            result.push_str(
+                format!(
+                    "<Block><Name>{}</Name><Size>{}</Size></Block>",
+                    block_id, size
+                )
+                .as_str(),
+            );
+        }
+
+        result.push_str("</CommittedBlocks></BlockList>");
+        result
+    }
+
+    async fn cancel_after_first_chunk(
+        mut progress: watch::Receiver<TransferProgress>,
+        cancellation_token: CancellationToken,
+    ) {
+        while progress.changed().await.is_ok() {
+            if progress.borrow().bytes_transferred > 0 {
+                cancellation_token.cancel();
+                return;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_transfers_are_resumed() {
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+        tokio::fs::create_dir_all(folder.as_str()).await.unwrap();
+
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        let content: Vec<u8> = (0..1000).map(|itm| (itm % 251) as u8).collect();
+
+        let src_file = format!("{}test-resumable-transfer.src", folder);
+        let dest_file = format!("{}test-resumable-transfer.dest", folder);
+
+        tokio::fs::write(src_file.as_str(), content.as_slice())
+            .await
+            .unwrap();
+
+        let options = TransferOptions::new()
+            .with_chunk_size(64)
+            .with_max_concurrency(2);
+
+        let cancellation_token = CancellationToken::new();
+
+        let transfer_manager = TransferManager::new(connection.clone())
+            .with_options(options.clone())
+            .with_cancellation_token(cancellation_token.clone());
+
+        let (result, _) = tokio::join!(
+            transfer_manager.upload_file("test", "blob", src_file.as_str()),
+            cancel_after_first_chunk(transfer_manager.subscribe(), cancellation_token)
+        );
+        assert!(matches!(result, Err(AzureStorageError::TransferCancelled)));
+        assert!(tokio::fs::metadata(get_journal_path(src_file.as_str()))
+            .await
+            .is_ok());
+
+        let staged = connection
+            .get_block_list("test", "blob", BlockListType::Uncommitted)
+            .await
+            .unwrap();
+        assert!(!staged.uncommitted.is_empty());
+        assert!(staged.uncommitted.len() < 16);
+
+        // Resumed by a manager without the cancelled token
+        let transfer_manager = TransferManager::new(connection.clone()).with_options(options);
+        let progress = transfer_manager.subscribe();
+
+        transfer_manager
+            .upload_file("test", "blob", src_file.as_str())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            TransferProgress {
+                bytes_transferred: 1000,
+                total_bytes: 1000
+            },
+            *progress.borrow()
+        );
+        assert!(tokio::fs::metadata(get_journal_path(src_file.as_str()))
+            .await
+            .is_err());
+
+        let block_list = connection
+            .get_block_list("test", "blob", BlockListType::All)
+            .await
+            .unwrap();
+        assert_eq!(16, block_list.committed.len());
+        assert!(block_list.uncommitted.is_empty());
+
+        let properties = connection
+            .get_storage_backend()
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+        assert_eq!(
+            get_file_md5(src_file.as_str()).await.ok(),
+            properties.content_md5
+        );
+
+        let cancellation_token = CancellationToken::new();
+
+        let transfer_manager = TransferManager::new(connection.clone())
+            .with_options(options.clone())
+            .with_cancellation_token(cancellation_token.clone());
+
+        let (result, _) = tokio::join!(
+            transfer_manager.download_file("test", "blob", dest_file.as_str()),
+            cancel_after_first_chunk(transfer_manager.subscribe(), cancellation_token)
+        );
+        assert!(matches!(result, Err(AzureStorageError::TransferCancelled)));
+
+        let transfer_manager = TransferManager::new(connection.clone()).with_options(options);
+
+        let size = transfer_manager
+            .download_file("test", "blob", dest_file.as_str())
+            .await
+            .unwrap();
+
+        assert_eq!(1000, size);
+        assert_eq!(content, tokio::fs::read(dest_file.as_str()).await.unwrap());
+        assert!(tokio::fs::metadata(get_journal_path(dest_file.as_str()))
+            .await
+            .is_err());
+
+        tokio::fs::remove_file(src_file).await.unwrap();
+        tokio::fs::remove_file(dest_file).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_starts_over_once_staged_blocks_are_gone_from_azure() {
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+        tokio::fs::create_dir_all(folder.as_str()).await.unwrap();
+
+        let src_file = format!("{}test-resumable-upload-restart.src", folder);
+        let content: Vec<u8> = (0..100).map(|itm| itm as u8).collect();
+
+        tokio::fs::write(src_file.as_str(), content.as_slice())
+            .await
+            .unwrap();
+
+        let transport = Arc::new(ScriptedTransport::new());
+        let connection = Arc::new(AzureStorageConnection::AzureStorage(
+            get_scripted_connection(&transport),
+        ));
+
+        let transfer_manager = TransferManager::new(connection).with_options(
+            TransferOptions::new()
+                .with_chunk_size(64)
+                .with_max_concurrency(1),
+        );
+
+        // The first block is put, the second one fails - the journal keeps the first one
+        transport.push_response(AzureHttpResponse::new(201));
+        transport.push_response(AzureHttpResponse::new(500));
+
+        let result = transfer_manager
+            .upload_file("test", "blob", src_file.as_str())
+            .await;
+        assert!(result.is_err());
+
+        let block_ids: Vec<String> = transport
+            .get_requests()
+            .iter()
+            .map(|itm| itm.get_query_param("blockid").unwrap().to_string())
+            .collect();
+
+        // The staged blocks are garbage-collected together with the blob
+        transport.push_response(
+            AzureHttpResponse::new(404).with_header("x-ms-error-code", "BlobNotFound"),
+        );
+        transport.push_response(AzureHttpResponse::new(201));
+        transport.push_response(AzureHttpResponse::new(201));
+        transport.push_response(AzureHttpResponse::new(201).with_header("etag", "\"0x1\""));
+        transport.push_response(
+            AzureHttpResponse::new(200).with_body(compile_committed_blocks(&[
+                (block_ids[0].as_str(), 64),
+                (block_ids[1].as_str(), 36),
+            ])),
+        );
+
+        let etag = transfer_manager
+            .upload_file("test", "blob", src_file.as_str())
+            .await
+            .unwrap();
+        assert_eq!("\"0x1\"", etag);
+
+        let requests = transport.get_requests();
+        assert_eq!(7, requests.len());
+        assert_eq!(Some("blocklist"), requests[2].get_query_param("comp"));
+        assert_eq!(Some("block"), requests[3].get_query_param("comp"));
+        assert_eq!(
+            requests[0].get_query_param("blockid"),
+            requests[3].get_query_param("blockid")
+        );
+        assert_eq!(Some("block"), requests[4].get_query_param("comp"));
+        assert!(requests[4].get_header("Content-MD5").is_some());
+
+        tokio::fs::remove_file(src_file).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_of_misplaced_blocks_fails_verification() {
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+        tokio::fs::create_dir_all(folder.as_str()).await.unwrap();
+
+        let src_file = format!("{}test-upload-verification.src", folder);
+        tokio::fs::write(src_file.as_str(), vec![7u8; 100])
+            .await
+            .unwrap();
+
+        // The journal is started ahead to know the ids of the blocks
+        let file_metadata = tokio::fs::metadata(src_file.as_str()).await.unwrap();
+        let journal = TransferJournal::open(
+            get_journal_path(src_file.as_str()).as_str(),
+            get_upload_key("test", "blob", &file_metadata, 64).as_str(),
+        )
+        .await
+        .unwrap();
+
+        let first = generate_block_id(journal.get_transfer_id(), 0);
+        let second = generate_block_id(journal.get_transfer_id(), 1);
+        drop(journal);
+
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(201));
+        transport.push_response(AzureHttpResponse::new(201));
+        transport.push_response(AzureHttpResponse::new(201).with_header("etag", "\"0x1\""));
+        transport.push_response(
+            AzureHttpResponse::new(200).with_body(compile_committed_blocks(&[
+                (second.as_str(), 36),
+                (first.as_str(), 64),
+            ])),
+        );
+
+        let connection = Arc::new(AzureStorageConnection::AzureStorage(
+            get_scripted_connection(&transport),
+        ));
+
+        let result = TransferManager::new(connection)
+            .with_options(
+                TransferOptions::new()
+                    .with_chunk_size(64)
+                    .with_max_concurrency(1),
+            )
+            .upload_file("test", "blob", src_file.as_str())
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::TransferVerificationFailed)
+        ));
+
+        tokio::fs::remove_file(src_file).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zero_chunk_size_or_concurrency_is_an_error() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        for options in [
+            TransferOptions::new().with_chunk_size(0),
+            TransferOptions::new().with_max_concurrency(0),
+        ] {
+            let transfer_manager = TransferManager::new(connection.clone()).with_options(options);
+
+            let result = transfer_manager
+                .upload_file("test", "blob", "not-exists.src")
+                .await;
+            assert!(matches!(
+                result,
+                Err(AzureStorageError::InvalidOptions { .. })
+            ));
+
+            let result = transfer_manager
+                .download_file("test", "blob", "not-exists.dest")
+                .await;
+            assert!(matches!(
+                result,
+                Err(AzureStorageError::InvalidOptions { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_with_other_md5_fails_verification() {
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+        tokio::fs::create_dir_all(folder.as_str()).await.unwrap();
+
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob_with_options(
+                "test",
+                "blob",
+                vec![1u8, 2, 3],
+                UploadBlockBlobOptions::new().with_http_headers(
+                    BlobHttpHeaders::new().with_content_md5("Q2hlY2sgSW50ZWdyaXR5IQ=="),
+                ),
+            )
+            .await
+            .unwrap();
+
+        let dest_file = format!("{}test-transfer-verification.dest", folder);
+
+        let result = TransferManager::new(connection)
+            .download_file("test", "blob", dest_file.as_str())
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::TransferVerificationFailed)
+        ));
+
+        tokio::fs::remove_file(dest_file).await.unwrap();
+    }
+}
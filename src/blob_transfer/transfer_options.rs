@@ -0,0 +1,67 @@
+use crate::{
+    block_blob::{UploadBlockBlobOptions, UploadFromReaderOptions},
+    AzureStorageError,
+};
+
+/// Settings of a parallel transfer: uploads are put as blocks of `chunk_size`, downloads are read as ranges
+/// of `chunk_size`, by up to `max_concurrency` requests at a time. At most `chunk_size * max_concurrency`
+/// bytes of the blob are kept in memory
+#[derive(Debug, Clone)]
+pub struct TransferOptions {
+    pub chunk_size: usize,
+    pub max_concurrency: usize,
+    /// HTTP headers, metadata and access conditions an upload commits the blob with. Not used by downloads
+    pub upload_options: UploadBlockBlobOptions,
+}
+
+impl TransferOptions {
+    pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+    pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    pub fn with_upload_options(mut self, upload_options: UploadBlockBlobOptions) -> Self {
+        self.upload_options = upload_options;
+        self
+    }
+
+    pub(crate) fn check(&self) -> Result<(), AzureStorageError> {
+        if self.chunk_size == 0 || self.max_concurrency == 0 {
+            return Err(AzureStorageError::InvalidOptions {
+                msg: "Chunk size and max concurrency of a transfer must be greater than 0"
+                    .to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn to_upload_from_reader_options(&self) -> UploadFromReaderOptions {
+        UploadFromReaderOptions::new()
+            .with_block_size(self.chunk_size)
+            .with_max_concurrency(self.max_concurrency)
+            .with_upload_options(self.upload_options.clone())
+    }
+}
+
+impl Default for TransferOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+            max_concurrency: Self::DEFAULT_MAX_CONCURRENCY,
+            upload_options: UploadBlockBlobOptions::new(),
+        }
+    }
+}
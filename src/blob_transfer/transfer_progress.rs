@@ -0,0 +1,13 @@
+/// Bytes of the blob transferred so far. Chunks a resumed transfer does not have to transfer again count as
+/// transferred from the start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferProgress {
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+}
+
+impl TransferProgress {
+    pub fn is_completed(&self) -> bool {
+        self.bytes_transferred == self.total_bytes
+    }
+}
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+
+use crate::{blob::BlobProperties, AzureStorageError};
+
+use super::{BlobVersionItem, DeletedBlobContainer};
+
+/// Prior versions and soft-deleted blobs and containers of accounts with versioning and soft delete enabled
+#[async_trait]
+pub trait BlobVersionApi {
+    /// Every blob of the container with its versions, soft-deleted blobs included
+    async fn get_list_of_blob_versions(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<BlobVersionItem>, AzureStorageError>;
+
+    async fn get_blob_version_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<BlobProperties, AzureStorageError>;
+
+    async fn download_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<Vec<u8>, AzureStorageError>;
+
+    /// Copies the version over the blob, so it becomes the current one. The replaced state is kept as a version.
+    /// A leased blob is overwritten with its lease id
+    async fn promote_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError>;
+
+    /// Restores a soft-deleted blob. A blob which is not deleted stays as it is
+    async fn undelete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<(), AzureStorageError>;
+
+    async fn get_list_of_deleted_blob_containers(
+        &self,
+    ) -> Result<Vec<DeletedBlobContainer>, AzureStorageError>;
+
+    /// Restores a soft-deleted container under its name. Fails if a container with the name exists
+    async fn undelete_blob_container(
+        &self,
+        container_name: &str,
+        version: &str,
+    ) -> Result<(), AzureStorageError>;
+}
@@ -0,0 +1,207 @@
+use crate::{
+    blob::BlobProperties, connection::AzureStorageConnection, storage_backend::BlobVersionBackend,
+    AzureStorageError,
+};
+
+use super::{api::BlobVersionApi, BlobVersionItem, DeletedBlobContainer};
+
+use async_trait::async_trait;
+
+#[async_trait]
+impl BlobVersionApi for AzureStorageConnection {
+    async fn get_list_of_blob_versions(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<BlobVersionItem>, AzureStorageError> {
+        self.get_storage_backend()
+            .get_list_of_blob_versions(container_name)
+            .await
+    }
+
+    async fn get_blob_version_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        self.get_storage_backend()
+            .get_blob_version_properties(container_name, blob_name, version_id)
+            .await
+    }
+
+    async fn download_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        self.get_storage_backend()
+            .download_blob_version(container_name, blob_name, version_id)
+            .await
+    }
+
+    async fn promote_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .promote_blob_version(container_name, blob_name, version_id, lease_id)
+            .await
+    }
+
+    async fn undelete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .undelete_blob(container_name, blob_name)
+            .await
+    }
+
+    async fn get_list_of_deleted_blob_containers(
+        &self,
+    ) -> Result<Vec<DeletedBlobContainer>, AzureStorageError> {
+        self.get_storage_backend()
+            .get_list_of_deleted_blob_containers()
+            .await
+    }
+
+    async fn undelete_blob_container(
+        &self,
+        container_name: &str,
+        version: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .undelete_blob_container(container_name, version)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        blob::BlobApi, blob_container::BlobContainersApi, blob_version::LocalRetentionPolicy,
+        block_blob::BlockBlobApi,
+    };
+
+    #[tokio::test]
+    async fn test_versions_and_soft_delete_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-blob-versions";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str())
+            .with_retention_policy(
+                LocalRetentionPolicy::new()
+                    .with_versioning()
+                    .with_delete_retention(Duration::from_secs(60)),
+            );
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![1u8])
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob(CONTAINER_NAME, BLOB_NAME, vec![2u8])
+            .await
+            .unwrap();
+
+        let versions = connection
+            .get_list_of_blob_versions(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        assert_eq!(2, versions.len());
+        assert!(!versions[0].is_current_version);
+        assert!(versions[1].is_current_version);
+
+        // The version folder is not a blob of the container
+        assert_eq!(
+            vec![BLOB_NAME.to_string()],
+            connection.get_list_of_blobs(CONTAINER_NAME).await.unwrap()
+        );
+
+        let first = versions[0].version_id.clone().unwrap();
+
+        assert_eq!(
+            vec![1u8],
+            connection
+                .download_blob_version(CONTAINER_NAME, BLOB_NAME, first.as_str())
+                .await
+                .unwrap()
+        );
+
+        connection
+            .promote_blob_version(CONTAINER_NAME, BLOB_NAME, first.as_str(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![1u8],
+            connection
+                .download_blob(CONTAINER_NAME, BLOB_NAME)
+                .await
+                .unwrap()
+        );
+
+        connection
+            .delete_blob(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+
+        let versions = connection
+            .get_list_of_blob_versions(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        assert_eq!(3, versions.len());
+        assert!(versions.iter().all(|itm| !itm.is_current_version));
+
+        connection.delete_container(CONTAINER_NAME).await.unwrap();
+
+        assert!(!connection
+            .get_list_of_blob_containers()
+            .await
+            .unwrap()
+            .contains(&CONTAINER_NAME.to_string()));
+
+        let deleted = connection
+            .get_list_of_deleted_blob_containers()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|itm| itm.name == CONTAINER_NAME)
+            .unwrap();
+
+        connection
+            .undelete_blob_container(CONTAINER_NAME, deleted.version.as_str())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            3,
+            connection
+                .get_list_of_blob_versions(CONTAINER_NAME)
+                .await
+                .unwrap()
+                .len()
+        );
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+/// Entry of a container listing with include=versions,deleted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobVersionItem {
+    pub blob_name: String,
+    /// None for blobs written before versioning was enabled
+    pub version_id: Option<String>,
+    pub is_current_version: bool,
+    /// Soft-deleted blob which can be brought back with `undelete_blob`
+    pub deleted: bool,
+}
+
+/// Soft-deleted container. The version tells apart containers deleted under the same name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletedBlobContainer {
+    pub name: String,
+    pub version: String,
+}
+
+/// Blob versioning and soft delete the local (file and in-memory) backends emulate.
+/// Both are off by default, so deletes and overwrites are final - as on an account without them
+#[derive(Debug, Clone, Default)]
+pub struct LocalRetentionPolicy {
+    /// Every overwrite, metadata change and delete keeps the previous state as a version
+    pub versioning: bool,
+    /// How long deleted blobs and containers can be undeleted. None disables soft delete
+    pub delete_retention: Option<Duration>,
+}
+
+impl LocalRetentionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_versioning(mut self) -> Self {
+        self.versioning = true;
+        self
+    }
+
+    pub fn with_delete_retention(mut self, delete_retention: Duration) -> Self {
+        self.delete_retention = Some(delete_retention);
+        self
+    }
+
+    /// Whether a blob or container deleted at `deleted_at` can not be undeleted anymore
+    pub(crate) fn is_expired(
+        &self,
+        deleted_at: DateTimeAsMicroseconds,
+        now: DateTimeAsMicroseconds,
+    ) -> bool {
+        match self.delete_retention {
+            Some(delete_retention) => {
+                now.unix_microseconds - deleted_at.unix_microseconds
+                    >= delete_retention.as_micros() as i64
+            }
+            None => true,
+        }
+    }
+}
@@ -1,8 +1,15 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use rust_extensions::SliceOrVec;
+use tokio::io::AsyncRead;
 
 use crate::types::AzureStorageError;
 
+use super::{
+    BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions, UploadFromReaderOptions,
+};
+
 #[async_trait]
 pub trait BlockBlobApi {
     async fn upload_block_blob<'s>(
@@ -11,4 +18,80 @@ pub trait BlockBlobApi {
         blob_name: &str,
         content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
     ) -> Result<(), AzureStorageError>;
+
+    /// Uploads the blob with user-defined metadata sent as x-ms-meta-* headers
+    async fn upload_block_blob_with_metadata<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError>;
+
+    /// Uploads the blob with HTTP headers (content-type, cache-control, ...), metadata and access conditions.
+    /// Returns ETag of the uploaded blob; a not met condition fails with ConditionNotMet,
+    /// If-None-Match: * on an existing blob - with BlobAlreadyExists
+    async fn upload_block_blob_with_options<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Uploads content of unknown length as it is read: blocks are staged as data arrives and committed
+    /// at the end. Returns ETag of the uploaded blob
+    async fn upload_from_reader(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        reader: impl AsyncRead + Unpin + Send,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Block size and concurrency bound the memory the upload takes. Fails with InvalidOptions if either is 0
+    async fn upload_from_reader_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        reader: impl AsyncRead + Unpin + Send,
+        options: UploadFromReaderOptions,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Stages a block of the blob. The blob does not change until a block list commits the block.
+    /// Ids are Base64 strings of up to 64 bytes, all of the same length for the blocks of one blob.
+    /// Blocks of a leased blob are put with the lease id
+    async fn put_block<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        block_id: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError>;
+
+    /// Commits the blocks, in the order of the list, as the content of the blob. Committed blocks of the
+    /// blob can be listed again, so content is appended by committing the current blocks plus new ones.
+    /// A block which is not found fails with InvalidBlockList. Returns ETag of the blob
+    async fn put_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        blocks: Vec<BlockListItem>,
+    ) -> Result<String, AzureStorageError>;
+
+    /// The same as put_block_list, with HTTP headers, metadata and access conditions of Put Blob
+    async fn put_block_list_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        blocks: Vec<BlockListItem>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError>;
+
+    async fn get_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        list_type: BlockListType,
+    ) -> Result<BlockList, AzureStorageError>;
 }
@@ -0,0 +1,63 @@
+use base64::Engine;
+
+use crate::AzureStorageError;
+
+/// Blocks a block blob can be committed from
+pub const MAX_COMMITTED_BLOCKS: usize = 50_000;
+
+/// Blocks a blob can have staged and not committed yet
+pub const MAX_UNCOMMITTED_BLOCKS: usize = 100_000;
+
+const MAX_BLOCK_ID_LEN: usize = 64;
+
+/// Block ids are Base64 strings of up to 64 bytes before encoding.
+/// Azure takes ids of the same length only for the blocks of one blob - `same_length_as` is an id of another block
+pub(crate) fn check_block_id(
+    block_id: &str,
+    same_length_as: Option<&str>,
+) -> Result<(), AzureStorageError> {
+    let decoded = match base64::engine::general_purpose::STANDARD.decode(block_id) {
+        Ok(decoded) => decoded,
+        Err(_) => return Err(AzureStorageError::InvalidBlockId(None)),
+    };
+
+    if decoded.is_empty() || decoded.len() > MAX_BLOCK_ID_LEN {
+        return Err(AzureStorageError::InvalidBlockId(None));
+    }
+
+    if let Some(other) = same_length_as {
+        if other.len() != block_id.len() {
+            return Err(AzureStorageError::InvalidBlockId(None));
+        }
+    }
+
+    Ok(())
+}
+
+/// Ids of the blocks one upload stages: the same length, unique for the upload
+pub(crate) fn generate_block_id(upload_id: &str, block_no: usize) -> String {
+    base64::engine::general_purpose::STANDARD.encode(format!("{}-{:06}", upload_id, block_no))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_ids() {
+        let first = generate_block_id("upload", 0);
+        let next = generate_block_id("upload", 12345);
+
+        assert_eq!(first.len(), next.len());
+        check_block_id(first.as_str(), Some(next.as_str())).unwrap();
+
+        assert!(matches!(
+            check_block_id("not base64!", None),
+            Err(AzureStorageError::InvalidBlockId(_))
+        ));
+        assert!(matches!(
+            check_block_id("QUJD", Some(first.as_str())),
+            Err(AzureStorageError::InvalidBlockId(_))
+        ));
+    }
+}
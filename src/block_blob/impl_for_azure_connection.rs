@@ -1,9 +1,17 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use rust_extensions::SliceOrVec;
+use tokio::io::AsyncRead;
 
-use crate::{connection::AzureStorageConnection, types::AzureStorageError};
+use crate::{
+    connection::AzureStorageConnection, storage_backend::BlockBlobBackend, types::AzureStorageError,
+};
 
-use super::api::BlockBlobApi;
+use super::{
+    api::BlockBlobApi, BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions,
+    UploadFromReaderOptions,
+};
 
 #[async_trait]
 impl BlockBlobApi for AzureStorageConnection {
@@ -13,49 +21,158 @@ impl BlockBlobApi for AzureStorageConnection {
         blob_name: &str,
         content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
     ) -> Result<(), AzureStorageError> {
-        match self {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                super::sdk::upload_block_blob(connection_data, container_name, blob_name, content)
-                    .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                let result = crate::sdk_files::blobs::upload(
-                    connection_data,
-                    container_name,
-                    blob_name,
-                    content,
-                )
-                .await;
+        self.get_storage_backend()
+            .upload_block_blob(
+                container_name,
+                blob_name,
+                content.into().into_vec(),
+                UploadBlockBlobOptions::new(),
+            )
+            .await?;
 
-                crate::sdk_files::containers::check_error_if_container_exists(
-                    result,
-                    connection_data,
-                    container_name,
-                )
-                .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = connection_data.get_container(container_name).await;
-                if container.is_none() {
-                    return Err(AzureStorageError::ContainerNotFound);
-                }
-
-                container
-                    .unwrap()
-                    .upload_block_blob(blob_name.to_string(), content)
-                    .await;
-
-                Ok(())
-            }
-        }
+        Ok(())
+    }
+
+    async fn upload_block_blob_with_metadata<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError> {
+        let mut options = UploadBlockBlobOptions::new();
+        options.metadata = metadata;
+
+        self.get_storage_backend()
+            .upload_block_blob(
+                container_name,
+                blob_name,
+                content.into().into_vec(),
+                options,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upload_block_blob_with_options<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        self.get_storage_backend()
+            .upload_block_blob(
+                container_name,
+                blob_name,
+                content.into().into_vec(),
+                options,
+            )
+            .await
+    }
+
+    async fn upload_from_reader(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        reader: impl AsyncRead + Unpin + Send,
+    ) -> Result<String, AzureStorageError> {
+        self.upload_from_reader_with_options(
+            container_name,
+            blob_name,
+            reader,
+            UploadFromReaderOptions::new(),
+        )
+        .await
+    }
+
+    async fn upload_from_reader_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        reader: impl AsyncRead + Unpin + Send,
+        options: UploadFromReaderOptions,
+    ) -> Result<String, AzureStorageError> {
+        super::upload_from_reader::upload_from_reader(
+            self.get_storage_backend(),
+            container_name,
+            blob_name,
+            reader,
+            options,
+        )
+        .await
+    }
+
+    async fn put_block<'s>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        block_id: &str,
+        content: impl Into<SliceOrVec<'s, u8>> + Send + Sync + 'static,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        self.get_storage_backend()
+            .put_block(
+                container_name,
+                blob_name,
+                block_id,
+                content.into().into_vec(),
+                lease_id,
+            )
+            .await
+    }
+
+    async fn put_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        blocks: Vec<BlockListItem>,
+    ) -> Result<String, AzureStorageError> {
+        self.get_storage_backend()
+            .put_block_list(
+                container_name,
+                blob_name,
+                blocks,
+                UploadBlockBlobOptions::new(),
+            )
+            .await
+    }
+
+    async fn put_block_list_with_options(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        blocks: Vec<BlockListItem>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        self.get_storage_backend()
+            .put_block_list(container_name, blob_name, blocks, options)
+            .await
+    }
+
+    async fn get_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        list_type: BlockListType,
+    ) -> Result<BlockList, AzureStorageError> {
+        self.get_storage_backend()
+            .get_block_list(container_name, blob_name, list_type)
+            .await
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
-        blob::BlobApi, blob_container::BlobContainersApi, block_blob::BlockBlobApi,
-        AzureStorageConnection,
+        blob::{BlobAccessConditions, BlobApi},
+        blob_container::BlobContainersApi,
+        block_blob::{
+            BlockBlobApi, BlockListItem, BlockListType, UploadBlockBlobOptions,
+            UploadFromReaderOptions,
+        },
+        AzureStorageConnection, AzureStorageError,
     };
 
     #[tokio::test]
@@ -149,4 +266,264 @@ mod test {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_conditional_uploads_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-conditional-uploads";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        let etag = connection
+            .upload_block_blob_with_options(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                vec![0u8, 1u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::if_not_exists()),
+            )
+            .await
+            .unwrap();
+
+        // Same length and written right after - the ETag must still change
+        let new_etag = connection
+            .upload_block_blob_with_options(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                vec![2u8, 3u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::new().with_if_match(etag.as_str())),
+            )
+            .await
+            .unwrap();
+
+        assert_ne!(etag, new_etag);
+
+        let result = connection
+            .upload_block_blob_with_options(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                vec![4u8, 5u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::new().with_if_match(etag.as_str())),
+            )
+            .await;
+
+        assert!(matches!(result, Err(AzureStorageError::ConditionNotMet(_))));
+
+        let downloaded = connection
+            .download_blob_with_conditions(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                BlobAccessConditions::new().with_if_match(new_etag.as_str()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(vec![2u8, 3u8], downloaded.content);
+        assert_eq!(Some(new_etag.clone()), downloaded.properties.etag);
+
+        let result = connection
+            .delete_blob_with_conditions(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                BlobAccessConditions::new().with_if_match(etag.as_str()),
+            )
+            .await;
+
+        assert!(matches!(result, Err(AzureStorageError::ConditionNotMet(_))));
+
+        connection
+            .delete_blob_with_conditions(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                BlobAccessConditions::new().with_if_match(new_etag.as_str()),
+            )
+            .await
+            .unwrap();
+
+        let container_folder = format!("{}{}", folder.as_str(), CONTAINER_NAME);
+
+        tokio::fs::remove_dir_all(container_folder.as_str())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_from_reader_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-upload-from-reader";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        let content: Vec<u8> = (0u8..10u8).collect();
+
+        let options = UploadFromReaderOptions::new()
+            .with_block_size(3)
+            .with_max_concurrency(2)
+            .with_upload_options(UploadBlockBlobOptions::new().with_metadata("Source", "reader"));
+
+        let etag = connection
+            .upload_from_reader_with_options(CONTAINER_NAME, BLOB_NAME, content.as_slice(), options)
+            .await
+            .unwrap();
+
+        let properties = connection
+            .get_blob_properties(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+        assert_eq!(Some(etag), properties.etag);
+        assert_eq!(
+            Some(&"reader".to_string()),
+            properties.metadata.get("Source")
+        );
+
+        let downloaded = connection
+            .download_blob(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+        assert_eq!(content, downloaded);
+
+        // Staged blocks are gone once committed
+        let blocks_folder = format!(
+            "{}{}{separator}.blob-blocks{separator}{}",
+            folder,
+            CONTAINER_NAME,
+            BLOB_NAME,
+            separator = std::path::MAIN_SEPARATOR
+        );
+        assert!(tokio::fs::metadata(blocks_folder.as_str()).await.is_err());
+
+        assert_eq!(
+            vec![BLOB_NAME.to_string()],
+            connection.get_list_of_blobs(CONTAINER_NAME).await.unwrap()
+        );
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_from_reader_with_zero_block_size_or_concurrency() {
+        let connection = AzureStorageConnection::new_in_memory();
+
+        for options in [
+            UploadFromReaderOptions::new().with_block_size(0),
+            UploadFromReaderOptions::new().with_max_concurrency(0),
+        ] {
+            let result = connection
+                .upload_from_reader_with_options("test", "blob", [1u8, 2, 3].as_slice(), options)
+                .await;
+            assert!(matches!(
+                result,
+                Err(AzureStorageError::InvalidOptions { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_block_lifecycle_on_file_backend() {
+        const CONTAINER_NAME: &str = "test-block-lifecycle";
+        const BLOB_NAME: &str = "test-blob-name";
+        let folder = crate::sdk_files::test_utils::get_test_folder();
+
+        let connection = AzureStorageConnection::from_conn_string(folder.as_str());
+
+        connection
+            .create_container_if_not_exists(CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        connection
+            .put_block(CONTAINER_NAME, BLOB_NAME, "QUFB", vec![1u8, 2u8], None)
+            .await
+            .unwrap();
+
+        let block_list = connection
+            .get_block_list(CONTAINER_NAME, BLOB_NAME, BlockListType::All)
+            .await
+            .unwrap();
+        assert!(block_list.committed.is_empty());
+        assert_eq!(1, block_list.uncommitted.len());
+        assert_eq!(2, block_list.uncommitted[0].size);
+
+        // Staged blocks are not listed as blobs
+        assert!(connection
+            .get_list_of_blobs(CONTAINER_NAME)
+            .await
+            .unwrap()
+            .is_empty());
+
+        connection
+            .put_block_list(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                vec![BlockListItem::Latest("QUFB".to_string())],
+            )
+            .await
+            .unwrap();
+
+        // Appending reads the committed block from the current blob
+        connection
+            .put_block(CONTAINER_NAME, BLOB_NAME, "QkJC", vec![3u8], None)
+            .await
+            .unwrap();
+
+        let block_list = connection
+            .get_block_list(CONTAINER_NAME, BLOB_NAME, BlockListType::Committed)
+            .await
+            .unwrap();
+        assert_eq!("QUFB", block_list.committed[0].block_id);
+        assert!(block_list.uncommitted.is_empty());
+
+        let mut blocks = block_list.to_committed_items();
+        blocks.push(BlockListItem::Uncommitted("QkJC".to_string()));
+
+        connection
+            .put_block_list(CONTAINER_NAME, BLOB_NAME, blocks)
+            .await
+            .unwrap();
+
+        let downloaded = connection
+            .download_blob(CONTAINER_NAME, BLOB_NAME)
+            .await
+            .unwrap();
+        assert_eq!(vec![1u8, 2u8, 3u8], downloaded);
+
+        let block_list = connection
+            .get_block_list(CONTAINER_NAME, BLOB_NAME, BlockListType::All)
+            .await
+            .unwrap();
+        assert_eq!(2, block_list.committed.len());
+        assert!(block_list.uncommitted.is_empty());
+
+        let result = connection
+            .put_block_list(
+                CONTAINER_NAME,
+                BLOB_NAME,
+                vec![BlockListItem::Committed("Q0ND".to_string())],
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::InvalidBlockList(_))
+        ));
+
+        tokio::fs::remove_dir_all(format!("{}{}", folder, CONTAINER_NAME))
+            .await
+            .unwrap();
+    }
 }
@@ -1,5 +1,15 @@
 mod api;
+mod block_id;
 pub mod impl_for_azure_connection;
+mod models;
 pub mod sdk;
+mod upload_block_blob_options;
+mod upload_from_reader;
+mod upload_from_reader_options;
 
 pub use api::BlockBlobApi;
+pub use block_id::{MAX_COMMITTED_BLOCKS, MAX_UNCOMMITTED_BLOCKS};
+pub use models::{BlockItem, BlockList, BlockListItem, BlockListType};
+pub(crate) use block_id::{check_block_id, generate_block_id};
+pub use upload_block_blob_options::UploadBlockBlobOptions;
+pub use upload_from_reader_options::UploadFromReaderOptions;
@@ -0,0 +1,78 @@
+/// Entry of Put Block List: where the block with the id is looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockListItem {
+    /// Block of the current committed block list of the blob
+    Committed(String),
+    /// Block put and not committed yet
+    Uncommitted(String),
+    /// Uncommitted block if there is one with the id, committed otherwise
+    Latest(String),
+}
+
+impl BlockListItem {
+    pub fn get_block_id(&self) -> &str {
+        match self {
+            Self::Committed(block_id) => block_id.as_str(),
+            Self::Uncommitted(block_id) => block_id.as_str(),
+            Self::Latest(block_id) => block_id.as_str(),
+        }
+    }
+
+    pub(crate) fn get_node_name(&self) -> &'static str {
+        match self {
+            Self::Committed(_) => "Committed",
+            Self::Uncommitted(_) => "Uncommitted",
+            Self::Latest(_) => "Latest",
+        }
+    }
+}
+
+/// blocklisttype of Get Block List
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockListType {
+    Committed,
+    Uncommitted,
+    All,
+}
+
+impl BlockListType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Committed => "committed",
+            Self::Uncommitted => "uncommitted",
+            Self::All => "all",
+        }
+    }
+
+    pub(crate) fn has_committed(&self) -> bool {
+        !matches!(self, Self::Uncommitted)
+    }
+
+    pub(crate) fn has_uncommitted(&self) -> bool {
+        !matches!(self, Self::Committed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockItem {
+    pub block_id: String,
+    pub size: usize,
+}
+
+/// Committed blocks are in the order they make up the blob.
+/// Blobs uploaded with a single Put Blob have no committed blocks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockList {
+    pub committed: Vec<BlockItem>,
+    pub uncommitted: Vec<BlockItem>,
+}
+
+impl BlockList {
+    /// Entries which commit the blob again as it is - the start of a block list which appends to the blob
+    pub fn to_committed_items(&self) -> Vec<BlockListItem> {
+        self.committed
+            .iter()
+            .map(|itm| BlockListItem::Committed(itm.block_id.clone()))
+            .collect()
+    }
+}
@@ -1,11 +1,16 @@
-use crate::sdk_azure::azure_response_handler::ToAzureResponseHandler;
+use crate::sdk_azure::access_conditions::{with_access_conditions, with_lease_id};
+use crate::sdk_azure::blob_http_headers::with_blob_http_headers;
+use crate::sdk_azure::blob_metadata::with_metadata_headers;
 use crate::sdk_azure::flurl_ext::FlUrlAzureExtensions;
+use crate::sdk_azure::http_transport::AzureHttpRequest;
+use crate::sdk_azure::retry_policy::execute_request;
 use crate::sdk_azure::sign_utils::SignVerb;
 use crate::{connection::AzureStorageConnectionData, types::AzureStorageError};
 
+use super::{BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions};
+
 use crate::sdk_azure::consts::AZURE_REST_VERSION;
 
-use flurl::body::FlUrlBody;
 use flurl::FlUrl;
 use rust_extensions::SliceOrVec;
 
@@ -14,29 +19,268 @@ pub async fn upload_block_blob<'s>(
     container_name: &str,
     blob_name: &str,
     content: impl Into<SliceOrVec<'s, u8>>,
+    options: &UploadBlockBlobOptions,
+) -> Result<String, AzureStorageError> {
+    let content = content.into().into_vec();
+
+    let response = execute_request(connection, true, || {
+        let content = content.clone();
+
+        async move {
+            let fl_url: FlUrl = connection.into();
+
+            let fl_url = fl_url
+                .append_path_segment(container_name)
+                .append_path_segment(blob_name)
+                .with_header("x-ms-blob-type", "BlockBlob");
+
+            let fl_url = with_blob_http_headers(fl_url, &options.http_headers);
+            let fl_url = with_access_conditions(fl_url, &options.conditions);
+
+            let fl_url = with_metadata_headers(fl_url, &options.metadata)
+                .add_azure_headers(
+                    SignVerb::PUT,
+                    connection,
+                    Some(content.len()),
+                    None,
+                    AZURE_REST_VERSION,
+                )
+                .await?;
+
+            Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url).with_body(content))
+        }
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    response.get_etag()
+}
+
+/// Put Block. The block is staged and does not change the blob until Put Block List commits it.
+/// The block is sent with its MD5, so the service fails a block damaged on the way with Md5Mismatch.
+/// Blocks of a leased blob are put with the lease id
+pub async fn put_block(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    block_id: &str,
+    content: Vec<u8>,
+    lease_id: Option<&str>,
 ) -> Result<(), AzureStorageError> {
-    let fl_url: FlUrl = connection.into();
+    let content_md5 = get_content_md5(content.as_slice());
 
-    let content = content.into().into_vec();
+    execute_request(connection, true, || {
+        let content = content.clone();
+        let content_md5 = content_md5.clone();
 
-    fl_url
-        .append_path_segment(container_name)
-        .append_path_segment(blob_name)
-        .with_header("x-ms-blob-type", "BlockBlob")
-        .add_azure_headers(
-            SignVerb::PUT,
-            connection,
-            Some(content.len()),
-            None,
-            AZURE_REST_VERSION,
-        )
-        .put(FlUrlBody::Raw {
-            data: content,
-            content_type: None,
-        })
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error()?;
+        async move {
+            let fl_url: FlUrl = connection.into();
+
+            let fl_url = fl_url
+                .append_path_segment(container_name)
+                .append_path_segment(blob_name)
+                .append_query_param("comp", Some("block"))
+                .append_query_param("blockid", Some(block_id))
+                .with_header("Content-MD5", content_md5);
+
+            let fl_url = with_lease_id(fl_url, lease_id)
+                .add_azure_headers(
+                    SignVerb::PUT,
+                    connection,
+                    Some(content.len()),
+                    None,
+                    AZURE_REST_VERSION,
+                )
+                .await?;
+
+            Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url).with_body(content))
+        }
+    })
+    .await?
+    .check_if_there_is_an_error()?;
 
     Ok(())
 }
+
+pub async fn put_block_list(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    blocks: &[BlockListItem],
+    options: &UploadBlockBlobOptions,
+) -> Result<String, AzureStorageError> {
+    let body = compile_block_list_body(blocks);
+
+    let response = execute_request(connection, true, || {
+        let body = body.clone();
+
+        async move {
+            let fl_url: FlUrl = connection.into();
+
+            let fl_url = fl_url
+                .append_path_segment(container_name)
+                .append_path_segment(blob_name)
+                .append_query_param("comp", Some("blocklist"));
+
+            let fl_url = with_blob_http_headers(fl_url, &options.http_headers);
+            let fl_url = with_access_conditions(fl_url, &options.conditions);
+
+            let fl_url = with_metadata_headers(fl_url, &options.metadata)
+                .add_azure_headers(
+                    SignVerb::PUT,
+                    connection,
+                    Some(body.len()),
+                    None,
+                    AZURE_REST_VERSION,
+                )
+                .await?;
+
+            Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url).with_body(body))
+        }
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    response.get_etag()
+}
+
+pub async fn get_block_list(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    list_type: BlockListType,
+) -> Result<BlockList, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("blocklist"))
+            .append_query_param("blocklisttype", Some(list_type.as_str()))
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    let body = response.get_body().await?;
+
+    Ok(crate::sdk_azure::models::deserialize_block_list(
+        body.as_ref(),
+    ))
+}
+
+fn get_content_md5(content: &[u8]) -> String {
+    use base64::Engine;
+    use md5::Digest;
+
+    base64::engine::general_purpose::STANDARD.encode(md5::Md5::digest(content))
+}
+
+fn compile_block_list_body(blocks: &[BlockListItem]) -> Vec<u8> {
+    let mut result = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?><BlockList>");
+
+    // Base64 ids have nothing to escape
+    for block in blocks {
+        result.push('<');
+        result.push_str(block.get_node_name());
+        result.push('>');
+        result.push_str(block.get_block_id());
+        result.push_str("</");
+        result.push_str(block.get_node_name());
+        result.push('>');
+    }
+
+    result.push_str("</BlockList>");
+
+    result.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::sdk_azure::http_transport::{
+        get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+    };
+
+    #[tokio::test]
+    async fn test_put_block_and_block_list_requests() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(201));
+        transport.push_response(AzureHttpResponse::new(201).with_header("etag", "\"0x1\""));
+
+        let connection = get_scripted_connection(&transport);
+
+        put_block(
+            &connection,
+            "container",
+            "blob",
+            "QUJD",
+            b"abc".to_vec(),
+            Some("lease-1"),
+        )
+        .await
+        .unwrap();
+
+        let etag = put_block_list(
+            &connection,
+            "container",
+            "blob",
+            &[
+                BlockListItem::Committed("QUJD".to_string()),
+                BlockListItem::Latest("REVG".to_string()),
+            ],
+            &UploadBlockBlobOptions::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!("\"0x1\"", etag);
+
+        let requests = transport.get_requests();
+        assert_eq!(Some("block"), requests[0].get_query_param("comp"));
+        assert_eq!(Some("QUJD"), requests[0].get_query_param("blockid"));
+        assert_eq!(Some(b"abc".to_vec()), requests[0].body);
+        assert_eq!(
+            Some("kAFQmDzST7DWlj99KOF/cg=="),
+            requests[0].get_header("Content-MD5")
+        );
+        assert_eq!(Some("lease-1"), requests[0].get_header("x-ms-lease-id"));
+
+        assert_eq!(Some("blocklist"), requests[1].get_query_param("comp"));
+        assert_eq!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><BlockList><Committed>QUJD</Committed><Latest>REVG</Latest></BlockList>",
+            String::from_utf8(requests[1].body.clone().unwrap()).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_block_list_request() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(200).with_body(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><BlockList><CommittedBlocks><Block><Name>QUJD</Name><Size>3</Size></Block></CommittedBlocks><UncommittedBlocks><Block><Name>REVG</Name><Size>5</Size></Block></UncommittedBlocks></BlockList>",
+        ));
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = get_block_list(&connection, "container", "blob", BlockListType::All)
+            .await
+            .unwrap();
+
+        assert_eq!(1, result.committed.len());
+        assert_eq!("QUJD", result.committed[0].block_id);
+        assert_eq!(3, result.committed[0].size);
+        assert_eq!(1, result.uncommitted.len());
+        assert_eq!("REVG", result.uncommitted[0].block_id);
+        assert_eq!(5, result.uncommitted[0].size);
+
+        let requests = transport.get_requests();
+        assert_eq!(Some("blocklist"), requests[0].get_query_param("comp"));
+        assert_eq!(Some("all"), requests[0].get_query_param("blocklisttype"));
+    }
+}
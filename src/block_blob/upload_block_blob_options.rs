@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use crate::blob::{BlobAccessConditions, BlobHttpHeaders};
+
+/// Optional settings of Put Blob: HTTP headers stored with the blob, user-defined metadata
+/// and access conditions the existing blob has to meet
+#[derive(Debug, Clone, Default)]
+pub struct UploadBlockBlobOptions {
+    pub http_headers: BlobHttpHeaders,
+    pub metadata: HashMap<String, String>,
+    pub conditions: BlobAccessConditions,
+}
+
+impl UploadBlockBlobOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_http_headers(mut self, http_headers: BlobHttpHeaders) -> Self {
+        self.http_headers = http_headers;
+        self
+    }
+
+    pub fn with_content_type(mut self, value: impl Into<String>) -> Self {
+        self.http_headers.content_type = Some(value.into());
+        self
+    }
+
+    pub fn with_cache_control(mut self, value: impl Into<String>) -> Self {
+        self.http_headers.cache_control = Some(value.into());
+        self
+    }
+
+    pub fn with_metadata(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_conditions(mut self, conditions: BlobAccessConditions) -> Self {
+        self.conditions = conditions;
+        self
+    }
+}
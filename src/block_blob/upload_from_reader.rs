@@ -0,0 +1,120 @@
+use std::{future::Future, task::Poll};
+
+use tokio::io::AsyncRead;
+
+use crate::{in_flight::InFlight, storage_backend::StorageBackend, AzureStorageError};
+
+use super::{
+    block_id::{generate_block_id, MAX_COMMITTED_BLOCKS},
+    BlockListItem, UploadFromReaderOptions,
+};
+
+/// Content which fits into one block goes as a single Put Blob. Otherwise blocks are put while the next one
+/// is being read, and the block list is committed once all of them are put
+pub(crate) async fn upload_from_reader<TReader: AsyncRead + Unpin + Send>(
+    backend: &dyn StorageBackend,
+    container_name: &str,
+    blob_name: &str,
+    mut reader: TReader,
+    options: UploadFromReaderOptions,
+) -> Result<String, AzureStorageError> {
+    options.check()?;
+
+    let mut block = read_block(&mut reader, options.block_size).await?;
+
+    if block.len() < options.block_size {
+        return backend
+            .upload_block_blob(container_name, blob_name, block, options.upload_options)
+            .await;
+    }
+
+    let upload_id = uuid::Uuid::new_v4().simple().to_string();
+
+    // Blocks of a leased blob are put with the lease id the block list is committed with
+    let lease_id = options.upload_options.conditions.lease_id.clone();
+
+    let mut blocks = Vec::new();
+    let mut in_flight = InFlight::new();
+
+    loop {
+        if blocks.len() == MAX_COMMITTED_BLOCKS {
+            return Err(AzureStorageError::BlockListTooLong(None));
+        }
+
+        let block_id = generate_block_id(upload_id.as_str(), blocks.len());
+        blocks.push(BlockListItem::Uncommitted(block_id.clone()));
+
+        let is_last_block = block.len() < options.block_size;
+
+        let lease_id = lease_id.as_deref();
+
+        in_flight.push(async move {
+            backend
+                .put_block(
+                    container_name,
+                    blob_name,
+                    block_id.as_str(),
+                    block,
+                    lease_id,
+                )
+                .await
+        });
+
+        if is_last_block {
+            break;
+        }
+
+        // The block being read counts against max_concurrency too
+        std::future::poll_fn(|cx| match in_flight.poll_all(cx, |()| ()) {
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            _ if in_flight.len() < options.max_concurrency => Poll::Ready(Ok(())),
+            _ => Poll::Pending,
+        })
+        .await?;
+
+        // Blocks already read keep being put while the reader is waited for
+        let mut read = std::pin::pin!(read_block(&mut reader, options.block_size));
+
+        block = std::future::poll_fn(|cx| {
+            if let Poll::Ready(Err(err)) = in_flight.poll_all(cx, |()| ()) {
+                return Poll::Ready(Err(err));
+            }
+
+            read.as_mut().poll(cx)
+        })
+        .await?;
+
+        if block.is_empty() {
+            break;
+        }
+    }
+
+    std::future::poll_fn(|cx| in_flight.poll_all(cx, |()| ())).await?;
+
+    backend
+        .put_block_list(container_name, blob_name, blocks, options.upload_options)
+        .await
+}
+
+/// Fills the block up to the block size. A shorter block means the end of the content
+async fn read_block<TReader: AsyncRead + Unpin + Send>(
+    reader: &mut TReader,
+    block_size: usize,
+) -> Result<Vec<u8>, AzureStorageError> {
+    let mut result = vec![0u8; block_size];
+    let mut filled = 0;
+
+    while filled < block_size {
+        let read = tokio::io::AsyncReadExt::read(reader, &mut result[filled..]).await?;
+
+        if read == 0 {
+            break;
+        }
+
+        filled += read;
+    }
+
+    result.truncate(filled);
+
+    Ok(result)
+}
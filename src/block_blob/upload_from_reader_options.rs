@@ -0,0 +1,59 @@
+use crate::AzureStorageError;
+
+use super::UploadBlockBlobOptions;
+
+/// Settings of a staged upload: the content is put as blocks of `block_size`, by up to `max_concurrency`
+/// requests at a time, and committed as the blob at the end. At most `block_size * max_concurrency`
+/// bytes of the content are kept in memory
+#[derive(Debug, Clone)]
+pub struct UploadFromReaderOptions {
+    pub block_size: usize,
+    pub max_concurrency: usize,
+    /// HTTP headers, metadata and access conditions the blob is committed with
+    pub upload_options: UploadBlockBlobOptions,
+}
+
+impl UploadFromReaderOptions {
+    pub const DEFAULT_BLOCK_SIZE: usize = 4 * 1024 * 1024;
+    pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    pub fn with_upload_options(mut self, upload_options: UploadBlockBlobOptions) -> Self {
+        self.upload_options = upload_options;
+        self
+    }
+
+    pub(crate) fn check(&self) -> Result<(), AzureStorageError> {
+        if self.block_size == 0 || self.max_concurrency == 0 {
+            return Err(AzureStorageError::InvalidOptions {
+                msg: "Block size and max concurrency of an upload must be greater than 0"
+                    .to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for UploadFromReaderOptions {
+    fn default() -> Self {
+        Self {
+            block_size: Self::DEFAULT_BLOCK_SIZE,
+            max_concurrency: Self::DEFAULT_MAX_CONCURRENCY,
+            upload_options: UploadBlockBlobOptions::new(),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    sync::atomic::{AtomicI64, Ordering},
+    time::Duration,
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+/// Time source of the in-memory and file backends for time-bound state (lease expiry, ...).
+/// Tests inject a `ManualClock` to move time without sleeping
+pub trait StorageClock: Send + Sync {
+    fn now(&self) -> DateTimeAsMicroseconds;
+}
+
+pub struct SystemClock;
+
+impl StorageClock for SystemClock {
+    fn now(&self) -> DateTimeAsMicroseconds {
+        DateTimeAsMicroseconds::now()
+    }
+}
+
+/// Clock which moves only when told to
+pub struct ManualClock {
+    now: AtomicI64,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTimeAsMicroseconds) -> Self {
+        Self {
+            now: AtomicI64::new(now.unix_microseconds),
+        }
+    }
+
+    pub fn set(&self, now: DateTimeAsMicroseconds) {
+        self.now.store(now.unix_microseconds, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, delta: Duration) {
+        self.now
+            .fetch_add(delta.as_micros() as i64, Ordering::SeqCst);
+    }
+}
+
+impl StorageClock for ManualClock {
+    fn now(&self) -> DateTimeAsMicroseconds {
+        DateTimeAsMicroseconds::new(self.now.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new(DateTimeAsMicroseconds::new(1_000_000));
+
+        clock.advance(Duration::from_secs(15));
+
+        assert_eq!(16_000_000, clock.now().unix_microseconds);
+    }
+}
@@ -1,22 +1,75 @@
-use super::{in_mem::MemStorageData, AzureStorageConnectionData, FileConnectionData};
+use std::sync::Arc;
+
+use crate::{blob_version::LocalRetentionPolicy, storage_backend::StorageBackend, StorageClock};
+
+use super::{
+    in_mem::MemStorageData, AzureStorageConnectionData, ConnectionStringError, FileConnectionData,
+};
 
 pub enum AzureStorageConnection {
     AzureStorage(AzureStorageConnectionData),
     File(FileConnectionData),
     InMemory(MemStorageData),
+    /// Third-party blob storage. Table storage is not available through it
+    Custom(Arc<dyn StorageBackend>),
 }
 
 impl AzureStorageConnection {
     pub fn new_in_memory() -> Self {
         Self::InMemory(MemStorageData::new())
     }
+
+    pub fn new_custom(backend: Arc<dyn StorageBackend>) -> Self {
+        Self::Custom(backend)
+    }
+
+    /// Clock the in-memory and file backends expire leases by. Azure and custom backends keep their own time
+    pub fn with_clock(self, clock: Arc<dyn StorageClock>) -> Self {
+        match self {
+            AzureStorageConnection::File(connection_data) => {
+                AzureStorageConnection::File(connection_data.with_clock(clock))
+            }
+            AzureStorageConnection::InMemory(connection_data) => {
+                AzureStorageConnection::InMemory(connection_data.with_clock(clock))
+            }
+            _ => self,
+        }
+    }
+
+    /// Versioning and soft delete emulation of the in-memory and file backends. Azure configures them on the account
+    pub fn with_retention_policy(self, retention: LocalRetentionPolicy) -> Self {
+        match self {
+            AzureStorageConnection::File(connection_data) => {
+                AzureStorageConnection::File(connection_data.with_retention_policy(retention))
+            }
+            AzureStorageConnection::InMemory(connection_data) => {
+                AzureStorageConnection::InMemory(connection_data.with_retention_policy(retention))
+            }
+            _ => self,
+        }
+    }
+
+    /// Backend every blob operation of the connection is dispatched to
+    pub fn get_storage_backend(&self) -> &dyn StorageBackend {
+        match self {
+            AzureStorageConnection::AzureStorage(connection_data) => connection_data,
+            AzureStorageConnection::File(connection_data) => connection_data,
+            AzureStorageConnection::InMemory(connection_data) => connection_data,
+            AzureStorageConnection::Custom(backend) => backend.as_ref(),
+        }
+    }
     pub fn from_conn_string(connection_string: &str) -> Self {
-        if connection_string.contains("DefaultEndpointsProtocol")
-            && connection_string.contains("AccountName")
-            && connection_string.contains("AccountKey")
-        {
-            let connection_data = AzureStorageConnectionData::from_conn_string(connection_string);
-            return Self::AzureStorage(connection_data);
+        match Self::try_from_conn_string(connection_string) {
+            Ok(result) => result,
+            Err(err) => panic!("Can not parse connection string: {}", err),
+        }
+    }
+
+    pub fn try_from_conn_string(connection_string: &str) -> Result<Self, ConnectionStringError> {
+        let connection_string = connection_string.trim();
+
+        if connection_string.is_empty() {
+            return Err(ConnectionStringError::Empty);
         }
 
         if connection_string.starts_with("~")
@@ -31,10 +84,20 @@ impl AzureStorageConnection {
 
             let connection_data = FileConnectionData::new(connection_string);
 
-            return Self::File(connection_data);
+            return Ok(Self::File(connection_data));
+        }
+
+        if connection_string.starts_with("https://") || connection_string.starts_with("http://") {
+            let connection_data = AzureStorageConnectionData::try_from_sas_url(connection_string)?;
+            return Ok(Self::AzureStorage(connection_data));
+        }
+
+        if connection_string.contains('=') {
+            let connection_data = AzureStorageConnectionData::try_from_conn_string(connection_string)?;
+            return Ok(Self::AzureStorage(connection_data));
         }
 
-        panic!("Can not parse connection string: {}", connection_string);
+        Err(ConnectionStringError::UnknownFormat)
     }
 
     pub fn is_file(&self) -> bool {
@@ -44,3 +107,30 @@ impl AzureStorageConnection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_conn_string() {
+        let connection =
+            AzureStorageConnection::try_from_conn_string("UseDevelopmentStorage=true").unwrap();
+        assert!(matches!(connection, AzureStorageConnection::AzureStorage(_)));
+
+        let connection = AzureStorageConnection::try_from_conn_string("/tmp/storage").unwrap();
+        assert!(connection.is_file());
+
+        let connection = AzureStorageConnection::try_from_conn_string(
+            "https://myaccount.blob.core.windows.net/mycontainer?sv=2019-12-12&sr=c&sp=rl&sig=abc",
+        )
+        .unwrap();
+        assert!(matches!(connection, AzureStorageConnection::AzureStorage(_)));
+
+        let result = AzureStorageConnection::try_from_conn_string("something");
+        assert_eq!(
+            ConnectionStringError::UnknownFormat,
+            result.err().unwrap()
+        );
+    }
+}
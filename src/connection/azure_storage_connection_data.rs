@@ -1,19 +1,42 @@
-use std::{collections::HashMap, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use flurl::FlUrl;
 
-use crate::sdk_azure::sign_utils::SignVerb;
+use crate::{
+    sdk_azure::{
+        http_transport::{AzureHttpTransport, FlUrlTransport},
+        retry_policy::RetryPolicy,
+        sign_utils::SignVerb,
+    },
+    token_credential::TokenCredential,
+};
+
+use super::{
+    connection_string::{ConnectionStringError, ConnectionStringKeys},
+    AzureStorageCredentials, SasToken,
+};
+
+pub const DEV_STORAGE_ACCOUNT_NAME: &str = "devstoreaccount1";
+const DEV_STORAGE_ACCOUNT_KEY: &str =
+    "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+const DEV_STORAGE_HOST: &str = "http://127.0.0.1";
+
+const DEFAULT_ENDPOINT_SUFFIX: &str = "core.windows.net";
+const DEFAULT_ENDPOINTS_PROTOCOL: &str = "https";
 
 #[derive(Clone)]
 pub struct AzureStorageConnectionData {
     pub account_name: String,
-    pub account_key: Vec<u8>,
-    pub endpoint_suffix: String,
+    pub credentials: AzureStorageCredentials,
+    pub endpoint_suffix: Option<String>,
     pub default_endpoints_protocol: String,
     pub blobs_api_url: String,
     pub time_out: Duration,
     pub time_out_as_string: String,
     pub table_storage_api_url: String,
+    pub queue_api_url: String,
+    pub retry_policy: RetryPolicy,
+    pub transport: Arc<dyn AzureHttpTransport>,
 }
 
 impl AzureStorageConnectionData {
@@ -22,73 +45,292 @@ impl AzureStorageConnectionData {
     }
 
     pub fn from_conn_string_with_timeout(conn_string: &str, timeout_secs: u64) -> Self {
-        let key_values = conn_string.split(";");
-
-        let mut conn_keys: HashMap<&str, &str> = HashMap::new();
+        match Self::try_from_conn_string_with_timeout(conn_string, timeout_secs) {
+            Ok(result) => result,
+            Err(err) => panic!("Can not parse connection string: {}", err),
+        }
+    }
 
-        for kv in key_values {
-            let eq_index = kv.find("=").unwrap();
+    pub fn try_from_conn_string(conn_string: &str) -> Result<Self, ConnectionStringError> {
+        Self::try_from_conn_string_with_timeout(conn_string, 10)
+    }
 
-            let key = &kv[..eq_index];
-            let value = &kv[eq_index + 1..];
+    pub fn try_from_conn_string_with_timeout(
+        conn_string: &str,
+        timeout_secs: u64,
+    ) -> Result<Self, ConnectionStringError> {
+        let conn_keys = ConnectionStringKeys::parse(conn_string)?;
 
-            conn_keys.insert(key, value);
+        if conn_keys.is_development_storage() {
+            return Self::from_development_storage(&conn_keys, timeout_secs);
         }
 
-        let account_name = conn_keys.get("AccountName").unwrap().to_string();
-
-        let account_key = conn_keys.get("AccountKey").unwrap();
+        let credentials = read_credentials(&conn_keys)?;
 
-        //let account_key = base64::decode(account_key).unwrap();
+        let blob_endpoint = conn_keys.get_endpoint("BlobEndpoint")?;
+        let table_endpoint = conn_keys.get_endpoint("TableEndpoint")?;
+        let queue_endpoint = conn_keys.get_endpoint("QueueEndpoint")?;
 
-        use base64::Engine;
-        let account_key = base64::engine::general_purpose::STANDARD
-            .decode(account_key)
-            .unwrap();
+        let account_name = match conn_keys.get("AccountName") {
+            Some(account_name) => account_name.to_string(),
+            None => [&blob_endpoint, &table_endpoint, &queue_endpoint]
+                .into_iter()
+                .flatten()
+                .find_map(|url| super::connection_string::extract_account_name(url))
+                .ok_or(ConnectionStringError::MissingKey("AccountName"))?,
+        };
 
-        let endpoint_suffix = conn_keys.get("EndpointSuffix").unwrap().to_string();
+        let endpoint_suffix = conn_keys.get("EndpointSuffix").map(|itm| itm.to_string());
 
         let default_endpoints_protocol = conn_keys
             .get("DefaultEndpointsProtocol")
-            .unwrap()
+            .unwrap_or(DEFAULT_ENDPOINTS_PROTOCOL)
             .to_string();
 
-        let blobs_api_url = format!(
-            "{}://{}.blob.{}",
-            default_endpoints_protocol, account_name, endpoint_suffix
-        );
+        let compile_service_url = |service: &str| {
+            format!(
+                "{}://{}.{}.{}",
+                default_endpoints_protocol,
+                account_name,
+                service,
+                endpoint_suffix.as_deref().unwrap_or(DEFAULT_ENDPOINT_SUFFIX)
+            )
+        };
 
-        let table_storage_api_url = format!(
-            "{}://{}.table.{}",
-            default_endpoints_protocol, account_name, endpoint_suffix
-        );
-        Self {
+        let blobs_api_url = match blob_endpoint {
+            Some(url) => url,
+            None => compile_service_url("blob"),
+        };
+
+        let table_storage_api_url = match table_endpoint {
+            Some(url) => url,
+            None => compile_service_url("table"),
+        };
+
+        let queue_api_url = match queue_endpoint {
+            Some(url) => url,
+            None => compile_service_url("queue"),
+        };
+
+        Ok(Self {
             account_name,
-            account_key: account_key,
-            endpoint_suffix: conn_keys.get("EndpointSuffix").unwrap().to_string(),
+            credentials,
+            endpoint_suffix,
             default_endpoints_protocol,
             blobs_api_url,
             time_out: Duration::from_secs(timeout_secs),
             time_out_as_string: timeout_secs.to_string(),
             table_storage_api_url,
+            queue_api_url,
+            retry_policy: RetryPolicy::default(),
+            transport: Arc::new(FlUrlTransport),
+        })
+    }
+
+    fn from_development_storage(
+        conn_keys: &ConnectionStringKeys,
+        timeout_secs: u64,
+    ) -> Result<Self, ConnectionStringError> {
+        let account_name = conn_keys
+            .get("AccountName")
+            .unwrap_or(DEV_STORAGE_ACCOUNT_NAME)
+            .to_string();
+
+        let credentials = match conn_keys.get("SharedAccessSignature") {
+            Some(sas_token) => AzureStorageCredentials::SasToken(SasToken::parse(sas_token)?),
+            None => AzureStorageCredentials::SharedKey(decode_account_key(
+                conn_keys.get("AccountKey").unwrap_or(DEV_STORAGE_ACCOUNT_KEY),
+            )?),
+        };
+
+        let host = match conn_keys.get_endpoint("DevelopmentStorageProxyUri")? {
+            Some(proxy_uri) => proxy_uri,
+            None => DEV_STORAGE_HOST.to_string(),
+        };
+
+        let compile_service_url =
+            |port: u16| format!("{}:{}/{}", host, port, account_name.as_str());
+
+        let blobs_api_url = match conn_keys.get_endpoint("BlobEndpoint")? {
+            Some(url) => url,
+            None => compile_service_url(10000),
+        };
+
+        let queue_api_url = match conn_keys.get_endpoint("QueueEndpoint")? {
+            Some(url) => url,
+            None => compile_service_url(10001),
+        };
+
+        let table_storage_api_url = match conn_keys.get_endpoint("TableEndpoint")? {
+            Some(url) => url,
+            None => compile_service_url(10002),
+        };
+
+        Ok(Self {
+            account_name,
+            credentials,
+            endpoint_suffix: None,
+            default_endpoints_protocol: "http".to_string(),
+            blobs_api_url,
+            time_out: Duration::from_secs(timeout_secs),
+            time_out_as_string: timeout_secs.to_string(),
+            table_storage_api_url,
+            queue_api_url,
+            retry_policy: RetryPolicy::default(),
+            transport: Arc::new(FlUrlTransport),
+        })
+    }
+
+    /// Creates a connection from a blob or container SAS url
+    /// (https://account.blob.core.windows.net/container?sv=...&sig=...).
+    /// The container part of the url is not kept, so requests still pass the container name.
+    pub fn try_from_sas_url(sas_url: &str) -> Result<Self, ConnectionStringError> {
+        let sas_url = sas_url.trim();
+
+        let (resource_url, sas_token) = match sas_url.split_once('?') {
+            Some(result) => result,
+            None => return Err(ConnectionStringError::MissingKey("SharedAccessSignature")),
+        };
+
+        let sas_token = SasToken::parse(sas_token)?;
+
+        let resource_url = super::connection_string::normalize_endpoint("SasUrl", resource_url)?;
+
+        let invalid_url = || ConnectionStringError::InvalidEndpoint {
+            key: "SasUrl",
+            value: resource_url.to_string(),
+        };
+
+        let account_name = super::connection_string::extract_account_name(&resource_url)
+            .ok_or_else(invalid_url)?;
+
+        let service_root = super::connection_string::extract_service_root(&resource_url)
+            .ok_or_else(invalid_url)?;
+
+        // Path-style urls of Azurite carry the service in the port instead of the host
+        let service_ports = [
+            (".blob.", ":10000/"),
+            (".queue.", ":10001/"),
+            (".table.", ":10002/"),
+        ];
+
+        let compile_service_url = |service: &str| {
+            for (src_service, src_port) in service_ports {
+                if service_root.contains(src_service) {
+                    return service_root.replacen(src_service, service, 1);
+                }
+
+                if service_root.contains(src_port) {
+                    let (_, port) = service_ports
+                        .iter()
+                        .find(|(itm, _)| *itm == service)
+                        .unwrap();
+
+                    return service_root.replacen(src_port, port, 1);
+                }
+            }
+
+            service_root.to_string()
+        };
+
+        let default_endpoints_protocol = match service_root.split_once("://") {
+            Some((protocol, _)) => protocol.to_string(),
+            None => DEFAULT_ENDPOINTS_PROTOCOL.to_string(),
+        };
+
+        Ok(Self {
+            blobs_api_url: compile_service_url(".blob."),
+            table_storage_api_url: compile_service_url(".table."),
+            queue_api_url: compile_service_url(".queue."),
+            account_name,
+            credentials: AzureStorageCredentials::SasToken(sas_token),
+            endpoint_suffix: None,
+            default_endpoints_protocol,
+            time_out: Duration::from_secs(10),
+            time_out_as_string: "10".to_string(),
+            retry_policy: RetryPolicy::default(),
+            transport: Arc::new(FlUrlTransport),
+        })
+    }
+
+    /// Creates a connection to the public Azure endpoints of the account authorized by bearer tokens
+    pub fn from_token_credential(
+        account_name: &str,
+        token_credential: Arc<dyn TokenCredential>,
+    ) -> Self {
+        let compile_service_url = |service: &str| {
+            format!(
+                "{}://{}.{}.{}",
+                DEFAULT_ENDPOINTS_PROTOCOL, account_name, service, DEFAULT_ENDPOINT_SUFFIX
+            )
+        };
+
+        Self {
+            account_name: account_name.to_string(),
+            credentials: AzureStorageCredentials::Token(token_credential),
+            endpoint_suffix: None,
+            default_endpoints_protocol: DEFAULT_ENDPOINTS_PROTOCOL.to_string(),
+            blobs_api_url: compile_service_url("blob"),
+            time_out: Duration::from_secs(10),
+            time_out_as_string: "10".to_string(),
+            table_storage_api_url: compile_service_url("table"),
+            queue_api_url: compile_service_url("queue"),
+            retry_policy: RetryPolicy::default(),
+            transport: Arc::new(FlUrlTransport),
         }
     }
 
+    /// Keeps endpoints of the connection but authorizes requests by bearer tokens
+    pub fn with_token_credential(mut self, token_credential: Arc<dyn TokenCredential>) -> Self {
+        self.credentials = AzureStorageCredentials::Token(token_credential);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Replaces the HTTP layer. Used to route requests through a custom client or a scripted fake in tests
+    pub fn with_transport(mut self, transport: Arc<dyn AzureHttpTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
     pub fn with_timeout(mut self, seconds: u64) -> Self {
         self.time_out = Duration::from_secs(seconds);
         self.time_out_as_string = seconds.to_string();
         self
     }
+
+    /// Account key of SharedKey connections
+    #[deprecated(
+        note = "the account_key field was replaced by credentials, use credentials.get_account_key()"
+    )]
+    pub fn get_account_key(&self) -> Option<&[u8]> {
+        self.credentials.get_account_key()
+    }
+
+    /// URL of the blob in this account, e.g. the source of a copy
+    pub fn get_blob_url(&self, container_name: &str, blob_name: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.blobs_api_url,
+            crate::sas::utils::encode_path(container_name),
+            crate::sas::utils::encode_path(blob_name)
+        )
+    }
+
+    /// SharedKey authorization header. Returns None if the connection is not authorized by an account key
     pub fn get_auth_header(
         &self,
         verb: SignVerb,
         content_len: Option<usize>,
         flurl: &FlUrl,
-    ) -> String {
-        let content_len = match content_len {
-            Some(len) => len.to_string(),
-            None => "".to_string(),
-        };
+    ) -> Option<String> {
+        let account_key = self.credentials.get_account_key()?;
+
+        let content_len = crate::sdk_azure::sign_utils::get_content_len_to_sign(content_len);
 
         let string_to_sign = crate::sdk_azure::sign_utils::get_auth_header(
             self.account_name.as_str(),
@@ -97,10 +339,240 @@ impl AzureStorageConnectionData {
             &flurl,
         );
 
-        let signature = crate::sdk_azure::sign_utils::sign_transaction(
-            string_to_sign.as_str(),
-            &self.account_key,
+        let signature =
+            crate::sdk_azure::sign_utils::sign_transaction(string_to_sign.as_str(), account_key);
+
+        Some(format!("SharedKey {}:{}", &self.account_name, signature))
+    }
+}
+
+fn read_credentials(
+    conn_keys: &ConnectionStringKeys,
+) -> Result<AzureStorageCredentials, ConnectionStringError> {
+    if let Some(account_key) = conn_keys.get("AccountKey") {
+        return Ok(AzureStorageCredentials::SharedKey(decode_account_key(
+            account_key,
+        )?));
+    }
+
+    if let Some(sas_token) = conn_keys.get("SharedAccessSignature") {
+        return Ok(AzureStorageCredentials::SasToken(SasToken::parse(
+            sas_token,
+        )?));
+    }
+
+    Err(ConnectionStringError::MissingKey(
+        "AccountKey or SharedAccessSignature",
+    ))
+}
+
+fn decode_account_key(account_key: &str) -> Result<Vec<u8>, ConnectionStringError> {
+    use base64::Engine;
+    match base64::engine::general_purpose::STANDARD.decode(account_key) {
+        Ok(result) => Ok(result),
+        Err(err) => Err(ConnectionStringError::InvalidAccountKey(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_azure_connection_string() {
+        let connection = AzureStorageConnectionData::try_from_conn_string(
+            "DefaultEndpointsProtocol=https;AccountName=myaccount;AccountKey=YWJjZA==;EndpointSuffix=core.windows.net",
+        )
+        .unwrap();
+
+        assert_eq!("myaccount", connection.account_name);
+        assert_eq!(
+            b"abcd".as_slice(),
+            connection.credentials.get_account_key().unwrap()
+        );
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net",
+            connection.blobs_api_url
+        );
+        assert_eq!(
+            "https://myaccount.table.core.windows.net",
+            connection.table_storage_api_url
+        );
+        assert_eq!(
+            "https://myaccount.queue.core.windows.net",
+            connection.queue_api_url
+        );
+    }
+
+    #[test]
+    fn test_endpoint_suffix_is_optional() {
+        let connection = AzureStorageConnectionData::try_from_conn_string(
+            "AccountName=myaccount;AccountKey=YWJjZA==",
+        )
+        .unwrap();
+
+        assert_eq!(None, connection.endpoint_suffix);
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net",
+            connection.blobs_api_url
+        );
+    }
+
+    #[test]
+    fn test_development_storage() {
+        let connection =
+            AzureStorageConnectionData::try_from_conn_string("UseDevelopmentStorage=true")
+                .unwrap();
+
+        assert_eq!(DEV_STORAGE_ACCOUNT_NAME, connection.account_name);
+        assert_eq!(
+            "http://127.0.0.1:10000/devstoreaccount1",
+            connection.blobs_api_url
+        );
+        assert_eq!(
+            "http://127.0.0.1:10001/devstoreaccount1",
+            connection.queue_api_url
+        );
+        assert_eq!(
+            "http://127.0.0.1:10002/devstoreaccount1",
+            connection.table_storage_api_url
+        );
+    }
+
+    #[test]
+    fn test_explicit_path_style_endpoints() {
+        let connection = AzureStorageConnectionData::try_from_conn_string(
+            "DefaultEndpointsProtocol=http;AccountName=devstoreaccount1;AccountKey=YWJjZA==;BlobEndpoint=http://azurite:10000/devstoreaccount1/;TableEndpoint=http://azurite:10002/devstoreaccount1;",
+        )
+        .unwrap();
+
+        assert_eq!(
+            "http://azurite:10000/devstoreaccount1",
+            connection.blobs_api_url
+        );
+        assert_eq!(
+            "http://azurite:10002/devstoreaccount1",
+            connection.table_storage_api_url
+        );
+        assert_eq!(
+            "http://devstoreaccount1.queue.core.windows.net",
+            connection.queue_api_url
+        );
+    }
+
+    #[test]
+    fn test_account_name_of_custom_and_ipv6_path_style_endpoints() {
+        let connection = AzureStorageConnectionData::try_from_conn_string(
+            "DefaultEndpointsProtocol=http;AccountKey=YWJjZA==;BlobEndpoint=http://azurite:10000/devstoreaccount1",
+        )
+        .unwrap();
+
+        assert_eq!("devstoreaccount1", connection.account_name);
+        assert_eq!(
+            "http://azurite:10000/devstoreaccount1",
+            connection.blobs_api_url
+        );
+
+        let connection = AzureStorageConnectionData::try_from_conn_string(
+            "DefaultEndpointsProtocol=http;AccountKey=YWJjZA==;BlobEndpoint=http://[::1]:10000/devstoreaccount1",
+        )
+        .unwrap();
+
+        assert_eq!("devstoreaccount1", connection.account_name);
+        assert_eq!(
+            "http://[::1]:10000/devstoreaccount1",
+            connection.blobs_api_url
+        );
+    }
+
+    #[test]
+    fn test_errors_instead_of_panics() {
+        let result = AzureStorageConnectionData::try_from_conn_string("AccountName=myaccount");
+        assert_eq!(
+            ConnectionStringError::MissingKey("AccountKey or SharedAccessSignature"),
+            result.err().unwrap()
+        );
+
+        let result = AzureStorageConnectionData::try_from_conn_string(
+            "AccountName=myaccount;AccountKey=not base64!",
         );
-        format!("SharedKey {}:{}", &self.account_name, signature)
+        assert!(matches!(
+            result.err().unwrap(),
+            ConnectionStringError::InvalidAccountKey(_)
+        ));
+
+        let result = AzureStorageConnectionData::try_from_conn_string(
+            "AccountName=myaccount;AccountKey=YWJjZA==;BlobEndpoint=localhost",
+        );
+        assert!(matches!(
+            result.err().unwrap(),
+            ConnectionStringError::InvalidEndpoint { .. }
+        ));
+    }
+
+    #[test]
+    fn test_sas_connection_string() {
+        let connection = AzureStorageConnectionData::try_from_conn_string(
+            "BlobEndpoint=https://myaccount.blob.core.windows.net/;SharedAccessSignature=sv=2019-12-12&ss=b&srt=sco&sp=rl&sig=ab%2Bcd",
+        )
+        .unwrap();
+
+        assert_eq!("myaccount", connection.account_name);
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net",
+            connection.blobs_api_url
+        );
+        assert!(connection.credentials.get_account_key().is_none());
+        assert_eq!(
+            "ab+cd",
+            connection
+                .credentials
+                .get_sas_token()
+                .unwrap()
+                .get_param("sig")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sas_url() {
+        let connection = AzureStorageConnectionData::try_from_sas_url(
+            "https://myaccount.blob.core.windows.net/mycontainer?sv=2019-12-12&sr=c&sp=rl&sig=ab%2Bcd",
+        )
+        .unwrap();
+
+        assert_eq!("myaccount", connection.account_name);
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net",
+            connection.blobs_api_url
+        );
+        assert_eq!(
+            "https://myaccount.table.core.windows.net",
+            connection.table_storage_api_url
+        );
+
+        let connection = AzureStorageConnectionData::try_from_sas_url(
+            "http://127.0.0.1:10000/devstoreaccount1/mycontainer/myblob?sv=2019-12-12&sr=b&sp=r&sig=ab",
+        )
+        .unwrap();
+
+        assert_eq!("devstoreaccount1", connection.account_name);
+        assert_eq!(
+            "http://127.0.0.1:10000/devstoreaccount1",
+            connection.blobs_api_url
+        );
+        assert_eq!(
+            "http://127.0.0.1:10001/devstoreaccount1",
+            connection.queue_api_url
+        );
+        assert_eq!(
+            "http://127.0.0.1:10002/devstoreaccount1",
+            connection.table_storage_api_url
+        );
+
+        assert!(AzureStorageConnectionData::try_from_sas_url(
+            "https://myaccount.blob.core.windows.net/mycontainer"
+        )
+        .is_err());
     }
 }
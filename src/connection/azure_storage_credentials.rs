@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use crate::token_credential::TokenCredential;
+
+use super::SasToken;
+
+#[derive(Clone)]
+pub enum AzureStorageCredentials {
+    SharedKey(Vec<u8>),
+    SasToken(SasToken),
+    Token(Arc<dyn TokenCredential>),
+}
+
+impl AzureStorageCredentials {
+    pub fn get_account_key(&self) -> Option<&[u8]> {
+        match self {
+            AzureStorageCredentials::SharedKey(account_key) => Some(account_key.as_slice()),
+            _ => None,
+        }
+    }
+
+    pub fn get_sas_token(&self) -> Option<&SasToken> {
+        match self {
+            AzureStorageCredentials::SasToken(sas_token) => Some(sas_token),
+            _ => None,
+        }
+    }
+
+    pub fn get_token_credential(&self) -> Option<&Arc<dyn TokenCredential>> {
+        match self {
+            AzureStorageCredentials::Token(token_credential) => Some(token_credential),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionStringError {
+    Empty,
+    InvalidSegment { position: usize },
+    MissingKey(&'static str),
+    InvalidAccountKey(String),
+    InvalidSharedAccessSignature,
+    InvalidEndpoint { key: &'static str, value: String },
+    UnknownFormat,
+}
+
+impl std::fmt::Display for ConnectionStringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionStringError::Empty => write!(f, "Connection string is empty"),
+            ConnectionStringError::InvalidSegment { position } => write!(
+                f,
+                "Connection string segment #{} is not a Key=Value pair",
+                position
+            ),
+            ConnectionStringError::MissingKey(key) => {
+                write!(f, "Connection string does not contain {}", key)
+            }
+            ConnectionStringError::InvalidAccountKey(err) => {
+                write!(f, "AccountKey is not a valid base64 string: {}", err)
+            }
+            ConnectionStringError::InvalidSharedAccessSignature => {
+                write!(f, "SharedAccessSignature is not a valid SAS token")
+            }
+            ConnectionStringError::InvalidEndpoint { key, value } => {
+                write!(f, "{} has invalid value: {}", key, value)
+            }
+            ConnectionStringError::UnknownFormat => {
+                write!(f, "Connection string format is not recognized")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConnectionStringError {}
+
+/// Key-value pairs of an Azure storage connection string. Keys are case-insensitive.
+pub struct ConnectionStringKeys<'s> {
+    keys: HashMap<String, &'s str>,
+}
+
+impl<'s> ConnectionStringKeys<'s> {
+    pub fn parse(conn_string: &'s str) -> Result<Self, ConnectionStringError> {
+        let mut keys = HashMap::new();
+
+        for (position, kv) in conn_string.split(';').enumerate() {
+            let kv = kv.trim();
+
+            if kv.is_empty() {
+                continue;
+            }
+
+            let eq_index = match kv.find('=') {
+                Some(index) => index,
+                None => return Err(ConnectionStringError::InvalidSegment { position }),
+            };
+
+            let key = kv[..eq_index].trim();
+
+            if key.is_empty() {
+                return Err(ConnectionStringError::InvalidSegment { position });
+            }
+
+            keys.insert(key.to_lowercase(), kv[eq_index + 1..].trim());
+        }
+
+        if keys.is_empty() {
+            return Err(ConnectionStringError::Empty);
+        }
+
+        Ok(Self { keys })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&'s str> {
+        let result = self.keys.get(key.to_lowercase().as_str())?;
+
+        if result.is_empty() {
+            return None;
+        }
+
+        Some(*result)
+    }
+
+    pub fn get_required(&self, key: &'static str) -> Result<&'s str, ConnectionStringError> {
+        match self.get(key) {
+            Some(value) => Ok(value),
+            None => Err(ConnectionStringError::MissingKey(key)),
+        }
+    }
+
+    pub fn get_endpoint(&self, key: &'static str) -> Result<Option<String>, ConnectionStringError> {
+        match self.get(key) {
+            Some(value) => Ok(Some(normalize_endpoint(key, value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn is_development_storage(&self) -> bool {
+        match self.get("UseDevelopmentStorage") {
+            Some(value) => value.eq_ignore_ascii_case("true"),
+            None => false,
+        }
+    }
+}
+
+pub fn normalize_endpoint(key: &'static str, value: &str) -> Result<String, ConnectionStringError> {
+    let value = value.trim_end_matches('/');
+
+    let host_start = if value.starts_with("https://") {
+        "https://".len()
+    } else if value.starts_with("http://") {
+        "http://".len()
+    } else {
+        return Err(ConnectionStringError::InvalidEndpoint {
+            key,
+            value: value.to_string(),
+        });
+    };
+
+    if value.len() == host_start || value.contains('?') {
+        return Err(ConnectionStringError::InvalidEndpoint {
+            key,
+            value: value.to_string(),
+        });
+    }
+
+    Ok(value.to_string())
+}
+
+/// Extracts the account name from a service url. Path-style urls (emulators, IP endpoints,
+/// custom hosts) carry the account as the first path segment, the rest carry it as the first host label.
+pub fn extract_account_name(url: &str) -> Option<String> {
+    let (host, path) = split_host_and_path(url)?;
+
+    if is_path_style_host(host) {
+        let account_name = path.split('/').find(|itm| !itm.is_empty())?;
+        return Some(account_name.to_string());
+    }
+
+    let account_name = host.split('.').next()?;
+
+    if account_name.is_empty() {
+        return None;
+    }
+
+    Some(account_name.to_string())
+}
+
+/// Returns scheme://host[:port] plus the account segment for path-style urls.
+pub fn extract_service_root(url: &str) -> Option<String> {
+    let (host, _) = split_host_and_path(url)?;
+
+    let scheme_end = url.find("://")? + 3;
+    let authority_end = scheme_end + url[scheme_end..].find('/').unwrap_or(url.len() - scheme_end);
+
+    let root = &url[..authority_end];
+
+    if is_path_style_host(host) {
+        return Some(format!("{}/{}", root, extract_account_name(url)?));
+    }
+
+    Some(root.to_string())
+}
+
+fn split_host_and_path(url: &str) -> Option<(&str, &str)> {
+    let scheme_end = url.find("://")? + 3;
+    let url = &url[scheme_end..];
+
+    let (authority, path) = match url.find('/') {
+        Some(index) => (&url[..index], &url[index..]),
+        None => (url, ""),
+    };
+
+    // An IPv6 host is bracketed and the port follows the closing bracket
+    let host = if authority.starts_with('[') {
+        &authority[..authority.find(']')? + 1]
+    } else {
+        match authority.rfind(':') {
+            Some(index) => &authority[..index],
+            None => authority,
+        }
+    };
+
+    Some((host, path))
+}
+
+const STORAGE_SERVICES: [&str; 6] = ["blob", "table", "queue", "file", "dfs", "web"];
+
+/// Only `{account}.{service}.{endpoint suffix}` hosts (e.g. myaccount.blob.core.windows.net)
+/// carry the account in the host, any other host is path-style.
+fn is_path_style_host(host: &str) -> bool {
+    if host.starts_with('[') {
+        return true;
+    }
+
+    let labels: Vec<&str> = host.split('.').collect();
+
+    let is_virtual_hosted =
+        labels.len() >= 4 && !labels[0].is_empty() && STORAGE_SERVICES.contains(&labels[1]);
+
+    !is_virtual_hosted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_are_case_insensitive_and_value_may_contain_eq() {
+        let keys =
+            ConnectionStringKeys::parse("accountname=test;AccountKey=YWJj==;").unwrap();
+
+        assert_eq!("test", keys.get("AccountName").unwrap());
+        assert_eq!("YWJj==", keys.get("accountkey").unwrap());
+        assert_eq!(None, keys.get("EndpointSuffix"));
+    }
+
+    #[test]
+    fn test_segment_without_eq_is_an_error() {
+        let result = ConnectionStringKeys::parse("AccountName=test;garbage");
+
+        assert_eq!(
+            ConnectionStringError::InvalidSegment { position: 1 },
+            result.err().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_endpoint_must_be_http_url() {
+        assert_eq!(
+            "http://127.0.0.1:10000/devstoreaccount1",
+            normalize_endpoint("BlobEndpoint", "http://127.0.0.1:10000/devstoreaccount1/")
+                .unwrap()
+        );
+
+        assert!(normalize_endpoint("BlobEndpoint", "127.0.0.1:10000").is_err());
+    }
+
+    #[test]
+    fn test_extract_account_name() {
+        assert_eq!(
+            "myaccount",
+            extract_account_name("https://myaccount.blob.core.windows.net/container").unwrap()
+        );
+
+        assert_eq!(
+            "devstoreaccount1",
+            extract_account_name("http://127.0.0.1:10000/devstoreaccount1/container").unwrap()
+        );
+
+        assert_eq!(
+            "http://127.0.0.1:10000/devstoreaccount1",
+            extract_service_root("http://127.0.0.1:10000/devstoreaccount1/container/blob")
+                .unwrap()
+        );
+
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net",
+            extract_service_root("https://myaccount.blob.core.windows.net/container").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_custom_and_ipv6_hosts_are_path_style() {
+        assert_eq!(
+            "devstoreaccount1",
+            extract_account_name("http://azurite:10000/devstoreaccount1").unwrap()
+        );
+
+        assert_eq!(
+            "http://azurite:10000/devstoreaccount1",
+            extract_service_root("http://azurite:10000/devstoreaccount1/container").unwrap()
+        );
+
+        assert_eq!(
+            "devstoreaccount1",
+            extract_account_name("http://[::1]:10000/devstoreaccount1").unwrap()
+        );
+
+        assert_eq!(
+            "http://[::1]:10000/devstoreaccount1",
+            extract_service_root("http://[::1]:10000/devstoreaccount1/container").unwrap()
+        );
+
+        assert_eq!(
+            "devstoreaccount1",
+            extract_account_name("http://[::1]/devstoreaccount1").unwrap()
+        );
+    }
+}
@@ -1,14 +1,26 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use rust_extensions::SliceOrVec;
-use tokio::sync::Mutex;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, SliceOrVec};
+use tokio::sync::{Mutex, MutexGuard};
 
-use crate::{blob::BlobProperties, sdk_files::utils::FileConnectionInfo, AzureStorageError};
+use crate::{
+    blob::{BlobAccessConditions, BlobProperties},
+    blob_lease::local_lease::LocalLease,
+    blob_version::LocalRetentionPolicy,
+    sdk_files::utils::FileConnectionInfo,
+    AzureStorageError, StorageClock, SystemClock,
+};
 
 use super::PageBlobFileEngine;
 
 pub struct FileConnectionData {
     page_data: Mutex<HashMap<String, PageBlobFileEngine>>,
+    // Blob writes check access conditions and leases and write under this lock, so a condition can not go stale in between
+    blob_writes: Mutex<()>,
+    // Leases live in the process only - they are not shared with other processes using the same folder
+    leases: Mutex<HashMap<String, LocalLease>>,
+    clock: Arc<dyn StorageClock>,
+    retention: LocalRetentionPolicy,
     pub root_path: String,
     pub path_separator: char,
 }
@@ -36,9 +48,91 @@ impl FileConnectionData {
             root_path: root_path.to_string(),
             path_separator,
             page_data: Mutex::new(HashMap::new()),
+            blob_writes: Mutex::new(()),
+            leases: Mutex::new(HashMap::new()),
+            clock: Arc::new(SystemClock),
+            retention: LocalRetentionPolicy::default(),
         }
     }
 
+    /// Clock of lease expiry
+    pub fn with_clock(mut self, clock: Arc<dyn StorageClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Versioning and soft delete emulation
+    pub fn with_retention_policy(mut self, retention: LocalRetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    pub fn get_retention_policy(&self) -> &LocalRetentionPolicy {
+        &self.retention
+    }
+
+    /// Moment blobs and containers are soft-deleted at and expire against
+    pub fn now(&self) -> DateTimeAsMicroseconds {
+        self.clock.now()
+    }
+
+    pub async fn lock_blob_writes(&self) -> MutexGuard<'_, ()> {
+        self.blob_writes.lock().await
+    }
+
+    /// Runs `action` against the lease of the blob. Leases which end up available are forgotten
+    pub async fn with_lease<TResult>(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        action: impl FnOnce(
+            &mut LocalLease,
+            DateTimeAsMicroseconds,
+        ) -> Result<TResult, AzureStorageError>,
+    ) -> Result<TResult, AzureStorageError> {
+        let id = crate::sdk_files::utils::compile_blob_path(self, container_name, blob_name);
+
+        let mut leases = self.leases.lock().await;
+
+        let lease = leases.entry(id.to_string()).or_insert_with(LocalLease::new);
+        let result = action(lease, self.clock.now());
+
+        if lease.is_available() {
+            leases.remove(id.as_str());
+        }
+
+        result
+    }
+
+    pub async fn check_lease_access(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: Option<&str>,
+        is_write: bool,
+    ) -> Result<(), AzureStorageError> {
+        self.with_lease(container_name, blob_name, |lease, now| {
+            lease.check_access(lease_id, is_write, now)
+        })
+        .await
+    }
+
+    pub async fn drop_leases_of_container(&self, container_name: &str) {
+        let prefix = format!(
+            "{}{}",
+            crate::sdk_files::utils::compile_container_path(self, container_name),
+            self.path_separator
+        );
+
+        let mut leases = self.leases.lock().await;
+        leases.retain(|id, _| !id.starts_with(prefix.as_str()));
+    }
+
+    pub async fn drop_lease(&self, container_name: &str, blob_name: &str) {
+        let id = crate::sdk_files::utils::compile_blob_path(self, container_name, blob_name);
+        self.leases.lock().await.remove(id.as_str());
+    }
+
     pub async fn apply_page_blob_data(
         &self,
         id: String,
@@ -60,6 +154,46 @@ impl FileConnectionData {
         page_data_access.remove(id.as_str());
     }
 
+    /// Page blob data opened by the storage backend is keyed by the file path of the blob
+    pub fn get_page_blob_id(&self, container_name: &str, blob_name: &str) -> String {
+        crate::sdk_files::utils::compile_blob_path(self, container_name, blob_name)
+    }
+
+    pub async fn get_or_apply_page_blob_data(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> String {
+        let id = self.get_page_blob_id(container_name, blob_name);
+
+        let mut page_data_access = self.page_data.lock().await;
+
+        if !page_data_access.contains_key(id.as_str()) {
+            let engine = PageBlobFileEngine::new(
+                self.root_path.to_string(),
+                self.path_separator,
+                container_name.to_string(),
+                blob_name.to_string(),
+            );
+
+            page_data_access.insert(id.to_string(), engine);
+        }
+
+        id
+    }
+
+    /// Closes page blob files of the container, so removing the folder does not leave open handles
+    pub async fn drop_page_blob_data_of_container(&self, container_name: &str) {
+        let prefix = format!(
+            "{}{}",
+            crate::sdk_files::utils::compile_container_path(self, container_name),
+            self.path_separator
+        );
+
+        let mut page_data_access = self.page_data.lock().await;
+        page_data_access.retain(|id, _| !id.starts_with(prefix.as_str()));
+    }
+
     pub async fn download(&self, id: &str) -> Result<Vec<u8>, AzureStorageError> {
         let mut write_access = self.page_data.lock().await;
 
@@ -119,11 +253,12 @@ impl FileConnectionData {
         &self,
         id: &str,
         pages_amount: usize,
+        metadata: HashMap<String, String>,
     ) -> Result<(), AzureStorageError> {
         let mut write_access = self.page_data.lock().await;
 
         if let Some(page_blob_file) = write_access.get_mut(id) {
-            return page_blob_file.create_blob(pages_amount).await;
+            return page_blob_file.create_blob(pages_amount, metadata).await;
         }
 
         panic!("{}", PANIC_MESSAGE);
@@ -183,11 +318,14 @@ impl FileConnectionData {
         id: &str,
         start_page_no: usize,
         payload: impl Into<SliceOrVec<'s, u8>>,
-    ) -> Result<(), AzureStorageError> {
+        conditions: &BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
         let mut write_access = self.page_data.lock().await;
 
         if let Some(page_blob_file) = write_access.get_mut(id) {
-            return page_blob_file.save_pages(start_page_no, payload).await;
+            return page_blob_file
+                .save_pages(start_page_no, payload, conditions)
+                .await;
         }
 
         panic!("{}", PANIC_MESSAGE);
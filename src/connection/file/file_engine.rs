@@ -1,11 +1,14 @@
+use std::collections::HashMap;
 use std::io::SeekFrom;
 
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_extensions::SliceOrVec;
 use tokio::fs::{self, File, OpenOptions};
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 
-use crate::blob::BlobProperties;
+use crate::blob::{BlobAccessConditions, BlobProperties, BlobType};
 use crate::page_blob::consts::BLOB_PAGE_SIZE;
+use crate::sdk_files::blob_sidecar::BlobSidecar;
 use crate::sdk_files::utils::FileConnectionInfo;
 use crate::AzureStorageError;
 
@@ -52,7 +55,7 @@ impl PageBlobFileEngine {
             crate::sdk_files::utils::compile_container_path(self, self.container_name.as_str());
 
         if !fs::metadata(folder_name.as_str()).await.is_ok() {
-            return Err(AzureStorageError::ContainerNotFound);
+            return Err(AzureStorageError::ContainerNotFound(None));
         }
 
         let file_name = crate::sdk_files::utils::compile_blob_path(
@@ -75,7 +78,7 @@ impl PageBlobFileEngine {
             }
             Err(err) => match err.kind() {
                 std::io::ErrorKind::NotFound => {
-                    return Err(AzureStorageError::BlobNotFound);
+                    return Err(AzureStorageError::BlobNotFound(None));
                 }
 
                 _ => Err(AzureStorageError::UnknownError {
@@ -115,6 +118,7 @@ impl PageBlobFileEngine {
             .await?;
 
         file_access.write_all(payload.into().as_slice()).await?;
+        file_access.flush().await?;
 
         return Ok(());
     }
@@ -149,16 +153,20 @@ impl PageBlobFileEngine {
         }
     }
 
-    pub async fn create_blob(&mut self, pages_amount: usize) -> Result<(), AzureStorageError> {
+    pub async fn create_blob(
+        &mut self,
+        pages_amount: usize,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError> {
         if self.file.is_some() {
-            return Err(AzureStorageError::BlobAlreadyExists);
+            return Err(AzureStorageError::BlobAlreadyExists(None));
         }
 
         let folder_name =
             crate::sdk_files::utils::compile_container_path(self, self.container_name.as_str());
 
         if tokio::fs::metadata(folder_name.as_str()).await.is_err() {
-            return Err(AzureStorageError::ContainerNotFound);
+            return Err(AzureStorageError::ContainerNotFound(None));
         }
 
         let file_name = crate::sdk_files::utils::compile_blob_path(
@@ -168,7 +176,7 @@ impl PageBlobFileEngine {
         );
 
         if tokio::fs::metadata(file_name.as_str()).await.is_ok() {
-            return Err(AzureStorageError::BlobAlreadyExists);
+            return Err(AzureStorageError::BlobAlreadyExists(None));
         }
 
         let file = tokio::fs::File::create(file_name.as_str()).await?;
@@ -179,7 +187,7 @@ impl PageBlobFileEngine {
 
         self.file = None;
 
-        Ok(())
+        self.write_sidecar(metadata).await
     }
 
     pub async fn create_blob_if_not_exists(
@@ -187,14 +195,14 @@ impl PageBlobFileEngine {
         pages_amount: usize,
     ) -> Result<BlobProperties, AzureStorageError> {
         if self.file.is_some() {
-            return Err(AzureStorageError::BlobAlreadyExists);
+            return Err(AzureStorageError::BlobAlreadyExists(None));
         }
 
         let folder_name =
             crate::sdk_files::utils::compile_container_path(self, self.container_name.as_str());
 
         if tokio::fs::metadata(folder_name.as_str()).await.is_err() {
-            return Err(AzureStorageError::ContainerNotFound);
+            return Err(AzureStorageError::ContainerNotFound(None));
         }
 
         let file_name = crate::sdk_files::utils::compile_blob_path(
@@ -203,8 +211,8 @@ impl PageBlobFileEngine {
             self.blob_name.as_str(),
         );
 
-        if self.get_file_mut().await.is_ok() {
-            return crate::sdk_files::utils::get_blob_properties(file_name.as_str()).await;
+        if tokio::fs::metadata(file_name.as_str()).await.is_ok() {
+            return self.get_blob_properties().await;
         }
 
         let file = tokio::fs::File::create(file_name.as_str()).await?;
@@ -212,11 +220,34 @@ impl PageBlobFileEngine {
 
         self.resize(pages_amount).await?;
 
-        let result = crate::sdk_files::utils::get_blob_properties(file_name.as_str()).await;
-
         self.file = None;
 
-        result
+        self.write_sidecar(HashMap::new()).await?;
+
+        self.get_blob_properties().await
+    }
+
+    async fn get_blob_properties(&self) -> Result<BlobProperties, AzureStorageError> {
+        crate::sdk_files::utils::get_blob_properties(
+            self,
+            self.container_name.as_str(),
+            self.blob_name.as_str(),
+        )
+        .await
+    }
+
+    async fn write_sidecar(
+        &self,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError> {
+        BlobSidecar::new(BlobType::PageBlob)
+            .with_metadata(metadata)
+            .write(self, self.container_name.as_str(), self.blob_name.as_str())
+            .await
+    }
+
+    async fn delete_sidecar(&self) {
+        BlobSidecar::delete(self, self.container_name.as_str(), self.blob_name.as_str()).await;
     }
 
     pub async fn get(
@@ -242,19 +273,43 @@ impl PageBlobFileEngine {
         Ok(result)
     }
 
+    /// Returns ETag of the blob after the write
     pub async fn save_pages<'s>(
         &mut self,
         start_page_no: usize,
         payload: impl Into<SliceOrVec<'s, u8>>,
-    ) -> Result<(), AzureStorageError> {
+        conditions: &BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        if !conditions.is_empty() {
+            let properties = self.get_blob_properties().await?;
+
+            conditions.check(Some((
+                properties.etag.as_deref().unwrap_or_default(),
+                properties
+                    .last_modified
+                    .unwrap_or(DateTimeAsMicroseconds::new(0)),
+            )))?;
+        }
+
         let file = self.get_file_mut().await?;
         let pos = BLOB_PAGE_SIZE * start_page_no;
 
         file.seek(SeekFrom::Start(pos as u64)).await?;
 
         file.write_all(payload.into().as_slice()).await?;
+        file.flush().await?;
 
-        Ok(())
+        let file_name = crate::sdk_files::utils::compile_blob_path(
+            self,
+            self.container_name.as_str(),
+            self.blob_name.as_str(),
+        );
+
+        crate::sdk_files::utils::touch_file(file_name.as_str()).await?;
+
+        let properties = self.get_blob_properties().await?;
+
+        Ok(properties.etag.unwrap_or_default())
     }
 
     pub async fn delete_blob(&mut self) -> Result<(), AzureStorageError> {
@@ -265,6 +320,7 @@ impl PageBlobFileEngine {
             self.blob_name.as_str(),
         );
         tokio::fs::remove_file(file_name.as_str()).await?;
+        self.delete_sidecar().await;
         Ok(())
     }
 
@@ -276,15 +332,16 @@ impl PageBlobFileEngine {
             self.blob_name.as_str(),
         );
 
-        match tokio::fs::remove_file(file_name.as_str()).await {
-            Ok(_) => Ok(()),
-            Err(_) => Ok(()),
-        }
+        let _ = tokio::fs::remove_file(file_name.as_str()).await;
+        self.delete_sidecar().await;
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
     use crate::connection::FileConnectionData;
 
     #[tokio::test]
@@ -335,7 +392,10 @@ mod test {
             .await
             .unwrap();
 
-        connection_data.create_blob(id, 1).await.unwrap();
+        connection_data
+            .create_blob(id, 1, HashMap::new())
+            .await
+            .unwrap();
 
         connection_data
             .delete_container_if_exists(id)
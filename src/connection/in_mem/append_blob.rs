@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use crate::{append_blob::AppendBlockConditions, AzureStorageError};
+
+/// Content is shared with the snapshots of the blob and copied on the first append after a snapshot
+#[derive(Clone, Default)]
+pub struct AppendBlobInMem {
+    content: Arc<Vec<u8>>,
+    committed_block_count: u64,
+    sealed: bool,
+}
+
+impl AppendBlobInMem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_content(&self) -> &[u8] {
+        self.content.as_slice()
+    }
+
+    pub fn get_committed_block_count(&self) -> u64 {
+        self.committed_block_count
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.sealed
+    }
+
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    /// Returns the offset the block is appended at
+    pub fn append_block(
+        &mut self,
+        block: Vec<u8>,
+        conditions: &AppendBlockConditions,
+    ) -> Result<u64, AzureStorageError> {
+        if self.sealed {
+            return Err(AzureStorageError::BlobIsSealed(None));
+        }
+
+        let append_offset = self.content.len() as u64;
+
+        conditions.check(append_offset, self.committed_block_count, block.len())?;
+
+        Arc::make_mut(&mut self.content).extend_from_slice(block.as_slice());
+        self.committed_block_count += 1;
+
+        Ok(append_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        append_blob::{
+            AppendBlobApi, AppendBlockConditions, CreateAppendBlobOptions, MAX_APPEND_BLOCKS,
+        },
+        blob::{BlobAccessConditions, BlobApi, BlobType},
+        blob_container::BlobContainersApi,
+        block_blob::BlockBlobApi,
+        AzureStorageConnection, AzureStorageError,
+    };
+
+    #[tokio::test]
+    async fn test_append_blob_is_emulated_by_in_mem_backend() {
+        let connection = AzureStorageConnection::new_in_memory();
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .create_append_blob_with_options(
+                "test",
+                "log",
+                CreateAppendBlobOptions::new().with_metadata("tenant", "acme"),
+            )
+            .await
+            .unwrap();
+
+        let result = connection
+            .append_block("test", "log", b"abc".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(0, result.append_offset);
+        assert_eq!(1, result.committed_block_count);
+
+        let result = connection
+            .append_block_with_conditions(
+                "test",
+                "log",
+                b"de".to_vec(),
+                AppendBlockConditions::new()
+                    .with_append_position(3)
+                    .with_max_size(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(3, result.append_offset);
+        assert_eq!(2, result.committed_block_count);
+
+        let result = connection
+            .append_block_with_conditions(
+                "test",
+                "log",
+                b"f".to_vec(),
+                AppendBlockConditions::new().with_append_position(3),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::AppendPositionConditionNotMet(_))
+        ));
+
+        let result = connection
+            .append_block_with_conditions(
+                "test",
+                "log",
+                b"f".to_vec(),
+                AppendBlockConditions::new().with_max_size(5),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::MaxBlobSizeConditionNotMet(_))
+        ));
+
+        assert_eq!(
+            b"abcde".to_vec(),
+            connection.download_blob("test", "log").await.unwrap()
+        );
+
+        let props = connection.get_blob_properties("test", "log").await.unwrap();
+        assert_eq!(BlobType::AppendBlob, props.blob_type);
+        assert_eq!(5, props.blob_size);
+        assert_eq!(Some(2), props.committed_block_count);
+        assert_eq!(Some(false), props.is_sealed);
+        assert_eq!(
+            Some("acme"),
+            props.metadata.get("tenant").map(|v| v.as_str())
+        );
+
+        connection
+            .upload_block_blob("test", "block-blob", b"abc".to_vec())
+            .await
+            .unwrap();
+
+        let result = connection
+            .append_block("test", "block-blob", b"f".to_vec())
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::InvalidBlobType(_))));
+
+        let result = connection
+            .append_block("test", "not-exists", b"f".to_vec())
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        let result = connection
+            .seal_append_blob_with_conditions(
+                "test",
+                "log",
+                BlobAccessConditions::new().with_if_match("\"wrong\""),
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::ConditionNotMet(_))));
+
+        connection.seal_append_blob("test", "log").await.unwrap();
+
+        let result = connection.append_block("test", "log", b"f".to_vec()).await;
+        assert!(matches!(result, Err(AzureStorageError::BlobIsSealed(_))));
+
+        let props = connection.get_blob_properties("test", "log").await.unwrap();
+        assert_eq!(Some(true), props.is_sealed);
+
+        // Recreating the blob starts it over
+        connection.create_append_blob("test", "log").await.unwrap();
+
+        for _ in 0..MAX_APPEND_BLOCKS {
+            connection
+                .append_block("test", "log", b"a".to_vec())
+                .await
+                .unwrap();
+        }
+
+        let result = connection.append_block("test", "log", b"a".to_vec()).await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::BlockCountExceedsLimit(_))
+        ));
+    }
+}
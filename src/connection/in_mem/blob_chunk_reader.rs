@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    blob::BlobAccessConditions,
+    blob_stream::{BlobChunkReader, BlobDownloadStream},
+    AzureStorageError,
+};
+
+use super::ContainerInMem;
+
+/// Reads the blob chunk by chunk, the way Azure does. Chunks of a blob overwritten
+/// in the middle of the download fail with ConditionNotMet
+pub struct MemBlobChunkReader {
+    container: Arc<ContainerInMem>,
+    blob_name: String,
+    conditions: BlobAccessConditions,
+    position: u64,
+    blob_size: u64,
+    chunk_size: u64,
+}
+
+impl MemBlobChunkReader {
+    pub async fn open(
+        container: Arc<ContainerInMem>,
+        blob_name: &str,
+        chunk_size: usize,
+    ) -> Result<BlobDownloadStream, AzureStorageError> {
+        let properties = container.get_blob_properties(blob_name).await?;
+
+        let blob_size = properties.blob_size as u64;
+
+        let mut conditions = BlobAccessConditions::new();
+        if let Some(etag) = properties.etag {
+            conditions = conditions.with_if_match(etag);
+        }
+
+        let reader = Self {
+            container,
+            blob_name: blob_name.to_string(),
+            conditions,
+            position: 0,
+            blob_size,
+            chunk_size: chunk_size as u64,
+        };
+
+        Ok(BlobDownloadStream::new(blob_size, Box::new(reader)))
+    }
+}
+
+#[async_trait]
+impl BlobChunkReader for MemBlobChunkReader {
+    async fn read_chunk(&mut self) -> Result<Option<Vec<u8>>, AzureStorageError> {
+        if self.position >= self.blob_size {
+            return Ok(None);
+        }
+
+        let length = self.chunk_size.min(self.blob_size - self.position);
+
+        let chunk = self
+            .container
+            .download_range_with_conditions(
+                self.blob_name.as_str(),
+                self.position,
+                Some(length),
+                &self.conditions,
+            )
+            .await?;
+
+        self.position += chunk.len() as u64;
+
+        Ok(Some(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        blob_container::BlobContainersApi, blob_stream::BlobStreamApi, block_blob::BlockBlobApi,
+        page_blob::AzurePageBlobStorage, AzureStorageConnection, AzureStorageError,
+    };
+
+    #[tokio::test]
+    async fn test_download_stream_is_emulated_by_in_mem_backend() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![1u8, 2u8, 3u8, 4u8, 5u8])
+            .await
+            .unwrap();
+
+        let mut stream = connection
+            .download_stream_with_chunk_size("test", "blob", 2)
+            .await
+            .unwrap();
+
+        assert_eq!(Some(vec![1u8, 2u8]), stream.get_next().await.unwrap());
+        assert_eq!(Some(vec![3u8, 4u8]), stream.get_next().await.unwrap());
+        assert_eq!(Some(vec![5u8]), stream.get_next().await.unwrap());
+        assert_eq!(None, stream.get_next().await.unwrap());
+
+        let page_blob = AzurePageBlobStorage::new(connection.clone(), "test", "pages").await;
+        page_blob.create(2).await.unwrap();
+        page_blob.save_pages(1, vec![7u8; 512]).await.unwrap();
+
+        let stream = connection
+            .download_stream_with_chunk_size("test", "pages", 300)
+            .await
+            .unwrap();
+        assert_eq!(1024, stream.get_blob_size());
+
+        let content = stream.read_to_end().await.unwrap();
+        assert_eq!(page_blob.get_pages(0, 2).await.unwrap(), content);
+
+        let result = connection.download_stream("test", "not-exists").await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_blob_overwritten_during_the_stream_fails_with_condition_not_met() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![1u8, 2u8, 3u8, 4u8, 5u8])
+            .await
+            .unwrap();
+
+        let mut stream = connection
+            .download_stream_with_chunk_size("test", "blob", 2)
+            .await
+            .unwrap();
+
+        assert_eq!(Some(vec![1u8, 2u8]), stream.get_next().await.unwrap());
+
+        connection
+            .upload_block_blob("test", "blob", vec![9u8; 5])
+            .await
+            .unwrap();
+
+        let result = stream.get_next().await;
+        assert!(matches!(result, Err(AzureStorageError::ConditionNotMet(_))));
+
+        // After an error the stream is over
+        assert_eq!(None, stream.get_next().await.unwrap());
+    }
+}
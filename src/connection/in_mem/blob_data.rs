@@ -1,32 +1,872 @@
-use crate::blob::BlobProperties;
+use std::collections::{BTreeMap, HashMap};
 
-use super::{block_blob::BlockBlob, PageBlobInMem};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-pub enum BlobData {
+use crate::{
+    blob::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, BlobType},
+    blob_copy::BlobCopyState,
+    blob_lease::local_lease::LocalLease,
+    AzureStorageError,
+};
+
+use super::{block_blob::BlockBlob, AppendBlobInMem, PageBlobInMem};
+
+#[derive(Clone)]
+pub enum BlobContent {
     BlockBlob(BlockBlob),
     PageBlob(PageBlobInMem),
+    AppendBlob(AppendBlobInMem),
+}
+
+pub struct BlobData {
+    pub content: BlobContent,
+    etag: String,
+    creation_time: DateTimeAsMicroseconds,
+    last_modified: DateTimeAsMicroseconds,
+    metadata: HashMap<String, String>,
+    http_headers: BlobHttpHeaders,
+    /// Survives overwrites of the blob, as the Azure lease does
+    pub lease: LocalLease,
+    /// Snapshot id -> read-only copy of the blob. Ids sort chronologically. Survive overwrites as well
+    pub snapshots: BTreeMap<String, BlobData>,
+    /// Set only if the container keeps versions
+    version_id: Option<String>,
+    /// Set if the blob is the destination of a copy. The next write clears it
+    pub copy_state: Option<BlobCopyState>,
 }
 
 impl BlobData {
+    pub fn new(
+        content: BlobContent,
+        metadata: HashMap<String, String>,
+        http_headers: BlobHttpHeaders,
+    ) -> Self {
+        let (etag, now) = generate_etag();
+        Self {
+            content,
+            etag,
+            creation_time: now,
+            last_modified: now,
+            metadata: normalize_metadata(metadata),
+            http_headers,
+            lease: LocalLease::new(),
+            snapshots: BTreeMap::new(),
+            version_id: None,
+            copy_state: None,
+        }
+    }
+
+    /// Copy of the content and properties, without the lease and snapshots.
+    /// Content is shared with the blob until the blob is written to
+    pub fn read_only_copy(&self) -> BlobData {
+        BlobData {
+            content: self.content.clone(),
+            etag: self.etag.clone(),
+            creation_time: self.creation_time,
+            last_modified: self.last_modified,
+            metadata: self.metadata.clone(),
+            http_headers: self.http_headers.clone(),
+            lease: LocalLease::new(),
+            snapshots: BTreeMap::new(),
+            version_id: self.version_id.clone(),
+            copy_state: self.copy_state.clone(),
+        }
+    }
+
+    /// Returns the snapshot id
+    pub fn create_snapshot(&mut self) -> String {
+        let snapshot_id = generate_timestamp_id();
+
+        self.snapshots
+            .insert(snapshot_id.clone(), self.read_only_copy());
+
+        snapshot_id
+    }
+
+    pub fn get_version_id(&self) -> Option<&str> {
+        self.version_id.as_deref()
+    }
+
+    /// Gives the blob a new version id - the blob state becomes a new version
+    pub fn stamp_version(&mut self) {
+        self.version_id = Some(generate_timestamp_id());
+    }
+
     pub fn download(&self) -> Vec<u8> {
-        match self {
-            BlobData::BlockBlob(block_blob) => return block_blob.get_content().to_vec(),
-            BlobData::PageBlob(page_blob) => return page_blob.download(),
+        match &self.content {
+            BlobContent::BlockBlob(block_blob) => return block_blob.get_content().to_vec(),
+            BlobContent::PageBlob(page_blob) => return page_blob.download(),
+            BlobContent::AppendBlob(append_blob) => return append_blob.get_content().to_vec(),
         }
     }
 
+    /// Slice of the content. Only the pages the range touches are read from a page blob
+    pub fn download_range(
+        &self,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let range = crate::blob::resolve_range(offset, length, self.get_size() as u64)?;
+        let range = range.start as usize..range.end as usize;
+
+        match &self.content {
+            BlobContent::BlockBlob(block_blob) => Ok(block_blob.get_content()[range].to_vec()),
+            BlobContent::PageBlob(page_blob) => Ok(page_blob.download_range(range)),
+            BlobContent::AppendBlob(append_blob) => Ok(append_blob.get_content()[range].to_vec()),
+        }
+    }
+
+    pub fn get_size(&self) -> usize {
+        match &self.content {
+            BlobContent::BlockBlob(block_blob) => block_blob.get_content().len(),
+            BlobContent::PageBlob(page_blob) => page_blob.get_size(),
+            BlobContent::AppendBlob(append_blob) => append_blob.get_content().len(),
+        }
+    }
+
+    pub fn get_blob_type(&self) -> BlobType {
+        match &self.content {
+            BlobContent::BlockBlob(_) => BlobType::BlockBlob,
+            BlobContent::PageBlob(_) => BlobType::PageBlob,
+            BlobContent::AppendBlob(_) => BlobType::AppendBlob,
+        }
+    }
+
+    /// Must be called after every content change - the same way Azure issues a new ETag on each write
+    pub fn update_last_modified(&mut self) {
+        let (etag, now) = generate_etag();
+        self.etag = etag;
+        self.last_modified = now;
+    }
+
+    pub fn get_etag(&self) -> &str {
+        self.etag.as_str()
+    }
+
+    pub fn get_last_modified(&self) -> DateTimeAsMicroseconds {
+        self.last_modified
+    }
+
+    /// Lease first, then ETag and date conditions
+    pub fn check_conditions(
+        &self,
+        conditions: &BlobAccessConditions,
+        is_write: bool,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<(), AzureStorageError> {
+        self.lease
+            .check_access(conditions.lease_id.as_deref(), is_write, now)?;
+        conditions.check(Some((self.get_etag(), self.last_modified)))
+    }
+
+    pub fn get_metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// Replaces the whole metadata set, as Set Blob Metadata does
+    pub fn set_metadata(&mut self, metadata: HashMap<String, String>) {
+        self.metadata = normalize_metadata(metadata);
+        self.update_last_modified();
+    }
+
+    pub fn get_http_headers(&self) -> &BlobHttpHeaders {
+        &self.http_headers
+    }
+
+    pub fn set_http_headers(&mut self, http_headers: BlobHttpHeaders) {
+        self.http_headers = http_headers;
+        self.update_last_modified();
+    }
+
     pub fn get_blob_properties(&self) -> BlobProperties {
-        match self {
-            BlobData::BlockBlob(block_blob) => {
-                return BlobProperties {
-                    blob_size: block_blob.get_content().len(),
-                }
-            }
-            BlobData::PageBlob(page_blob) => {
-                return BlobProperties {
-                    blob_size: page_blob.get_size(),
-                }
+        let mut result = BlobProperties::new(self.get_blob_type(), self.get_size());
+
+        result.etag = Some(self.etag.clone());
+        result.creation_time = Some(self.creation_time);
+        result.last_modified = Some(self.last_modified);
+        result.metadata = self.metadata.clone();
+        result.set_http_headers(&self.http_headers);
+
+        match &self.content {
+            BlobContent::BlockBlob(_) => {}
+            BlobContent::PageBlob(_) => result.sequence_number = Some(0),
+            BlobContent::AppendBlob(append_blob) => {
+                result.committed_block_count = Some(append_blob.get_committed_block_count());
+                result.is_sealed = Some(append_blob.is_sealed());
             }
         }
+
+        result
+    }
+}
+
+// Azure hands metadata names back lowercased through our transport, so local backends do the same
+fn normalize_metadata(metadata: HashMap<String, String>) -> HashMap<String, String> {
+    metadata
+        .into_iter()
+        .map(|(name, value)| (name.to_lowercase(), value))
+        .collect()
+}
+
+/// Snapshot ids and version ids are timestamps, so they sort chronologically
+fn generate_timestamp_id() -> String {
+    crate::sdk_azure::http_date::to_azure_timestamp(crate::blob::next_write_moment())
+}
+
+fn generate_etag() -> (String, DateTimeAsMicroseconds) {
+    let now = crate::blob::next_write_moment();
+    (format!("\"0x{:X}\"", now.unix_microseconds), now)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::*;
+    use crate::{
+        blob::{BlobAccessConditions, BlobApi, LeaseState, LeaseStatus},
+        blob_container::BlobContainersApi,
+        blob_lease::{BlobLeaseApi, LeaseDuration},
+        blob_snapshot::{BlobSnapshotApi, DeleteSnapshotsOption},
+        block_blob::{BlockBlobApi, UploadBlockBlobOptions},
+        page_blob::AzurePageBlobStorage,
+        AzureStorageConnection, ManualClock, StorageError,
+    };
+
+    #[test]
+    fn test_etag_changes_on_update() {
+        let mut blob_data = BlobData::new(
+            BlobContent::BlockBlob(BlockBlob::new(vec![1, 2, 3])),
+            HashMap::new(),
+            BlobHttpHeaders::new(),
+        );
+
+        let before = blob_data.get_blob_properties();
+
+        blob_data.update_last_modified();
+
+        let after = blob_data.get_blob_properties();
+
+        assert_ne!(before.etag, after.etag);
+        assert_eq!(
+            before.creation_time.unwrap().unix_microseconds,
+            after.creation_time.unwrap().unix_microseconds
+        );
+        assert!(
+            after.last_modified.unwrap().unix_microseconds
+                > before.last_modified.unwrap().unix_microseconds
+        );
+    }
+
+    #[test]
+    fn test_page_blob_properties() {
+        let blob_data = BlobData::new(
+            BlobContent::PageBlob(PageBlobInMem::new(2)),
+            HashMap::new(),
+            BlobHttpHeaders::new(),
+        );
+
+        let result = blob_data.get_blob_properties();
+
+        assert_eq!(BlobType::PageBlob, result.blob_type);
+        assert_eq!(1024, result.blob_size);
+        assert_eq!(Some(0), result.sequence_number);
+    }
+
+    #[test]
+    fn test_snapshot_keeps_content_of_the_moment() {
+        let mut blob_data = BlobData::new(
+            BlobContent::PageBlob(PageBlobInMem::new(2)),
+            HashMap::new(),
+            BlobHttpHeaders::new(),
+        );
+
+        let etag_before = blob_data.get_etag().to_string();
+
+        let first = blob_data.create_snapshot();
+
+        if let BlobContent::PageBlob(page_blob) = &mut blob_data.content {
+            page_blob.save_pages(1, vec![1u8; 512]).unwrap();
+        }
+        blob_data.update_last_modified();
+
+        let second = blob_data.create_snapshot();
+
+        assert!(first < second);
+        assert_eq!(
+            vec![first.clone(), second.clone()],
+            blob_data.snapshots.keys().cloned().collect::<Vec<_>>()
+        );
+
+        let snapshot = blob_data.snapshots.get(&first).unwrap();
+        assert_eq!(vec![0u8; 1024], snapshot.download());
+        assert_eq!(etag_before, snapshot.get_etag());
+
+        let snapshot = blob_data.snapshots.get(&second).unwrap();
+        assert_eq!(vec![1u8; 512], snapshot.download()[512..].to_vec());
+        assert_eq!(blob_data.get_etag(), snapshot.get_etag());
+    }
+
+    #[test]
+    fn test_set_metadata() {
+        let mut blob_data = BlobData::new(
+            BlobContent::BlockBlob(BlockBlob::new(vec![])),
+            HashMap::from([("Tenant".to_string(), "acme".to_string())]),
+            BlobHttpHeaders::new(),
+        );
+
+        let etag_before = blob_data.get_blob_properties().etag;
+
+        assert_eq!("acme", blob_data.get_metadata().get("tenant").unwrap());
+
+        blob_data.set_metadata(HashMap::from([("schema".to_string(), "2".to_string())]));
+
+        let result = blob_data.get_blob_properties();
+
+        assert_eq!(1, result.metadata.len());
+        assert_eq!("2", result.metadata.get("schema").unwrap());
+        assert_ne!(etag_before, result.etag);
+    }
+
+    #[test]
+    fn test_set_http_headers() {
+        let mut blob_data = BlobData::new(
+            BlobContent::BlockBlob(BlockBlob::new(vec![])),
+            HashMap::new(),
+            BlobHttpHeaders::new().with_content_type("text/plain"),
+        );
+
+        assert_eq!(
+            Some("text/plain".to_string()),
+            blob_data.get_blob_properties().content_type
+        );
+
+        blob_data.set_http_headers(BlobHttpHeaders::new().with_cache_control("no-cache"));
+
+        let result = blob_data.get_blob_properties();
+
+        assert!(result.content_type.is_none());
+        assert_eq!(Some("no-cache".to_string()), result.cache_control);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_is_kept_by_in_mem_backend() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob_with_metadata(
+                "test",
+                "blob",
+                vec![1u8],
+                HashMap::from([("Tenant".to_string(), "acme".to_string())]),
+            )
+            .await
+            .unwrap();
+
+        let props = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+        assert_eq!("acme", props.metadata.get("tenant").unwrap());
+
+        connection
+            .set_blob_metadata(
+                "test",
+                "blob",
+                HashMap::from([("producer".to_string(), "importer".to_string())]),
+                BlobAccessConditions::new(),
+            )
+            .await
+            .unwrap();
+
+        let metadata = connection.get_blob_metadata("test", "blob").await.unwrap();
+        assert_eq!(
+            HashMap::from([("producer".to_string(), "importer".to_string())]),
+            metadata
+        );
+
+        let page_blob = AzurePageBlobStorage::new(connection.clone(), "test", "page-blob").await;
+
+        page_blob
+            .create_with_metadata(1, HashMap::from([("schema".to_string(), "2".to_string())]))
+            .await
+            .unwrap();
+
+        let props = page_blob.get_blob_properties().await.unwrap();
+        assert_eq!("2", props.metadata.get("schema").unwrap());
+
+        let result = connection.get_blob_metadata("test", "not-exists").await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_http_headers_are_kept_by_in_mem_backend() {
+        let connection = AzureStorageConnection::new_in_memory();
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob_with_options(
+                "test",
+                "blob",
+                vec![1u8],
+                UploadBlockBlobOptions::new()
+                    .with_content_type("application/json")
+                    .with_metadata("tenant", "acme"),
+            )
+            .await
+            .unwrap();
+
+        let props = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+
+        assert_eq!(Some("application/json".to_string()), props.content_type);
+        assert_eq!("acme", props.metadata.get("tenant").unwrap());
+
+        connection
+            .set_blob_http_headers(
+                "test",
+                "blob",
+                BlobHttpHeaders::new()
+                    .with_content_type("text/csv")
+                    .with_content_disposition("attachment; filename=report.csv"),
+                BlobAccessConditions::new(),
+            )
+            .await
+            .unwrap();
+
+        let props = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+
+        assert_eq!(Some("text/csv".to_string()), props.content_type);
+        assert_eq!(
+            Some("attachment; filename=report.csv".to_string()),
+            props.content_disposition
+        );
+        assert_eq!("acme", props.metadata.get("tenant").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_access_conditions_are_emulated_by_in_mem_backend() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        let etag = connection
+            .upload_block_blob_with_options(
+                "test",
+                "blob",
+                vec![1u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::if_not_exists()),
+            )
+            .await
+            .unwrap();
+
+        let result = connection
+            .upload_block_blob_with_options(
+                "test",
+                "blob",
+                vec![2u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::if_not_exists()),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::BlobAlreadyExists(_))
+        ));
+
+        let downloaded = connection
+            .download_blob_with_conditions("test", "blob", BlobAccessConditions::new())
+            .await
+            .unwrap();
+        assert_eq!(vec![1u8], downloaded.content);
+        assert_eq!(Some(etag.clone()), downloaded.properties.etag);
+
+        let new_etag = connection
+            .upload_block_blob_with_options(
+                "test",
+                "blob",
+                vec![2u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::new().with_if_match(etag.as_str())),
+            )
+            .await
+            .unwrap();
+        assert_ne!(etag, new_etag);
+
+        // The second writer still holds the old ETag - its update must not be lost silently
+        let result = connection
+            .upload_block_blob_with_options(
+                "test",
+                "blob",
+                vec![3u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::new().with_if_match(etag.as_str())),
+            )
+            .await;
+        assert!(result.unwrap_err().is_condition_not_met());
+
+        let result = connection
+            .download_blob_with_conditions(
+                "test",
+                "blob",
+                BlobAccessConditions::new().with_if_none_match(new_etag.as_str()),
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::ConditionNotMet(_))));
+
+        let result = connection
+            .delete_blob_with_conditions(
+                "test",
+                "blob",
+                BlobAccessConditions::new().with_if_match(etag.as_str()),
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::ConditionNotMet(_))));
+
+        connection
+            .delete_blob_with_conditions(
+                "test",
+                "blob",
+                BlobAccessConditions::new().with_if_match(new_etag.as_str()),
+            )
+            .await
+            .unwrap();
+
+        let page_blob = AzurePageBlobStorage::new(connection.clone(), "test", "page-blob").await;
+        page_blob.create(1).await.unwrap();
+
+        let etag = page_blob.get_blob_properties().await.unwrap().etag.unwrap();
+
+        let new_etag = page_blob
+            .save_pages_with_conditions(
+                0,
+                vec![1u8; 512],
+                BlobAccessConditions::new().with_if_match(etag.as_str()),
+            )
+            .await
+            .unwrap();
+        assert_ne!(etag, new_etag);
+
+        let result = page_blob
+            .save_pages_with_conditions(
+                0,
+                vec![2u8; 512],
+                BlobAccessConditions::new().with_if_match(etag.as_str()),
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::ConditionNotMet(_))));
+        assert_eq!(vec![1u8; 512], page_blob.get_pages(0, 1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_leases_are_emulated_by_in_mem_backend() {
+        let clock = Arc::new(ManualClock::new(DateTimeAsMicroseconds::now()));
+        let connection =
+            Arc::new(AzureStorageConnection::new_in_memory().with_clock(clock.clone()));
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![1u8])
+            .await
+            .unwrap();
+
+        let lease_id = connection
+            .acquire_blob_lease("test", "blob", LeaseDuration::from_secs(15), None)
+            .await
+            .unwrap();
+
+        let result = connection
+            .acquire_blob_lease("test", "blob", LeaseDuration::from_secs(15), None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::LeaseAlreadyPresent(_))
+        ));
+
+        let props = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+        assert_eq!(LeaseState::Leased, props.lease_state);
+        assert_eq!(LeaseStatus::Locked, props.lease_status);
+
+        let result = connection
+            .upload_block_blob("test", "blob", vec![2u8])
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        let result = connection
+            .delete_blob_with_conditions(
+                "test",
+                "blob",
+                BlobAccessConditions::new().with_lease_id("not-the-lease"),
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMismatch(_))));
+
+        let result = connection.delete_blob_if_exists("test", "blob").await;
+        assert!(result.unwrap_err().is_condition_not_met());
+
+        let result = connection
+            .set_blob_metadata("test", "blob", HashMap::new(), BlobAccessConditions::new())
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        let result = connection
+            .set_blob_http_headers(
+                "test",
+                "blob",
+                BlobHttpHeaders::new(),
+                BlobAccessConditions::new(),
+            )
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        let result = connection
+            .put_block("test", "blob", "QUFB", vec![2u8], None)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        // The lease holder writes with its lease id
+        connection
+            .set_blob_metadata(
+                "test",
+                "blob",
+                HashMap::from([("owner".to_string(), "holder".to_string())]),
+                BlobAccessConditions::new().with_lease_id(lease_id.as_str()),
+            )
+            .await
+            .unwrap();
+
+        connection
+            .set_blob_http_headers(
+                "test",
+                "blob",
+                BlobHttpHeaders::new().with_content_type("text/plain"),
+                BlobAccessConditions::new().with_lease_id(lease_id.as_str()),
+            )
+            .await
+            .unwrap();
+
+        connection
+            .put_block("test", "blob", "QUFB", vec![2u8], Some(lease_id.as_str()))
+            .await
+            .unwrap();
+
+        // Reads do not need the lease id
+        assert_eq!(
+            vec![1u8],
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        connection
+            .upload_block_blob_with_options(
+                "test",
+                "blob",
+                vec![2u8],
+                UploadBlockBlobOptions::new()
+                    .with_conditions(BlobAccessConditions::new().with_lease_id(lease_id.as_str())),
+            )
+            .await
+            .unwrap();
+
+        // The lease survives the overwrite
+        clock.advance(Duration::from_secs(10));
+        connection
+            .renew_blob_lease("test", "blob", &lease_id)
+            .await
+            .unwrap();
+
+        clock.advance(Duration::from_secs(10));
+        let result = connection
+            .upload_block_blob("test", "blob", vec![3u8])
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::LeaseIdMissing(_))));
+
+        clock.advance(Duration::from_secs(6));
+        let props = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+        assert_eq!(LeaseState::Expired, props.lease_state);
+        assert_eq!(LeaseStatus::Unlocked, props.lease_status);
+
+        connection
+            .upload_block_blob("test", "blob", vec![3u8])
+            .await
+            .unwrap();
+
+        let lease_id = connection
+            .acquire_blob_lease("test", "blob", LeaseDuration::Infinite, Some("my-lease"))
+            .await
+            .unwrap();
+        assert_eq!("my-lease", lease_id);
+
+        let remaining = connection
+            .break_blob_lease("test", "blob", Some(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert_eq!(Duration::from_secs(5), remaining);
+
+        let props = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+        assert_eq!(LeaseState::Breaking, props.lease_state);
+
+        clock.advance(Duration::from_secs(5));
+
+        let props = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+        assert_eq!(LeaseState::Broken, props.lease_state);
+
+        connection.delete_blob("test", "blob").await.unwrap();
+
+        let result = connection
+            .release_blob_lease("test", "blob", &lease_id)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_are_emulated_by_in_mem_backend() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        let page_blob = AzurePageBlobStorage::new(connection.clone(), "test", "pages").await;
+        page_blob.create_container_if_not_exists().await.unwrap();
+        page_blob.create(2).await.unwrap();
+        page_blob.save_pages(0, vec![1u8; 512]).await.unwrap();
+
+        let snapshot = page_blob.create_snapshot().await.unwrap();
+
+        page_blob.save_pages(0, vec![2u8; 1024]).await.unwrap();
+
+        assert_eq!(
+            vec![1u8; 512],
+            page_blob
+                .get_pages_at_snapshot(snapshot.as_str(), 0, 1)
+                .await
+                .unwrap()
+        );
+        assert_eq!(vec![2u8; 512], page_blob.get_pages(0, 1).await.unwrap());
+
+        let result = page_blob.delete().await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::SnapshotsPresent(_))
+        ));
+
+        connection
+            .delete_blob_with_snapshots("test", "pages", DeleteSnapshotsOption::Include, None)
+            .await
+            .unwrap();
+
+        assert!(connection
+            .get_list_of_blob_snapshots("test", "pages")
+            .await
+            .unwrap()
+            .is_empty());
+
+        connection
+            .upload_block_blob("test", "blob", vec![1u8])
+            .await
+            .unwrap();
+
+        let props_before = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+
+        let first = connection
+            .create_blob_snapshot("test", "blob")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![2u8])
+            .await
+            .unwrap();
+
+        let second = connection
+            .create_blob_snapshot("test", "blob")
+            .await
+            .unwrap();
+
+        // Snapshots survive the overwrite and are listed oldest first
+        assert_eq!(
+            vec![first.clone(), second.clone()],
+            connection
+                .get_list_of_blob_snapshots("test", "blob")
+                .await
+                .unwrap()
+        );
+
+        assert_eq!(
+            vec![1u8],
+            connection
+                .download_blob_snapshot("test", "blob", first.as_str())
+                .await
+                .unwrap()
+        );
+
+        let snapshot_props = connection
+            .get_blob_snapshot_properties("test", "blob", first.as_str())
+            .await
+            .unwrap();
+        assert_eq!(props_before.etag, snapshot_props.etag);
+
+        let result = connection
+            .get_blob_snapshot_pages("test", "blob", first.as_str(), 0, 1)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::InvalidBlobType(_))));
+
+        connection
+            .delete_blob_snapshot("test", "blob", first.as_str())
+            .await
+            .unwrap();
+
+        let result = connection
+            .download_blob_snapshot("test", "blob", first.as_str())
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        let result = connection.delete_blob_if_exists("test", "blob").await;
+        assert!(result.unwrap_err().is_conflict());
+
+        connection
+            .delete_blob_with_snapshots("test", "blob", DeleteSnapshotsOption::Only, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![2u8],
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        connection.delete_blob("test", "blob").await.unwrap();
     }
 }
@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::BlobData;
+
+/// What the retention policy of the container keeps of a blob name: prior versions and the soft-deleted blob
+#[derive(Default)]
+pub struct BlobHistory {
+    /// Version id -> prior version. Ids sort chronologically
+    pub versions: BTreeMap<String, BlobData>,
+    pub deleted: Option<DeletedBlob>,
+}
+
+impl BlobHistory {
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty() && self.deleted.is_none()
+    }
+}
+
+pub struct DeletedBlob {
+    pub blob_data: BlobData,
+    pub deleted_at: DateTimeAsMicroseconds,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::{
+        blob::BlobApi,
+        blob_container::BlobContainersApi,
+        blob_version::{BlobVersionApi, LocalRetentionPolicy},
+        block_blob::BlockBlobApi,
+        AzureStorageConnection, AzureStorageError, ManualClock, StorageError,
+    };
+
+    #[tokio::test]
+    async fn test_versions_are_emulated_by_in_mem_backend() {
+        let connection = AzureStorageConnection::new_in_memory().with_retention_policy(
+            LocalRetentionPolicy::new()
+                .with_versioning()
+                .with_delete_retention(Duration::from_secs(60)),
+        );
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![1u8])
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![2u8])
+            .await
+            .unwrap();
+
+        let versions = connection.get_list_of_blob_versions("test").await.unwrap();
+
+        assert_eq!(2, versions.len());
+        assert!(!versions[0].is_current_version);
+        assert!(versions[1].is_current_version);
+
+        let first = versions[0].version_id.clone().unwrap();
+
+        assert_eq!(
+            vec![1u8],
+            connection
+                .download_blob_version("test", "blob", first.as_str())
+                .await
+                .unwrap()
+        );
+
+        connection
+            .promote_blob_version("test", "blob", first.as_str(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![1u8],
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        // Promoting keeps the overwritten blob as a version too
+        assert_eq!(
+            3,
+            connection
+                .get_list_of_blob_versions("test")
+                .await
+                .unwrap()
+                .len()
+        );
+
+        // With versioning a deleted blob is kept as versions - undelete does not bring it back
+        connection.delete_blob("test", "blob").await.unwrap();
+        connection.undelete_blob("test", "blob").await.unwrap();
+
+        let result = connection.download_blob("test", "blob").await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        let versions = connection.get_list_of_blob_versions("test").await.unwrap();
+        assert_eq!(3, versions.len());
+        assert!(versions.iter().all(|itm| !itm.is_current_version));
+
+        connection.delete_container("test").await.unwrap();
+
+        let deleted = connection
+            .get_list_of_deleted_blob_containers()
+            .await
+            .unwrap();
+
+        assert_eq!(1, deleted.len());
+        assert_eq!("test", deleted[0].name);
+
+        connection
+            .undelete_blob_container("test", deleted[0].version.as_str())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            3,
+            connection
+                .get_list_of_blob_versions("test")
+                .await
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_is_emulated_by_in_mem_backend() {
+        let clock = Arc::new(ManualClock::new(DateTimeAsMicroseconds::now()));
+        let connection = AzureStorageConnection::new_in_memory()
+            .with_clock(clock.clone())
+            .with_retention_policy(
+                LocalRetentionPolicy::new().with_delete_retention(Duration::from_secs(60)),
+            );
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![1u8])
+            .await
+            .unwrap();
+
+        connection.delete_blob("test", "blob").await.unwrap();
+
+        let versions = connection.get_list_of_blob_versions("test").await.unwrap();
+
+        assert_eq!(1, versions.len());
+        assert!(versions[0].deleted);
+        assert_eq!(None, versions[0].version_id);
+
+        connection.undelete_blob("test", "blob").await.unwrap();
+
+        assert_eq!(
+            vec![1u8],
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        connection.delete_blob("test", "blob").await.unwrap();
+
+        clock.advance(Duration::from_secs(61));
+
+        let result = connection.undelete_blob("test", "blob").await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        assert!(connection
+            .get_list_of_blob_versions("test")
+            .await
+            .unwrap()
+            .is_empty());
+
+        connection.delete_container("test").await.unwrap();
+
+        let deleted = connection
+            .get_list_of_deleted_blob_containers()
+            .await
+            .unwrap();
+        assert_eq!(1, deleted.len());
+
+        clock.advance(Duration::from_secs(61));
+
+        assert!(connection
+            .get_list_of_deleted_blob_containers()
+            .await
+            .unwrap()
+            .is_empty());
+
+        let result = connection
+            .undelete_blob_container("test", deleted[0].version.as_str())
+            .await;
+        assert!(result.unwrap_err().is_not_found());
+    }
+}
@@ -1,13 +1,172 @@
+use std::sync::Arc;
+
+use crate::block_blob::BlockItem;
+
+/// Content is shared with the snapshots of the blob - an upload replaces it as a whole
+#[derive(Clone)]
 pub struct BlockBlob {
-    content: Vec<u8>,
+    content: Arc<Vec<u8>>,
+    /// Committed blocks the content is made of. Empty if the blob was uploaded with a single Put Blob
+    blocks: Arc<Vec<BlockItem>>,
 }
 
 impl BlockBlob {
     pub fn new(content: Vec<u8>) -> Self {
-        Self { content }
+        Self {
+            content: Arc::new(content),
+            blocks: Arc::new(Vec::new()),
+        }
+    }
+
+    pub fn from_blocks(content: Vec<u8>, blocks: Vec<BlockItem>) -> Self {
+        Self {
+            content: Arc::new(content),
+            blocks: Arc::new(blocks),
+        }
     }
 
     pub fn get_content(&self) -> &[u8] {
         self.content.as_slice()
     }
+
+    pub fn get_blocks(&self) -> &[BlockItem] {
+        self.blocks.as_slice()
+    }
+
+    pub fn get_committed_block(&self, block_id: &str) -> Option<&[u8]> {
+        let mut offset = 0;
+
+        for block in self.blocks.iter() {
+            if block.block_id == block_id {
+                return Some(&self.content[offset..offset + block.size]);
+            }
+
+            offset += block.size;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        blob::BlobApi,
+        blob_container::BlobContainersApi,
+        blob_transfer::{BlobTransferApi, TransferOptions},
+        block_blob::{
+            BlockBlobApi, BlockListType, UploadBlockBlobOptions, UploadFromReaderOptions,
+        },
+        AzureStorageConnection, AzureStorageError,
+    };
+
+    #[tokio::test]
+    async fn test_upload_from_reader_is_emulated_by_in_mem_backend() {
+        let connection = AzureStorageConnection::new_in_memory();
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        let content: Vec<u8> = (0u8..7u8).collect();
+
+        let options = UploadFromReaderOptions::new()
+            .with_block_size(2)
+            .with_max_concurrency(2)
+            .with_upload_options(UploadBlockBlobOptions::new().with_content_type("text/plain"));
+
+        let etag = connection
+            .upload_from_reader_with_options("test", "blob", content.as_slice(), options)
+            .await
+            .unwrap();
+
+        let properties = connection
+            .get_blob_properties("test", "blob")
+            .await
+            .unwrap();
+        assert_eq!(Some(etag), properties.etag);
+        assert_eq!(Some("text/plain".to_string()), properties.content_type);
+
+        assert_eq!(
+            content,
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        // Content shorter than a block goes as a single upload
+        connection
+            .upload_from_reader("test", "blob", [9u8].as_slice())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![9u8],
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        let result = connection
+            .upload_from_reader("not-exists", "blob", content.as_slice())
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::ContainerNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parallel_transfer_is_emulated_by_in_mem_backend() {
+        let connection = AzureStorageConnection::new_in_memory();
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        let content: Vec<u8> = (0u8..100u8).collect();
+
+        let options = TransferOptions::new()
+            .with_chunk_size(7)
+            .with_max_concurrency(3);
+
+        // 15 blocks, put 3 at a time
+        connection
+            .upload_from_reader_with_options(
+                "test",
+                "blob",
+                content.as_slice(),
+                UploadFromReaderOptions::new()
+                    .with_block_size(options.chunk_size)
+                    .with_max_concurrency(options.max_concurrency),
+            )
+            .await
+            .unwrap();
+
+        let block_list = connection
+            .get_block_list("test", "blob", BlockListType::Committed)
+            .await
+            .unwrap();
+        assert_eq!(15, block_list.committed.len());
+
+        let downloaded = connection
+            .download_in_parallel("test", "blob", options.clone())
+            .await
+            .unwrap();
+        assert_eq!(content, downloaded);
+
+        connection
+            .upload_block_blob("test", "empty", Vec::<u8>::new())
+            .await
+            .unwrap();
+
+        let downloaded = connection
+            .download_in_parallel("test", "empty", options.clone())
+            .await
+            .unwrap();
+        assert!(downloaded.is_empty());
+
+        let result = connection
+            .download_in_parallel("test", "not-exists", options)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+    }
 }
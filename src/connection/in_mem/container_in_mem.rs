@@ -1,37 +1,135 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use rust_extensions::SliceOrVec;
 use tokio::sync::RwLock;
 
-use crate::{blob::BlobProperties, page_blob::consts::BLOB_PAGE_SIZE, AzureStorageError};
+use crate::{
+    append_blob::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions},
+    blob::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, DownloadedBlob},
+    blob_copy::BlobCopyState,
+    blob_lease::{local_lease::LocalLease, LeaseDuration},
+    blob_snapshot::DeleteSnapshotsOption,
+    blob_version::{BlobVersionItem, LocalRetentionPolicy},
+    block_blob::{BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions},
+    AzureStorageError, StorageClock,
+};
 
-use super::{block_blob::BlockBlob, BlobData, PageBlobInMem};
+use super::{
+    blob_history::{BlobHistory, DeletedBlob},
+    block_blob::BlockBlob,
+    staged_blocks::StagedBlocks,
+    AppendBlobInMem, BlobContent, BlobData, PageBlobInMem,
+};
 
 pub struct ContainerInMem {
     blobs: RwLock<HashMap<String, BlobData>>,
+    // Always locked after `blobs`
+    history: RwLock<HashMap<String, BlobHistory>>,
+    // Always locked after `blobs`
+    staged_blocks: RwLock<HashMap<String, StagedBlocks>>,
+    clock: Arc<dyn StorageClock>,
+    retention: LocalRetentionPolicy,
 }
 
 impl ContainerInMem {
-    pub fn new() -> Self {
+    pub fn new(clock: Arc<dyn StorageClock>, retention: LocalRetentionPolicy) -> Self {
         Self {
             blobs: RwLock::new(HashMap::new()),
+            history: RwLock::new(HashMap::new()),
+            staged_blocks: RwLock::new(HashMap::new()),
+            clock,
+            retention,
         }
     }
 
+    fn new_blob_data(
+        &self,
+        content: BlobContent,
+        metadata: HashMap<String, String>,
+        http_headers: BlobHttpHeaders,
+    ) -> BlobData {
+        let mut result = BlobData::new(content, metadata, http_headers);
+
+        if self.retention.versioning {
+            result.stamp_version();
+        }
+
+        result
+    }
+
+    /// The state of the blob before a write becomes a prior version
+    async fn keep_version(&self, blob_name: &str, blob_data: &BlobData) {
+        if !self.retention.versioning {
+            return;
+        }
+
+        let version_id = match blob_data.get_version_id() {
+            Some(version_id) => version_id.to_string(),
+            // Written before the container kept versions
+            None => crate::sdk_azure::http_date::to_azure_timestamp(blob_data.get_last_modified()),
+        };
+
+        let mut write_access = self.history.write().await;
+
+        write_access
+            .entry(blob_name.to_string())
+            .or_default()
+            .versions
+            .insert(version_id, blob_data.read_only_copy());
+    }
+
+    /// With versioning the deleted blob stays as a prior version, with soft delete it can be undeleted.
+    /// Snapshots are not kept
+    async fn keep_deleted(&self, blob_name: &str, blob_data: BlobData) {
+        // Uncommitted blocks go with the blob
+        self.staged_blocks.write().await.remove(blob_name);
+
+        if self.retention.versioning {
+            self.keep_version(blob_name, &blob_data).await;
+            return;
+        }
+
+        if self.retention.delete_retention.is_none() {
+            return;
+        }
+
+        let mut write_access = self.history.write().await;
+
+        let blob_history = write_access.entry(blob_name.to_string()).or_default();
+
+        blob_history.deleted = Some(DeletedBlob {
+            blob_data: blob_data.read_only_copy(),
+            deleted_at: self.clock.now(),
+        });
+    }
+
+    fn get_blob_properties_of(&self, blob_data: &BlobData) -> BlobProperties {
+        let mut result = blob_data.get_blob_properties();
+        blob_data
+            .lease
+            .apply_to_properties(&mut result, self.clock.now());
+        result
+    }
+
     pub async fn create_page_blob(
         &self,
         blob_name: &str,
         pages_amount: usize,
+        metadata: HashMap<String, String>,
     ) -> Result<(), AzureStorageError> {
         let mut write_access = self.blobs.write().await;
 
         if write_access.contains_key(blob_name) {
-            return Err(AzureStorageError::BlobAlreadyExists);
+            return Err(AzureStorageError::BlobAlreadyExists(None));
         }
 
         write_access.insert(
             blob_name.to_string(),
-            BlobData::PageBlob(PageBlobInMem::new(pages_amount)),
+            self.new_blob_data(
+                BlobContent::PageBlob(PageBlobInMem::new(pages_amount)),
+                metadata,
+                BlobHttpHeaders::new(),
+            ),
         );
 
         Ok(())
@@ -45,22 +143,23 @@ impl ContainerInMem {
         let mut write_access = self.blobs.write().await;
 
         if let Some(blob_data) = write_access.get(blob_name) {
-            if let BlobData::PageBlob(page_blob) = blob_data {
-                let result = page_blob.get_blob_properties();
-                return Ok(result);
+            if let BlobContent::PageBlob(_) = &blob_data.content {
+                return Ok(self.get_blob_properties_of(blob_data));
             } else {
-                panic!("{}", NOT_A_PAGE_BLOB_PANIC);
+                return Err(AzureStorageError::InvalidBlobType(None));
             }
         }
 
-        write_access.insert(
-            blob_name.to_string(),
-            BlobData::PageBlob(PageBlobInMem::new(pages_amount)),
+        let blob_data = self.new_blob_data(
+            BlobContent::PageBlob(PageBlobInMem::new(pages_amount)),
+            HashMap::new(),
+            BlobHttpHeaders::new(),
         );
+        let result = blob_data.get_blob_properties();
 
-        Ok(BlobProperties {
-            blob_size: pages_amount * BLOB_PAGE_SIZE,
-        })
+        write_access.insert(blob_name.to_string(), blob_data);
+
+        Ok(result)
     }
 
     pub async fn get_list_of_blobs(&self) -> Vec<String> {
@@ -68,14 +167,240 @@ impl ContainerInMem {
         read_access.keys().map(|itm| itm.to_string()).collect()
     }
 
+    /// Returns ETag of the uploaded blob
     pub async fn upload_block_blob<'s>(
         &self,
         blob_name: String,
         content: impl Into<SliceOrVec<'s, u8>>,
-    ) {
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
         let content = content.into().into_vec();
         let mut write_access = self.blobs.write().await;
-        write_access.insert(blob_name, BlobData::BlockBlob(BlockBlob::new(content)));
+
+        self.write_blob(
+            &mut write_access,
+            blob_name,
+            BlobContent::BlockBlob(BlockBlob::new(content)),
+            options,
+        )
+        .await
+    }
+
+    /// Put Blob of any type. Replaces the blob, keeping its lease and snapshots
+    async fn write_blob(
+        &self,
+        write_access: &mut HashMap<String, BlobData>,
+        blob_name: String,
+        content: BlobContent,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let current = write_access.get(blob_name.as_str());
+
+        let lease = match current {
+            Some(blob_data) => blob_data.lease.clone(),
+            None => LocalLease::new(),
+        };
+
+        lease.check_access(
+            options.conditions.lease_id.as_deref(),
+            true,
+            self.clock.now(),
+        )?;
+
+        options.conditions.check_before_write(
+            current.map(|blob_data| (blob_data.get_etag(), blob_data.get_last_modified())),
+        )?;
+
+        let snapshots = match write_access.get_mut(blob_name.as_str()) {
+            Some(blob_data) => {
+                self.keep_version(blob_name.as_str(), blob_data).await;
+                std::mem::take(&mut blob_data.snapshots)
+            }
+            None => Default::default(),
+        };
+
+        let mut blob_data = self.new_blob_data(content, options.metadata, options.http_headers);
+        blob_data.lease = lease;
+        blob_data.snapshots = snapshots;
+
+        let etag = blob_data.get_etag().to_string();
+
+        // Blocks not committed by the upload are discarded
+        self.staged_blocks.write().await.remove(blob_name.as_str());
+
+        write_access.insert(blob_name, blob_data);
+
+        Ok(etag)
+    }
+
+    /// The blob does not have to exist - the blocks are kept until a block list commits them.
+    /// Blocks of a leased blob are put with the lease id
+    pub async fn put_block(
+        &self,
+        blob_name: &str,
+        block_id: &str,
+        content: Vec<u8>,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        if let Some(blob_data) = self.blobs.read().await.get(blob_name) {
+            blob_data
+                .lease
+                .check_access(lease_id, true, self.clock.now())?;
+        }
+
+        let mut write_access = self.staged_blocks.write().await;
+
+        write_access
+            .entry(blob_name.to_string())
+            .or_default()
+            .put(block_id, content)
+    }
+
+    /// Returns ETag of the blob
+    pub async fn commit_block_list(
+        &self,
+        blob_name: String,
+        blocks: Vec<BlockListItem>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        let block_blob = {
+            let committed = match write_access.get(blob_name.as_str()) {
+                Some(BlobData {
+                    content: BlobContent::BlockBlob(block_blob),
+                    ..
+                }) => Some(block_blob),
+                _ => None,
+            };
+
+            let staged_access = self.staged_blocks.read().await;
+
+            match staged_access.get(blob_name.as_str()) {
+                Some(staged_blocks) => {
+                    staged_blocks.compile_block_blob(blocks.as_slice(), committed)?
+                }
+                None => StagedBlocks::default().compile_block_blob(blocks.as_slice(), committed)?,
+            }
+        };
+
+        self.write_blob(
+            &mut write_access,
+            blob_name,
+            BlobContent::BlockBlob(block_blob),
+            options,
+        )
+        .await
+    }
+
+    /// Returns ETag of the blob
+    pub async fn create_append_blob(
+        &self,
+        blob_name: String,
+        options: CreateAppendBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        self.write_blob(
+            &mut write_access,
+            blob_name,
+            BlobContent::AppendBlob(AppendBlobInMem::new()),
+            options.into_upload_options(),
+        )
+        .await
+    }
+
+    pub async fn append_block(
+        &self,
+        blob_name: &str,
+        content: Vec<u8>,
+        conditions: &AppendBlockConditions,
+    ) -> Result<AppendBlockResult, AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        let blob_data = match write_access.get_mut(blob_name) {
+            Some(blob_data) => blob_data,
+            None => return Err(AzureStorageError::BlobNotFound(None)),
+        };
+
+        blob_data.check_conditions(&conditions.access, true, self.clock.now())?;
+
+        let append_blob = match &mut blob_data.content {
+            BlobContent::AppendBlob(append_blob) => append_blob,
+            _ => return Err(AzureStorageError::InvalidBlobType(None)),
+        };
+
+        let append_offset = append_blob.append_block(content, conditions)?;
+        let committed_block_count = append_blob.get_committed_block_count();
+
+        blob_data.update_last_modified();
+
+        Ok(AppendBlockResult {
+            etag: blob_data.get_etag().to_string(),
+            append_offset,
+            committed_block_count,
+        })
+    }
+
+    /// Returns ETag of the blob
+    pub async fn seal_append_blob(
+        &self,
+        blob_name: &str,
+        conditions: &BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        let blob_data = match write_access.get_mut(blob_name) {
+            Some(blob_data) => blob_data,
+            None => return Err(AzureStorageError::BlobNotFound(None)),
+        };
+
+        blob_data.check_conditions(conditions, true, self.clock.now())?;
+
+        match &mut blob_data.content {
+            BlobContent::AppendBlob(append_blob) => append_blob.seal(),
+            _ => return Err(AzureStorageError::InvalidBlobType(None)),
+        }
+
+        blob_data.update_last_modified();
+
+        Ok(blob_data.get_etag().to_string())
+    }
+
+    pub async fn get_block_list(
+        &self,
+        blob_name: &str,
+        list_type: BlockListType,
+    ) -> Result<BlockList, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+        let staged_access = self.staged_blocks.read().await;
+
+        let staged_blocks = staged_access.get(blob_name);
+
+        let mut result = BlockList::default();
+
+        match read_access.get(blob_name) {
+            Some(blob_data) => {
+                if let BlobContent::BlockBlob(block_blob) = &blob_data.content {
+                    if list_type.has_committed() {
+                        result.committed = block_blob.get_blocks().to_vec();
+                    }
+                }
+            }
+            None => {
+                if staged_blocks.is_none() {
+                    return Err(AzureStorageError::BlobNotFound(None));
+                }
+            }
+        }
+
+        if let Some(staged_blocks) = staged_blocks {
+            if list_type.has_uncommitted() {
+                result.uncommitted = staged_blocks.get_blocks();
+            }
+        }
+
+        Ok(result)
     }
 
     pub async fn download(&self, blob_name: &str) -> Result<Vec<u8>, AzureStorageError> {
@@ -83,7 +408,59 @@ impl ContainerInMem {
 
         match read_access.get(blob_name) {
             Some(blob_data) => Ok(blob_data.download()),
-            None => Err(AzureStorageError::BlobNotFound),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn download_range(
+        &self,
+        blob_name: &str,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match read_access.get(blob_name) {
+            Some(blob_data) => blob_data.download_range(offset, length),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn download_range_with_conditions(
+        &self,
+        blob_name: &str,
+        offset: u64,
+        length: Option<u64>,
+        conditions: &BlobAccessConditions,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match read_access.get(blob_name) {
+            Some(blob_data) => {
+                blob_data.check_conditions(conditions, false, self.clock.now())?;
+                blob_data.download_range(offset, length)
+            }
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn download_with_conditions(
+        &self,
+        blob_name: &str,
+        conditions: &BlobAccessConditions,
+    ) -> Result<DownloadedBlob, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match read_access.get(blob_name) {
+            Some(blob_data) => {
+                blob_data.check_conditions(conditions, false, self.clock.now())?;
+
+                Ok(DownloadedBlob {
+                    content: blob_data.download(),
+                    properties: self.get_blob_properties_of(blob_data),
+                })
+            }
+            None => Err(AzureStorageError::BlobNotFound(None)),
         }
     }
 
@@ -94,15 +471,116 @@ impl ContainerInMem {
         let read_access = self.blobs.read().await;
 
         match read_access.get(blob_name) {
-            Some(blob_data) => Ok(blob_data.get_blob_properties()),
-            None => Err(AzureStorageError::BlobNotFound),
+            Some(blob_data) => Ok(self.get_blob_properties_of(blob_data)),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn set_blob_metadata(
+        &self,
+        blob_name: &str,
+        metadata: HashMap<String, String>,
+        conditions: &BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        match write_access.get_mut(blob_name) {
+            Some(blob_data) => {
+                blob_data.check_conditions(conditions, true, self.clock.now())?;
+
+                self.keep_version(blob_name, blob_data).await;
+
+                blob_data.set_metadata(metadata);
+
+                if self.retention.versioning {
+                    blob_data.stamp_version();
+                }
+
+                Ok(())
+            }
+            None => Err(AzureStorageError::BlobNotFound(None)),
         }
     }
 
-    pub async fn delete(&self, blob_name: &str) -> bool {
+    pub async fn set_blob_http_headers(
+        &self,
+        blob_name: &str,
+        http_headers: BlobHttpHeaders,
+        conditions: &BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
         let mut write_access = self.blobs.write().await;
-        let result = write_access.remove(blob_name);
-        return result.is_some();
+
+        match write_access.get_mut(blob_name) {
+            Some(blob_data) => {
+                blob_data.check_conditions(conditions, true, self.clock.now())?;
+
+                blob_data.set_http_headers(http_headers);
+                Ok(())
+            }
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn get_blob_metadata(
+        &self,
+        blob_name: &str,
+    ) -> Result<HashMap<String, String>, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match read_access.get(blob_name) {
+            Some(blob_data) => Ok(blob_data.get_metadata().clone()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    /// Missing blob is not an error. A leased blob is deleted only with its lease id, a blob with snapshots is not deleted
+    pub async fn delete(
+        &self,
+        blob_name: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        if let Some(blob_data) = write_access.get(blob_name) {
+            blob_data
+                .lease
+                .check_access(lease_id, true, self.clock.now())?;
+
+            if !blob_data.snapshots.is_empty() {
+                return Err(AzureStorageError::SnapshotsPresent(None));
+            }
+        }
+
+        if let Some(blob_data) = write_access.remove(blob_name) {
+            self.keep_deleted(blob_name, blob_data).await;
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_with_conditions(
+        &self,
+        blob_name: &str,
+        conditions: &BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        match write_access.get(blob_name) {
+            Some(blob_data) => {
+                blob_data.check_conditions(conditions, true, self.clock.now())?;
+
+                if !blob_data.snapshots.is_empty() {
+                    return Err(AzureStorageError::SnapshotsPresent(None));
+                }
+            }
+            None => return Err(AzureStorageError::BlobNotFound(None)),
+        }
+
+        if let Some(blob_data) = write_access.remove(blob_name) {
+            self.keep_deleted(blob_name, blob_data).await;
+        }
+
+        Ok(())
     }
 
     pub async fn resize_page_blob(
@@ -115,36 +593,43 @@ impl ContainerInMem {
         let blob_data = write_access.get_mut(blob_name);
 
         if let Some(blob_data) = blob_data {
-            if let BlobData::PageBlob(page_blob) = blob_data {
+            if let BlobContent::PageBlob(page_blob) = &mut blob_data.content {
                 page_blob.resize(pages_amount);
+                blob_data.update_last_modified();
                 return Ok(());
             } else {
-                panic!("{}", NOT_A_PAGE_BLOB_PANIC);
+                return Err(AzureStorageError::InvalidBlobType(None));
             }
         }
 
-        return Err(AzureStorageError::BlobNotFound);
+        return Err(AzureStorageError::BlobNotFound(None));
     }
 
+    /// Returns ETag of the blob after the write
     pub async fn upload_pages_to_page_blob<'s>(
         &self,
         blob_name: &str,
         start_page_no: usize,
         content: impl Into<SliceOrVec<'s, u8>>,
-    ) -> Result<(), AzureStorageError> {
+        conditions: &BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
         let mut write_access = self.blobs.write().await;
 
         let blob_data = write_access.get_mut(blob_name);
 
         if let Some(blob_data) = blob_data {
-            if let BlobData::PageBlob(page_blob) = blob_data {
-                return page_blob.save_pages(start_page_no, content);
+            blob_data.check_conditions(conditions, true, self.clock.now())?;
+
+            if let BlobContent::PageBlob(page_blob) = &mut blob_data.content {
+                page_blob.save_pages(start_page_no, content)?;
+                blob_data.update_last_modified();
+                return Ok(blob_data.get_etag().to_string());
             } else {
-                panic!("{}", NOT_A_PAGE_BLOB_PANIC);
+                return Err(AzureStorageError::InvalidBlobType(None));
             }
         }
 
-        return Err(AzureStorageError::BlobNotFound);
+        return Err(AzureStorageError::BlobNotFound(None));
     }
 
     pub async fn get_page_blob_pages(
@@ -158,16 +643,676 @@ impl ContainerInMem {
         let blob_data = read_access.get(blob_name);
 
         if let Some(blob_data) = blob_data {
-            if let BlobData::PageBlob(page_blob) = blob_data {
+            if let BlobContent::PageBlob(page_blob) = &blob_data.content {
                 let result = page_blob.get_pages(start_page_no, pages_amount);
                 return Ok(result);
             } else {
-                panic!("{}", NOT_A_PAGE_BLOB_PANIC);
+                return Err(AzureStorageError::InvalidBlobType(None));
             }
         }
 
-        return Err(AzureStorageError::BlobNotFound);
+        return Err(AzureStorageError::BlobNotFound(None));
     }
+
+    pub async fn acquire_lease(
+        &self,
+        blob_name: &str,
+        duration: LeaseDuration,
+        proposed_lease_id: Option<&str>,
+    ) -> Result<String, AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        match write_access.get_mut(blob_name) {
+            Some(blob_data) => {
+                blob_data
+                    .lease
+                    .acquire(duration, proposed_lease_id, self.clock.now())
+            }
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn renew_lease(
+        &self,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        match write_access.get_mut(blob_name) {
+            Some(blob_data) => blob_data.lease.renew(lease_id, self.clock.now()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn release_lease(
+        &self,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        match write_access.get_mut(blob_name) {
+            Some(blob_data) => blob_data.lease.release(lease_id, self.clock.now()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn break_lease(
+        &self,
+        blob_name: &str,
+        break_period: Option<Duration>,
+    ) -> Result<Duration, AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        match write_access.get_mut(blob_name) {
+            Some(blob_data) => blob_data.lease.break_lease(break_period, self.clock.now()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn change_lease(
+        &self,
+        blob_name: &str,
+        lease_id: &str,
+        proposed_lease_id: &str,
+    ) -> Result<String, AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        match write_access.get_mut(blob_name) {
+            Some(blob_data) => {
+                blob_data
+                    .lease
+                    .change(lease_id, proposed_lease_id, self.clock.now())
+            }
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    /// Returns the snapshot id
+    pub async fn create_snapshot(&self, blob_name: &str) -> Result<String, AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        match write_access.get_mut(blob_name) {
+            Some(blob_data) => Ok(blob_data.create_snapshot()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    /// Oldest first. Missing blob gives an empty list, the way listing with a prefix does
+    pub async fn get_list_of_snapshots(&self, blob_name: &str) -> Vec<String> {
+        let read_access = self.blobs.read().await;
+
+        match read_access.get(blob_name) {
+            Some(blob_data) => blob_data.snapshots.keys().cloned().collect(),
+            None => vec![],
+        }
+    }
+
+    pub async fn get_snapshot_properties(
+        &self,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match get_snapshot(&read_access, blob_name, snapshot) {
+            Some(snapshot) => Ok(snapshot.get_blob_properties()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn download_snapshot(
+        &self,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match get_snapshot(&read_access, blob_name, snapshot) {
+            Some(snapshot) => Ok(snapshot.download()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn get_snapshot_pages(
+        &self,
+        blob_name: &str,
+        snapshot: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match get_snapshot(&read_access, blob_name, snapshot) {
+            Some(snapshot) => {
+                if let BlobContent::PageBlob(page_blob) = &snapshot.content {
+                    Ok(page_blob.get_pages(start_page_no, pages_amount))
+                } else {
+                    Err(AzureStorageError::InvalidBlobType(None))
+                }
+            }
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn delete_snapshot(
+        &self,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<(), AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        let removed = match write_access.get_mut(blob_name) {
+            Some(blob_data) => blob_data.snapshots.remove(snapshot),
+            None => None,
+        };
+
+        match removed {
+            Some(_) => Ok(()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    /// Include deletes the blob too, so a leased blob is not deleted without the lease id
+    pub async fn delete_with_snapshots(
+        &self,
+        blob_name: &str,
+        option: DeleteSnapshotsOption,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let mut write_access = self.blobs.write().await;
+
+        let blob_data = match write_access.get_mut(blob_name) {
+            Some(blob_data) => blob_data,
+            None => return Err(AzureStorageError::BlobNotFound(None)),
+        };
+
+        match option {
+            DeleteSnapshotsOption::Include => {
+                blob_data
+                    .lease
+                    .check_access(lease_id, true, self.clock.now())?;
+
+                if let Some(blob_data) = write_access.remove(blob_name) {
+                    self.keep_deleted(blob_name, blob_data).await;
+                }
+            }
+            DeleteSnapshotsOption::Only => {
+                blob_data.snapshots.clear();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Current blobs with the versions and soft-deleted blobs the retention policy keeps, by blob name
+    pub async fn get_list_of_versions(&self) -> Vec<BlobVersionItem> {
+        let blobs = self.blobs.read().await;
+        let history = self.history.read().await;
+
+        let now = self.clock.now();
+
+        let mut result = vec![];
+
+        for (blob_name, blob_data) in blobs.iter() {
+            result.push(BlobVersionItem {
+                blob_name: blob_name.to_string(),
+                version_id: blob_data.get_version_id().map(|itm| itm.to_string()),
+                is_current_version: true,
+                deleted: false,
+            });
+        }
+
+        for (blob_name, blob_history) in history.iter() {
+            for version_id in blob_history.versions.keys() {
+                result.push(BlobVersionItem {
+                    blob_name: blob_name.to_string(),
+                    version_id: Some(version_id.to_string()),
+                    is_current_version: false,
+                    deleted: false,
+                });
+            }
+
+            if let Some(deleted) = &blob_history.deleted {
+                if !self.retention.is_expired(deleted.deleted_at, now) {
+                    result.push(BlobVersionItem {
+                        blob_name: blob_name.to_string(),
+                        version_id: None,
+                        is_current_version: false,
+                        deleted: true,
+                    });
+                }
+            }
+        }
+
+        result.sort_by(|a, b| {
+            (a.blob_name.as_str(), a.version_id.as_deref())
+                .cmp(&(b.blob_name.as_str(), b.version_id.as_deref()))
+        });
+
+        result
+    }
+
+    pub async fn get_version_properties(
+        &self,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        let blobs = self.blobs.read().await;
+        let history = self.history.read().await;
+
+        match get_version(&blobs, &history, blob_name, version_id) {
+            Some(blob_data) => Ok(blob_data.get_blob_properties()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    pub async fn download_version(
+        &self,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let blobs = self.blobs.read().await;
+        let history = self.history.read().await;
+
+        match get_version(&blobs, &history, blob_name, version_id) {
+            Some(blob_data) => Ok(blob_data.download()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    /// Overwrites the blob with the version. Lease and snapshots of the blob stay
+    pub async fn promote_version(
+        &self,
+        blob_name: &str,
+        version_id: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let mut blobs = self.blobs.write().await;
+
+        let version = {
+            let history = self.history.read().await;
+
+            match get_version(&blobs, &history, blob_name, version_id) {
+                Some(blob_data) => blob_data.read_only_copy(),
+                None => return Err(AzureStorageError::BlobNotFound(None)),
+            }
+        };
+
+        let promoted = self.new_blob_data_from(version);
+
+        self.replace_blob(&mut blobs, blob_name, promoted, lease_id)
+            .await
+    }
+
+    /// Read-only copy of the blob a copy reads from
+    pub async fn get_copy_source(&self, blob_name: &str) -> Result<BlobData, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match read_access.get(blob_name) {
+            Some(blob_data) => Ok(blob_data.read_only_copy()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    /// Copies complete at once. The copy shares the content with the source until either is written to
+    pub async fn copy_blob(
+        &self,
+        blob_name: &str,
+        source: BlobData,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        let mut blobs = self.blobs.write().await;
+
+        let copy_state = BlobCopyState::completed(source.get_blob_properties().blob_size as u64);
+
+        let mut blob_data = self.new_blob_data_from(source);
+        blob_data.copy_state = Some(copy_state.clone());
+
+        self.replace_blob(&mut blobs, blob_name, blob_data, lease_id)
+            .await?;
+
+        Ok(copy_state)
+    }
+
+    pub async fn get_copy_state(
+        &self,
+        blob_name: &str,
+    ) -> Result<Option<BlobCopyState>, AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        match read_access.get(blob_name) {
+            Some(blob_data) => Ok(blob_data.copy_state.clone()),
+            None => Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
+    /// Copies complete at once, so there is never a pending one
+    pub async fn abort_copy(&self, blob_name: &str) -> Result<(), AzureStorageError> {
+        let read_access = self.blobs.read().await;
+
+        if !read_access.contains_key(blob_name) {
+            return Err(AzureStorageError::BlobNotFound(None));
+        }
+
+        Err(AzureStorageError::NoPendingCopyOperation(None))
+    }
+
+    /// New write of the content and properties of `src`
+    fn new_blob_data_from(&self, src: BlobData) -> BlobData {
+        let metadata = src.get_metadata().clone();
+        let http_headers = src.get_http_headers().clone();
+
+        self.new_blob_data(src.content, metadata, http_headers)
+    }
+
+    /// Overwrites the blob keeping its lease and snapshots. A leased blob is overwritten only with its lease id
+    async fn replace_blob(
+        &self,
+        blobs: &mut HashMap<String, BlobData>,
+        blob_name: &str,
+        mut blob_data: BlobData,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        if let Some(current) = blobs.get_mut(blob_name) {
+            current
+                .lease
+                .check_access(lease_id, true, self.clock.now())?;
+
+            self.keep_version(blob_name, current).await;
+
+            blob_data.lease = current.lease.clone();
+            blob_data.snapshots = std::mem::take(&mut current.snapshots);
+        }
+
+        blobs.insert(blob_name.to_string(), blob_data);
+
+        Ok(())
+    }
+
+    /// A blob which is not deleted stays as it is. With versioning a deleted blob is kept as versions
+    /// and is brought back by `promote_version` - undelete does not change anything then
+    pub async fn undelete(&self, blob_name: &str) -> Result<(), AzureStorageError> {
+        let mut blobs = self.blobs.write().await;
+
+        if blobs.contains_key(blob_name) {
+            return Ok(());
+        }
+
+        let mut history = self.history.write().await;
+
+        let blob_history = match history.get_mut(blob_name) {
+            Some(blob_history) => blob_history,
+            None => return Err(AzureStorageError::BlobNotFound(None)),
+        };
+
+        if let Some(deleted) = blob_history.deleted.take() {
+            if !self
+                .retention
+                .is_expired(deleted.deleted_at, self.clock.now())
+            {
+                blobs.insert(blob_name.to_string(), deleted.blob_data);
+                return Ok(());
+            }
+        }
+
+        if blob_history.is_empty() {
+            history.remove(blob_name);
+            return Err(AzureStorageError::BlobNotFound(None));
+        }
+
+        Ok(())
+    }
+}
+
+/// The current blob if the version id is its own, a prior version otherwise
+fn get_version<'s>(
+    blobs: &'s HashMap<String, BlobData>,
+    history: &'s HashMap<String, BlobHistory>,
+    blob_name: &str,
+    version_id: &str,
+) -> Option<&'s BlobData> {
+    if let Some(blob_data) = blobs.get(blob_name) {
+        if blob_data.get_version_id() == Some(version_id) {
+            return Some(blob_data);
+        }
+    }
+
+    history.get(blob_name)?.versions.get(version_id)
 }
 
-const NOT_A_PAGE_BLOB_PANIC: &str = "The type of the blob is not a pageBlob";
+fn get_snapshot<'s>(
+    blobs: &'s HashMap<String, BlobData>,
+    blob_name: &str,
+    snapshot: &str,
+) -> Option<&'s BlobData> {
+    blobs.get(blob_name)?.snapshots.get(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        blob::BlobApi,
+        blob_container::BlobContainersApi,
+        blob_copy::{BlobCopyApi, CopySource, CopyStatus},
+        block_blob::{BlockBlobApi, UploadBlockBlobOptions},
+        page_blob::AzurePageBlobStorage,
+        AzureStorageConnection, AzureStorageError, StorageError,
+    };
+
+    #[tokio::test]
+    async fn test_copy_is_emulated_by_in_mem_backend() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("from")
+            .await
+            .unwrap();
+        connection
+            .create_container_if_not_exists("to")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob_with_options(
+                "from",
+                "blob",
+                vec![1u8, 2u8, 3u8],
+                UploadBlockBlobOptions::new().with_metadata("tenant", "acme"),
+            )
+            .await
+            .unwrap();
+
+        let copy_state = connection
+            .start_copy_from_url("to", "blob", &CopySource::from_blob("from", "blob"), None)
+            .await
+            .unwrap();
+
+        assert_eq!(CopyStatus::Success, copy_state.status);
+        assert_eq!(3, copy_state.progress.unwrap().bytes_total);
+
+        assert_eq!(
+            vec![1u8, 2u8, 3u8],
+            connection.download_blob("to", "blob").await.unwrap()
+        );
+        assert_eq!(
+            "acme",
+            connection
+                .get_blob_metadata("to", "blob")
+                .await
+                .unwrap()
+                .get("tenant")
+                .unwrap()
+        );
+
+        assert_eq!(
+            Some(copy_state.clone()),
+            connection.get_copy_status("to", "blob").await.unwrap()
+        );
+        assert_eq!(
+            None,
+            connection.get_copy_status("from", "blob").await.unwrap()
+        );
+
+        let result = connection
+            .abort_copy("to", "blob", copy_state.copy_id.as_str())
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::NoPendingCopyOperation(_))
+        ));
+
+        // The copy does not share writes with the source
+        connection
+            .upload_block_blob("from", "blob", vec![4u8])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![1u8, 2u8, 3u8],
+            connection.download_blob("to", "blob").await.unwrap()
+        );
+
+        let page_blob = AzurePageBlobStorage::new(connection.clone(), "from", "pages").await;
+        page_blob.create(1).await.unwrap();
+        page_blob.save_pages(0, vec![5u8; 512]).await.unwrap();
+
+        connection
+            .copy_from_url("to", "pages", &CopySource::from_blob("from", "pages"), None)
+            .await
+            .unwrap();
+
+        let copied_page_blob = AzurePageBlobStorage::new(connection.clone(), "to", "pages").await;
+        assert_eq!(
+            vec![5u8; 512],
+            copied_page_blob.get_pages(0, 1).await.unwrap()
+        );
+
+        let result = connection
+            .start_copy_from_url(
+                "to",
+                "blob",
+                &CopySource::from_blob("from", "missing"),
+                None,
+            )
+            .await;
+        assert!(result.unwrap_err().is_not_found());
+
+        let result = connection
+            .start_copy_from_url(
+                "to",
+                "blob",
+                &CopySource::from_url("https://other.blob.core.windows.net/c/b"),
+                None,
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::CannotVerifyCopySource(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_download_range_is_emulated_by_in_mem_backend() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![0u8, 1u8, 2u8, 3u8, 4u8])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![1u8, 2u8, 3u8],
+            connection
+                .download_range("test", "blob", 1, Some(3))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            vec![3u8, 4u8],
+            connection
+                .download_range("test", "blob", 3, None)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            vec![4u8],
+            connection
+                .download_range("test", "blob", 4, Some(10))
+                .await
+                .unwrap()
+        );
+
+        let err = connection
+            .download_range("test", "blob", 5, Some(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AzureStorageError::InvalidRange(_)));
+        assert_eq!(
+            Some(crate::AzureErrorCode::InvalidRange),
+            err.get_error_code()
+        );
+
+        connection
+            .upload_block_blob("test", "empty", Vec::<u8>::new())
+            .await
+            .unwrap();
+        assert!(connection
+            .download_range("test", "empty", 0, None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let page_blob = AzurePageBlobStorage::new(connection.clone(), "test", "pages").await;
+        page_blob.create(3).await.unwrap();
+        page_blob.save_pages(1, vec![9u8; 512]).await.unwrap();
+
+        let result = connection
+            .download_range("test", "pages", 500, Some(600))
+            .await
+            .unwrap();
+        assert_eq!(600, result.len());
+        assert_eq!(vec![0u8; 12], result[..12]);
+        assert_eq!(vec![9u8; 512], result[12..524]);
+        assert_eq!(vec![0u8; 76], result[524..]);
+
+        let result = connection
+            .download_range("test", "not-exists", 0, None)
+            .await;
+        assert!(result.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_page_operations_on_a_block_blob_fail_with_invalid_blob_type() {
+        let connection = Arc::new(AzureStorageConnection::new_in_memory());
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![1u8, 2u8])
+            .await
+            .unwrap();
+
+        let page_blob = AzurePageBlobStorage::new(connection.clone(), "test", "blob").await;
+
+        let result = page_blob.resize(2).await;
+        assert!(matches!(result, Err(AzureStorageError::InvalidBlobType(_))));
+
+        let result = page_blob.save_pages(0, vec![1u8; 512]).await;
+        assert!(matches!(result, Err(AzureStorageError::InvalidBlobType(_))));
+
+        let result = page_blob.get_pages(0, 1).await;
+        assert!(matches!(result, Err(AzureStorageError::InvalidBlobType(_))));
+    }
+}
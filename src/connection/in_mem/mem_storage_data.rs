@@ -1,5 +1,6 @@
 use std::{collections::HashMap, sync::Arc};
 
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use tokio::sync::RwLock;
 
 use super::ContainerInMem;
@@ -7,22 +8,52 @@ use super::ContainerInMem;
 use super::TableStorageInMem;
 #[cfg(feature = "table-storage")]
 use crate::table_storage::TableStorageError;
+use crate::{
+    blob_version::{DeletedBlobContainer, LocalRetentionPolicy},
+    AzureStorageError, StorageClock, SystemClock,
+};
+
+struct DeletedContainerInMem {
+    name: String,
+    version: String,
+    deleted_at: DateTimeAsMicroseconds,
+    container: Arc<ContainerInMem>,
+}
 
 pub struct MemStorageData {
     containers: RwLock<HashMap<String, Arc<ContainerInMem>>>,
+    // Always locked after `containers`
+    deleted_containers: RwLock<Vec<DeletedContainerInMem>>,
     #[cfg(feature = "table-storage")]
     tables: RwLock<HashMap<String, Arc<TableStorageInMem>>>,
+    clock: Arc<dyn StorageClock>,
+    retention: LocalRetentionPolicy,
 }
 
 impl MemStorageData {
     pub fn new() -> Self {
         Self {
             containers: RwLock::new(HashMap::new()),
+            deleted_containers: RwLock::new(Vec::new()),
             #[cfg(feature = "table-storage")]
             tables: RwLock::new(HashMap::new()),
+            clock: Arc::new(SystemClock),
+            retention: LocalRetentionPolicy::default(),
         }
     }
 
+    /// Clock of lease expiry. Applies to the containers created afterwards
+    pub fn with_clock(mut self, clock: Arc<dyn StorageClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Versioning and soft delete emulation. Applies to the containers created afterwards
+    pub fn with_retention_policy(mut self, retention: LocalRetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
     pub async fn get_list(&self) -> Vec<String> {
         let read_access = self.containers.read().await;
         read_access.keys().map(|itm| itm.to_string()).collect()
@@ -33,7 +64,13 @@ impl MemStorageData {
         if write_access.contains_key(container_name) {
             return;
         }
-        write_access.insert(container_name.to_string(), Arc::new(ContainerInMem::new()));
+        write_access.insert(
+            container_name.to_string(),
+            Arc::new(ContainerInMem::new(
+                self.clock.clone(),
+                self.retention.clone(),
+            )),
+        );
     }
 
     pub async fn get_container(&self, container_name: &str) -> Option<Arc<ContainerInMem>> {
@@ -42,9 +79,74 @@ impl MemStorageData {
         Some(result.clone())
     }
 
+    /// With soft delete the container can be undeleted within the retention period
     pub async fn delete(&self, container_name: &str) -> bool {
         let mut write_access = self.containers.write().await;
-        write_access.remove(container_name).is_some()
+
+        let container = match write_access.remove(container_name) {
+            Some(container) => container,
+            None => return false,
+        };
+
+        if self.retention.delete_retention.is_some() {
+            let moment = crate::blob::next_write_moment();
+
+            let mut deleted_containers = self.deleted_containers.write().await;
+
+            deleted_containers.push(DeletedContainerInMem {
+                name: container_name.to_string(),
+                version: format!("{:016X}", moment.unix_microseconds),
+                deleted_at: self.clock.now(),
+                container,
+            });
+        }
+
+        true
+    }
+
+    pub async fn get_list_of_deleted(&self) -> Vec<DeletedBlobContainer> {
+        let read_access = self.deleted_containers.read().await;
+
+        let now = self.clock.now();
+
+        read_access
+            .iter()
+            .filter(|itm| !self.retention.is_expired(itm.deleted_at, now))
+            .map(|itm| DeletedBlobContainer {
+                name: itm.name.to_string(),
+                version: itm.version.to_string(),
+            })
+            .collect()
+    }
+
+    pub async fn undelete(
+        &self,
+        container_name: &str,
+        version: &str,
+    ) -> Result<(), AzureStorageError> {
+        let mut write_access = self.containers.write().await;
+
+        if write_access.contains_key(container_name) {
+            return Err(AzureStorageError::ContainerAlreadyExists(None));
+        }
+
+        let mut deleted_containers = self.deleted_containers.write().await;
+
+        let now = self.clock.now();
+        deleted_containers.retain(|itm| !self.retention.is_expired(itm.deleted_at, now));
+
+        let index = deleted_containers
+            .iter()
+            .position(|itm| itm.name == container_name && itm.version == version);
+
+        match index {
+            Some(index) => {
+                let deleted = deleted_containers.remove(index);
+                write_access.insert(container_name.to_string(), deleted.container);
+                Ok(())
+            }
+            None => Err(AzureStorageError::ContainerNotFound(None)),
+        }
     }
 
     #[cfg(feature = "table-storage")]
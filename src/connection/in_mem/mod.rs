@@ -1,12 +1,18 @@
+mod append_blob;
+mod blob_chunk_reader;
 mod blob_data;
+mod blob_history;
 mod block_blob;
 mod container_in_mem;
 mod mem_storage_data;
 pub mod operations;
 mod page_blob;
+mod staged_blocks;
 #[cfg(feature = "table-storage")]
 mod table_storage;
-pub use blob_data::BlobData;
+pub use append_blob::AppendBlobInMem;
+pub use blob_chunk_reader::MemBlobChunkReader;
+pub use blob_data::{BlobContent, BlobData};
 pub use container_in_mem::ContainerInMem;
 pub use mem_storage_data::MemStorageData;
 pub use page_blob::PageBlobInMem;
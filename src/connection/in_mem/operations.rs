@@ -14,7 +14,7 @@ pub async fn get_container(
 ) -> Result<Arc<ContainerInMem>, AzureStorageError> {
     match storage_data.get_container(container_name).await {
         Some(result) => Ok(result),
-        None => Err(AzureStorageError::ContainerNotFound),
+        None => Err(AzureStorageError::ContainerNotFound(None)),
     }
 }
 
@@ -1,7 +1,10 @@
+use std::{ops::Range, sync::Arc};
+
 use rust_extensions::SliceOrVec;
 
-use crate::{blob::BlobProperties, page_blob::consts::BLOB_PAGE_SIZE, AzureStorageError};
+use crate::{page_blob::consts::BLOB_PAGE_SIZE, AzureStorageError};
 
+#[derive(Clone)]
 struct Page {
     data: [u8; BLOB_PAGE_SIZE],
 }
@@ -22,8 +25,10 @@ impl Page {
     }
 }
 
+/// Pages are shared with the snapshots of the blob and copied on the first write after a snapshot
+#[derive(Clone)]
 pub struct PageBlobInMem {
-    pages: Vec<Page>,
+    pages: Vec<Arc<Page>>,
 }
 
 impl PageBlobInMem {
@@ -35,15 +40,9 @@ impl PageBlobInMem {
         result
     }
 
-    pub fn get_blob_properties(&self) -> BlobProperties {
-        BlobProperties {
-            blob_size: self.pages.len() * BLOB_PAGE_SIZE,
-        }
-    }
-
     pub fn resize(&mut self, pages_amount: usize) {
         while self.pages.len() < pages_amount {
-            self.pages.push(Page::new());
+            self.pages.push(Arc::new(Page::new()));
         }
 
         while self.pages.len() > pages_amount {
@@ -94,10 +93,10 @@ impl PageBlobInMem {
             let page = self.pages.get_mut(page_index);
 
             if page.is_none() {
-                return Err(AzureStorageError::InvalidPageRange);
+                return Err(AzureStorageError::InvalidPageRange(None));
             }
 
-            let page = page.unwrap();
+            let page = Arc::make_mut(page.unwrap());
 
             page.as_slice_mut().copy_from_slice(slice);
 
@@ -124,6 +123,25 @@ impl PageBlobInMem {
         result
     }
 
+    /// Bytes of the range, which may start and end inside a page
+    pub fn download_range(&self, range: Range<usize>) -> Vec<u8> {
+        let mut result = Vec::with_capacity(range.len());
+
+        let mut position = range.start;
+
+        while position < range.end {
+            let page = self.pages[position / BLOB_PAGE_SIZE].as_slice();
+            let page_offset = position % BLOB_PAGE_SIZE;
+            let to_read = (BLOB_PAGE_SIZE - page_offset).min(range.end - position);
+
+            result.extend_from_slice(&page[page_offset..page_offset + to_read]);
+
+            position += to_read;
+        }
+
+        result
+    }
+
     pub fn get_size(&self) -> usize {
         self.pages.len() * BLOB_PAGE_SIZE
     }
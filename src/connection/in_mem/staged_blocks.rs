@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use crate::{
+    block_blob::{
+        check_block_id, BlockItem, BlockListItem, MAX_COMMITTED_BLOCKS, MAX_UNCOMMITTED_BLOCKS,
+    },
+    AzureStorageError,
+};
+
+use super::block_blob::BlockBlob;
+
+/// Blocks put to a blob and not committed yet, in the order they were put
+#[derive(Default)]
+pub struct StagedBlocks {
+    blocks: Vec<(String, Arc<Vec<u8>>)>,
+}
+
+impl StagedBlocks {
+    /// Putting a block with the id of a staged one replaces it
+    pub fn put(&mut self, block_id: &str, content: Vec<u8>) -> Result<(), AzureStorageError> {
+        let same_length_as = self.blocks.first().map(|(id, _)| id.as_str());
+        check_block_id(block_id, same_length_as)?;
+
+        if let Some((_, staged)) = self.blocks.iter_mut().find(|(id, _)| id == block_id) {
+            *staged = Arc::new(content);
+            return Ok(());
+        }
+
+        if self.blocks.len() >= MAX_UNCOMMITTED_BLOCKS {
+            return Err(AzureStorageError::BlockCountExceedsLimit(None));
+        }
+
+        self.blocks.push((block_id.to_string(), Arc::new(content)));
+
+        Ok(())
+    }
+
+    pub fn get_blocks(&self) -> Vec<BlockItem> {
+        self.blocks
+            .iter()
+            .map(|(block_id, content)| BlockItem {
+                block_id: block_id.clone(),
+                size: content.len(),
+            })
+            .collect()
+    }
+
+    /// The blob the block list commits. `committed` is the block blob being overwritten
+    pub fn compile_block_blob(
+        &self,
+        blocks: &[BlockListItem],
+        committed: Option<&BlockBlob>,
+    ) -> Result<BlockBlob, AzureStorageError> {
+        if blocks.len() > MAX_COMMITTED_BLOCKS {
+            return Err(AzureStorageError::BlockListTooLong(None));
+        }
+
+        let mut content = Vec::new();
+        let mut block_items = Vec::with_capacity(blocks.len());
+
+        for block in blocks {
+            let block_content = match block {
+                BlockListItem::Committed(block_id) => {
+                    committed.and_then(|itm| itm.get_committed_block(block_id))
+                }
+                BlockListItem::Uncommitted(block_id) => self.get_block(block_id),
+                BlockListItem::Latest(block_id) => self
+                    .get_block(block_id)
+                    .or_else(|| committed.and_then(|itm| itm.get_committed_block(block_id))),
+            };
+
+            let block_content = block_content.ok_or(AzureStorageError::InvalidBlockList(None))?;
+
+            content.extend_from_slice(block_content);
+            block_items.push(BlockItem {
+                block_id: block.get_block_id().to_string(),
+                size: block_content.len(),
+            });
+        }
+
+        Ok(BlockBlob::from_blocks(content, block_items))
+    }
+
+    fn get_block(&self, block_id: &str) -> Option<&[u8]> {
+        let (_, content) = self.blocks.iter().find(|(id, _)| id == block_id)?;
+        Some(content.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        blob::BlobApi,
+        blob_container::BlobContainersApi,
+        block_blob::{BlockBlobApi, BlockItem, BlockListItem, BlockListType},
+        AzureStorageConnection, AzureStorageError,
+    };
+
+    #[tokio::test]
+    async fn test_block_lifecycle_is_emulated_by_in_mem_backend() {
+        let connection = AzureStorageConnection::new_in_memory();
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        // "AAA", "BBB", "CCC"
+        connection
+            .put_block("test", "blob", "QUFB", vec![1u8, 2u8], None)
+            .await
+            .unwrap();
+        connection
+            .put_block("test", "blob", "QkJC", vec![3u8], None)
+            .await
+            .unwrap();
+
+        let result = connection
+            .put_block("test", "blob", "QUFBQQ==", vec![4u8], None)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::InvalidBlockId(_))));
+
+        // Uncommitted blocks do not make a blob
+        let result = connection.download_blob("test", "blob").await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+
+        let block_list = connection
+            .get_block_list("test", "blob", BlockListType::All)
+            .await
+            .unwrap();
+        assert!(block_list.committed.is_empty());
+        assert_eq!(
+            vec![
+                BlockItem {
+                    block_id: "QUFB".to_string(),
+                    size: 2
+                },
+                BlockItem {
+                    block_id: "QkJC".to_string(),
+                    size: 1
+                },
+            ],
+            block_list.uncommitted
+        );
+
+        connection
+            .put_block_list(
+                "test",
+                "blob",
+                vec![
+                    BlockListItem::Uncommitted("QUFB".to_string()),
+                    BlockListItem::Latest("QkJC".to_string()),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![1u8, 2u8, 3u8],
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        let block_list = connection
+            .get_block_list("test", "blob", BlockListType::All)
+            .await
+            .unwrap();
+        assert_eq!(2, block_list.committed.len());
+        assert!(block_list.uncommitted.is_empty());
+
+        // Appending: the committed blocks plus a new one
+        connection
+            .put_block("test", "blob", "Q0ND", vec![5u8, 6u8], None)
+            .await
+            .unwrap();
+
+        let mut blocks = block_list.to_committed_items();
+        blocks.push(BlockListItem::Uncommitted("Q0ND".to_string()));
+
+        connection
+            .put_block_list("test", "blob", blocks)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![1u8, 2u8, 3u8, 5u8, 6u8],
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        let result = connection
+            .put_block_list(
+                "test",
+                "blob",
+                vec![BlockListItem::Uncommitted("QUFB".to_string())],
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::InvalidBlockList(_))
+        ));
+
+        // Put Blob leaves no blocks behind
+        connection
+            .put_block("test", "blob", "QUFB", vec![7u8], None)
+            .await
+            .unwrap();
+        connection
+            .upload_block_blob("test", "blob", vec![8u8])
+            .await
+            .unwrap();
+
+        let block_list = connection
+            .get_block_list("test", "blob", BlockListType::All)
+            .await
+            .unwrap();
+        assert!(block_list.committed.is_empty());
+        assert!(block_list.uncommitted.is_empty());
+
+        let result = connection
+            .get_block_list("test", "not-exists", BlockListType::All)
+            .await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+    }
+}
@@ -1,9 +1,15 @@
 mod azure_storage_connection;
 mod azure_storage_connection_data;
+mod azure_storage_credentials;
+mod connection_string;
 mod file;
 pub mod in_mem;
 pub mod into_fl_url;
+mod sas_token;
 
 pub use azure_storage_connection::AzureStorageConnection;
 pub use azure_storage_connection_data::AzureStorageConnectionData;
+pub use azure_storage_credentials::AzureStorageCredentials;
+pub use connection_string::ConnectionStringError;
 pub use file::*;
+pub use sas_token::SasToken;
@@ -0,0 +1,114 @@
+use flurl::FlUrl;
+
+use super::ConnectionStringError;
+
+/// Shared access signature parsed into query parameters. Values are kept url-decoded,
+/// so they are encoded once when they are appended to the request.
+#[derive(Debug, Clone)]
+pub struct SasToken {
+    params: Vec<(String, String)>,
+}
+
+impl SasToken {
+    pub fn parse(token: &str) -> Result<Self, ConnectionStringError> {
+        let token = token.trim();
+        let token = token.strip_prefix('?').unwrap_or(token);
+
+        let mut params = Vec::new();
+
+        for kv in token.split('&') {
+            if kv.is_empty() {
+                continue;
+            }
+
+            let (key, value) = match kv.split_once('=') {
+                Some(result) => result,
+                None => return Err(ConnectionStringError::InvalidSharedAccessSignature),
+            };
+
+            params.push((percent_decode(key)?, percent_decode(value)?));
+        }
+
+        if !params.iter().any(|(key, _)| key == "sig") {
+            return Err(ConnectionStringError::InvalidSharedAccessSignature);
+        }
+
+        Ok(Self { params })
+    }
+
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        let (_, value) = self.params.iter().find(|(key, _)| key == name)?;
+        Some(value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn append_to_fl_url(&self, mut fl_url: FlUrl) -> FlUrl {
+        for (key, value) in self.iter() {
+            fl_url = fl_url.append_query_param(key, Some(value));
+        }
+
+        fl_url
+    }
+}
+
+fn percent_decode(src: &str) -> Result<String, ConnectionStringError> {
+    let src = src.as_bytes();
+    let mut result = Vec::with_capacity(src.len());
+
+    let mut index = 0;
+
+    while index < src.len() {
+        match src[index] {
+            b'%' => {
+                let hex = src
+                    .get(index + 1..index + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+                match hex {
+                    Some(value) => result.push(value),
+                    None => return Err(ConnectionStringError::InvalidSharedAccessSignature),
+                }
+
+                index += 3;
+            }
+            value => {
+                result.push(value);
+                index += 1;
+            }
+        }
+    }
+
+    match String::from_utf8(result) {
+        Ok(result) => Ok(result),
+        Err(_) => Err(ConnectionStringError::InvalidSharedAccessSignature),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sas_token() {
+        let token = SasToken::parse(
+            "?sv=2019-12-12&ss=b&srt=sco&sp=rl&se=2030-01-01T00:00:00Z&sig=ab%2Bcd%2F12%3D",
+        )
+        .unwrap();
+
+        assert_eq!("2019-12-12", token.get_param("sv").unwrap());
+        assert_eq!("2030-01-01T00:00:00Z", token.get_param("se").unwrap());
+        assert_eq!("ab+cd/12=", token.get_param("sig").unwrap());
+    }
+
+    #[test]
+    fn test_token_without_signature_is_rejected() {
+        assert!(SasToken::parse("sv=2019-12-12&sp=r").is_err());
+        assert!(SasToken::parse("sv=2019-12-12&sig=%zz").is_err());
+    }
+}
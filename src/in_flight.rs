@@ -0,0 +1,196 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::AzureStorageError;
+
+type InFlightFuture<'s, T> =
+    Pin<Box<dyn Future<Output = Result<T, AzureStorageError>> + Send + 's>>;
+
+/// Requests of a transfer which run concurrently on the task of the transfer.
+/// Every request gets a waker of its own, so a wake-up polls only the requests which were woken
+/// and costs the same however many of them are in flight
+pub(crate) struct InFlight<'s, T> {
+    slots: Vec<Option<(InFlightFuture<'s, T>, Waker)>>,
+    free_slots: Vec<usize>,
+    len: usize,
+    woken: Arc<WokenSlots>,
+}
+
+#[derive(Default)]
+struct WokenSlots {
+    slots: Mutex<Vec<usize>>,
+    waker: Mutex<Option<Waker>>,
+}
+
+struct SlotWaker {
+    slot: usize,
+    woken: Arc<WokenSlots>,
+}
+
+impl Wake for SlotWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.slots.lock().unwrap().push(self.slot);
+
+        if let Some(waker) = self.woken.waker.lock().unwrap().as_ref() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+impl<'s, T> InFlight<'s, T> {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+            len: 0,
+            woken: Arc::new(WokenSlots::default()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The request is first polled by the next poll of the set
+    pub fn push(&mut self, future: impl Future<Output = Result<T, AzureStorageError>> + Send + 's) {
+        let slot = match self.free_slots.pop() {
+            Some(slot) => slot,
+            None => {
+                self.slots.push(None);
+                self.slots.len() - 1
+            }
+        };
+
+        let waker = Waker::from(Arc::new(SlotWaker {
+            slot,
+            woken: self.woken.clone(),
+        }));
+
+        self.slots[slot] = Some((Box::pin(future), waker));
+        self.len += 1;
+        self.woken.slots.lock().unwrap().push(slot);
+    }
+
+    /// Ready with the first woken request which completes, or None if nothing is in flight
+    pub fn poll_next(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<T, AzureStorageError>>> {
+        if self.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        *self.woken.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        let mut woken = std::mem::take(&mut *self.woken.slots.lock().unwrap());
+        woken.sort_unstable();
+        woken.dedup();
+
+        while let Some(slot) = woken.pop() {
+            let (future, waker) = match self.slots[slot].as_mut() {
+                Some(slot) => slot,
+                // A wake-up of a request which has completed since
+                None => continue,
+            };
+
+            if let Poll::Ready(result) = future.as_mut().poll(&mut Context::from_waker(waker)) {
+                self.slots[slot] = None;
+                self.free_slots.push(slot);
+                self.len -= 1;
+
+                // The rest of the woken ones are polled by the next poll
+                self.woken.slots.lock().unwrap().extend(woken);
+
+                return Poll::Ready(Some(result));
+            }
+        }
+
+        Poll::Pending
+    }
+
+    /// Drives the requests and hands over the completed ones.
+    /// Ready once all of them are completed, or with the first one which fails
+    pub fn poll_all(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut completed: impl FnMut(T),
+    ) -> Poll<Result<(), AzureStorageError>> {
+        loop {
+            match self.poll_next(cx) {
+                Poll::Ready(Some(Ok(value))) => completed(value),
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Err(err)),
+                Poll::Ready(None) => return Poll::Ready(Ok(())),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_complete_in_the_order_they_are_done() {
+        let mut in_flight = InFlight::new();
+
+        for (value, delay) in [(1, 30), (2, 10), (3, 20)] {
+            in_flight.push(async move {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                Ok(value)
+            });
+        }
+
+        assert_eq!(3, in_flight.len());
+
+        let mut completed = Vec::new();
+
+        while let Some(value) = std::future::poll_fn(|cx| in_flight.poll_next(cx)).await {
+            completed.push(value.unwrap());
+        }
+
+        assert_eq!(vec![2, 3, 1], completed);
+        assert!(in_flight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_poll_all_fails_with_the_first_failed_request() {
+        let mut in_flight = InFlight::new();
+
+        in_flight.push(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(1)
+        });
+        in_flight.push(async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Err(AzureStorageError::ConditionNotMet(None))
+        });
+        in_flight.push(async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(3)
+        });
+
+        let mut completed = Vec::new();
+
+        let result =
+            std::future::poll_fn(|cx| in_flight.poll_all(cx, |value| completed.push(value))).await;
+
+        assert!(matches!(result, Err(AzureStorageError::ConditionNotMet(_))));
+        assert_eq!(vec![1], completed);
+        assert_eq!(1, in_flight.len());
+    }
+}
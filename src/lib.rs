@@ -1,14 +1,33 @@
+pub mod append_blob;
 pub mod blob;
 pub mod blob_container;
+pub mod blob_copy;
+pub mod blob_lease;
+pub mod blob_snapshot;
+pub mod blob_stream;
+pub mod blob_transfer;
+pub mod blob_version;
 pub mod block_blob;
+mod clock;
 mod connection;
+mod in_flight;
 
 pub mod page_blob;
+pub mod sas;
 pub mod sdk_azure;
 mod sdk_files;
+pub mod storage_backend;
 #[cfg(feature = "table-storage")]
 pub mod table_storage;
+#[cfg(test)]
+mod test_http_server;
+pub mod token_credential;
 
-pub use connection::{AzureStorageConnection, AzureStorageConnectionData};
+pub use clock::{ManualClock, StorageClock, SystemClock};
+pub use connection::{
+    AzureStorageConnection, AzureStorageConnectionData, AzureStorageCredentials,
+    ConnectionStringError, SasToken,
+};
 mod types;
-pub use types::AzureStorageError;
+pub use sdk_azure::retry_policy::RetryPolicy;
+pub use types::{AzureErrorCode, AzureServiceError, AzureStorageError, ErrorKind, StorageError};
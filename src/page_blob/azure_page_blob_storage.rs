@@ -1,8 +1,12 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use rust_extensions::{SliceOrVec, StrOrString};
 
-use crate::{blob::BlobProperties, AzureStorageConnection, AzureStorageError};
+use crate::{
+    blob::{BlobAccessConditions, BlobProperties},
+    storage_backend::{BlobBackend, BlobContainersBackend, BlobSnapshotBackend, PageBlobBackend},
+    AzureErrorCode, AzureStorageConnection, AzureStorageError,
+};
 
 use super::{consts::BLOB_PAGE_SIZE, PageBlobAbstractions, PageBlobProperties};
 
@@ -22,8 +26,8 @@ pub trait MyAzurePageBlobStorage {
     ) -> Result<PageBlobProperties, AzureStorageError> {
         match self.get_blob_properties().await {
             Ok(result) => Ok(result.into()),
-            Err(err) => match err {
-                AzureStorageError::ContainerNotFound => {
+            Err(err) => match err.get_error_code() {
+                Some(AzureErrorCode::ContainerNotFound) => {
                     if auto_create_container {
                         self.create_container_if_not_exists().await?;
                     } else {
@@ -31,17 +35,11 @@ pub trait MyAzurePageBlobStorage {
                     }
 
                     self.create(pages_amount).await?;
-                    return Ok(BlobProperties {
-                        blob_size: pages_amount * BLOB_PAGE_SIZE,
-                    }
-                    .into());
+                    return self.get_blob_properties().await;
                 }
-                AzureStorageError::BlobNotFound => {
+                Some(AzureErrorCode::BlobNotFound) => {
                     self.create(pages_amount).await?;
-                    return Ok(BlobProperties {
-                        blob_size: pages_amount * BLOB_PAGE_SIZE,
-                    }
-                    .into());
+                    return self.get_blob_properties().await;
                 }
                 _ => Err(err),
             },
@@ -108,16 +106,18 @@ pub struct AzurePageBlobStorage {
     connection: Arc<AzureStorageConnection>,
     container_name: String,
     blob_name: String,
-    id: String,
 }
 
 impl Drop for AzurePageBlobStorage {
     fn drop(&mut self) {
         if self.connection.as_ref().is_file() {
-            let id = self.id.to_string();
             let connection = self.connection.clone();
+            let container_name = self.container_name.to_string();
+            let blob_name = self.blob_name.to_string();
             tokio::spawn(async move {
                 if let AzureStorageConnection::File(connection_data) = connection.as_ref() {
+                    let id = connection_data
+                        .get_page_blob_id(container_name.as_str(), blob_name.as_str());
                     connection_data.drop_page_blob_data(id).await;
                 }
             });
@@ -131,28 +131,15 @@ impl AzurePageBlobStorage {
         container_name: impl Into<StrOrString<'static>>,
         blob_name: impl Into<StrOrString<'static>>,
     ) -> Self {
-        let id = generate_id();
-
         let container_name: StrOrString<'static> = container_name.into();
         let container_name = container_name.to_string();
         let blob_name: StrOrString<'static> = blob_name.into();
         let blob_name = blob_name.to_string();
 
-        if let AzureStorageConnection::File(connection_data) = connection.as_ref() {
-            connection_data
-                .apply_page_blob_data(
-                    id.to_string(),
-                    container_name.to_string(),
-                    blob_name.to_string(),
-                )
-                .await;
-        }
-
         Self {
             connection,
             container_name,
             blob_name,
-            id,
         }
     }
 
@@ -165,156 +152,85 @@ impl AzurePageBlobStorage {
     }
 
     pub async fn resize(&self, pages_amount: usize) -> Result<(), AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::page_blobs::resize_page_blob(
-                    connection_data,
-                    self.container_name.as_str(),
-                    self.blob_name.as_str(),
-                    pages_amount,
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                connection_data.resize(self.id.as_str(), pages_amount).await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    self.container_name.as_ref(),
-                )
-                .await?;
-
-                container
-                    .resize_page_blob(self.blob_name.as_str(), pages_amount)
-                    .await
-            }
-        }
+        self.connection
+            .get_storage_backend()
+            .resize_page_blob(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                pages_amount,
+            )
+            .await
     }
 
     pub async fn create_container_if_not_exists(&self) -> Result<(), AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::containers::create_if_not_exists(
-                    connection_data,
-                    self.container_name.as_str(),
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                connection_data
-                    .create_container_if_not_exists(self.id.as_str())
-                    .await?;
-
-                Ok(())
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                connection_data.create(self.container_name.as_ref()).await;
-                Ok(())
-            }
-        }
+        self.connection
+            .get_storage_backend()
+            .create_container_if_not_exists(self.container_name.as_str())
+            .await
     }
 
     pub async fn create(&self, pages_amount: usize) -> Result<(), AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::page_blobs::create_page_blob(
-                    connection_data,
-                    self.container_name.as_str(),
-                    &self.blob_name,
-                    pages_amount,
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                connection_data
-                    .create_blob(self.id.as_str(), pages_amount)
-                    .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    self.container_name.as_ref(),
-                )
-                .await?;
-
-                container
-                    .create_page_blob(self.blob_name.as_ref(), pages_amount)
-                    .await
-            }
-        }
+        self.create_with_metadata(pages_amount, HashMap::new())
+            .await
     }
 
-    /*
-       pub async fn create_if_not_exists(
-           &self,
-           pages_amount: usize,
-       ) -> Result<usize, AzureStorageError> {
-           let props = match self.connection.as_ref() {
-               AzureStorageConnection::AzureStorage(connection_data) => {
-                   crate::sdk_azure::page_blobs::create_page_blob_if_not_exists(
-                       connection_data,
-                       self.container_name.as_str(),
-                       &self.blob_name,
-                       pages_amount,
-                   )
-                   .await?
-               }
-               AzureStorageConnection::File(connection_data) => {
-                   connection_data
-                       .create_blob_if_not_exists(self.id.as_str(), pages_amount)
-                       .await?
-               }
-               AzureStorageConnection::InMemory(connection_data) => {
-                   let container = crate::connection::in_mem::operations::get_container(
-                       connection_data,
-                       self.container_name.as_ref(),
-                   )
-                   .await?;
-
-                   container
-                       .create_page_blob_if_not_exists(self.blob_name.as_ref(), pages_amount)
-                       .await?
-               }
-           };
-
-           Ok(props.blob_size / crate::page_blob::consts::BLOB_PAGE_SIZE)
-       }
-    */
+    /// Creates the blob with user-defined metadata sent as x-ms-meta-* headers
+    pub async fn create_with_metadata(
+        &self,
+        pages_amount: usize,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError> {
+        self.connection
+            .get_storage_backend()
+            .create_page_blob(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                pages_amount,
+                metadata,
+            )
+            .await
+    }
 
     pub async fn get_pages(
         &self,
         start_page_no: usize,
         pages_amount: usize,
     ) -> Result<Vec<u8>, AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::page_blobs::get_pages(
-                    connection_data,
-                    self.container_name.as_str(),
-                    self.blob_name.as_str(),
-                    start_page_no,
-                    pages_amount,
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                connection_data
-                    .get(self.id.as_str(), start_page_no, pages_amount)
-                    .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    self.container_name.as_ref(),
-                )
-                .await?;
+        self.connection
+            .get_storage_backend()
+            .get_pages(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                start_page_no,
+                pages_amount,
+            )
+            .await
+    }
 
-                container
-                    .get_page_blob_pages(self.blob_name.as_ref(), start_page_no, pages_amount)
-                    .await
-            }
-        }
+    /// Point-in-time copy of the blob. Returns the snapshot id
+    pub async fn create_snapshot(&self) -> Result<String, AzureStorageError> {
+        self.connection
+            .get_storage_backend()
+            .create_blob_snapshot(self.container_name.as_str(), self.blob_name.as_str())
+            .await
+    }
+
+    pub async fn get_pages_at_snapshot(
+        &self,
+        snapshot: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        self.connection
+            .get_storage_backend()
+            .get_blob_snapshot_pages(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                snapshot,
+                start_page_no,
+                pages_amount,
+            )
+            .await
     }
 
     pub async fn save_pages<'s>(
@@ -322,176 +238,61 @@ impl AzurePageBlobStorage {
         start_page_no: usize,
         payload: impl Into<SliceOrVec<'s, u8>>,
     ) -> Result<(), AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::page_blobs::save_pages(
-                    connection_data,
-                    self.container_name.as_str(),
-                    self.blob_name.as_str(),
-                    start_page_no,
-                    payload.into().into_vec(),
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                connection_data
-                    .save_pages(self.id.as_str(), start_page_no, payload)
-                    .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    self.container_name.as_ref(),
-                )
-                .await?;
+        self.save_pages_with_conditions(start_page_no, payload, BlobAccessConditions::new())
+            .await?;
 
-                container
-                    .upload_pages_to_page_blob(self.blob_name.as_ref(), start_page_no, payload)
-                    .await
-            }
-        }
+        Ok(())
     }
 
-    pub async fn delete(&self) -> Result<(), AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::delete(
-                    connection_data,
-                    self.container_name.as_str(),
-                    self.blob_name.as_str(),
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                let result = connection_data.delete_blob(self.id.as_str()).await;
-
-                crate::sdk_files::containers::check_error_if_container_exists(
-                    result,
-                    connection_data,
-                    self.container_name.as_str(),
-                )
-                .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    self.container_name.as_ref(),
-                )
-                .await?;
+    /// Writes the pages if the conditions are met. Returns ETag of the blob after the write
+    pub async fn save_pages_with_conditions<'s>(
+        &self,
+        start_page_no: usize,
+        payload: impl Into<SliceOrVec<'s, u8>>,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        self.connection
+            .get_storage_backend()
+            .save_pages(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                start_page_no,
+                payload.into().into_vec(),
+                conditions,
+            )
+            .await
+    }
 
-                if container.delete(self.blob_name.as_ref()).await {
-                    Ok(())
-                } else {
-                    Err(AzureStorageError::BlobNotFound)
-                }
-            }
-        }
+    pub async fn delete(&self) -> Result<(), AzureStorageError> {
+        self.connection
+            .get_storage_backend()
+            .delete_blob(
+                self.container_name.as_str(),
+                self.blob_name.as_str(),
+                BlobAccessConditions::new(),
+            )
+            .await
     }
 
     pub async fn delete_if_exists(&self) -> Result<(), AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::delete_if_exists(
-                    connection_data,
-                    self.container_name.as_str(),
-                    self.blob_name.as_str(),
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                let result = connection_data
-                    .delete_blob_if_exists(self.id.as_str())
-                    .await;
-
-                crate::sdk_files::containers::check_error_if_container_exists(
-                    result,
-                    connection_data,
-                    self.container_name.as_str(),
-                )
-                .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    self.container_name.as_ref(),
-                )
-                .await?;
-
-                container.delete(self.blob_name.as_ref()).await;
-
-                Ok(())
-            }
-        }
+        self.connection
+            .get_storage_backend()
+            .delete_blob_if_exists(self.container_name.as_str(), self.blob_name.as_str(), None)
+            .await
     }
 
     pub async fn download(&self) -> Result<Vec<u8>, AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::download(
-                    connection_data,
-                    self.container_name.as_str(),
-                    self.blob_name.as_str(),
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                let result = connection_data.download(self.id.as_str()).await;
-
-                crate::sdk_files::containers::check_error_if_container_exists(
-                    result,
-                    connection_data,
-                    self.container_name.as_str(),
-                )
-                .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    self.container_name.as_ref(),
-                )
-                .await?;
-
-                container.download(self.blob_name.as_str()).await
-            }
-        }
+        self.connection
+            .get_storage_backend()
+            .download_blob(self.container_name.as_str(), self.blob_name.as_str())
+            .await
     }
 
     pub async fn get_blob_properties(&self) -> Result<BlobProperties, AzureStorageError> {
-        match self.connection.as_ref() {
-            AzureStorageConnection::AzureStorage(connection_data) => {
-                crate::sdk_azure::blobs::get_blob_properties(
-                    connection_data,
-                    self.container_name.as_ref(),
-                    self.blob_name.as_ref(),
-                )
-                .await
-            }
-            AzureStorageConnection::File(connection_data) => {
-                let file_name = crate::sdk_files::utils::compile_blob_path(
-                    connection_data,
-                    self.container_name.as_str(),
-                    self.blob_name.as_str(),
-                );
-
-                let result = crate::sdk_files::utils::get_blob_properties(file_name.as_str()).await;
-
-                crate::sdk_files::containers::check_error_if_container_exists(
-                    result,
-                    connection_data,
-                    self.container_name.as_str(),
-                )
-                .await
-            }
-            AzureStorageConnection::InMemory(connection_data) => {
-                let container = crate::connection::in_mem::operations::get_container(
-                    connection_data,
-                    self.container_name.as_ref(),
-                )
-                .await?;
-
-                container.get_blob_properties(self.blob_name.as_str()).await
-            }
-        }
+        self.connection
+            .get_storage_backend()
+            .get_blob_properties(self.container_name.as_str(), self.blob_name.as_str())
+            .await
     }
 }
 
@@ -559,6 +360,3 @@ impl MyAzurePageBlobStorage for AzurePageBlobStorage {
         Ok(result.into())
     }
 }
-fn generate_id() -> String {
-    uuid::Uuid::new_v4().to_string()
-}
@@ -0,0 +1,7 @@
+mod sas_builder;
+mod sas_error;
+mod sas_permissions;
+pub(crate) mod utils;
+pub use sas_builder::*;
+pub use sas_error::*;
+pub use sas_permissions::*;
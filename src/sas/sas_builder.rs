@@ -0,0 +1,488 @@
+use std::net::IpAddr;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::AzureStorageConnectionData;
+
+use super::{
+    utils, SasError, SasPermissions, SasResourceTypes, SasServices, ACCOUNT_PERMISSIONS_ORDER,
+    BLOB_PERMISSIONS_ORDER, CONTAINER_PERMISSIONS_ORDER, TABLE_PERMISSIONS_ORDER,
+};
+
+pub const SAS_VERSION: &str = "2018-11-09";
+
+#[derive(Debug, Clone)]
+pub enum SasResource {
+    Blob {
+        container_name: String,
+        blob_name: String,
+    },
+    Container {
+        container_name: String,
+    },
+    Table {
+        table_name: String,
+    },
+    Account {
+        services: SasServices,
+        resource_types: SasResourceTypes,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SasProtocol {
+    HttpsOnly,
+    HttpsAndHttp,
+}
+
+impl SasProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SasProtocol::HttpsOnly => "https",
+            SasProtocol::HttpsAndHttp => "https,http",
+        }
+    }
+}
+
+pub struct SasBuilder<'s> {
+    connection: &'s AzureStorageConnectionData,
+    resource: SasResource,
+    permissions: SasPermissions,
+    start: Option<DateTimeAsMicroseconds>,
+    expiry: Option<DateTimeAsMicroseconds>,
+    ip_range: Option<String>,
+    protocol: Option<SasProtocol>,
+    stored_policy_id: Option<String>,
+}
+
+impl<'s> SasBuilder<'s> {
+    pub fn new(connection: &'s AzureStorageConnectionData, resource: SasResource) -> Self {
+        Self {
+            connection,
+            resource,
+            permissions: SasPermissions::default(),
+            start: None,
+            expiry: None,
+            ip_range: None,
+            protocol: None,
+            stored_policy_id: None,
+        }
+    }
+
+    pub fn with_permissions(mut self, permissions: SasPermissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn with_start(mut self, start: DateTimeAsMicroseconds) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    pub fn with_expiry(mut self, expiry: DateTimeAsMicroseconds) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    pub fn with_ip(mut self, ip: IpAddr) -> Self {
+        self.ip_range = Some(ip.to_string());
+        self
+    }
+
+    pub fn with_ip_range(mut self, from: IpAddr, to: IpAddr) -> Self {
+        self.ip_range = Some(format!("{}-{}", from, to));
+        self
+    }
+
+    pub fn with_protocol(mut self, protocol: SasProtocol) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    /// Refers to a stored access policy of the container or table.
+    /// Permissions and expiry may then be omitted, since the policy provides them
+    pub fn with_stored_policy_id(mut self, stored_policy_id: impl Into<String>) -> Self {
+        self.stored_policy_id = Some(stored_policy_id.into());
+        self
+    }
+
+    /// Signed query string without a leading '?'
+    pub fn to_query_string(&self) -> Result<String, SasError> {
+        let params = self.compile_params()?;
+
+        let mut result = String::new();
+
+        for (key, value) in params {
+            if !result.is_empty() {
+                result.push('&');
+            }
+
+            result.push_str(key);
+            result.push('=');
+            result.push_str(utils::encode_query_value(value.as_str()).as_str());
+        }
+
+        Ok(result)
+    }
+
+    pub fn to_url(&self) -> Result<String, SasError> {
+        let query_string = self.to_query_string()?;
+
+        let resource_url = match &self.resource {
+            SasResource::Blob {
+                container_name,
+                blob_name,
+            } => format!(
+                "{}/{}/{}",
+                self.connection.blobs_api_url,
+                utils::encode_path(container_name),
+                utils::encode_path(blob_name)
+            ),
+            SasResource::Container { container_name } => format!(
+                "{}/{}",
+                self.connection.blobs_api_url,
+                utils::encode_path(container_name)
+            ),
+            SasResource::Table { table_name } => format!(
+                "{}/{}",
+                self.connection.table_storage_api_url,
+                utils::encode_path(table_name)
+            ),
+            SasResource::Account { services, .. } => {
+                if !services.blob && services.table {
+                    format!("{}/", self.connection.table_storage_api_url)
+                } else if !services.blob && services.queue {
+                    format!("{}/", self.connection.queue_api_url)
+                } else {
+                    format!("{}/", self.connection.blobs_api_url)
+                }
+            }
+        };
+
+        Ok(format!("{}?{}", resource_url, query_string))
+    }
+
+    fn compile_params(&self) -> Result<Vec<(&'static str, String)>, SasError> {
+        let account_key = match self.connection.credentials.get_account_key() {
+            Some(account_key) => account_key,
+            None => return Err(SasError::AccountKeyRequired),
+        };
+
+        let permissions = self.compile_permissions()?;
+        let start = self.start.map(utils::to_sas_date).unwrap_or_default();
+        let expiry = self.expiry.map(utils::to_sas_date).unwrap_or_default();
+        let ip_range = self.ip_range.clone().unwrap_or_default();
+        let protocol = self
+            .protocol
+            .map(|itm| itm.as_str().to_string())
+            .unwrap_or_default();
+        let stored_policy_id = self.stored_policy_id.clone().unwrap_or_default();
+
+        if self.stored_policy_id.is_none() {
+            if expiry.is_empty() {
+                return Err(SasError::ExpiryRequired);
+            }
+
+            if permissions.is_empty() {
+                return Err(SasError::PermissionsRequired);
+            }
+        }
+
+        let mut params = vec![("sv", SAS_VERSION.to_string())];
+
+        let string_to_sign = match &self.resource {
+            SasResource::Account {
+                services,
+                resource_types,
+            } => {
+                if self.stored_policy_id.is_some() {
+                    return Err(SasError::StoredPolicyNotSupported);
+                }
+
+                params.push(("ss", services.as_string()));
+                params.push(("srt", resource_types.as_string()));
+
+                format!(
+                    "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
+                    self.connection.account_name,
+                    permissions,
+                    services.as_string(),
+                    resource_types.as_string(),
+                    start,
+                    expiry,
+                    ip_range,
+                    protocol,
+                    SAS_VERSION
+                )
+            }
+            SasResource::Table { .. } => format!(
+                "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n\n\n\n",
+                permissions,
+                start,
+                expiry,
+                self.get_canonicalized_resource(),
+                stored_policy_id,
+                ip_range,
+                protocol,
+                SAS_VERSION,
+            ),
+            SasResource::Blob { .. } | SasResource::Container { .. } => format!(
+                "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n\n\n\n\n\n",
+                permissions,
+                start,
+                expiry,
+                self.get_canonicalized_resource(),
+                stored_policy_id,
+                ip_range,
+                protocol,
+                SAS_VERSION,
+                self.get_signed_resource().unwrap_or_default(),
+            ),
+        };
+
+        add_if_not_empty(&mut params, "sp", permissions);
+        add_if_not_empty(&mut params, "st", start);
+        add_if_not_empty(&mut params, "se", expiry);
+        add_if_not_empty(&mut params, "sip", ip_range);
+        add_if_not_empty(&mut params, "spr", protocol);
+
+        if let Some(signed_resource) = self.get_signed_resource() {
+            params.push(("sr", signed_resource.to_string()));
+        }
+
+        add_if_not_empty(&mut params, "si", stored_policy_id);
+
+        if let SasResource::Table { table_name } = &self.resource {
+            params.push(("tn", table_name.to_string()));
+        }
+
+        let signature =
+            crate::sdk_azure::sign_utils::sign_transaction(string_to_sign.as_str(), account_key);
+
+        params.push(("sig", signature));
+
+        Ok(params)
+    }
+
+    fn compile_permissions(&self) -> Result<String, SasError> {
+        let order = match &self.resource {
+            SasResource::Blob { .. } => BLOB_PERMISSIONS_ORDER,
+            SasResource::Container { .. } => CONTAINER_PERMISSIONS_ORDER,
+            SasResource::Table { .. } => TABLE_PERMISSIONS_ORDER,
+            SasResource::Account { .. } => ACCOUNT_PERMISSIONS_ORDER,
+        };
+
+        self.permissions.compile(order)
+    }
+
+    fn get_signed_resource(&self) -> Option<&'static str> {
+        match &self.resource {
+            SasResource::Blob { .. } => Some("b"),
+            SasResource::Container { .. } => Some("c"),
+            _ => None,
+        }
+    }
+
+    fn get_canonicalized_resource(&self) -> String {
+        let account_name = self.connection.account_name.as_str();
+
+        match &self.resource {
+            SasResource::Blob {
+                container_name,
+                blob_name,
+            } => format!("/blob/{}/{}/{}", account_name, container_name, blob_name),
+            SasResource::Container { container_name } => {
+                format!("/blob/{}/{}", account_name, container_name)
+            }
+            SasResource::Table { table_name } => {
+                format!("/table/{}/{}", account_name, table_name.to_lowercase())
+            }
+            SasResource::Account { .. } => String::new(),
+        }
+    }
+}
+
+fn add_if_not_empty(params: &mut Vec<(&'static str, String)>, key: &'static str, value: String) {
+    if !value.is_empty() {
+        params.push((key, value));
+    }
+}
+
+impl AzureStorageConnectionData {
+    pub fn blob_sas(&self, container_name: &str, blob_name: &str) -> SasBuilder {
+        SasBuilder::new(
+            self,
+            SasResource::Blob {
+                container_name: container_name.to_string(),
+                blob_name: blob_name.to_string(),
+            },
+        )
+    }
+
+    pub fn container_sas(&self, container_name: &str) -> SasBuilder {
+        SasBuilder::new(
+            self,
+            SasResource::Container {
+                container_name: container_name.to_string(),
+            },
+        )
+    }
+
+    pub fn table_sas(&self, table_name: &str) -> SasBuilder {
+        SasBuilder::new(
+            self,
+            SasResource::Table {
+                table_name: table_name.to_string(),
+            },
+        )
+    }
+
+    pub fn account_sas(
+        &self,
+        services: SasServices,
+        resource_types: SasResourceTypes,
+    ) -> SasBuilder {
+        SasBuilder::new(
+            self,
+            SasResource::Account {
+                services,
+                resource_types,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_893_456_000_000_000;
+    const EXPIRY: i64 = 1_893_542_400_000_000;
+
+    fn get_connection() -> AzureStorageConnectionData {
+        AzureStorageConnectionData::from_conn_string("UseDevelopmentStorage=true")
+    }
+
+    #[test]
+    fn test_blob_sas_url() {
+        let connection = get_connection();
+
+        let url = connection
+            .blob_sas("images", "dir/my file.txt")
+            .with_permissions(SasPermissions::read_only())
+            .with_start(DateTimeAsMicroseconds::new(START))
+            .with_expiry(DateTimeAsMicroseconds::new(EXPIRY))
+            .with_ip_range("168.1.5.60".parse().unwrap(), "168.1.5.70".parse().unwrap())
+            .with_protocol(SasProtocol::HttpsAndHttp)
+            .to_url()
+            .unwrap();
+
+        assert_eq!(
+            "http://127.0.0.1:10000/devstoreaccount1/images/dir/my%20file.txt?sv=2018-11-09&sp=r&st=2030-01-01T00%3A00%3A00Z&se=2030-01-02T00%3A00%3A00Z&sip=168.1.5.60-168.1.5.70&spr=https%2Chttp&sr=b&sig=CoWUOSrhVOnHHFVO9XAAqLlqubU43w1Mb%2BuWJM7uHTw%3D",
+            url
+        );
+    }
+
+    #[test]
+    fn test_container_sas_with_stored_policy() {
+        let connection = get_connection();
+
+        let query_string = connection
+            .container_sas("images")
+            .with_stored_policy_id("policy1")
+            .to_query_string()
+            .unwrap();
+
+        assert_eq!(
+            "sv=2018-11-09&sr=c&si=policy1&sig=8Y752U8VHRXQOTc3pVjsvIb19ojQZ3E7HvhLkZlARHc%3D",
+            query_string
+        );
+    }
+
+    #[test]
+    fn test_table_sas() {
+        let connection = get_connection();
+
+        let permissions = SasPermissions {
+            read: true,
+            add: true,
+            update: true,
+            delete: true,
+            ..Default::default()
+        };
+
+        let query_string = connection
+            .table_sas("Orders")
+            .with_permissions(permissions)
+            .with_expiry(DateTimeAsMicroseconds::new(EXPIRY))
+            .with_protocol(SasProtocol::HttpsOnly)
+            .to_query_string()
+            .unwrap();
+
+        assert_eq!(
+            "sv=2018-11-09&sp=raud&se=2030-01-02T00%3A00%3A00Z&spr=https&tn=Orders&sig=ZhIcQU6ybhee3XDJxSFBdj0ZotmVhRFxfdZTo4Xs0AM%3D",
+            query_string
+        );
+    }
+
+    #[test]
+    fn test_account_sas() {
+        let connection = get_connection();
+
+        let services = SasServices {
+            blob: true,
+            table: true,
+            ..Default::default()
+        };
+
+        let resource_types = SasResourceTypes {
+            service: true,
+            container: true,
+            object: true,
+        };
+
+        let permissions = SasPermissions {
+            read: true,
+            write: true,
+            list: true,
+            ..Default::default()
+        };
+
+        let url = connection
+            .account_sas(services, resource_types)
+            .with_permissions(permissions)
+            .with_expiry(DateTimeAsMicroseconds::new(EXPIRY))
+            .to_url()
+            .unwrap();
+
+        assert_eq!(
+            "http://127.0.0.1:10000/devstoreaccount1/?sv=2018-11-09&ss=bt&srt=sco&sp=rwl&se=2030-01-02T00%3A00%3A00Z&sig=vPFwqozcOJ7XCLKOVP1QDf2pOEBDE4Y9FcWZtgLdEqo%3D",
+            url
+        );
+    }
+
+    #[test]
+    fn test_sas_requires_expiry_and_account_key() {
+        let connection = get_connection();
+
+        let result = connection
+            .blob_sas("images", "file.txt")
+            .with_permissions(SasPermissions::read_only())
+            .to_query_string();
+
+        assert_eq!(SasError::ExpiryRequired, result.err().unwrap());
+
+        let connection = AzureStorageConnectionData::try_from_sas_url(
+            "https://myaccount.blob.core.windows.net/images?sv=2018-11-09&sr=c&sp=r&sig=abc",
+        )
+        .unwrap();
+
+        let result = connection
+            .blob_sas("images", "file.txt")
+            .with_permissions(SasPermissions::read_only())
+            .with_expiry(DateTimeAsMicroseconds::new(EXPIRY))
+            .to_query_string();
+
+        assert_eq!(SasError::AccountKeyRequired, result.err().unwrap());
+    }
+}
@@ -0,0 +1,35 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SasError {
+    AccountKeyRequired,
+    ExpiryRequired,
+    PermissionsRequired,
+    PermissionNotSupported(char),
+    StoredPolicyNotSupported,
+}
+
+impl std::fmt::Display for SasError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SasError::AccountKeyRequired => {
+                write!(f, "SAS can only be signed by a connection with an account key")
+            }
+            SasError::ExpiryRequired => {
+                write!(f, "SAS expiry is required when no stored access policy is used")
+            }
+            SasError::PermissionsRequired => write!(
+                f,
+                "SAS permissions are required when no stored access policy is used"
+            ),
+            SasError::PermissionNotSupported(permission) => write!(
+                f,
+                "Permission '{}' is not supported by this SAS resource",
+                permission
+            ),
+            SasError::StoredPolicyNotSupported => {
+                write!(f, "Account SAS can not reference a stored access policy")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SasError {}
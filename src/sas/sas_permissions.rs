@@ -0,0 +1,146 @@
+use super::SasError;
+
+pub const BLOB_PERMISSIONS_ORDER: &str = "racwd";
+pub const CONTAINER_PERMISSIONS_ORDER: &str = "racwdl";
+pub const TABLE_PERMISSIONS_ORDER: &str = "raud";
+pub const ACCOUNT_PERMISSIONS_ORDER: &str = "rwdlacup";
+
+/// Permissions granted by a SAS. For table SAS `read` means query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SasPermissions {
+    pub read: bool,
+    pub add: bool,
+    pub create: bool,
+    pub write: bool,
+    pub delete: bool,
+    pub list: bool,
+    pub update: bool,
+    pub process: bool,
+}
+
+impl SasPermissions {
+    pub fn read_only() -> Self {
+        Self {
+            read: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Compiles permissions in the order the service expects. Fails if a granted permission
+    /// is not a part of the order of the resource
+    pub fn compile(&self, order: &str) -> Result<String, SasError> {
+        let granted = [
+            (self.read, 'r'),
+            (self.add, 'a'),
+            (self.create, 'c'),
+            (self.write, 'w'),
+            (self.delete, 'd'),
+            (self.list, 'l'),
+            (self.update, 'u'),
+            (self.process, 'p'),
+        ];
+
+        for (is_granted, permission) in granted {
+            if is_granted && !order.contains(permission) {
+                return Err(SasError::PermissionNotSupported(permission));
+            }
+        }
+
+        let result = order
+            .chars()
+            .filter(|permission| {
+                granted
+                    .iter()
+                    .any(|(is_granted, itm)| *is_granted && itm == permission)
+            })
+            .collect();
+
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SasServices {
+    pub blob: bool,
+    pub queue: bool,
+    pub table: bool,
+    pub file: bool,
+}
+
+impl SasServices {
+    pub fn as_string(&self) -> String {
+        let mut result = String::new();
+
+        for (is_set, service) in [
+            (self.blob, 'b'),
+            (self.queue, 'q'),
+            (self.table, 't'),
+            (self.file, 'f'),
+        ] {
+            if is_set {
+                result.push(service);
+            }
+        }
+
+        result
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SasResourceTypes {
+    pub service: bool,
+    pub container: bool,
+    pub object: bool,
+}
+
+impl SasResourceTypes {
+    pub fn as_string(&self) -> String {
+        let mut result = String::new();
+
+        for (is_set, resource_type) in [
+            (self.service, 's'),
+            (self.container, 'c'),
+            (self.object, 'o'),
+        ] {
+            if is_set {
+                result.push(resource_type);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permissions_are_compiled_in_resource_order() {
+        let permissions = SasPermissions {
+            list: true,
+            write: true,
+            read: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            "rwl",
+            permissions.compile(CONTAINER_PERMISSIONS_ORDER).unwrap()
+        );
+
+        assert_eq!(
+            "rwl",
+            permissions.compile(ACCOUNT_PERMISSIONS_ORDER).unwrap()
+        );
+
+        assert_eq!(
+            SasError::PermissionNotSupported('l'),
+            permissions.compile(BLOB_PERMISSIONS_ORDER).err().unwrap()
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+/// Formats date as ISO 8601 UTC without fractions (2030-01-01T00:00:00Z) - the format SAS accepts
+pub fn to_sas_date(date: DateTimeAsMicroseconds) -> String {
+    let unix_seconds = date.unix_microseconds.div_euclid(1_000_000);
+
+    let days = unix_seconds.div_euclid(86_400);
+    let seconds_of_day = unix_seconds.rem_euclid(86_400);
+
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+// Days since 1970-01-01 to the proleptic Gregorian calendar date
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+
+    let year = year_of_era + era * 400;
+
+    if month <= 2 {
+        (year + 1, month, day)
+    } else {
+        (year, month, day)
+    }
+}
+
+pub fn encode_query_value(src: &str) -> String {
+    encode(src, false)
+}
+
+pub fn encode_path(src: &str) -> String {
+    encode(src, true)
+}
+
+fn encode(src: &str, keep_slash: bool) -> String {
+    let mut result = String::with_capacity(src.len());
+
+    for b in src.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                result.push(b as char)
+            }
+            b'/' if keep_slash => result.push('/'),
+            _ => result.push_str(format!("%{:02X}", b).as_str()),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sas_date() {
+        assert_eq!(
+            "1970-01-01T00:00:00Z",
+            to_sas_date(DateTimeAsMicroseconds::new(0))
+        );
+
+        assert_eq!(
+            "2030-01-01T00:00:00Z",
+            to_sas_date(DateTimeAsMicroseconds::new(1_893_456_000_000_000))
+        );
+
+        assert_eq!(
+            "2024-02-29T13:45:30Z",
+            to_sas_date(DateTimeAsMicroseconds::new(1_709_214_330_123_456))
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!("ab%2Bc%2F%3D", encode_query_value("ab+c/="));
+        assert_eq!("dir/my%20file.txt", encode_path("dir/my file.txt"));
+    }
+}
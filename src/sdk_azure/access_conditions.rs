@@ -0,0 +1,28 @@
+use flurl::FlUrl;
+
+use crate::blob::BlobAccessConditions;
+
+/// Standard conditional headers are part of the Shared Key string to sign, x-ms-lease-id goes with the canonicalized headers
+pub fn with_access_conditions(mut fl_url: FlUrl, conditions: &BlobAccessConditions) -> FlUrl {
+    if let Some(value) = conditions.if_match.as_ref() {
+        fl_url = fl_url.with_header("If-Match", value.to_string());
+    }
+
+    if let Some(value) = conditions.if_none_match.as_ref() {
+        fl_url = fl_url.with_header("If-None-Match", value.to_string());
+    }
+
+    if let Some(value) = conditions.if_modified_since.as_ref() {
+        fl_url = fl_url.with_header("If-Modified-Since", value.to_rfc7231());
+    }
+
+    with_lease_id(fl_url, conditions.lease_id.as_deref())
+}
+
+/// x-ms-lease-id of requests which take no other conditions, e.g. Put Block
+pub fn with_lease_id(fl_url: FlUrl, lease_id: Option<&str>) -> FlUrl {
+    match lease_id {
+        Some(lease_id) => fl_url.with_header("x-ms-lease-id", lease_id.to_string()),
+        None => fl_url,
+    }
+}
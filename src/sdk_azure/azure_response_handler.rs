@@ -1,29 +1,28 @@
-use flurl::FlUrlResponse;
 use std::collections::HashMap;
 
-use crate::AzureStorageError;
+use crate::{AzureErrorCode, AzureServiceError, AzureStorageError};
+
+use super::http_transport::AzureHttpResponse;
 
 pub struct AzureResponseHandler {
-    fl_response: FlUrlResponse,
-    headers: HashMap<String, String>,
+    response: AzureHttpResponse,
 }
 
 impl<'t> AzureResponseHandler {
-    pub fn new(fl_response: FlUrlResponse) -> Self {
-        let mut headers = HashMap::new();
-        for (key, value) in fl_response.get_headers().iter() {
-            if let Some(value) = value {
-                headers.insert(key.to_string(), value.to_string());
-            }
-        }
-        Self {
-            headers,
-            fl_response,
-        }
+    pub fn new(response: AzureHttpResponse) -> Self {
+        Self { response }
+    }
+
+    pub fn get_status_code(&self) -> u16 {
+        self.response.status_code
+    }
+
+    pub fn get_headers(&self) -> &HashMap<String, String> {
+        &self.response.headers
     }
 
     pub fn get_header(&'t self, header_name: &'t str) -> Result<&'t String, AzureStorageError> {
-        let result = self.headers.get(header_name);
+        let result = self.response.headers.get(header_name.to_lowercase().as_str());
         let result = match result {
             Some(value) => Ok(value),
             None => {
@@ -36,23 +35,43 @@ impl<'t> AzureResponseHandler {
         result
     }
 
+    /// ETag a write operation responds with
+    pub fn get_etag(&self) -> Result<String, AzureStorageError> {
+        Ok(self.get_header("etag")?.to_string())
+    }
+
     fn get_azure_error(&self) -> Option<AzureStorageError> {
-        let status_code = self.fl_response.get_status_code();
-        if status_code >= 300 {
-            let err_header_result = self.get_header("x-ms-error-code");
+        let status_code = self.response.status_code;
 
-            if let Ok(err_header) = err_header_result {
-                let err_header = err_header.as_str();
-                return Some(AzureStorageError::parse(err_header));
-            }
+        if status_code < 300 {
+            return None;
         }
 
-        None
+        let (body_code, message) = read_error_body(self.response.body.as_slice());
+
+        let code = match self.response.get_header("x-ms-error-code") {
+            Some(code) => AzureErrorCode::parse(code),
+            None => match body_code {
+                Some(code) => AzureErrorCode::parse(code.as_str()),
+                None => AzureErrorCode::from_status_code(status_code),
+            },
+        };
+
+        let err = AzureServiceError {
+            code,
+            status_code,
+            request_id: self
+                .response
+                .get_header("x-ms-request-id")
+                .map(|itm| itm.to_string()),
+            message,
+        };
+
+        Some(AzureStorageError::from_service_error(err))
     }
 
     pub async fn get_body(self) -> Result<Vec<u8>, AzureStorageError> {
-        let result = self.fl_response.receive_body().await?;
-        return Ok(result);
+        Ok(self.response.body)
     }
 
     pub fn check_if_there_is_an_error(self) -> Result<AzureResponseHandler, AzureStorageError> {
@@ -68,29 +87,123 @@ impl<'t> AzureResponseHandler {
 
     pub fn check_if_there_is_an_error_and_ignore_one(
         self,
-        error_to_ignore: AzureStorageError,
+        error_to_ignore: AzureErrorCode,
     ) -> Result<AzureResponseHandler, AzureStorageError> {
-        let has_error = self.get_azure_error();
-
-        match has_error {
-            Some(_err) => {
-                if matches!(error_to_ignore, _err) {
+        match self.get_azure_error() {
+            Some(err) => {
+                if err.get_error_code().as_ref() == Some(&error_to_ignore) {
                     return Ok(self);
                 }
 
-                return Err(_err);
+                Err(err)
             }
             None => Ok(self),
         }
     }
 }
 
+/// Code and message of an XML error body: <Error><Code>..</Code><Message>..</Message></Error>
+fn read_error_body(body: &[u8]) -> (Option<String>, Option<String>) {
+    let body = match std::str::from_utf8(body) {
+        Ok(body) => body,
+        Err(_) => return (None, None),
+    };
+
+    (
+        read_xml_value(body, "Code"),
+        read_xml_value(body, "Message"),
+    )
+}
+
+fn read_xml_value(xml: &str, tag: &str) -> Option<String> {
+    let open_tag = format!("<{}>", tag);
+    let close_tag = format!("</{}>", tag);
+
+    let start = xml.find(open_tag.as_str())? + open_tag.len();
+    let end = start + xml[start..].find(close_tag.as_str())?;
+
+    let value = xml[start..end].trim();
+
+    if value.is_empty() {
+        return None;
+    }
+
+    Some(value.to_string())
+}
+
 pub trait ToAzureResponseHandler {
     fn to_azure_response_handler(self) -> AzureResponseHandler;
 }
 
-impl<'t> ToAzureResponseHandler for FlUrlResponse {
+impl ToAzureResponseHandler for AzureHttpResponse {
     fn to_azure_response_handler(self) -> AzureResponseHandler {
         AzureResponseHandler::new(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_details_are_parsed() {
+        let response = AzureHttpResponse::new(404)
+            .with_header("x-ms-error-code", "BlobNotFound")
+            .with_header("x-ms-request-id", "req-1")
+            .with_body(
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?><Error><Code>BlobNotFound</Code><Message>The specified blob does not exist.</Message></Error>",
+            );
+
+        let err = response
+            .to_azure_response_handler()
+            .check_if_there_is_an_error()
+            .err()
+            .unwrap();
+
+        assert!(matches!(err, AzureStorageError::BlobNotFound(Some(_))));
+        assert_eq!(Some(AzureErrorCode::BlobNotFound), err.get_error_code());
+        assert_eq!(Some(404), err.get_status_code());
+        assert_eq!(Some("req-1"), err.get_request_id());
+        assert_eq!(
+            Some("The specified blob does not exist."),
+            err.get_service_error().unwrap().message.as_deref()
+        );
+    }
+
+    #[test]
+    fn test_status_without_error_code_is_an_error() {
+        let err = AzureHttpResponse::new(404)
+            .to_azure_response_handler()
+            .check_if_there_is_an_error()
+            .err()
+            .unwrap();
+
+        assert!(matches!(err, AzureStorageError::ServiceError(_)));
+        assert_eq!(Some(AzureErrorCode::ResourceNotFound), err.get_error_code());
+
+        let response = AzureHttpResponse::new(202).to_azure_response_handler();
+        assert!(response.check_if_there_is_an_error().is_ok());
+    }
+
+    #[test]
+    fn test_only_given_error_is_ignored() {
+        let response = AzureHttpResponse::new(404)
+            .with_header("x-ms-error-code", "BlobNotFound")
+            .to_azure_response_handler();
+
+        assert!(response
+            .check_if_there_is_an_error_and_ignore_one(AzureErrorCode::BlobNotFound)
+            .is_ok());
+
+        let response = AzureHttpResponse::new(403)
+            .with_header("x-ms-error-code", "AuthenticationFailed")
+            .to_azure_response_handler();
+
+        let err = response
+            .check_if_there_is_an_error_and_ignore_one(AzureErrorCode::BlobNotFound)
+            .err()
+            .unwrap();
+
+        assert_eq!(Some(AzureErrorCode::AuthenticationFailed), err.get_error_code());
+    }
+}
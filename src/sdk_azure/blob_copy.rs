@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use flurl::FlUrl;
+
+use super::access_conditions::with_lease_id;
+use super::consts::AZURE_REST_VERSION;
+use super::flurl_ext::FlUrlAzureExtensions;
+use super::http_transport::AzureHttpRequest;
+use super::retry_policy::execute_request;
+use super::sign_utils::SignVerb;
+use crate::blob_copy::{BlobCopyState, CopyProgress, CopySource, CopyStatus};
+use crate::connection::AzureStorageConnectionData;
+use crate::AzureStorageError;
+
+/// Copy Blob (PUT with x-ms-copy-source). Copies inside the account usually complete before the response
+pub async fn start_copy(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    source: &CopySource,
+    lease_id: Option<&str>,
+) -> Result<BlobCopyState, AzureStorageError> {
+    put_copy(
+        connection,
+        container_name,
+        blob_name,
+        source,
+        lease_id,
+        false,
+    )
+    .await
+}
+
+/// Copy Blob From URL (x-ms-requires-sync). The copy is completed by the response
+pub async fn copy_sync(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    source: &CopySource,
+    lease_id: Option<&str>,
+) -> Result<BlobCopyState, AzureStorageError> {
+    put_copy(
+        connection,
+        container_name,
+        blob_name,
+        source,
+        lease_id,
+        true,
+    )
+    .await
+}
+
+/// A leased destination is copied to with its lease id
+async fn put_copy(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    source: &CopySource,
+    lease_id: Option<&str>,
+    requires_sync: bool,
+) -> Result<BlobCopyState, AzureStorageError> {
+    let copy_source = get_copy_source_url(connection, source);
+
+    // Each attempt starts a copy of its own, with its own copy id
+    let response = execute_request(connection, false, || {
+        let copy_source = copy_source.clone();
+
+        async move {
+            let fl_url: FlUrl = connection.into();
+
+            let mut fl_url = fl_url
+                .append_path_segment(container_name)
+                .append_path_segment(blob_name)
+                .with_header("x-ms-copy-source", copy_source);
+
+            if requires_sync {
+                fl_url = fl_url.with_header("x-ms-requires-sync", "true");
+            }
+
+            let fl_url = with_lease_id(fl_url, lease_id)
+                .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+                .await?;
+
+            Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+        }
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    match read_copy_state(response.get_headers()) {
+        Some(result) => Ok(result),
+        None => Err(AzureStorageError::UnknownError {
+            msg: "Header not found x-ms-copy-id or x-ms-copy-status".to_string(),
+        }),
+    }
+}
+
+/// Get Blob Properties (HEAD). x-ms-copy-* headers are there only if the blob was copied to
+pub async fn get_status(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Option<BlobCopyState>, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .add_azure_headers(SignVerb::HEAD, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::HEAD, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(read_copy_state(response.get_headers()))
+}
+
+/// Abort Copy Blob (PUT ?comp=copy&copyid=... with x-ms-copy-action: abort)
+pub async fn abort(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    copy_id: &str,
+) -> Result<(), AzureStorageError> {
+    // A retry of an abort which went through fails with NoPendingCopyOperation
+    execute_request(connection, false, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("copy"))
+            .append_query_param("copyid", Some(copy_id))
+            .with_header("x-ms-copy-action", "abort")
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(())
+}
+
+/// A blob of the account is addressed by its url. The copy of the same account is authorized by the destination request
+pub fn get_copy_source_url(connection: &AzureStorageConnectionData, source: &CopySource) -> String {
+    match source {
+        CopySource::Blob {
+            container_name,
+            blob_name,
+        } => connection.get_blob_url(container_name, blob_name),
+        CopySource::Url(url) => url.to_string(),
+    }
+}
+
+/// Header names are lowercase
+pub fn read_copy_state(headers: &HashMap<String, String>) -> Option<BlobCopyState> {
+    let copy_id = headers.get("x-ms-copy-id")?;
+    let status = CopyStatus::parse(headers.get("x-ms-copy-status")?)?;
+
+    Some(BlobCopyState {
+        copy_id: copy_id.to_string(),
+        status,
+        progress: headers
+            .get("x-ms-copy-progress")
+            .and_then(|value| CopyProgress::parse(value)),
+        status_description: headers.get("x-ms-copy-status-description").cloned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::sdk_azure::http_transport::{
+        get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+    };
+
+    #[tokio::test]
+    async fn test_start_copy_of_a_blob_of_the_account() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(202)
+                .with_header("x-ms-copy-id", "copy-1")
+                .with_header("x-ms-copy-status", "pending"),
+        );
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = start_copy(
+            &connection,
+            "to",
+            "blob",
+            &CopySource::from_blob("from", "my blob"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!("copy-1", result.copy_id);
+        assert_eq!(CopyStatus::Pending, result.status);
+
+        let request = &transport.get_requests()[0];
+        assert_eq!(SignVerb::PUT, request.verb);
+        assert_eq!("/test/to/blob", request.path);
+        assert_eq!(
+            Some("http://127.0.0.1:10000/test/from/my%20blob"),
+            request.get_header("x-ms-copy-source")
+        );
+        assert_eq!(None, request.get_header("x-ms-requires-sync"));
+    }
+
+    #[tokio::test]
+    async fn test_sync_copy_from_url() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(202)
+                .with_header("x-ms-copy-id", "copy-2")
+                .with_header("x-ms-copy-status", "success"),
+        );
+
+        let connection = get_scripted_connection(&transport);
+
+        let source_url = "https://other.blob.core.windows.net/c/b?sv=2020&sig=xxx";
+
+        let result = copy_sync(
+            &connection,
+            "to",
+            "blob",
+            &CopySource::from_url(source_url),
+            Some("lease-1"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(CopyStatus::Success, result.status);
+
+        let request = &transport.get_requests()[0];
+        assert_eq!(Some(source_url), request.get_header("x-ms-copy-source"));
+        assert_eq!(Some("true"), request.get_header("x-ms-requires-sync"));
+        assert_eq!(Some("lease-1"), request.get_header("x-ms-lease-id"));
+    }
+
+    #[tokio::test]
+    async fn test_get_status_and_abort() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(200)
+                .with_header("content-length", "0")
+                .with_header("x-ms-copy-id", "copy-3")
+                .with_header("x-ms-copy-status", "pending")
+                .with_header("x-ms-copy-progress", "512/2048"),
+        );
+        transport.push_response(AzureHttpResponse::new(204));
+        transport.push_response(AzureHttpResponse::new(200).with_header("content-length", "0"));
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = get_status(&connection, "to", "blob")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            Some(CopyProgress {
+                bytes_copied: 512,
+                bytes_total: 2048,
+            }),
+            result.progress
+        );
+
+        abort(&connection, "to", "blob", result.copy_id.as_str())
+            .await
+            .unwrap();
+
+        let request = &transport.get_requests()[1];
+        assert_eq!(Some("copy"), request.get_query_param("comp"));
+        assert_eq!(Some("copy-3"), request.get_query_param("copyid"));
+        assert_eq!(Some("abort"), request.get_header("x-ms-copy-action"));
+
+        // A blob which was never copied to has no copy state
+        assert!(get_status(&connection, "to", "other")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,32 @@
+use flurl::FlUrl;
+
+use crate::blob::BlobHttpHeaders;
+
+/// Sends the values as x-ms-blob-* headers - the form Put Blob and Set Blob Properties store them from
+pub fn with_blob_http_headers(mut fl_url: FlUrl, http_headers: &BlobHttpHeaders) -> FlUrl {
+    if let Some(value) = http_headers.content_type.as_ref() {
+        fl_url = fl_url.with_header("x-ms-blob-content-type", value.to_string());
+    }
+
+    if let Some(value) = http_headers.content_encoding.as_ref() {
+        fl_url = fl_url.with_header("x-ms-blob-content-encoding", value.to_string());
+    }
+
+    if let Some(value) = http_headers.content_language.as_ref() {
+        fl_url = fl_url.with_header("x-ms-blob-content-language", value.to_string());
+    }
+
+    if let Some(value) = http_headers.content_disposition.as_ref() {
+        fl_url = fl_url.with_header("x-ms-blob-content-disposition", value.to_string());
+    }
+
+    if let Some(value) = http_headers.cache_control.as_ref() {
+        fl_url = fl_url.with_header("x-ms-blob-cache-control", value.to_string());
+    }
+
+    if let Some(value) = http_headers.content_md5.as_ref() {
+        fl_url = fl_url.with_header("x-ms-blob-content-md5", value.to_string());
+    }
+
+    fl_url
+}
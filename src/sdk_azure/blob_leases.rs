@@ -0,0 +1,261 @@
+use std::time::Duration;
+
+use flurl::FlUrl;
+
+use super::azure_response_handler::AzureResponseHandler;
+use super::consts::AZURE_REST_VERSION;
+use super::flurl_ext::FlUrlAzureExtensions;
+use super::http_transport::AzureHttpRequest;
+use super::retry_policy::execute_request;
+use super::sign_utils::SignVerb;
+use crate::blob_lease::LeaseDuration;
+use crate::connection::AzureStorageConnectionData;
+use crate::AzureStorageError;
+
+/// Lease Blob (PUT ?comp=lease). `headers` go next to x-ms-lease-action
+async fn lease_request(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    idempotent: bool,
+    headers: &[(&'static str, String)],
+) -> Result<AzureResponseHandler, AzureStorageError> {
+    execute_request(connection, idempotent, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let mut fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("lease"));
+
+        for (name, value) in headers {
+            fl_url = fl_url.with_header(*name, value.to_string());
+        }
+
+        let fl_url = fl_url
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()
+}
+
+pub async fn acquire(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    duration: LeaseDuration,
+    proposed_lease_id: Option<&str>,
+) -> Result<String, AzureStorageError> {
+    let mut headers = vec![
+        ("x-ms-lease-action", "acquire".to_string()),
+        ("x-ms-lease-duration", duration.as_header_value()),
+    ];
+
+    if let Some(proposed_lease_id) = proposed_lease_id {
+        headers.push(("x-ms-proposed-lease-id", proposed_lease_id.to_string()));
+    }
+
+    // Without a proposed id a retried acquire would fight the lease the first attempt got
+    let response = lease_request(
+        connection,
+        container_name,
+        blob_name,
+        proposed_lease_id.is_some(),
+        &headers,
+    )
+    .await?;
+
+    Ok(response.get_header("x-ms-lease-id")?.to_string())
+}
+
+pub async fn renew(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    lease_id: &str,
+) -> Result<(), AzureStorageError> {
+    let headers = [
+        ("x-ms-lease-action", "renew".to_string()),
+        ("x-ms-lease-id", lease_id.to_string()),
+    ];
+
+    lease_request(connection, container_name, blob_name, true, &headers).await?;
+
+    Ok(())
+}
+
+pub async fn release(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    lease_id: &str,
+) -> Result<(), AzureStorageError> {
+    let headers = [
+        ("x-ms-lease-action", "release".to_string()),
+        ("x-ms-lease-id", lease_id.to_string()),
+    ];
+
+    lease_request(connection, container_name, blob_name, true, &headers).await?;
+
+    Ok(())
+}
+
+pub async fn break_lease(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    break_period: Option<Duration>,
+) -> Result<Duration, AzureStorageError> {
+    let mut headers = vec![("x-ms-lease-action", "break".to_string())];
+
+    if let Some(break_period) = break_period {
+        headers.push((
+            "x-ms-lease-break-period",
+            break_period.as_secs().to_string(),
+        ));
+    }
+
+    let response = lease_request(connection, container_name, blob_name, true, &headers).await?;
+
+    let lease_time = response.get_header("x-ms-lease-time")?;
+
+    match lease_time.parse::<u64>() {
+        Ok(secs) => Ok(Duration::from_secs(secs)),
+        Err(_) => Err(AzureStorageError::UnknownError {
+            msg: format!("Invalid x-ms-lease-time: {}", lease_time),
+        }),
+    }
+}
+
+pub async fn change(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    lease_id: &str,
+    proposed_lease_id: &str,
+) -> Result<String, AzureStorageError> {
+    let headers = [
+        ("x-ms-lease-action", "change".to_string()),
+        ("x-ms-lease-id", lease_id.to_string()),
+        ("x-ms-proposed-lease-id", proposed_lease_id.to_string()),
+    ];
+
+    let response = lease_request(connection, container_name, blob_name, true, &headers).await?;
+
+    Ok(response.get_header("x-ms-lease-id")?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::blob::BlobAccessConditions;
+    use crate::sdk_azure::http_transport::{
+        get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+    };
+    use crate::StorageError;
+
+    #[tokio::test]
+    async fn test_acquire_and_break_requests() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(201).with_header("x-ms-lease-id", "id-1"));
+        transport.push_response(AzureHttpResponse::new(202).with_header("x-ms-lease-time", "7"));
+
+        let connection = get_scripted_connection(&transport);
+
+        let lease_id = acquire(
+            &connection,
+            "container",
+            "blob",
+            LeaseDuration::from_secs(30),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!("id-1", lease_id);
+
+        let remaining = break_lease(
+            &connection,
+            "container",
+            "blob",
+            Some(Duration::from_secs(10)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(Duration::from_secs(7), remaining);
+
+        let requests = transport.get_requests();
+
+        let request = &requests[0];
+        assert_eq!(SignVerb::PUT, request.verb);
+        assert_eq!("/test/container/blob", request.path);
+        assert_eq!(Some("lease"), request.get_query_param("comp"));
+        assert_eq!(Some("acquire"), request.get_header("x-ms-lease-action"));
+        assert_eq!(Some("30"), request.get_header("x-ms-lease-duration"));
+        assert!(request.get_header("x-ms-proposed-lease-id").is_none());
+
+        let request = &requests[1];
+        assert_eq!(Some("break"), request.get_header("x-ms-lease-action"));
+        assert_eq!(Some("10"), request.get_header("x-ms-lease-break-period"));
+    }
+
+    #[tokio::test]
+    async fn test_change_sends_both_ids() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(200).with_header("x-ms-lease-id", "id-2"));
+
+        let connection = get_scripted_connection(&transport);
+
+        let lease_id = change(&connection, "container", "blob", "id-1", "id-2")
+            .await
+            .unwrap();
+        assert_eq!("id-2", lease_id);
+
+        let request = &transport.get_requests()[0];
+        assert_eq!(Some("change"), request.get_header("x-ms-lease-action"));
+        assert_eq!(Some("id-1"), request.get_header("x-ms-lease-id"));
+        assert_eq!(Some("id-2"), request.get_header("x-ms-proposed-lease-id"));
+    }
+
+    #[tokio::test]
+    async fn test_write_of_leased_blob_without_lease_id() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(412).with_header("x-ms-error-code", "LeaseIdMissing"),
+        );
+        transport.push_response(AzureHttpResponse::new(202));
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = crate::sdk_azure::blobs::delete(
+            &connection,
+            "container",
+            "blob",
+            &BlobAccessConditions::new(),
+        )
+        .await;
+
+        let err = result.unwrap_err();
+        assert_eq!(
+            Some(crate::AzureErrorCode::LeaseIdMissing),
+            err.get_error_code()
+        );
+        assert!(err.is_condition_not_met());
+
+        crate::sdk_azure::blobs::delete(
+            &connection,
+            "container",
+            "blob",
+            &BlobAccessConditions::new().with_lease_id("id-1"),
+        )
+        .await
+        .unwrap();
+
+        let request = &transport.get_requests()[1];
+        assert_eq!(Some("id-1"), request.get_header("x-ms-lease-id"));
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use flurl::FlUrl;
+
+pub const META_PREFIX: &str = "x-ms-meta-";
+
+/// Sends every metadata entry as x-ms-meta-{name}. Names are lowercased since they are signed as x-ms- headers
+pub fn with_metadata_headers(mut fl_url: FlUrl, metadata: &HashMap<String, String>) -> FlUrl {
+    for (name, value) in metadata {
+        fl_url = fl_url.with_header(
+            format!("{}{}", META_PREFIX, name.to_lowercase()).as_str(),
+            value.to_string(),
+        );
+    }
+
+    fl_url
+}
+
+/// Picks x-ms-meta-* response headers. Header names are lowercase
+pub fn read_metadata(headers: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut result = HashMap::new();
+
+    for (name, value) in headers {
+        if let Some(key) = name.strip_prefix(META_PREFIX) {
+            result.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_metadata() {
+        let mut headers = HashMap::new();
+        headers.insert("x-ms-meta-tenant".to_string(), "acme".to_string());
+        headers.insert("x-ms-blob-type".to_string(), "BlockBlob".to_string());
+
+        let result = read_metadata(&headers);
+
+        assert_eq!(1, result.len());
+        assert_eq!("acme", result.get("tenant").unwrap());
+    }
+}
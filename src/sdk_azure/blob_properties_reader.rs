@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use crate::{
+    blob::{BlobProperties, BlobType, LeaseState, LeaseStatus},
+    AzureStorageError,
+};
+
+use super::http_date::parse_http_date;
+
+/// Builds BlobProperties from Get Blob Properties response headers. Header names are lowercase
+pub fn read_blob_properties(
+    headers: &HashMap<String, String>,
+) -> Result<BlobProperties, AzureStorageError> {
+    let blob_size = match headers.get("content-length") {
+        Some(value) => value.parse().map_err(|_| AzureStorageError::UnknownError {
+            msg: format!("Invalid content-length header: {}", value),
+        })?,
+        None => {
+            return Err(AzureStorageError::UnknownError {
+                msg: "Header not found content-length".to_string(),
+            })
+        }
+    };
+
+    let blob_type = headers
+        .get("x-ms-blob-type")
+        .and_then(|value| BlobType::parse(value))
+        .unwrap_or(BlobType::BlockBlob);
+
+    let mut result = BlobProperties::new(blob_type, blob_size);
+
+    result.etag = headers.get("etag").cloned();
+    result.last_modified = headers
+        .get("last-modified")
+        .and_then(|value| parse_http_date(value));
+    result.creation_time = headers
+        .get("x-ms-creation-time")
+        .and_then(|value| parse_http_date(value));
+    result.content_type = headers.get("content-type").cloned();
+    result.content_encoding = headers.get("content-encoding").cloned();
+    result.content_language = headers.get("content-language").cloned();
+    result.content_disposition = headers.get("content-disposition").cloned();
+    result.content_md5 = headers.get("content-md5").cloned();
+    result.cache_control = headers.get("cache-control").cloned();
+
+    if let Some(lease_state) = headers
+        .get("x-ms-lease-state")
+        .and_then(|value| LeaseState::parse(value))
+    {
+        result.lease_state = lease_state;
+    }
+
+    if let Some(lease_status) = headers
+        .get("x-ms-lease-status")
+        .and_then(|value| LeaseStatus::parse(value))
+    {
+        result.lease_status = lease_status;
+    }
+
+    result.access_tier = headers.get("x-ms-access-tier").cloned();
+    result.sequence_number = headers
+        .get("x-ms-blob-sequence-number")
+        .and_then(|value| value.parse().ok());
+    result.committed_block_count = headers
+        .get("x-ms-blob-committed-block-count")
+        .and_then(|value| value.parse().ok());
+    result.is_sealed = headers
+        .get("x-ms-blob-sealed")
+        .and_then(|value| value.parse().ok());
+
+    result.metadata = super::blob_metadata::read_metadata(headers);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sdk_azure::http_transport::AzureHttpResponse;
+
+    use super::*;
+
+    #[test]
+    fn test_read_all_properties() {
+        let response = AzureHttpResponse::new(200)
+            .with_header("content-length", "1024")
+            .with_header("x-ms-blob-type", "PageBlob")
+            .with_header("etag", "\"0x8D4BCC2E4835CD0\"")
+            .with_header("last-modified", "Mon, 27 Jul 2009 12:28:53 GMT")
+            .with_header("x-ms-creation-time", "Thu, 01 Jan 1970 00:00:00 GMT")
+            .with_header("content-type", "application/json")
+            .with_header("content-encoding", "gzip")
+            .with_header("content-language", "en-US")
+            .with_header("content-disposition", "attachment")
+            .with_header("content-md5", "Q2hlY2sgSW50ZWdyaXR5IQ==")
+            .with_header("cache-control", "no-cache")
+            .with_header("x-ms-lease-state", "leased")
+            .with_header("x-ms-lease-status", "locked")
+            .with_header("x-ms-access-tier", "Hot")
+            .with_header("x-ms-blob-sequence-number", "5")
+            .with_header("x-ms-meta-tenant", "acme");
+
+        let result = read_blob_properties(&response.headers).unwrap();
+
+        assert_eq!(1024, result.blob_size);
+        assert_eq!(BlobType::PageBlob, result.blob_type);
+        assert_eq!("\"0x8D4BCC2E4835CD0\"", result.etag.unwrap());
+        assert_eq!(
+            1_248_697_733_000_000,
+            result.last_modified.unwrap().unix_microseconds
+        );
+        assert_eq!(0, result.creation_time.unwrap().unix_microseconds);
+        assert_eq!("application/json", result.content_type.unwrap());
+        assert_eq!("gzip", result.content_encoding.unwrap());
+        assert_eq!("en-US", result.content_language.unwrap());
+        assert_eq!("attachment", result.content_disposition.unwrap());
+        assert_eq!("Q2hlY2sgSW50ZWdyaXR5IQ==", result.content_md5.unwrap());
+        assert_eq!("no-cache", result.cache_control.unwrap());
+        assert_eq!(LeaseState::Leased, result.lease_state);
+        assert_eq!(LeaseStatus::Locked, result.lease_status);
+        assert_eq!("Hot", result.access_tier.unwrap());
+        assert_eq!(Some(5), result.sequence_number);
+        assert_eq!("acme", result.metadata.get("tenant").unwrap());
+        assert_eq!(1, result.metadata.len());
+    }
+
+    #[test]
+    fn test_missing_optional_headers() {
+        let response = AzureHttpResponse::new(200).with_header("content-length", "5");
+
+        let result = read_blob_properties(&response.headers).unwrap();
+
+        assert_eq!(5, result.blob_size);
+        assert_eq!(BlobType::BlockBlob, result.blob_type);
+        assert_eq!(LeaseState::Available, result.lease_state);
+        assert_eq!(LeaseStatus::Unlocked, result.lease_status);
+        assert!(result.etag.is_none());
+        assert!(result.sequence_number.is_none());
+        assert!(result.committed_block_count.is_none());
+        assert!(result.is_sealed.is_none());
+        assert!(result.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_append_blob_headers() {
+        let response = AzureHttpResponse::new(200)
+            .with_header("content-length", "10")
+            .with_header("x-ms-blob-type", "AppendBlob")
+            .with_header("x-ms-blob-committed-block-count", "3")
+            .with_header("x-ms-blob-sealed", "true");
+
+        let result = read_blob_properties(&response.headers).unwrap();
+
+        assert_eq!(BlobType::AppendBlob, result.blob_type);
+        assert_eq!(Some(3), result.committed_block_count);
+        assert_eq!(Some(true), result.is_sealed);
+    }
+
+    #[test]
+    fn test_missing_content_length_is_an_error() {
+        let response = AzureHttpResponse::new(200);
+
+        assert!(read_blob_properties(&response.headers).is_err());
+    }
+}
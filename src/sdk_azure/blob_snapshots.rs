@@ -0,0 +1,350 @@
+use flurl::FlUrl;
+
+use super::access_conditions::with_lease_id;
+use super::blob_properties_reader::read_blob_properties;
+use super::consts::AZURE_REST_VERSION;
+use super::flurl_ext::FlUrlAzureExtensions;
+use super::http_transport::AzureHttpRequest;
+use super::models::deserialize_list_of_blob_items;
+use super::retry_policy::execute_request;
+use super::sign_utils::SignVerb;
+use crate::blob::BlobProperties;
+use crate::blob_snapshot::DeleteSnapshotsOption;
+use crate::connection::AzureStorageConnectionData;
+use crate::page_blob::consts::BLOB_PAGE_SIZE;
+use crate::AzureStorageError;
+
+/// Snapshot Blob (PUT ?comp=snapshot). Returns x-ms-snapshot
+pub async fn create(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<String, AzureStorageError> {
+    // Each attempt creates a snapshot, so a retry could leave an extra one behind
+    let response = execute_request(connection, false, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("snapshot"))
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(response.get_header("x-ms-snapshot")?.to_string())
+}
+
+/// List Blobs with include=snapshots and the blob name as the prefix. Snapshot ids sort chronologically
+pub async fn get_list(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Vec<String>, AzureStorageError> {
+    let mut result = vec![];
+
+    let mut next_marker: Option<String> = None;
+
+    loop {
+        let marker = next_marker.take();
+
+        let response = execute_request(connection, true, || {
+            let marker = marker.clone();
+
+            async move {
+                let fl_url: FlUrl = connection.into();
+
+                let fl_url = fl_url
+                    .append_path_segment(container_name)
+                    .append_query_param("comp", Some("list"))
+                    .append_query_param("restype", Some("container"))
+                    .append_query_param("include", Some("snapshots"))
+                    .append_query_param("prefix", Some(blob_name))
+                    .add_azure_headers(SignVerb::GET, connection, None, marker, AZURE_REST_VERSION)
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            }
+        })
+        .await?
+        .check_if_there_is_an_error()?;
+
+        let body = response.get_body().await?;
+
+        let chunk = deserialize_list_of_blob_items(body.as_ref());
+
+        for item in chunk.items {
+            if item.name != blob_name {
+                continue;
+            }
+
+            if let Some(snapshot) = item.snapshot {
+                result.push(snapshot);
+            }
+        }
+
+        match chunk.next_marker {
+            Some(marker) if !marker.is_empty() => next_marker = Some(marker),
+            _ => break,
+        }
+    }
+
+    result.sort();
+
+    Ok(result)
+}
+
+pub async fn get_properties(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    snapshot: &str,
+) -> Result<BlobProperties, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("snapshot", Some(snapshot))
+            .add_azure_headers(SignVerb::HEAD, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::HEAD, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    read_blob_properties(response.get_headers())
+}
+
+pub async fn download(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    snapshot: &str,
+) -> Result<Vec<u8>, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("snapshot", Some(snapshot))
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    response.get_body().await
+}
+
+pub async fn get_pages(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    snapshot: &str,
+    start_page_no: usize,
+    pages_to_read: usize,
+) -> Result<Vec<u8>, AzureStorageError> {
+    let start_bytes = start_page_no * BLOB_PAGE_SIZE;
+
+    let end_bytes = start_bytes + pages_to_read * BLOB_PAGE_SIZE - 1;
+
+    let response = execute_request(connection, true, || async move {
+        let range_header = format!("bytes={}-{}", start_bytes, end_bytes);
+
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("snapshot", Some(snapshot))
+            .with_header("x-ms-range", range_header)
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    response.get_body().await
+}
+
+pub async fn delete(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    snapshot: &str,
+) -> Result<(), AzureStorageError> {
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("snapshot", Some(snapshot))
+            .add_azure_headers(SignVerb::DELETE, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::DELETE, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(())
+}
+
+pub async fn delete_blob_with_snapshots(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    option: DeleteSnapshotsOption,
+    lease_id: Option<&str>,
+) -> Result<(), AzureStorageError> {
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .with_header("x-ms-delete-snapshots", option.as_str());
+
+        let fl_url = with_lease_id(fl_url, lease_id)
+            .add_azure_headers(SignVerb::DELETE, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::DELETE, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::sdk_azure::http_transport::{
+        get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+    };
+
+    #[tokio::test]
+    async fn test_create_and_read_snapshot() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(201)
+                .with_header("x-ms-snapshot", "2011-03-09T01:42:34.9360000Z"),
+        );
+        transport.push_response(AzureHttpResponse::new(206).with_body(vec![1u8; 512]));
+
+        let connection = get_scripted_connection(&transport);
+
+        let snapshot = create(&connection, "container", "blob").await.unwrap();
+        assert_eq!("2011-03-09T01:42:34.9360000Z", snapshot);
+
+        let pages = get_pages(&connection, "container", "blob", snapshot.as_str(), 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(vec![1u8; 512], pages);
+
+        let requests = transport.get_requests();
+
+        let request = &requests[0];
+        assert_eq!(SignVerb::PUT, request.verb);
+        assert_eq!("/test/container/blob", request.path);
+        assert_eq!(Some("snapshot"), request.get_query_param("comp"));
+
+        let request = &requests[1];
+        assert_eq!(SignVerb::GET, request.verb);
+        assert_eq!(
+            Some("2011-03-09T01:42:34.9360000Z"),
+            request.get_query_param("snapshot")
+        );
+        assert_eq!(Some("bytes=512-1023"), request.get_header("x-ms-range"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_with_snapshots() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(409).with_header("x-ms-error-code", "SnapshotsPresent"),
+        );
+        transport.push_response(AzureHttpResponse::new(202));
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = crate::sdk_azure::blobs::delete(
+            &connection,
+            "container",
+            "blob",
+            &crate::blob::BlobAccessConditions::new(),
+        )
+        .await;
+        assert_eq!(
+            Some(crate::AzureErrorCode::SnapshotsPresent),
+            result.unwrap_err().get_error_code()
+        );
+
+        delete_blob_with_snapshots(
+            &connection,
+            "container",
+            "blob",
+            DeleteSnapshotsOption::Include,
+            Some("lease-1"),
+        )
+        .await
+        .unwrap();
+
+        let request = &transport.get_requests()[1];
+        assert_eq!(SignVerb::DELETE, request.verb);
+        assert_eq!(Some("include"), request.get_header("x-ms-delete-snapshots"));
+        assert_eq!(Some("lease-1"), request.get_header("x-ms-lease-id"));
+    }
+
+    #[tokio::test]
+    async fn test_list_snapshots_of_one_blob() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(200).with_body(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ContainerName="container">
+  <Blobs>
+    <Blob><Name>blob</Name><Snapshot>2011-03-09T01:42:35.0000000Z</Snapshot></Blob>
+    <Blob><Name>blob</Name><Snapshot>2011-03-09T01:42:34.9360000Z</Snapshot></Blob>
+    <Blob><Name>blob</Name></Blob>
+    <Blob><Name>blob-2</Name><Snapshot>2011-03-09T01:42:36.0000000Z</Snapshot></Blob>
+  </Blobs>
+</EnumerationResults>"#
+                    .as_bytes()
+                    .to_vec(),
+            ),
+        );
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = get_list(&connection, "container", "blob").await.unwrap();
+
+        assert_eq!(
+            vec![
+                "2011-03-09T01:42:34.9360000Z".to_string(),
+                "2011-03-09T01:42:35.0000000Z".to_string()
+            ],
+            result
+        );
+
+        let request = &transport.get_requests()[0];
+        assert_eq!(Some("snapshots"), request.get_query_param("include"));
+        assert_eq!(Some("blob"), request.get_query_param("prefix"));
+    }
+}
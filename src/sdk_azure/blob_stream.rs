@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+
+use crate::blob::BlobAccessConditions;
+use crate::blob_stream::{BlobChunkReader, BlobDownloadStream};
+use crate::connection::AzureStorageConnectionData;
+use crate::AzureStorageError;
+
+/// Get Blob Properties (HEAD) for the size and the ETag, then a ranged Get Blob per chunk.
+/// The transport reads whole responses, so the chunk size bounds the memory a download takes
+pub async fn open(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    chunk_size: usize,
+) -> Result<BlobDownloadStream, AzureStorageError> {
+    let properties =
+        super::blobs::get_blob_properties(connection, container_name, blob_name).await?;
+
+    let blob_size = properties.blob_size as u64;
+
+    // Chunks of a blob overwritten in the middle of the download fail with ConditionNotMet
+    let mut conditions = BlobAccessConditions::new();
+    if let Some(etag) = properties.etag {
+        conditions = conditions.with_if_match(etag);
+    }
+
+    let reader = AzureBlobChunkReader {
+        connection: connection.clone(),
+        container_name: container_name.to_string(),
+        blob_name: blob_name.to_string(),
+        conditions,
+        position: 0,
+        blob_size,
+        chunk_size: chunk_size as u64,
+    };
+
+    Ok(BlobDownloadStream::new(blob_size, Box::new(reader)))
+}
+
+struct AzureBlobChunkReader {
+    connection: AzureStorageConnectionData,
+    container_name: String,
+    blob_name: String,
+    conditions: BlobAccessConditions,
+    position: u64,
+    blob_size: u64,
+    chunk_size: u64,
+}
+
+#[async_trait]
+impl BlobChunkReader for AzureBlobChunkReader {
+    async fn read_chunk(&mut self) -> Result<Option<Vec<u8>>, AzureStorageError> {
+        if self.position >= self.blob_size {
+            return Ok(None);
+        }
+
+        let length = self.chunk_size.min(self.blob_size - self.position);
+
+        let chunk = super::blobs::download_range_with_conditions(
+            &self.connection,
+            self.container_name.as_str(),
+            self.blob_name.as_str(),
+            self.position,
+            Some(length),
+            &self.conditions,
+        )
+        .await?;
+
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+
+        self.position += chunk.len() as u64;
+
+        Ok(Some(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::sdk_azure::http_transport::{
+        get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+    };
+
+    #[tokio::test]
+    async fn test_stream_reads_chunks_pinned_to_etag() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(200)
+                .with_header("content-length", "5")
+                .with_header("x-ms-blob-type", "BlockBlob")
+                .with_header("etag", "\"0x1\""),
+        );
+        transport.push_response(AzureHttpResponse::new(206).with_body(b"hel".to_vec()));
+        transport.push_response(AzureHttpResponse::new(206).with_body(b"lo".to_vec()));
+
+        let connection = get_scripted_connection(&transport);
+
+        let mut stream = open(&connection, "container", "blob", 3).await.unwrap();
+        assert_eq!(5, stream.get_blob_size());
+
+        // Nothing but the properties is requested until the stream is read
+        assert_eq!(1, transport.get_requests().len());
+
+        assert_eq!(Some(b"hel".to_vec()), stream.get_next().await.unwrap());
+        assert_eq!(Some(b"lo".to_vec()), stream.get_next().await.unwrap());
+        assert_eq!(None, stream.get_next().await.unwrap());
+
+        let requests = transport.get_requests();
+        assert_eq!(3, requests.len());
+        assert_eq!(Some("bytes=0-2"), requests[1].get_header("x-ms-range"));
+        assert_eq!(Some("bytes=3-4"), requests[2].get_header("x-ms-range"));
+        assert_eq!(Some("\"0x1\""), requests[2].get_header("If-Match"));
+    }
+}
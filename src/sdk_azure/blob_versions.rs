@@ -0,0 +1,348 @@
+use flurl::FlUrl;
+
+use super::access_conditions::with_lease_id;
+use super::blob_properties_reader::read_blob_properties;
+use super::consts::AZURE_REST_VERSION;
+use super::flurl_ext::FlUrlAzureExtensions;
+use super::http_transport::AzureHttpRequest;
+use super::models::{deserialize_list_of_blob_items, deserialize_list_of_container_items};
+use super::retry_policy::execute_request;
+use super::sign_utils::SignVerb;
+use crate::blob::BlobProperties;
+use crate::blob_version::{BlobVersionItem, DeletedBlobContainer};
+use crate::connection::AzureStorageConnectionData;
+use crate::AzureStorageError;
+
+/// List Blobs with include=versions,deleted
+pub async fn get_list(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+) -> Result<Vec<BlobVersionItem>, AzureStorageError> {
+    let mut result = vec![];
+
+    let mut next_marker: Option<String> = None;
+
+    loop {
+        let marker = next_marker.take();
+
+        let response = execute_request(connection, true, || {
+            let marker = marker.clone();
+
+            async move {
+                let fl_url: FlUrl = connection.into();
+
+                let fl_url = fl_url
+                    .append_path_segment(container_name)
+                    .append_query_param("comp", Some("list"))
+                    .append_query_param("restype", Some("container"))
+                    .append_query_param("include", Some("versions,deleted"))
+                    .add_azure_headers(SignVerb::GET, connection, None, marker, AZURE_REST_VERSION)
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            }
+        })
+        .await?
+        .check_if_there_is_an_error()?;
+
+        let body = response.get_body().await?;
+
+        let chunk = deserialize_list_of_blob_items(body.as_ref());
+
+        for item in chunk.items {
+            result.push(BlobVersionItem {
+                blob_name: item.name,
+                version_id: item.version_id,
+                is_current_version: item.is_current_version,
+                deleted: item.deleted,
+            });
+        }
+
+        match chunk.next_marker {
+            Some(marker) if !marker.is_empty() => next_marker = Some(marker),
+            _ => break,
+        }
+    }
+
+    Ok(result)
+}
+
+pub async fn get_properties(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    version_id: &str,
+) -> Result<BlobProperties, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("versionid", Some(version_id))
+            .add_azure_headers(SignVerb::HEAD, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::HEAD, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    read_blob_properties(response.get_headers())
+}
+
+pub async fn download(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    version_id: &str,
+) -> Result<Vec<u8>, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("versionid", Some(version_id))
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    response.get_body().await
+}
+
+/// Copy Blob with the version as x-ms-copy-source. A copy inside the account completes before the response
+pub async fn promote(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    version_id: &str,
+    lease_id: Option<&str>,
+) -> Result<(), AzureStorageError> {
+    let copy_source = format!(
+        "{}?versionid={}",
+        connection.get_blob_url(container_name, blob_name),
+        crate::sas::utils::encode_query_value(version_id)
+    );
+
+    // Each attempt writes a new current version
+    execute_request(connection, false, || {
+        let copy_source = copy_source.clone();
+
+        async move {
+            let fl_url: FlUrl = connection.into();
+
+            let fl_url = fl_url
+                .append_path_segment(container_name)
+                .append_path_segment(blob_name)
+                .with_header("x-ms-copy-source", copy_source);
+
+            let fl_url = with_lease_id(fl_url, lease_id)
+                .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+                .await?;
+
+            Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+        }
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(())
+}
+
+/// Undelete Blob (PUT ?comp=undelete)
+pub async fn undelete(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<(), AzureStorageError> {
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("undelete"))
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(())
+}
+
+/// List Containers with include=deleted, deleted ones only
+pub async fn get_list_of_deleted_containers(
+    connection: &AzureStorageConnectionData,
+) -> Result<Vec<DeletedBlobContainer>, AzureStorageError> {
+    let mut result = vec![];
+
+    let mut next_marker: Option<String> = None;
+
+    loop {
+        let marker = next_marker.take();
+
+        let response = execute_request(connection, true, || {
+            let marker = marker.clone();
+
+            async move {
+                let fl_url: FlUrl = connection.into();
+
+                let fl_url = fl_url
+                    .append_query_param("comp", Some("list"))
+                    .append_query_param("include", Some("deleted"))
+                    .add_azure_headers(SignVerb::GET, connection, None, marker, AZURE_REST_VERSION)
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            }
+        })
+        .await?
+        .check_if_there_is_an_error()?;
+
+        let body = response.get_body().await?;
+
+        let chunk = deserialize_list_of_container_items(body.as_ref());
+
+        for item in chunk.items {
+            if !item.deleted {
+                continue;
+            }
+
+            if let Some(version) = item.version {
+                result.push(DeletedBlobContainer {
+                    name: item.name,
+                    version,
+                });
+            }
+        }
+
+        match chunk.next_marker {
+            Some(marker) if !marker.is_empty() => next_marker = Some(marker),
+            _ => break,
+        }
+    }
+
+    Ok(result)
+}
+
+/// Restore Container (PUT ?restype=container&comp=undelete)
+pub async fn undelete_container(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    version: &str,
+) -> Result<(), AzureStorageError> {
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_query_param("restype", Some("container"))
+            .append_query_param("comp", Some("undelete"))
+            .with_header("x-ms-deleted-container-name", container_name)
+            .with_header("x-ms-deleted-container-version", version)
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::sdk_azure::http_transport::{
+        get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+    };
+
+    #[tokio::test]
+    async fn test_promote_copies_the_version_over_the_blob() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport
+            .push_response(AzureHttpResponse::new(202).with_header("x-ms-copy-status", "success"));
+
+        let connection = get_scripted_connection(&transport);
+
+        promote(
+            &connection,
+            "container",
+            "blob",
+            "2021-05-01T10:00:00.0000000Z",
+            Some("lease-1"),
+        )
+        .await
+        .unwrap();
+
+        let request = &transport.get_requests()[0];
+        assert_eq!(SignVerb::PUT, request.verb);
+        assert_eq!("/test/container/blob", request.path);
+        assert_eq!(
+            Some("http://127.0.0.1:10000/test/container/blob?versionid=2021-05-01T10%3A00%3A00.0000000Z"),
+            request.get_header("x-ms-copy-source")
+        );
+        assert_eq!(Some("lease-1"), request.get_header("x-ms-lease-id"));
+    }
+
+    #[tokio::test]
+    async fn test_undelete_blob_and_container() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(200));
+        transport.push_response(AzureHttpResponse::new(201));
+
+        let connection = get_scripted_connection(&transport);
+
+        undelete(&connection, "container", "blob").await.unwrap();
+        undelete_container(&connection, "container", "01D60F8BB59A4652")
+            .await
+            .unwrap();
+
+        let requests = transport.get_requests();
+
+        assert_eq!(Some("undelete"), requests[0].get_query_param("comp"));
+        assert_eq!("/test/container/blob", requests[0].path);
+
+        assert_eq!("/test/container", requests[1].path);
+        assert_eq!(Some("container"), requests[1].get_query_param("restype"));
+        assert_eq!(Some("undelete"), requests[1].get_query_param("comp"));
+        assert_eq!(
+            Some("01D60F8BB59A4652"),
+            requests[1].get_header("x-ms-deleted-container-version")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_version() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(200).with_body(vec![1u8, 2u8]));
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = download(
+            &connection,
+            "container",
+            "blob",
+            "2021-05-01T10:00:00.0000000Z",
+        )
+        .await
+        .unwrap();
+        assert_eq!(vec![1u8, 2u8], result);
+
+        assert_eq!(
+            Some("2021-05-01T10:00:00.0000000Z"),
+            transport.get_requests()[0].get_query_param("versionid")
+        );
+    }
+}
@@ -1,9 +1,15 @@
-use super::azure_response_handler::ToAzureResponseHandler;
+use std::collections::HashMap;
+
+use super::access_conditions::{with_access_conditions, with_lease_id};
+use super::blob_http_headers::with_blob_http_headers;
+use super::blob_metadata::{read_metadata, with_metadata_headers};
 use super::flurl_ext::FlUrlAzureExtensions;
+use super::http_transport::AzureHttpRequest;
+use super::retry_policy::execute_request;
 use super::sign_utils::SignVerb;
-use crate::blob::BlobProperties;
+use crate::blob::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, DownloadedBlob};
 use crate::connection::AzureStorageConnectionData;
-use crate::AzureStorageError;
+use crate::{AzureErrorCode, AzureStorageError};
 
 use flurl::FlUrl;
 
@@ -42,25 +48,35 @@ impl<'s> AzureBlobsListReader<'s> {
             return Ok(None);
         }
 
-        let fl_url: FlUrl = self.connection.into();
-
         let next_marker = self.get_next_marker();
 
-        let response = fl_url
-            .append_path_segment(self.container_name)
-            .append_query_param("comp", Some("list"))
-            .append_query_param("restype", Some("container"))
-            .add_azure_headers(
-                SignVerb::GET,
-                self.connection,
-                None,
-                next_marker,
-                AZURE_REST_VERSION,
-            )
-            .get()
-            .await?
-            .to_azure_response_handler()
-            .check_if_there_is_an_error()?;
+        let connection = self.connection;
+        let container_name = self.container_name;
+
+        let response = execute_request(connection, true, || {
+            let next_marker = next_marker.clone();
+
+            async move {
+                let fl_url: FlUrl = connection.into();
+
+                let fl_url = fl_url
+                    .append_path_segment(container_name)
+                    .append_query_param("comp", Some("list"))
+                    .append_query_param("restype", Some("container"))
+                    .add_azure_headers(
+                        SignVerb::GET,
+                        connection,
+                        None,
+                        next_marker,
+                        AZURE_REST_VERSION,
+                    )
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            }
+        })
+        .await?
+        .check_if_there_is_an_error()?;
 
         let body = response.get_body().await?;
 
@@ -99,77 +115,520 @@ pub async fn get_blob_properties(
             .await?
             .check_if_there_is_an_error()?;
 
-    let content_len = response.get_header("content-length").unwrap();
+    super::blob_properties_reader::read_blob_properties(response.get_headers())
+}
+
+pub async fn delete_if_exists(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    lease_id: Option<&str>,
+) -> Result<(), AzureStorageError> {
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
 
-    let blob_size = content_len.parse().unwrap();
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name);
 
-    let result = BlobProperties { blob_size };
+        let fl_url = with_lease_id(fl_url, lease_id)
+            .add_azure_headers(SignVerb::DELETE, connection, None, None, AZURE_REST_VERSION)
+            .await?;
 
-    Ok(result)
+        Ok(AzureHttpRequest::new(SignVerb::DELETE, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error_and_ignore_one(AzureErrorCode::BlobNotFound)?;
+
+    Ok(())
 }
 
-pub async fn delete_if_exists(
+pub async fn delete(
     connection: &AzureStorageConnectionData,
     container_name: &str,
     blob_name: &str,
+    conditions: &BlobAccessConditions,
 ) -> Result<(), AzureStorageError> {
-    let fl_url: FlUrl = connection.into();
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
 
-    fl_url
-        .append_path_segment(container_name)
-        .append_path_segment(blob_name)
-        .add_azure_headers(SignVerb::DELETE, connection, None, None, AZURE_REST_VERSION)
-        .delete()
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error_and_ignore_one(AzureStorageError::BlobNotFound)?;
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name);
+
+        let fl_url = with_access_conditions(fl_url, conditions)
+            .add_azure_headers(SignVerb::DELETE, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::DELETE, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
 
     Ok(())
 }
 
-pub async fn delete(
+pub async fn set_blob_metadata(
     connection: &AzureStorageConnectionData,
     container_name: &str,
     blob_name: &str,
+    metadata: &HashMap<String, String>,
+    conditions: &BlobAccessConditions,
 ) -> Result<(), AzureStorageError> {
-    let fl_url: FlUrl = connection.into();
-    fl_url
-        .append_path_segment(container_name)
-        .append_path_segment(blob_name)
-        .add_azure_headers(SignVerb::DELETE, connection, None, None, AZURE_REST_VERSION)
-        .delete()
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error()?;
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("metadata"));
+
+        let fl_url = with_access_conditions(fl_url, conditions);
+
+        let fl_url = with_metadata_headers(fl_url, metadata)
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(())
+}
+
+/// Set Blob Properties (comp=properties). Azure clears the headers which are not sent
+pub async fn set_blob_http_headers(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    http_headers: &BlobHttpHeaders,
+    conditions: &BlobAccessConditions,
+) -> Result<(), AzureStorageError> {
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("properties"));
+
+        let fl_url = with_access_conditions(fl_url, conditions);
+
+        let fl_url = with_blob_http_headers(fl_url, http_headers)
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
 
     Ok(())
 }
 
+pub async fn get_blob_metadata(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<HashMap<String, String>, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("metadata"))
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    Ok(read_metadata(response.get_headers()))
+}
+
 pub async fn download(
     connection: &AzureStorageConnectionData,
     container_name: &str,
     blob_name: &str,
 ) -> Result<Vec<u8>, AzureStorageError> {
-    let fl_url: FlUrl = connection.into();
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
 
-    let response = fl_url
-        .append_path_segment(container_name)
-        .append_path_segment(blob_name)
-        .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
-        .get()
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error()?;
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
 
     let result = response.get_body().await?;
 
     Ok(result)
 }
 
+/// Get Blob with x-ms-range. The range may be open-ended and does not have to be page-aligned for page blobs
+pub async fn download_range(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    offset: u64,
+    length: Option<u64>,
+) -> Result<Vec<u8>, AzureStorageError> {
+    download_range_with_conditions(
+        connection,
+        container_name,
+        blob_name,
+        offset,
+        length,
+        &BlobAccessConditions::new(),
+    )
+    .await
+}
+
+pub async fn download_range_with_conditions(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    offset: u64,
+    length: Option<u64>,
+    conditions: &BlobAccessConditions,
+) -> Result<Vec<u8>, AzureStorageError> {
+    // "bytes=5-4" is not a valid range - Azure would ignore it and respond with the whole blob
+    if length == Some(0) {
+        return Ok(Vec::new());
+    }
+
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .with_header("x-ms-range", crate::blob::get_range_header(offset, length));
+
+        let fl_url = with_access_conditions(fl_url, conditions)
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?;
+
+    let response = match response.check_if_there_is_an_error() {
+        Ok(response) => response,
+        // Azure answers any range of an empty blob with 416, even the one starting at 0
+        Err(err) if offset == 0 && err.get_status_code() == Some(416) => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    response.get_body().await
+}
+
+pub async fn download_with_conditions(
+    connection: &AzureStorageConnectionData,
+    container_name: &str,
+    blob_name: &str,
+    conditions: &BlobAccessConditions,
+) -> Result<DownloadedBlob, AzureStorageError> {
+    let response = execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name);
+
+        let fl_url = with_access_conditions(fl_url, conditions)
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    // Get Blob responds with the same property headers Get Blob Properties does
+    let properties = super::blob_properties_reader::read_blob_properties(response.get_headers())?;
+
+    Ok(DownloadedBlob {
+        content: response.get_body().await?,
+        properties,
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
 
     use super::*;
+    use crate::sdk_azure::http_transport::{
+        get_scripted_connection, AzureHttpResponse, ScriptedTransport,
+    };
+
+    #[tokio::test]
+    async fn test_upload_block_blob_request() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(201).with_header("ETag", "\"0x2\""));
+
+        let connection = get_scripted_connection(&transport);
+
+        let content = b"hello".to_vec();
+
+        let etag = crate::block_blob::sdk::upload_block_blob(
+            &connection,
+            "container",
+            "blob",
+            content,
+            &crate::block_blob::UploadBlockBlobOptions::new()
+                .with_content_type("text/plain")
+                .with_metadata("tenant", "acme"),
+        )
+        .await
+        .unwrap();
+
+        let requests = transport.get_requests();
+        assert_eq!(1, requests.len());
+
+        let request = &requests[0];
+        assert_eq!(SignVerb::PUT, request.verb);
+        assert_eq!("/test/container/blob", request.path);
+        assert_eq!(Some("BlockBlob"), request.get_header("x-ms-blob-type"));
+        assert_eq!(
+            Some("text/plain"),
+            request.get_header("x-ms-blob-content-type")
+        );
+        assert_eq!(Some("acme"), request.get_header("x-ms-meta-tenant"));
+        assert!(request.get_header("Authorization").is_some());
+        assert_eq!(Some(b"hello".to_vec()), request.body);
+        assert!(request.get_header("If-Match").is_none());
+        assert_eq!("\"0x2\"", etag);
+    }
+
+    #[tokio::test]
+    async fn test_conditional_upload_sends_headers_and_maps_412() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(412).with_header("x-ms-error-code", "ConditionNotMet"),
+        );
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = crate::block_blob::sdk::upload_block_blob(
+            &connection,
+            "container",
+            "blob",
+            b"hello".to_vec(),
+            &crate::block_blob::UploadBlockBlobOptions::new().with_conditions(
+                BlobAccessConditions::new()
+                    .with_if_match("\"0x1\"")
+                    .with_if_modified_since(
+                        rust_extensions::date_time::DateTimeAsMicroseconds::new(0),
+                    ),
+            ),
+        )
+        .await;
+
+        let err = result.err().unwrap();
+        assert_eq!(Some(AzureErrorCode::ConditionNotMet), err.get_error_code());
+        assert!(crate::StorageError::is_condition_not_met(&err));
+
+        let request = &transport.get_requests()[0];
+        assert_eq!(Some("\"0x1\""), request.get_header("If-Match"));
+        let if_modified_since = request.get_header("If-Modified-Since").unwrap();
+        assert_eq!(
+            0,
+            super::super::http_date::parse_http_date(if_modified_since)
+                .unwrap()
+                .unix_microseconds
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conditional_download_and_delete() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(200)
+                .with_header("Content-Length", "5")
+                .with_header("ETag", "\"0x3\"")
+                .with_body(b"hello".to_vec()),
+        );
+        transport.push_response(AzureHttpResponse::new(304));
+        transport.push_response(AzureHttpResponse::new(412));
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = download_with_conditions(
+            &connection,
+            "container",
+            "blob",
+            &BlobAccessConditions::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(b"hello".to_vec(), result.content);
+        assert_eq!("\"0x3\"", result.properties.etag.unwrap());
+
+        let err = download_with_conditions(
+            &connection,
+            "container",
+            "blob",
+            &BlobAccessConditions::new().with_if_none_match("\"0x3\""),
+        )
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(Some(AzureErrorCode::ConditionNotMet), err.get_error_code());
+
+        let err = delete(
+            &connection,
+            "container",
+            "blob",
+            &BlobAccessConditions::new().with_if_match("\"0x2\""),
+        )
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(Some(AzureErrorCode::ConditionNotMet), err.get_error_code());
+
+        let requests = transport.get_requests();
+        assert_eq!(Some("\"0x3\""), requests[1].get_header("If-None-Match"));
+        assert_eq!(SignVerb::DELETE, requests[2].verb);
+        assert_eq!(Some("\"0x2\""), requests[2].get_header("If-Match"));
+    }
+
+    #[tokio::test]
+    async fn test_download_returns_canned_body() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(200).with_body(b"hello".to_vec()));
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = download(&connection, "container", "blob").await.unwrap();
+
+        assert_eq!(b"hello".to_vec(), result);
+        assert_eq!(SignVerb::GET, transport.get_requests()[0].verb);
+    }
+
+    #[tokio::test]
+    async fn test_get_blob_properties_reads_headers() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(200)
+                .with_header("Content-Length", "512")
+                .with_header("x-ms-blob-type", "PageBlob")
+                .with_header("ETag", "\"0x1\"")
+                .with_header("x-ms-meta-producer", "test"),
+        );
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = get_blob_properties(&connection, "container", "blob")
+            .await
+            .unwrap();
+
+        assert_eq!(512, result.blob_size);
+        assert_eq!(crate::blob::BlobType::PageBlob, result.blob_type);
+        assert_eq!("\"0x1\"", result.etag.unwrap());
+        assert_eq!("test", result.metadata.get("producer").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_set_and_get_blob_metadata() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(200));
+        transport
+            .push_response(AzureHttpResponse::new(200).with_header("x-ms-meta-tenant", "acme"));
+
+        let connection = get_scripted_connection(&transport);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("Tenant".to_string(), "acme".to_string());
+
+        set_blob_metadata(
+            &connection,
+            "container",
+            "blob",
+            &metadata,
+            &BlobAccessConditions::new().with_lease_id("lease-1"),
+        )
+        .await
+        .unwrap();
+
+        let result = get_blob_metadata(&connection, "container", "blob")
+            .await
+            .unwrap();
+
+        assert_eq!("acme", result.get("tenant").unwrap());
+
+        let requests = transport.get_requests();
+        assert_eq!(SignVerb::PUT, requests[0].verb);
+        assert_eq!(Some("metadata"), requests[0].get_query_param("comp"));
+        assert_eq!(Some("acme"), requests[0].get_header("x-ms-meta-tenant"));
+        assert_eq!(Some("lease-1"), requests[0].get_header("x-ms-lease-id"));
+        assert_eq!(SignVerb::GET, requests[1].verb);
+        assert_eq!(Some("metadata"), requests[1].get_query_param("comp"));
+    }
+
+    #[tokio::test]
+    async fn test_set_blob_http_headers() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(200));
+
+        let connection = get_scripted_connection(&transport);
+
+        let http_headers = BlobHttpHeaders::new()
+            .with_content_type("application/json")
+            .with_cache_control("max-age=60");
+
+        set_blob_http_headers(
+            &connection,
+            "container",
+            "blob",
+            &http_headers,
+            &BlobAccessConditions::new().with_if_match("\"0x1\""),
+        )
+        .await
+        .unwrap();
+
+        let request = &transport.get_requests()[0];
+        assert_eq!(SignVerb::PUT, request.verb);
+        assert_eq!(Some("properties"), request.get_query_param("comp"));
+        assert_eq!(
+            Some("application/json"),
+            request.get_header("x-ms-blob-content-type")
+        );
+        assert_eq!(
+            Some("max-age=60"),
+            request.get_header("x-ms-blob-cache-control")
+        );
+        assert!(request.get_header("x-ms-blob-content-encoding").is_none());
+        assert_eq!(Some("\"0x1\""), request.get_header("If-Match"));
+    }
+
+    #[tokio::test]
+    async fn test_blob_not_found_is_mapped_from_error_code() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(404).with_header("x-ms-error-code", "BlobNotFound"),
+        );
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = get_blob_properties(&connection, "container", "blob").await;
+
+        let err = result.err().unwrap();
+        assert_eq!(Some(AzureErrorCode::BlobNotFound), err.get_error_code());
+        assert_eq!(Some(404), err.get_status_code());
+        assert_eq!(SignVerb::HEAD, transport.get_requests()[0].verb);
+    }
 
     #[tokio::test]
     async fn test_blob_not_found() {
@@ -188,9 +647,64 @@ mod tests {
         let result = get_blob_properties(&connection, "testtest", "notexists").await;
 
         if let Err(err) = result {
-            assert_eq!(true, matches!(err, AzureStorageError::BlobNotFound));
+            assert_eq!(Some(AzureErrorCode::BlobNotFound), err.get_error_code());
         } else {
             panic!("Unexpected result type: {:?}", result);
         }
     }
+
+    #[tokio::test]
+    async fn test_download_range_request() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(AzureHttpResponse::new(206).with_body(b"ell".to_vec()));
+        transport.push_response(AzureHttpResponse::new(206).with_body(b"llo".to_vec()));
+
+        let connection = get_scripted_connection(&transport);
+
+        let result = download_range(&connection, "container", "blob", 1, Some(3))
+            .await
+            .unwrap();
+        assert_eq!(b"ell".to_vec(), result);
+
+        let result = download_range(&connection, "container", "blob", 2, None)
+            .await
+            .unwrap();
+        assert_eq!(b"llo".to_vec(), result);
+
+        let requests = transport.get_requests();
+        assert_eq!(SignVerb::GET, requests[0].verb);
+        assert_eq!(Some("bytes=1-3"), requests[0].get_header("x-ms-range"));
+        assert_eq!(Some("bytes=2-"), requests[1].get_header("x-ms-range"));
+    }
+
+    #[tokio::test]
+    async fn test_download_range_maps_416() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_response(
+            AzureHttpResponse::new(416).with_header("x-ms-error-code", "InvalidRange"),
+        );
+        transport.push_response(
+            AzureHttpResponse::new(416).with_header("x-ms-error-code", "InvalidRange"),
+        );
+
+        let connection = get_scripted_connection(&transport);
+
+        let err = download_range(&connection, "container", "blob", 10, None)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(Some(AzureErrorCode::InvalidRange), err.get_error_code());
+        assert_eq!(
+            AzureStorageError::InvalidRange(None).get_error_code(),
+            err.get_error_code()
+        );
+
+        // 416 of a range starting at 0 means the blob is empty
+        let result = download_range(&connection, "container", "blob", 0, Some(512))
+            .await
+            .unwrap();
+        assert!(result.is_empty());
+
+        assert_eq!(2, transport.get_requests().len());
+    }
 }
@@ -1,2 +1,5 @@
-pub const AZURE_REST_VERSION: &str = "2017-07-29";
+/// x-ms-version of every request: the oldest version which has all the operations of the crate.
+/// Bearer tokens need 2017-11-09, Copy Blob From URL 2018-03-28, blob versions (versionid),
+/// Restore Container and Append Blob Seal 2019-12-12
+pub const AZURE_REST_VERSION: &str = "2019-12-12";
 pub const DEPENDENCY_TYPE: &str = "AZURE BLOB";
@@ -1,9 +1,9 @@
-use crate::{connection::AzureStorageConnectionData, AzureStorageError};
-use flurl::{body::FlUrlBody, FlUrl, FlUrlError};
+use crate::{connection::AzureStorageConnectionData, AzureErrorCode, AzureStorageError};
+use flurl::FlUrl;
 
 use super::{
-    azure_response_handler::ToAzureResponseHandler, flurl_ext::FlUrlAzureExtensions,
-    models::NextMarkerToRead,
+    consts::AZURE_REST_VERSION, flurl_ext::FlUrlAzureExtensions, http_transport::AzureHttpRequest,
+    models::NextMarkerToRead, retry_policy::execute_request, sign_utils::SignVerb,
 };
 
 pub struct AzureContainersListReader<'s> {
@@ -31,28 +31,39 @@ impl<'s> AzureContainersListReader<'s> {
         }
     }
 
-    pub async fn get_next(&mut self) -> Result<Option<Vec<String>>, FlUrlError> {
+    pub async fn get_next(&mut self) -> Result<Option<Vec<String>>, AzureStorageError> {
         if let NextMarkerToRead::End = &self.next_marker {
             return Ok(None);
         }
 
-        let fl_url: FlUrl = self.connection.into();
-
         let next_marker = self.get_next_marker();
 
-        let mut response = fl_url
-            .append_query_param("comp", Some("list"))
-            .add_azure_headers(
-                super::sign_utils::SignVerb::GET,
-                self.connection,
-                None,
-                next_marker,
-                AZURE_REST_VERSION,
-            )
-            .get()
-            .await?;
+        let connection = self.connection;
 
-        let body = response.get_body_as_slice().await?;
+        let response = execute_request(connection, true, || {
+            let next_marker = next_marker.clone();
+
+            async move {
+                let fl_url: FlUrl = connection.into();
+
+                let fl_url = fl_url
+                    .append_query_param("comp", Some("list"))
+                    .add_azure_headers(
+                        SignVerb::GET,
+                        connection,
+                        None,
+                        next_marker,
+                        AZURE_REST_VERSION,
+                    )
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            }
+        })
+        .await?
+        .check_if_there_is_an_error()?;
+
+        let body = response.get_body().await?;
 
         let azure_response = super::models::deserialize_list_of_containers(body.as_ref());
 
@@ -64,7 +75,9 @@ impl<'s> AzureContainersListReader<'s> {
     }
 }
 
-pub async fn get_list(connection: &AzureStorageConnectionData) -> Result<Vec<String>, FlUrlError> {
+pub async fn get_list(
+    connection: &AzureStorageConnectionData,
+) -> Result<Vec<String>, AzureStorageError> {
     let mut result = vec![];
 
     let mut reader = AzureContainersListReader::new(connection);
@@ -80,22 +93,25 @@ pub async fn create_if_not_exists(
     connection: &AzureStorageConnectionData,
     container_name: &str,
 ) -> Result<(), AzureStorageError> {
-    let fl_url: FlUrl = connection.into();
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
 
-    fl_url
-        .append_path_segment(container_name)
-        .append_query_param("restype", Some("container"))
-        .add_azure_headers(
-            super::sign_utils::SignVerb::PUT,
-            connection,
-            None,
-            None,
-            AZURE_REST_VERSION,
-        )
-        .put(FlUrlBody::Empty)
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error_and_ignore_one(AzureStorageError::ContainerAlreadyExists)?;
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_query_param("restype", Some("container"))
+            .add_azure_headers(
+                SignVerb::PUT,
+                connection,
+                None,
+                None,
+                AZURE_REST_VERSION,
+            )
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error_and_ignore_one(AzureErrorCode::ContainerAlreadyExists)?;
 
     return Ok(());
 }
@@ -104,22 +120,25 @@ pub async fn delete(
     connection: &AzureStorageConnectionData,
     container_name: &str,
 ) -> Result<(), AzureStorageError> {
-    let fl_url: FlUrl = connection.into();
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
 
-    fl_url
-        .append_path_segment(container_name)
-        .append_query_param("restype", Some("container"))
-        .add_azure_headers(
-            super::sign_utils::SignVerb::DELETE,
-            connection,
-            None,
-            None,
-            AZURE_REST_VERSION,
-        )
-        .delete()
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error()?;
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_query_param("restype", Some("container"))
+            .add_azure_headers(
+                SignVerb::DELETE,
+                connection,
+                None,
+                None,
+                AZURE_REST_VERSION,
+            )
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::DELETE, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
 
     Ok(())
 }
@@ -128,27 +147,29 @@ pub async fn delete_if_exists(
     connection: &AzureStorageConnectionData,
     container_name: &str,
 ) -> Result<(), AzureStorageError> {
-    let fl_url: FlUrl = connection.into();
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
 
-    fl_url
-        .append_path_segment(container_name)
-        .append_query_param("restype", Some("container"))
-        .add_azure_headers(
-            super::sign_utils::SignVerb::DELETE,
-            connection,
-            None,
-            None,
-            AZURE_REST_VERSION,
-        )
-        .delete()
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error_and_ignore_one(AzureStorageError::ContainerNotFound)?;
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_query_param("restype", Some("container"))
+            .add_azure_headers(
+                SignVerb::DELETE,
+                connection,
+                None,
+                None,
+                AZURE_REST_VERSION,
+            )
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::DELETE, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error_and_ignore_one(AzureErrorCode::ContainerNotFound)?;
 
     Ok(())
 }
 
-const AZURE_REST_VERSION: &str = "2017-07-29";
 
 #[cfg(test)]
 mod tests {
@@ -191,7 +212,7 @@ mod tests {
             super::super::blobs::get_blob_properties(&connection, "notexists", "notexists").await;
 
         if let Err(err) = result {
-            assert_eq!(true, matches!(err, AzureStorageError::ContainerNotFound));
+            assert_eq!(Some(AzureErrorCode::ContainerNotFound), err.get_error_code());
         } else {
             panic!("Unexpected result type: {:?}", result);
         }
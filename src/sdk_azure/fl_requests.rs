@@ -5,9 +5,8 @@ pub mod blobs {
     use crate::{
         connection::AzureStorageConnectionData,
         sdk_azure::{
-            azure_response_handler::{AzureResponseHandler, ToAzureResponseHandler},
-            flurl_ext::FlUrlAzureExtensions,
-            sign_utils::SignVerb,
+            azure_response_handler::AzureResponseHandler, flurl_ext::FlUrlAzureExtensions,
+            http_transport::AzureHttpRequest, retry_policy::execute_request, sign_utils::SignVerb,
         },
         AzureStorageError,
     };
@@ -17,24 +16,23 @@ pub mod blobs {
         container_name: &str,
         blob_name: &str,
     ) -> Result<AzureResponseHandler, AzureStorageError> {
-        let fl_url: FlUrl = connection.into();
+        execute_request(connection, true, || async move {
+            let fl_url: FlUrl = connection.into();
 
-        let resp = fl_url
-            .append_path_segment(container_name)
-            .append_path_segment(blob_name)
-            .add_azure_headers(
-                SignVerb::HEAD,
-                connection,
-                None,
-                None,
-                super::AZURE_REST_VERSION,
-            )
-            .head()
-            .await?
-            .to_azure_response_handler();
+            let fl_url = fl_url
+                .append_path_segment(container_name)
+                .append_path_segment(blob_name)
+                .add_azure_headers(
+                    SignVerb::HEAD,
+                    connection,
+                    None,
+                    None,
+                    crate::sdk_azure::consts::AZURE_REST_VERSION,
+                )
+                .await?;
 
-        Ok(resp)
+            Ok(AzureHttpRequest::new(SignVerb::HEAD, fl_url))
+        })
+        .await
     }
 }
-
-const AZURE_REST_VERSION: &str = "2017-07-29";
@@ -1,35 +1,41 @@
-use crate::{connection::AzureStorageConnectionData, sdk_azure::sign_utils};
+use crate::{
+    connection::AzureStorageConnectionData, sdk_azure::sign_utils,
+    token_credential::STORAGE_SCOPE, AzureStorageError,
+};
 
 use super::sign_utils::SignVerb;
+use async_trait::async_trait;
 use flurl::FlUrl;
 
-pub trait FlUrlAzureExtensions {
-    fn add_azure_headers(
+#[async_trait]
+pub trait FlUrlAzureExtensions: Sized {
+    async fn add_azure_headers(
         self,
         verb: SignVerb,
         connection: &AzureStorageConnectionData,
         content_len: Option<usize>,
         next_marker: Option<String>,
         azure_rest_version: &str,
-    ) -> Self;
+    ) -> Result<Self, AzureStorageError>;
 
     #[cfg(feature = "table-storage")]
-    fn add_table_storage_azure_headers(
+    async fn add_table_storage_azure_headers(
         self,
         connection: &AzureStorageConnectionData,
         content_len: Option<usize>,
-    ) -> Self;
+    ) -> Result<Self, crate::table_storage::TableStorageError>;
 }
 
+#[async_trait]
 impl FlUrlAzureExtensions for FlUrl {
-    fn add_azure_headers(
+    async fn add_azure_headers(
         mut self,
         verb: SignVerb,
         connection: &AzureStorageConnectionData,
         content_len: Option<usize>,
         next_marker: Option<String>,
         azure_rest_version: &str,
-    ) -> Self {
+    ) -> Result<Self, AzureStorageError> {
         let date = sign_utils::get_now_date();
 
         let date = date.as_str();
@@ -49,17 +55,28 @@ impl FlUrlAzureExtensions for FlUrl {
             flurl = flurl.append_query_param("marker", Some(next_marker));
         }
 
-        let auth_key = connection.get_auth_header(verb, content_len, &flurl);
+        if let Some(sas_token) = connection.credentials.get_sas_token() {
+            return Ok(sas_token.append_to_fl_url(flurl));
+        }
+
+        if let Some(token_credential) = connection.credentials.get_token_credential() {
+            let access_token = token_credential.get_token(STORAGE_SCOPE).await?;
+            let auth_key = format!("Bearer {}", access_token.token);
+            return Ok(flurl.with_header("Authorization", auth_key));
+        }
 
-        flurl.with_header("Authorization", auth_key)
+        match connection.get_auth_header(verb, content_len, &flurl) {
+            Some(auth_key) => Ok(flurl.with_header("Authorization", auth_key)),
+            None => Ok(flurl),
+        }
     }
 
     #[cfg(feature = "table-storage")]
-    fn add_table_storage_azure_headers(
+    async fn add_table_storage_azure_headers(
         mut self,
         connection: &AzureStorageConnectionData,
         content_len: Option<usize>,
-    ) -> Self {
+    ) -> Result<Self, crate::table_storage::TableStorageError> {
         let now = Utc::now();
 
         let date = now.to_rfc2822().replace("+0000", "GMT");
@@ -69,14 +86,153 @@ impl FlUrlAzureExtensions for FlUrl {
             None => self.with_header("Content-Length", "0"),
         };
 
+        let token_credential = connection.credentials.get_token_credential();
+
+        // The Table service takes bearer tokens from 2019-02-02 on
+        let rest_version = match token_credential {
+            Some(_) => "2019-02-02",
+            None => "2015-12-11",
+        };
+
         let fl_url = self
             .with_header("x-ms-date", date.as_str())
-            .with_header("x-ms-version", "2015-12-11")
+            .with_header("x-ms-version", rest_version)
             .with_header("Content-Type", "application/json")
             .with_header("Accept", "application/json;odata=nometadata");
 
-        let auth_key = connection.get_table_storage_auth_header(&date, &fl_url);
+        if let Some(sas_token) = connection.credentials.get_sas_token() {
+            return Ok(sas_token.append_to_fl_url(fl_url));
+        }
+
+        if let Some(token_credential) = token_credential {
+            let access_token = token_credential
+                .get_token(STORAGE_SCOPE)
+                .await
+                .map_err(AzureStorageError::from)?;
+            let auth_key = format!("Bearer {}", access_token.token);
+            return Ok(fl_url.with_header("Authorization", auth_key));
+        }
+
+        match connection.get_table_storage_auth_header(&date, &fl_url) {
+            Some(auth_key) => Ok(fl_url.with_header_val_string("Authorization", auth_key)),
+            None => Ok(fl_url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sas_connection_does_not_sign_requests() {
+        let connection = AzureStorageConnectionData::try_from_sas_url(
+            "https://myaccount.blob.core.windows.net/mycontainer?sv=2019-12-12&sr=c&sp=rl&sig=abc",
+        )
+        .unwrap();
+
+        let fl_url: FlUrl = (&connection).into();
+
+        let fl_url = fl_url.append_path_segment("mycontainer").add_azure_headers(
+            SignVerb::GET,
+            &connection,
+            None,
+            None,
+            super::super::consts::AZURE_REST_VERSION,
+        )
+        .await
+        .unwrap();
+
+        assert!(!fl_url
+            .headers
+            .iter()
+            .any(|(key, _)| key.eq_ignore_ascii_case("authorization")));
+
+        let has_signature = fl_url
+            .url_builder
+            .iter_query()
+            .unwrap()
+            .any(|(key, value)| key == "sig" && value.map(|itm| itm.as_str() == "abc") == Some(true));
+
+        assert!(has_signature);
+    }
+
+    struct StaticTokenCredential;
+
+    #[async_trait]
+    impl crate::token_credential::TokenCredential for StaticTokenCredential {
+        async fn get_token(
+            &self,
+            scope: &str,
+        ) -> Result<
+            crate::token_credential::AccessToken,
+            crate::token_credential::TokenCredentialError,
+        > {
+            assert_eq!(STORAGE_SCOPE, scope);
+
+            Ok(crate::token_credential::AccessToken {
+                token: "my-token".to_string(),
+                expires_on: rust_extensions::date_time::DateTimeAsMicroseconds::now(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_credential_adds_bearer_header() {
+        let connection = AzureStorageConnectionData::from_token_credential(
+            "myaccount",
+            std::sync::Arc::new(StaticTokenCredential),
+        );
+
+        let fl_url: FlUrl = (&connection).into();
+
+        let fl_url = fl_url
+            .append_path_segment("mycontainer")
+            .add_azure_headers(
+                SignVerb::GET,
+                &connection,
+                None,
+                None,
+                super::super::consts::AZURE_REST_VERSION,
+            )
+            .await
+            .unwrap();
+
+        let auth_header = fl_url
+            .headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("authorization"))
+            .map(|(_, value)| value.to_string());
+
+        assert_eq!(Some("Bearer my-token".to_string()), auth_header);
+    }
+
+    #[cfg(feature = "table-storage")]
+    #[tokio::test]
+    async fn test_token_credential_adds_bearer_header_to_table_requests() {
+        let connection = AzureStorageConnectionData::from_token_credential(
+            "myaccount",
+            std::sync::Arc::new(StaticTokenCredential),
+        );
+
+        let fl_url = FlUrl::new(connection.table_storage_api_url.as_str(), None)
+            .append_path_segment("Tables")
+            .add_table_storage_azure_headers(&connection, None)
+            .await
+            .unwrap();
+
+        let get_header = |name: &str| {
+            fl_url
+                .headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.to_string())
+        };
 
-        fl_url.with_header_val_string("Authorization", auth_key)
+        assert_eq!(
+            Some("Bearer my-token".to_string()),
+            get_header("authorization")
+        );
+        assert_eq!(Some("2019-02-02".to_string()), get_header("x-ms-version"));
     }
 }
@@ -0,0 +1,132 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Parses RFC 1123 date the service uses in Last-Modified and x-ms-* date headers:
+/// Mon, 27 Jul 2009 12:28:53 GMT
+pub fn parse_http_date(src: &str) -> Option<DateTimeAsMicroseconds> {
+    let src = match src.find(',') {
+        Some(index) => &src[index + 1..],
+        None => src,
+    };
+
+    let mut parts = src.split_whitespace();
+
+    let day: i64 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|itm| *itm == month)? as i64 + 1;
+    let year: i64 = parts.next()?.parse().ok()?;
+
+    let mut time = parts.next()?.split(':');
+    let hour: i64 = time.next()?.parse().ok()?;
+    let minute: i64 = time.next()?.parse().ok()?;
+    let second: i64 = time.next()?.parse().ok()?;
+
+    if parts.next()? != "GMT" {
+        return None;
+    }
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second;
+
+    Some(DateTimeAsMicroseconds::new(seconds * 1_000_000))
+}
+
+/// ISO 8601 timestamp with 7 fractional digits the service uses for snapshot ids:
+/// 2011-03-09T01:42:34.9360000Z
+pub fn to_azure_timestamp(moment: DateTimeAsMicroseconds) -> String {
+    let seconds = moment.unix_microseconds.div_euclid(1_000_000);
+    let microseconds = moment.unix_microseconds.rem_euclid(1_000_000);
+
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let seconds_of_day = seconds.rem_euclid(86_400);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}0Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+        microseconds
+    )
+}
+
+// Proleptic Gregorian calendar date to days since 1970-01-01
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * mp + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// Days since 1970-01-01 to proleptic Gregorian calendar date
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_http_date() {
+        assert_eq!(
+            0,
+            parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT")
+                .unwrap()
+                .unix_microseconds
+        );
+
+        assert_eq!(
+            1_248_697_733_000_000,
+            parse_http_date("Mon, 27 Jul 2009 12:28:53 GMT")
+                .unwrap()
+                .unix_microseconds
+        );
+
+        assert_eq!(
+            1_709_214_330_000_000,
+            parse_http_date("Thu, 29 Feb 2024 13:45:30 GMT")
+                .unwrap()
+                .unix_microseconds
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_http_date() {
+        assert!(parse_http_date("").is_none());
+        assert!(parse_http_date("2024-02-29T13:45:30Z").is_none());
+        assert!(parse_http_date("Thu, 29 Foo 2024 13:45:30 GMT").is_none());
+    }
+
+    #[test]
+    fn test_to_azure_timestamp() {
+        assert_eq!(
+            "1970-01-01T00:00:00.0000000Z",
+            to_azure_timestamp(DateTimeAsMicroseconds::new(0))
+        );
+
+        assert_eq!(
+            "2024-02-29T13:45:30.1234560Z",
+            to_azure_timestamp(DateTimeAsMicroseconds::new(1_709_214_330_123_456))
+        );
+    }
+}
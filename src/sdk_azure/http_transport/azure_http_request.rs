@@ -0,0 +1,25 @@
+use flurl::FlUrl;
+
+use crate::sdk_azure::sign_utils::SignVerb;
+
+/// Request ready to be sent: url, query and headers (including authorization) are already in the FlUrl
+pub struct AzureHttpRequest {
+    pub verb: SignVerb,
+    pub fl_url: FlUrl,
+    pub body: Option<Vec<u8>>,
+}
+
+impl AzureHttpRequest {
+    pub fn new(verb: SignVerb, fl_url: FlUrl) -> Self {
+        Self {
+            verb,
+            fl_url,
+            body: None,
+        }
+    }
+
+    pub fn with_body(mut self, body: Vec<u8>) -> Self {
+        self.body = Some(body);
+        self
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use flurl::FlUrlResponse;
+
+use crate::AzureStorageError;
+
+/// Fully received response. Header names are kept lowercased
+#[derive(Debug, Clone)]
+pub struct AzureHttpResponse {
+    pub status_code: u16,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl AzureHttpResponse {
+    pub fn new(status_code: u16) -> Self {
+        Self {
+            status_code,
+            headers: HashMap::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.insert(name.to_lowercase(), value.into());
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        let result = self.headers.get(name.to_lowercase().as_str())?;
+        Some(result.as_str())
+    }
+
+    pub async fn from_fl_url_response(response: FlUrlResponse) -> Result<Self, AzureStorageError> {
+        let mut headers = HashMap::new();
+
+        for (key, value) in response.get_headers().iter() {
+            if let Some(value) = value {
+                headers.insert(key.to_lowercase(), value.to_string());
+            }
+        }
+
+        let status_code = response.get_status_code();
+
+        let body = response.receive_body().await?;
+
+        Ok(Self {
+            status_code,
+            headers,
+            body,
+        })
+    }
+}
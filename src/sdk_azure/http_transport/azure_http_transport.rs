@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use flurl::body::FlUrlBody;
+
+use crate::{sdk_azure::sign_utils::SignVerb, AzureStorageError};
+
+use super::{AzureHttpRequest, AzureHttpResponse};
+
+/// The wire under the Azure REST backend. Request builders prepare and sign requests,
+/// the transport only sends them
+#[async_trait]
+pub trait AzureHttpTransport: Send + Sync {
+    async fn send(&self, request: AzureHttpRequest) -> Result<AzureHttpResponse, AzureStorageError>;
+}
+
+/// Default transport
+pub struct FlUrlTransport;
+
+#[async_trait]
+impl AzureHttpTransport for FlUrlTransport {
+    async fn send(&self, request: AzureHttpRequest) -> Result<AzureHttpResponse, AzureStorageError> {
+        let AzureHttpRequest { verb, fl_url, body } = request;
+
+        let body = match body {
+            Some(data) => FlUrlBody::Raw {
+                data,
+                content_type: None,
+            },
+            None => FlUrlBody::Empty,
+        };
+
+        let response = match verb {
+            SignVerb::GET => fl_url.get().await?,
+            SignVerb::HEAD => fl_url.head().await?,
+            SignVerb::DELETE => fl_url.delete().await?,
+            SignVerb::PUT => fl_url.put(body).await?,
+            SignVerb::POST => fl_url.post(body).await?,
+        };
+
+        AzureHttpResponse::from_fl_url_response(response).await
+    }
+}
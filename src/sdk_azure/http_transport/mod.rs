@@ -0,0 +1,8 @@
+mod azure_http_request;
+mod azure_http_response;
+mod azure_http_transport;
+mod scripted_transport;
+pub use azure_http_request::*;
+pub use azure_http_response::*;
+pub use azure_http_transport::*;
+pub use scripted_transport::*;
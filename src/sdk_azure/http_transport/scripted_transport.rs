@@ -0,0 +1,128 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+#[cfg(test)]
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+#[cfg(test)]
+use crate::{connection::AzureStorageConnectionData, RetryPolicy};
+use crate::{sdk_azure::sign_utils::SignVerb, AzureStorageError};
+
+use super::{AzureHttpRequest, AzureHttpResponse, AzureHttpTransport};
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub verb: SignVerb,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl RecordedRequest {
+    fn new(request: AzureHttpRequest) -> Self {
+        let fl_url = &request.fl_url;
+
+        let mut query = Vec::new();
+
+        if let Some(query_params) = fl_url.url_builder.iter_query() {
+            for (key, value) in query_params {
+                let value = value.map(|itm| itm.as_str().to_string());
+                query.push((key.to_string(), value.unwrap_or_default()));
+            }
+        }
+
+        let headers = fl_url
+            .headers
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        Self {
+            verb: request.verb,
+            path: fl_url.url_builder.get_path().to_string(),
+            query,
+            headers,
+            body: request.body,
+        }
+    }
+
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        let (_, value) = self
+            .headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))?;
+
+        Some(value.as_str())
+    }
+
+    pub fn get_query_param(&self, name: &str) -> Option<&str> {
+        let (_, value) = self.query.iter().find(|(key, _)| key == name)?;
+        Some(value.as_str())
+    }
+}
+
+/// Transport for tests: records every request and replies with canned responses in order.
+/// Fails with UnknownError when the script is exhausted.
+pub struct ScriptedTransport {
+    responses: Mutex<VecDeque<Result<AzureHttpResponse, AzureStorageError>>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl ScriptedTransport {
+    pub fn new() -> Self {
+        Self {
+            responses: Mutex::new(VecDeque::new()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn push_response(&self, response: AzureHttpResponse) {
+        self.responses.lock().unwrap().push_back(Ok(response));
+    }
+
+    pub fn push_error(&self, err: AzureStorageError) {
+        self.responses.lock().unwrap().push_back(Err(err));
+    }
+
+    pub fn get_requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Default for ScriptedTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AzureHttpTransport for ScriptedTransport {
+    async fn send(
+        &self,
+        request: AzureHttpRequest,
+    ) -> Result<AzureHttpResponse, AzureStorageError> {
+        self.requests
+            .lock()
+            .unwrap()
+            .push(RecordedRequest::new(request));
+
+        match self.responses.lock().unwrap().pop_front() {
+            Some(result) => result,
+            None => Err(AzureStorageError::UnknownError {
+                msg: "ScriptedTransport has no response left".to_string(),
+            }),
+        }
+    }
+}
+
+/// Connection of the tests which script the responses of Azure. Failed requests are not retried
+#[cfg(test)]
+pub fn get_scripted_connection(transport: &Arc<ScriptedTransport>) -> AzureStorageConnectionData {
+    AzureStorageConnectionData::from_conn_string(
+        "AccountName=test;AccountKey=YWJj;BlobEndpoint=http://127.0.0.1:10000/test",
+    )
+    .with_retry_policy(RetryPolicy::no_retry())
+    .with_transport(transport.clone())
+}
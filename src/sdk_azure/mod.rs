@@ -1,12 +1,24 @@
+pub mod access_conditions;
 mod azure_response_chunk;
 pub mod azure_response_handler;
+pub mod blob_copy;
+pub mod blob_http_headers;
+pub mod blob_leases;
+pub mod blob_metadata;
+pub mod blob_properties_reader;
+pub mod blob_snapshots;
+pub mod blob_stream;
+pub mod blob_versions;
 pub mod blobs;
 pub mod consts;
 pub mod containers;
 pub mod fl_requests;
 pub mod flurl_ext;
+pub mod http_date;
+pub mod http_transport;
 pub mod models;
 pub mod page_blobs;
+pub mod retry_policy;
 pub mod sign_utils;
 #[cfg(feature = "table-storage")]
 pub mod table_storage;
@@ -1,5 +1,7 @@
 use my_xml_reader::{MyXmlReader, XmlTagInfo};
 
+use crate::block_blob::{BlockItem, BlockList};
+
 use super::azure_response_chunk::AzureResponseChunk;
 
 const ROOT_NODE_NAME: &str = "EnumerationResults";
@@ -12,6 +14,35 @@ const BLOB_ARRAY_ITEM_NODE: &str = "Blob";
 
 const NEXT_MARKER_NODE: &str = "NextMarker";
 
+const NAME_NODE: &str = "Name";
+const SNAPSHOT_NODE: &str = "Snapshot";
+const VERSION_ID_NODE: &str = "VersionId";
+const IS_CURRENT_VERSION_NODE: &str = "IsCurrentVersion";
+const DELETED_NODE: &str = "Deleted";
+const VERSION_NODE: &str = "Version";
+
+const BLOCK_LIST_ROOT_NODE: &str = "BlockList";
+const COMMITTED_BLOCKS_NODE: &str = "CommittedBlocks";
+const UNCOMMITTED_BLOCKS_NODE: &str = "UncommittedBlocks";
+const BLOCK_NODE: &str = "Block";
+const SIZE_NODE: &str = "Size";
+
+/// Blob entry of List Blobs with `include=` - the same blob name comes once per snapshot or version
+pub struct BlobListItem {
+    pub name: String,
+    pub snapshot: Option<String>,
+    pub version_id: Option<String>,
+    pub is_current_version: bool,
+    pub deleted: bool,
+}
+
+/// Container entry of List Containers with `include=deleted`
+pub struct ContainerListItem {
+    pub name: String,
+    pub deleted: bool,
+    pub version: Option<String>,
+}
+
 pub enum NextMarkerToRead {
     Start,
     Next(String),
@@ -139,3 +170,461 @@ pub fn deserialize_list_of_blobs(xml: &[u8]) -> AzureResponseChunk<String> {
         items: containers.unwrap(),
     };
 }
+
+fn get_array_of_blob_items<'t>(
+    xml_reader: &mut MyXmlReader<'t>,
+    array_node: XmlTagInfo<'t>,
+) -> Vec<BlobListItem> {
+    let mut result = vec![];
+
+    loop {
+        let item_node = xml_reader
+            .find_the_node_inside_parent(&array_node, BLOB_ARRAY_ITEM_NODE)
+            .unwrap();
+
+        let item_node = match item_node {
+            Some(item_node) => item_node,
+            None => break,
+        };
+
+        let mut item = BlobListItem {
+            name: String::new(),
+            snapshot: None,
+            version_id: None,
+            is_current_version: false,
+            deleted: false,
+        };
+
+        loop {
+            let field_node = xml_reader
+                .find_any_of_these_nodes_inside_parent(
+                    &item_node,
+                    vec![
+                        NAME_NODE,
+                        SNAPSHOT_NODE,
+                        VERSION_ID_NODE,
+                        IS_CURRENT_VERSION_NODE,
+                        DELETED_NODE,
+                    ]
+                    .as_slice(),
+                )
+                .unwrap();
+
+            let field_node = match field_node {
+                Some(field_node) => field_node,
+                None => break,
+            };
+
+            match field_node.name {
+                NAME_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.name = node.get_value().unwrap_or_default();
+                }
+                SNAPSHOT_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.snapshot = node.get_value();
+                }
+                VERSION_ID_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.version_id = node.get_value();
+                }
+                IS_CURRENT_VERSION_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.is_current_version = is_true(node.get_value());
+                }
+                DELETED_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.deleted = is_true(node.get_value());
+                }
+                _ => {}
+            }
+        }
+
+        result.push(item);
+    }
+
+    result
+}
+
+fn is_true(value: Option<String>) -> bool {
+    value.as_deref() == Some("true")
+}
+
+fn get_array_of_container_items<'t>(
+    xml_reader: &mut MyXmlReader<'t>,
+    array_node: XmlTagInfo<'t>,
+) -> Vec<ContainerListItem> {
+    let mut result = vec![];
+
+    loop {
+        let item_node = xml_reader
+            .find_the_node_inside_parent(&array_node, CONTAINER_ARRAY_ITEM_NODE)
+            .unwrap();
+
+        let item_node = match item_node {
+            Some(item_node) => item_node,
+            None => break,
+        };
+
+        let mut item = ContainerListItem {
+            name: String::new(),
+            deleted: false,
+            version: None,
+        };
+
+        loop {
+            let field_node = xml_reader
+                .find_any_of_these_nodes_inside_parent(
+                    &item_node,
+                    vec![NAME_NODE, DELETED_NODE, VERSION_NODE].as_slice(),
+                )
+                .unwrap();
+
+            let field_node = match field_node {
+                Some(field_node) => field_node,
+                None => break,
+            };
+
+            match field_node.name {
+                NAME_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.name = node.get_value().unwrap_or_default();
+                }
+                DELETED_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.deleted = is_true(node.get_value());
+                }
+                VERSION_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.version = node.get_value();
+                }
+                _ => {}
+            }
+        }
+
+        result.push(item);
+    }
+
+    result
+}
+
+pub fn deserialize_list_of_container_items(xml: &[u8]) -> AzureResponseChunk<ContainerListItem> {
+    let mut xml_reader = MyXmlReader::from_slice(xml).unwrap();
+
+    let root_node = xml_reader
+        .find_the_open_node(ROOT_NODE_NAME)
+        .unwrap()
+        .unwrap();
+
+    let mut items: Vec<ContainerListItem> = vec![];
+
+    let mut next_marker: Option<String> = None;
+
+    loop {
+        let open_node = xml_reader
+            .find_any_of_these_nodes_inside_parent(
+                &root_node,
+                vec![CONTAINERS_ARRAY_NODE, NEXT_MARKER_NODE].as_slice(),
+            )
+            .unwrap();
+
+        if open_node.is_none() {
+            break;
+        }
+
+        let open_node = open_node.unwrap();
+
+        match open_node.name {
+            NEXT_MARKER_NODE => {
+                let next_marker_node = xml_reader.read_the_whole_node(open_node).unwrap();
+                next_marker = next_marker_node.get_value();
+            }
+            CONTAINERS_ARRAY_NODE => {
+                items = get_array_of_container_items(&mut xml_reader, open_node);
+            }
+            _ => {}
+        }
+    }
+
+    AzureResponseChunk { next_marker, items }
+}
+
+pub fn deserialize_list_of_blob_items(xml: &[u8]) -> AzureResponseChunk<BlobListItem> {
+    let mut xml_reader = MyXmlReader::from_slice(xml).unwrap();
+
+    let root_node = xml_reader
+        .find_the_open_node(ROOT_NODE_NAME)
+        .unwrap()
+        .unwrap();
+
+    let mut items: Vec<BlobListItem> = vec![];
+
+    let mut next_marker: Option<String> = None;
+
+    loop {
+        let open_node = xml_reader
+            .find_any_of_these_nodes_inside_parent(
+                &root_node,
+                vec![BLOBS_ARRAY_NODE, NEXT_MARKER_NODE].as_slice(),
+            )
+            .unwrap();
+
+        if open_node.is_none() {
+            break;
+        }
+
+        let open_node = open_node.unwrap();
+
+        match open_node.name {
+            NEXT_MARKER_NODE => {
+                let next_marker_node = xml_reader.read_the_whole_node(open_node).unwrap();
+                next_marker = next_marker_node.get_value();
+            }
+            BLOBS_ARRAY_NODE => {
+                items = get_array_of_blob_items(&mut xml_reader, open_node);
+            }
+            _ => {}
+        }
+    }
+
+    AzureResponseChunk { next_marker, items }
+}
+
+fn get_array_of_block_items<'t>(
+    xml_reader: &mut MyXmlReader<'t>,
+    array_node: XmlTagInfo<'t>,
+) -> Vec<BlockItem> {
+    let mut result = vec![];
+
+    loop {
+        let item_node = xml_reader
+            .find_the_node_inside_parent(&array_node, BLOCK_NODE)
+            .unwrap();
+
+        let item_node = match item_node {
+            Some(item_node) => item_node,
+            None => break,
+        };
+
+        let mut item = BlockItem {
+            block_id: String::new(),
+            size: 0,
+        };
+
+        loop {
+            let field_node = xml_reader
+                .find_any_of_these_nodes_inside_parent(
+                    &item_node,
+                    vec![NAME_NODE, SIZE_NODE].as_slice(),
+                )
+                .unwrap();
+
+            let field_node = match field_node {
+                Some(field_node) => field_node,
+                None => break,
+            };
+
+            match field_node.name {
+                NAME_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.block_id = node.get_value().unwrap_or_default();
+                }
+                SIZE_NODE => {
+                    let node = xml_reader.read_the_whole_node(field_node).unwrap();
+                    item.size = node
+                        .get_value()
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+
+        result.push(item);
+    }
+
+    result
+}
+
+/// Get Block List response
+pub fn deserialize_block_list(xml: &[u8]) -> BlockList {
+    let mut xml_reader = MyXmlReader::from_slice(xml).unwrap();
+
+    let root_node = xml_reader
+        .find_the_open_node(BLOCK_LIST_ROOT_NODE)
+        .unwrap()
+        .unwrap();
+
+    let mut result = BlockList::default();
+
+    loop {
+        let open_node = xml_reader
+            .find_any_of_these_nodes_inside_parent(
+                &root_node,
+                vec![COMMITTED_BLOCKS_NODE, UNCOMMITTED_BLOCKS_NODE].as_slice(),
+            )
+            .unwrap();
+
+        let open_node = match open_node {
+            Some(open_node) => open_node,
+            None => break,
+        };
+
+        match open_node.name {
+            COMMITTED_BLOCKS_NODE => {
+                result.committed = get_array_of_block_items(&mut xml_reader, open_node);
+            }
+            UNCOMMITTED_BLOCKS_NODE => {
+                result.uncommitted = get_array_of_block_items(&mut xml_reader, open_node);
+            }
+            _ => {}
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_list_of_blob_items() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ServiceEndpoint="http://myaccount.blob.core.windows.net/" ContainerName="state">
+  <Prefix>orders</Prefix>
+  <Blobs>
+    <Blob>
+      <Name>orders</Name>
+      <Snapshot>2011-03-09T01:42:34.9360000Z</Snapshot>
+      <Properties><Content-Length>3</Content-Length></Properties>
+    </Blob>
+    <Blob>
+      <Name>orders</Name>
+      <Properties><Content-Length>5</Content-Length></Properties>
+    </Blob>
+  </Blobs>
+</EnumerationResults>"#;
+
+        let result = deserialize_list_of_blob_items(xml.as_bytes());
+
+        assert_eq!(2, result.items.len());
+        assert_eq!("orders", result.items[0].name);
+        assert_eq!(
+            Some("2011-03-09T01:42:34.9360000Z".to_string()),
+            result.items[0].snapshot
+        );
+        assert_eq!("orders", result.items[1].name);
+        assert!(result.items[1].snapshot.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_list_of_blob_versions() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ContainerName="state">
+  <Blobs>
+    <Blob>
+      <Name>orders</Name>
+      <VersionId>2021-05-01T10:00:00.0000000Z</VersionId>
+      <Properties><Content-Length>3</Content-Length></Properties>
+    </Blob>
+    <Blob>
+      <Name>orders</Name>
+      <VersionId>2021-05-02T10:00:00.0000000Z</VersionId>
+      <IsCurrentVersion>true</IsCurrentVersion>
+    </Blob>
+    <Blob>
+      <Name>removed</Name>
+      <Deleted>true</Deleted>
+    </Blob>
+  </Blobs>
+</EnumerationResults>"#;
+
+        let result = deserialize_list_of_blob_items(xml.as_bytes());
+
+        assert_eq!(3, result.items.len());
+        assert_eq!(
+            Some("2021-05-01T10:00:00.0000000Z".to_string()),
+            result.items[0].version_id
+        );
+        assert!(!result.items[0].is_current_version);
+        assert!(result.items[1].is_current_version);
+        assert_eq!("removed", result.items[2].name);
+        assert!(result.items[2].deleted);
+    }
+
+    #[test]
+    fn test_deserialize_list_of_deleted_containers() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ServiceEndpoint="https://myaccount.blob.core.windows.net">
+  <Containers>
+    <Container>
+      <Name>orders</Name>
+      <Deleted>true</Deleted>
+      <Version>01D60F8BB59A4652</Version>
+    </Container>
+    <Container>
+      <Name>state</Name>
+    </Container>
+  </Containers>
+</EnumerationResults>"#;
+
+        let result = deserialize_list_of_container_items(xml.as_bytes());
+
+        assert_eq!(2, result.items.len());
+        assert_eq!("orders", result.items[0].name);
+        assert!(result.items[0].deleted);
+        assert_eq!(
+            Some("01D60F8BB59A4652".to_string()),
+            result.items[0].version
+        );
+        assert!(!result.items[1].deleted);
+        assert!(result.items[1].version.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_block_list() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<BlockList>
+  <CommittedBlocks>
+    <Block>
+      <Name>QUFB</Name>
+      <Size>4194304</Size>
+    </Block>
+    <Block>
+      <Name>QkJC</Name>
+      <Size>10</Size>
+    </Block>
+  </CommittedBlocks>
+  <UncommittedBlocks>
+    <Block>
+      <Name>Q0ND</Name>
+      <Size>5</Size>
+    </Block>
+  </UncommittedBlocks>
+</BlockList>"#;
+
+        let result = deserialize_block_list(xml.as_bytes());
+
+        assert_eq!(
+            vec![
+                BlockItem {
+                    block_id: "QUFB".to_string(),
+                    size: 4194304
+                },
+                BlockItem {
+                    block_id: "QkJC".to_string(),
+                    size: 10
+                },
+            ],
+            result.committed
+        );
+        assert_eq!(
+            vec![BlockItem {
+                block_id: "Q0ND".to_string(),
+                size: 5
+            }],
+            result.uncommitted
+        );
+    }
+}
@@ -1,13 +1,17 @@
-use flurl::body::FlUrlBody;
+use std::collections::HashMap;
+
 use flurl::FlUrl;
 use rust_extensions::SliceOrVec;
 
-use super::azure_response_handler::ToAzureResponseHandler;
+use super::access_conditions::with_access_conditions;
+use super::blob_metadata::with_metadata_headers;
 use super::consts::AZURE_REST_VERSION;
 use super::flurl_ext::FlUrlAzureExtensions;
-use crate::blob::BlobProperties;
+use super::http_transport::AzureHttpRequest;
+use super::retry_policy::execute_request;
+use crate::blob::{BlobAccessConditions, BlobProperties};
 use crate::connection::AzureStorageConnectionData;
-use crate::AzureStorageError;
+use crate::{AzureErrorCode, AzureStorageError};
 
 use super::sign_utils::SignVerb;
 
@@ -27,8 +31,15 @@ pub async fn create_page_blob_if_not_exists(
         match result {
             Ok(props) => return Ok(props),
             Err(err) => {
-                if matches!(err, AzureStorageError::BlobNotFound) {
-                    create_page_blob(connection, container_name, blob_name, pages_amount).await?;
+                if err.get_error_code() == Some(AzureErrorCode::BlobNotFound) {
+                    create_page_blob(
+                        connection,
+                        container_name,
+                        blob_name,
+                        pages_amount,
+                        &HashMap::new(),
+                    )
+                    .await?;
                 } else {
                     return Err(err);
                 }
@@ -45,19 +56,22 @@ pub async fn resize_page_blob(
 ) -> Result<(), AzureStorageError> {
     let new_size = pages_amount * BLOB_PAGE_SIZE;
 
-    let fl_url: FlUrl = connection.into();
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .append_query_param("comp", Some("properties"))
+            .with_header("x-ms-blob-content-length", new_size.to_string())
+            .with_header("x-ms-blob-type", "PageBlob")
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
 
-    fl_url
-        .append_path_segment(container_name)
-        .append_path_segment(blob_name)
-        .append_query_param("comp", Some("properties"))
-        .with_header("x-ms-blob-content-length", new_size.to_string())
-        .with_header("x-ms-blob-type", "PageBlob")
-        .add_azure_headers(SignVerb::PUT, &connection, None, None, AZURE_REST_VERSION)
-        .put(FlUrlBody::Empty)
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error()?;
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
 
     Ok(())
 }
@@ -68,39 +82,46 @@ pub async fn save_pages<'s>(
     blob_name: &str,
     start_page_no: usize,
     payload: impl Into<SliceOrVec<'s, u8>>,
-) -> Result<(), AzureStorageError> {
+    conditions: &BlobAccessConditions,
+) -> Result<String, AzureStorageError> {
     let start_bytes = start_page_no * BLOB_PAGE_SIZE;
 
     let payload = payload.into().into_vec();
 
     let end_bytes = start_bytes + payload.len() - 1;
 
-    let range_header = format!("bytes={}-{}", start_bytes, end_bytes);
-
-    let fl_url: FlUrl = connection.into();
-
-    fl_url
-        .append_path_segment(container_name)
-        .append_path_segment(blob_name)
-        .append_query_param("comp", Some("page"))
-        .with_header("x-ms-page-write", "update")
-        .with_header("x-ms-range", range_header)
-        .add_azure_headers(
-            SignVerb::PUT,
-            &connection,
-            Some(payload.len()),
-            None,
-            AZURE_REST_VERSION,
-        )
-        .put(FlUrlBody::Raw {
-            data: payload,
-            content_type: None,
-        })
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error()?;
-
-    Ok(())
+    let response = execute_request(connection, true, || {
+        let payload = payload.clone();
+
+        async move {
+            let range_header = format!("bytes={}-{}", start_bytes, end_bytes);
+
+            let fl_url: FlUrl = connection.into();
+
+            let fl_url = fl_url
+                .append_path_segment(container_name)
+                .append_path_segment(blob_name)
+                .append_query_param("comp", Some("page"))
+                .with_header("x-ms-page-write", "update")
+                .with_header("x-ms-range", range_header);
+
+            let fl_url = with_access_conditions(fl_url, conditions)
+                .add_azure_headers(
+                    SignVerb::PUT,
+                    connection,
+                    Some(payload.len()),
+                    None,
+                    AZURE_REST_VERSION,
+                )
+                .await?;
+
+            Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url).with_body(payload))
+        }
+    })
+    .await?
+    .check_if_there_is_an_error()?;
+
+    response.get_etag()
 }
 
 pub async fn get_pages(
@@ -116,19 +137,22 @@ pub async fn get_pages(
 
     let end_bytes = start_bytes + size_to_read - 1;
 
-    let range_header = format!("bytes={}-{}", start_bytes, end_bytes);
+    let response = execute_request(connection, true, || async move {
+        let range_header = format!("bytes={}-{}", start_bytes, end_bytes);
 
-    let fl_url: FlUrl = connection.into();
+        let fl_url: FlUrl = connection.into();
 
-    let response = fl_url
-        .append_path_segment(container_name)
-        .append_path_segment(blob_name)
-        .with_header("x-ms-range", range_header)
-        .add_azure_headers(SignVerb::GET, &connection, None, None, AZURE_REST_VERSION)
-        .get()
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error()?;
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .with_header("x-ms-range", range_header)
+            .add_azure_headers(SignVerb::GET, connection, None, None, AZURE_REST_VERSION)
+            .await?;
+
+        Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
 
     Ok(response.get_body().await?)
 }
@@ -138,21 +162,27 @@ pub async fn create_page_blob(
     container_name: &str,
     blob_name: &str,
     pages_amount: usize,
+    metadata: &HashMap<String, String>,
 ) -> Result<(), AzureStorageError> {
     let new_size = pages_amount * BLOB_PAGE_SIZE;
 
-    let fl_url: FlUrl = connection.into();
+    execute_request(connection, true, || async move {
+        let fl_url: FlUrl = connection.into();
+
+        let fl_url = fl_url
+            .append_path_segment(container_name)
+            .append_path_segment(blob_name)
+            .with_header("x-ms-blob-content-length", new_size.to_string())
+            .with_header("x-ms-blob-type", "PageBlob");
+
+        let fl_url = with_metadata_headers(fl_url, metadata)
+            .add_azure_headers(SignVerb::PUT, connection, None, None, AZURE_REST_VERSION)
+            .await?;
 
-    fl_url
-        .append_path_segment(container_name)
-        .append_path_segment(blob_name)
-        .with_header("x-ms-blob-content-length", new_size.to_string())
-        .with_header("x-ms-blob-type", "PageBlob")
-        .add_azure_headers(SignVerb::PUT, &connection, None, None, AZURE_REST_VERSION)
-        .put(FlUrlBody::Empty)
-        .await?
-        .to_azure_response_handler()
-        .check_if_there_is_an_error()?;
+        Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url))
+    })
+    .await?
+    .check_if_there_is_an_error()?;
 
     return Ok(());
 }
@@ -168,26 +198,33 @@ mod tests {
 
         let connection = AzureStorageConnectionData::from_conn_string(conn_string.as_str());
 
-        crate::sdk_azure::containers::create_if_not_exists(&connection, "testtest")
+        crate::sdk_azure::containers::create_if_not_exists(connection, "testtest")
             .await
             .unwrap();
 
-        super::create_page_blob(&connection, "testtest", "test", 1)
+        super::create_page_blob(connection, "testtest", "test", 1, &HashMap::new())
             .await
             .unwrap();
 
-        super::resize_page_blob(&connection, "testtest", "test", 4)
+        super::resize_page_blob(connection, "testtest", "test", 4)
             .await
             .unwrap();
 
         let my_vec: Vec<u8> = vec![33; 512];
 
-        super::save_pages(&connection, "testtest", "test", 1, my_vec)
-            .await
-            .unwrap();
+        super::save_pages(
+            connection,
+            "testtest",
+            "test",
+            1,
+            my_vec,
+            &BlobAccessConditions::new(),
+        )
+        .await
+        .unwrap();
 
         let blob_props =
-            crate::sdk_azure::blobs::get_blob_properties(&connection, "testtest", "test")
+            crate::sdk_azure::blobs::get_blob_properties(connection, "testtest", "test")
                 .await
                 .unwrap();
 
@@ -0,0 +1,327 @@
+use std::{future::Future, time::Duration};
+
+use crate::{AzureStorageConnectionData, AzureStorageError};
+
+use super::{
+    azure_response_handler::{AzureResponseHandler, ToAzureResponseHandler},
+    http_transport::{AzureHttpRequest, AzureHttpResponse, AzureHttpTransport},
+};
+
+pub const RETRYABLE_STATUS_CODES: [u16; 6] = [408, 429, 500, 502, 503, 504];
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Attempts including the first one. 1 means no retries
+    pub max_attempts: usize,
+    pub initial_delay: Duration,
+    /// Upper bound of every delay, the Retry-After of the service included
+    pub max_delay: Duration,
+    /// Operations which may be applied twice (POST entity insert, append block)
+    /// are retried only if this is set
+    pub retry_non_idempotent: bool,
+}
+
+impl RetryPolicy {
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Exponential backoff with jitter: a random delay between half and the full
+    /// backoff value, so concurrent clients do not retry in lockstep
+    pub fn get_delay(&self, attempt_no: usize) -> Duration {
+        let exponent = attempt_no.saturating_sub(1).min(16) as u32;
+
+        let backoff = self
+            .initial_delay
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_delay);
+
+        let half = backoff / 2;
+
+        let random = (uuid::Uuid::new_v4().as_u128() % 1_000) as u32;
+
+        half + half * random / 1_000
+    }
+
+    fn can_retry(&self, attempt_no: usize, idempotent: bool) -> bool {
+        if attempt_no >= self.max_attempts {
+            return false;
+        }
+
+        idempotent || self.retry_non_idempotent
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+/// Errors which may disappear if the request is sent again
+pub trait TransientError {
+    fn is_transient(&self) -> bool;
+}
+
+impl TransientError for AzureStorageError {
+    fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            AzureStorageError::FlUrlError(_) | AzureStorageError::Timeout
+        )
+    }
+}
+
+#[cfg(feature = "table-storage")]
+impl TransientError for crate::table_storage::TableStorageError {
+    fn is_transient(&self) -> bool {
+        matches!(self, crate::table_storage::TableStorageError::FlUrlError(_))
+    }
+}
+
+pub fn is_retryable_status_code(status_code: u16) -> bool {
+    RETRYABLE_STATUS_CODES.contains(&status_code)
+}
+
+/// Retry-After in seconds. The http-date form is not used by Azure Storage and is ignored
+pub fn read_retry_after(response: &AzureHttpResponse) -> Option<Duration> {
+    let seconds = response.get_header("retry-after")?.trim().parse::<u64>().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Sends the request built by `build_request` until it succeeds, fails with a non-transient error
+/// or the policy gives up. The request is rebuilt for every attempt, so x-ms-date,
+/// signature and bearer token are always fresh.
+pub async fn execute_with_retry<TError, TFuture>(
+    transport: &dyn AzureHttpTransport,
+    retry_policy: &RetryPolicy,
+    idempotent: bool,
+    build_request: impl Fn() -> TFuture,
+) -> Result<AzureHttpResponse, TError>
+where
+    TError: TransientError + From<AzureStorageError>,
+    TFuture: Future<Output = Result<AzureHttpRequest, TError>>,
+{
+    let mut attempt_no = 0;
+
+    loop {
+        attempt_no += 1;
+
+        let request = build_request().await?;
+
+        let delay = match transport.send(request).await {
+            Ok(response) => {
+                if !is_retryable_status_code(response.status_code)
+                    || !retry_policy.can_retry(attempt_no, idempotent)
+                {
+                    return Ok(response);
+                }
+
+                match read_retry_after(&response) {
+                    Some(retry_after) => retry_after.min(retry_policy.max_delay),
+                    None => retry_policy.get_delay(attempt_no),
+                }
+            }
+            Err(err) => {
+                let err = TError::from(err);
+
+                if !err.is_transient() || !retry_policy.can_retry(attempt_no, idempotent) {
+                    return Err(err);
+                }
+
+                retry_policy.get_delay(attempt_no)
+            }
+        };
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Blob REST request sent through the transport of the connection with its retry policy
+pub async fn execute_request<TFuture>(
+    connection: &AzureStorageConnectionData,
+    idempotent: bool,
+    build_request: impl Fn() -> TFuture,
+) -> Result<AzureResponseHandler, AzureStorageError>
+where
+    TFuture: Future<Output = Result<AzureHttpRequest, AzureStorageError>>,
+{
+    let response = execute_with_retry(
+        connection.transport.as_ref(),
+        &connection.retry_policy,
+        idempotent,
+        build_request,
+    )
+    .await?;
+
+    Ok(response.to_azure_response_handler())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sdk_azure::{http_transport::ScriptedTransport, sign_utils::SignVerb},
+        test_http_server::{TestHttpResponse, TestHttpServer},
+    };
+
+    #[test]
+    fn test_delay_is_exponential_with_jitter() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1_000),
+            retry_non_idempotent: false,
+        };
+
+        for (attempt_no, max_delay_ms) in [(1, 100), (2, 200), (3, 400), (4, 800), (5, 1000)] {
+            let delay = policy.get_delay(attempt_no).as_millis() as u64;
+
+            assert!(delay >= max_delay_ms / 2, "attempt {}: {}", attempt_no, delay);
+            assert!(delay <= max_delay_ms, "attempt {}: {}", attempt_no, delay);
+        }
+    }
+
+    #[test]
+    fn test_non_idempotent_requests_are_not_retried_by_default() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.can_retry(1, true));
+        assert!(!policy.can_retry(1, false));
+        assert!(!policy.can_retry(3, true));
+
+        let policy = RetryPolicy {
+            retry_non_idempotent: true,
+            ..Default::default()
+        };
+
+        assert!(policy.can_retry(1, false));
+
+        assert!(!RetryPolicy::no_retry().can_retry(1, true));
+    }
+
+    #[test]
+    fn test_retryable_status_codes() {
+        assert!(is_retryable_status_code(503));
+        assert!(is_retryable_status_code(500));
+        assert!(!is_retryable_status_code(404));
+        assert!(!is_retryable_status_code(412));
+    }
+
+    fn get_fast_retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            retry_non_idempotent: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_busy_is_retried_honouring_retry_after() {
+        let server = TestHttpServer::start(vec![
+            TestHttpResponse::new(503)
+                .with_header("x-ms-error-code", "ServerBusy")
+                .with_header("Retry-After", "0"),
+            TestHttpResponse::new(200).with_body(b"hello".to_vec()),
+        ])
+        .await;
+
+        let connection = AzureStorageConnectionData::from_conn_string(
+            format!("AccountName=test;AccountKey=YWJj;BlobEndpoint={}/test", server.url).as_str(),
+        )
+        .with_retry_policy(get_fast_retry_policy());
+
+        let result = crate::sdk_azure::blobs::download(&connection, "container", "blob")
+            .await
+            .unwrap();
+
+        assert_eq!(b"hello".to_vec(), result);
+        assert_eq!(2, server.get_requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_is_capped_by_max_delay() {
+        let transport = ScriptedTransport::new();
+        transport.push_response(AzureHttpResponse::new(503).with_header("Retry-After", "3600"));
+        transport.push_response(AzureHttpResponse::new(200));
+
+        let response: Result<AzureHttpResponse, AzureStorageError> = tokio::time::timeout(
+            Duration::from_secs(1),
+            execute_with_retry(&transport, &get_fast_retry_policy(), true, || async {
+                Ok(get_request())
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(200, response.unwrap().status_code);
+        assert_eq!(2, transport.get_requests().len());
+    }
+
+    fn get_request() -> AzureHttpRequest {
+        AzureHttpRequest::new(
+            SignVerb::GET,
+            flurl::FlUrl::new("http://127.0.0.1:10000/devstoreaccount1"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_request_is_sent_once() {
+        let transport = ScriptedTransport::new();
+        transport.push_response(AzureHttpResponse::new(503));
+        transport.push_response(AzureHttpResponse::new(201));
+
+        let response: Result<AzureHttpResponse, AzureStorageError> =
+            execute_with_retry(&transport, &get_fast_retry_policy(), false, || async {
+                Ok(get_request())
+            })
+            .await;
+
+        assert_eq!(503, response.unwrap().status_code);
+        assert_eq!(1, transport.get_requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_transport_errors_are_retried_up_to_max_attempts() {
+        let transport = ScriptedTransport::new();
+
+        for _ in 0..3 {
+            transport.push_error(AzureStorageError::Timeout);
+        }
+
+        let response: Result<AzureHttpResponse, AzureStorageError> =
+            execute_with_retry(&transport, &get_fast_retry_policy(), true, || async {
+                Ok(get_request())
+            })
+            .await;
+
+        assert!(matches!(response, Err(AzureStorageError::Timeout)));
+        assert_eq!(3, transport.get_requests().len());
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let transport = ScriptedTransport::new();
+        transport.push_response(
+            AzureHttpResponse::new(404).with_header("x-ms-error-code", "BlobNotFound"),
+        );
+
+        let response: Result<AzureHttpResponse, AzureStorageError> =
+            execute_with_retry(&transport, &get_fast_retry_policy(), true, || async {
+                Ok(get_request())
+            })
+            .await;
+
+        assert_eq!(404, response.unwrap().status_code);
+        assert_eq!(1, transport.get_requests().len());
+    }
+}
@@ -8,6 +8,7 @@ use flurl::FlUrl;
 
 type HmacSha256 = Hmac<Sha256>;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SignVerb {
     GET,
     POST,
@@ -42,16 +43,20 @@ pub fn get_auth_header(
     let canonicalized_headers = get_canonicalized_headers(flurl);
     let canonicalized_resource = get_canonicalized_resource(flurl, storage_account_name);
 
-    let content_md5 = "";
+    let content_md5 = get_header_value(flurl, "Content-MD5");
 
-    let if_match = "";
+    let if_modified_since = get_header_value(flurl, "If-Modified-Since");
+    let if_match = get_header_value(flurl, "If-Match");
+    let if_none_match = get_header_value(flurl, "If-None-Match");
 
     let result = format!(
-        "{}\n\n\n{}\n{}\n\n\n\n{}\n\n\n\n{}{}",
+        "{}\n\n\n{}\n{}\n\n\n{}\n{}\n{}\n\n\n{}{}",
         verb.as_str(),
         content_len,
         content_md5,
+        if_modified_since,
         if_match,
+        if_none_match,
         canonicalized_headers,
         canonicalized_resource
     );
@@ -59,6 +64,25 @@ pub fn get_auth_header(
     result
 }
 
+// Since 2015-02-21 a zero Content-Length is signed as an empty line
+pub fn get_content_len_to_sign(content_len: Option<usize>) -> String {
+    match content_len {
+        Some(len) if len > 0 => len.to_string(),
+        _ => String::new(),
+    }
+}
+
+// Standard headers are signed by value at their fixed position of the string to sign
+fn get_header_value<'s>(flurl: &'s FlUrl, header_name: &str) -> &'s str {
+    for (key, value) in flurl.headers.iter() {
+        if key.eq_ignore_ascii_case(header_name) {
+            return value;
+        }
+    }
+
+    ""
+}
+
 pub fn get_canonicalized_resource(flurl: &FlUrl, account_name: &str) -> String {
     let mut sb = StringBuilder::new();
 
@@ -167,4 +191,42 @@ mod tests {
 
         )
     }
+
+    #[test]
+    pub fn test_zero_length_put_signs_empty_content_length() {
+        let flurl = FlUrl::new("https://127.0.0.1")
+            .with_header("x-ms-date", "Fri, 17 Nov 2017 01:07:37 GMT")
+            .with_header("Content-Length", "0")
+            .append_query_param("comp", Some("seal"));
+
+        for content_len in [None, Some(0)] {
+            let content_len = get_content_len_to_sign(content_len);
+            let header_to_sign =
+                get_auth_header("contosorest", content_len.as_str(), SignVerb::PUT, &flurl);
+
+            assert_eq!(
+                "PUT\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:Fri, 17 Nov 2017 01:07:37 GMT\n/contosorest/\ncomp:seal",
+                header_to_sign
+            );
+        }
+
+        assert_eq!("5", get_content_len_to_sign(Some(5)));
+    }
+
+    #[test]
+    pub fn test_conditional_headers_are_signed() {
+        let flurl = FlUrl::new("https://127.0.0.1")
+            .with_header("x-ms-date", "Fri, 17 Nov 2017 01:07:37 GMT")
+            .with_header("If-Match", "\"0x8D5\"")
+            .with_header("If-None-Match", "*")
+            .with_header("If-Modified-Since", "Thu, 16 Nov 2017 01:07:37 GMT")
+            .with_header("Content-MD5", "kAFQmDzST7DWlj99KOF/cg==");
+
+        let header_to_sign = get_auth_header("contosorest", "5", SignVerb::PUT, &flurl);
+
+        assert_eq!(
+            "PUT\n\n\n5\nkAFQmDzST7DWlj99KOF/cg==\n\n\nThu, 16 Nov 2017 01:07:37 GMT\n\"0x8D5\"\n*\n\n\nx-ms-date:Fri, 17 Nov 2017 01:07:37 GMT\n/contosorest/",
+            header_to_sign
+        )
+    }
 }
@@ -1,9 +1,15 @@
+use std::future::Future;
+
 use flurl::FlUrl;
 use my_json::json_reader::JsonFirstLineReader;
 
 use crate::{
     sdk_azure::{
-        flurl_ext::FlUrlAzureExtensions, table_storage::query_builder::TableStorageQueryBuilder,
+        flurl_ext::FlUrlAzureExtensions,
+        http_transport::{AzureHttpRequest, AzureHttpResponse},
+        retry_policy::execute_with_retry,
+        sign_utils::SignVerb,
+        table_storage::query_builder::TableStorageQueryBuilder,
     },
     table_storage::{TableStorageEntity, TableStorageError},
 };
@@ -14,43 +20,48 @@ impl crate::AzureStorageConnectionData {
     pub async fn create_table(&self, table_name: &str) -> Result<(), TableStorageError> {
         let body = format!(r#"{{"TableName":"{}"}}"#, table_name);
 
-        let response = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
-            .append_path_segment("Tables")
-            .add_table_storage_azure_headers(self, Some(body.len()))
-            .post(Some(body.into_bytes()))
+        let response = self
+            .execute_table_request(false, || async {
+                let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
+                    .append_path_segment("Tables")
+                    .add_table_storage_azure_headers(self, Some(body.len()))
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::POST, fl_url)
+                    .with_body(body.clone().into_bytes()))
+            })
             .await?;
 
-        let status_code = response.get_status_code();
+        let status_code = response.status_code;
 
         if status_code == 204 || status_code == 201 {
             return Ok(());
         } else {
-            let payload = response.receive_body().await?;
-            return Err(super::models::read_error(payload));
+            let payload = response.body;
+            return Err(super::models::read_error(status_code, payload));
         }
     }
 
     pub async fn get_list_of_tables(&self) -> Result<Option<TableNamesChunk>, TableStorageError> {
-        let response = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
-            .append_path_segment("Tables")
-            .add_table_storage_azure_headers(self, None)
-            .get()
+        let response = self
+            .execute_table_request(true, || async {
+                let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
+                    .append_path_segment("Tables")
+                    .add_table_storage_azure_headers(self, None)
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            })
             .await?;
 
-        let status_code = response.get_status_code();
+        let status_code = response.status_code;
 
         if status_code == 200 {
-            let continuation_token = if let Some(token) = response
-                .get_headers()
-                .get("x-ms-continuation-nexttablename")
-            {
-                Some(token.to_string())
-            } else {
-                None
-            };
+            let continuation_token = response
+                .get_header("x-ms-continuation-nexttablename")
+                .map(|token| token.to_string());
 
-            let payload = response.receive_body().await?;
-            let payload = super::models::read_value_payload(payload.as_slice())?;
+            let payload = super::models::read_value_payload(response.body.as_slice())?;
 
             let result = super::models::read_table_names(payload);
 
@@ -64,8 +75,8 @@ impl crate::AzureStorageConnectionData {
                 return Ok(None);
             }
         } else {
-            let payload = response.receive_body().await?;
-            return Err(super::models::read_error(payload));
+            let payload = response.body;
+            return Err(super::models::read_error(status_code, payload));
         }
     }
 
@@ -85,16 +96,21 @@ impl crate::AzureStorageConnectionData {
 
         let raw_ending = query_builder.get_result();
 
-        let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
-            .append_path_segment(table_name.as_str())
-            .append_raw_ending(raw_ending.as_str())
-            .add_table_storage_azure_headers(self, None);
+        let result = self
+            .execute_table_request(true, || async {
+                let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
+                    .append_path_segment(table_name.as_str())
+                    .append_raw_ending(raw_ending.as_str())
+                    .add_table_storage_azure_headers(self, None)
+                    .await?;
 
-        let mut result = fl_url.get().await?;
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            })
+            .await?;
 
-        let status_code = result.get_status_code();
+        let status_code = result.status_code;
 
-        let body = result.receive_body().await?;
+        let body = result.body;
 
         if status_code == 200 {
             return Ok(Some(TResult::create(JsonFirstLineReader::new(
@@ -102,7 +118,7 @@ impl crate::AzureStorageConnectionData {
             ))));
         }
 
-        let err = super::models::read_error(body);
+        let err = super::models::read_error(status_code, body);
         match err {
             TableStorageError::ResourceNotFound => {
                 return Ok(None);
@@ -124,22 +140,27 @@ impl crate::AzureStorageConnectionData {
 
         let raw_ending = query_builder.get_result();
 
-        let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
-            .append_path_segment(table_name_for_request.as_str())
-            .append_raw_ending(raw_ending.as_str())
-            .add_table_storage_azure_headers(self, None);
+        let response = self
+            .execute_table_request(true, || async {
+                let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
+                    .append_path_segment(table_name_for_request.as_str())
+                    .append_raw_ending(raw_ending.as_str())
+                    .add_table_storage_azure_headers(self, None)
+                    .await?;
 
-        let response = fl_url.get().await?;
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            })
+            .await?;
 
-        let status_code = response.get_status_code();
+        let status_code = response.status_code;
 
         if status_code == 200 {
             return super::models::read_entities(self, table_name, response).await;
         }
 
-        let body = response.receive_body().await?;
+        let body = response.body;
 
-        let err = super::models::read_error(body);
+        let err = super::models::read_error(status_code, body);
 
         Err(err)
     }
@@ -158,22 +179,27 @@ impl crate::AzureStorageConnectionData {
 
         let raw_ending = query_builder.get_result();
 
-        let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
-            .append_path_segment(table_name_for_request.as_str())
-            .append_raw_ending(raw_ending.as_str())
-            .add_table_storage_azure_headers(self, None);
+        let response = self
+            .execute_table_request(true, || async {
+                let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
+                    .append_path_segment(table_name_for_request.as_str())
+                    .append_raw_ending(raw_ending.as_str())
+                    .add_table_storage_azure_headers(self, None)
+                    .await?;
 
-        let response = fl_url.get().await?;
+                Ok(AzureHttpRequest::new(SignVerb::GET, fl_url))
+            })
+            .await?;
 
-        let status_code = response.get_status_code();
+        let status_code = response.status_code;
 
         if status_code == 200 {
             return super::models::read_entities(self, table_name, response).await;
         }
 
-        let body = response.receive_body().await?;
+        let body = response.body;
 
-        let err = super::models::read_error(body);
+        let err = super::models::read_error(status_code, body);
 
         Err(err)
     }
@@ -192,21 +218,26 @@ impl crate::AzureStorageConnectionData {
 
         let body = entity.serialize();
 
-        let response = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
-            .append_path_segment(table_name_for_request.as_str())
-            .add_table_storage_azure_headers(self, Some(body.len()))
-            .put(Some(body))
+        let response = self
+            .execute_table_request(true, || async {
+                let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
+                    .append_path_segment(table_name_for_request.as_str())
+                    .add_table_storage_azure_headers(self, Some(body.len()))
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::PUT, fl_url).with_body(body.clone()))
+            })
             .await?;
 
-        let status_code = response.get_status_code();
+        let status_code = response.status_code;
 
         if status_code == 204 {
             return Ok(());
         }
 
-        let body = response.receive_body().await?;
+        let body = response.body;
 
-        let err = super::models::read_error(body);
+        let err = super::models::read_error(status_code, body);
         Err(err)
     }
 
@@ -219,22 +250,27 @@ impl crate::AzureStorageConnectionData {
 
         let body = entity.serialize();
 
-        let response = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
-            .append_path_segment(table_name_for_request.as_str())
-            .with_header("Content-Type", "application/json")
-            .add_table_storage_azure_headers(self, Some(body.len()))
-            .post(Some(body))
+        let response = self
+            .execute_table_request(false, || async {
+                let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
+                    .append_path_segment(table_name_for_request.as_str())
+                    .with_header("Content-Type", "application/json")
+                    .add_table_storage_azure_headers(self, Some(body.len()))
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::POST, fl_url).with_body(body.clone()))
+            })
             .await?;
 
-        let status_code = response.get_status_code();
+        let status_code = response.status_code;
 
         if status_code == 200 && status_code < 210 {
             return Ok(());
         }
 
-        let body = response.receive_body().await?;
+        let body = response.body;
 
-        let err = super::models::read_error(body);
+        let err = super::models::read_error(status_code, body);
         Err(err)
     }
 
@@ -249,14 +285,19 @@ impl crate::AzureStorageConnectionData {
             table_name, partition_key, row_key
         );
 
-        let response = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
-            .append_path_segment(table_name_for_request.as_str())
-            .with_header("If-Match", "*")
-            .add_table_storage_azure_headers(self, None)
-            .delete()
+        let response = self
+            .execute_table_request(true, || async {
+                let fl_url = flurl::FlUrl::new(&self.table_storage_api_url.as_str(), None)
+                    .append_path_segment(table_name_for_request.as_str())
+                    .with_header("If-Match", "*")
+                    .add_table_storage_azure_headers(self, None)
+                    .await?;
+
+                Ok(AzureHttpRequest::new(SignVerb::DELETE, fl_url))
+            })
             .await?;
 
-        let status_code = response.get_status_code();
+        let status_code = response.status_code;
 
         println!("Status code: {}", status_code);
 
@@ -264,16 +305,37 @@ impl crate::AzureStorageConnectionData {
             return Ok(true);
         }
 
-        let body = response.receive_body().await?;
+        let body = response.body;
 
-        let err = super::models::read_error(body);
+        let err = super::models::read_error(status_code, body);
         match err {
             TableStorageError::ResourceNotFound => Ok(false),
             _ => Err(err),
         }
     }
 
-    pub fn get_table_storage_auth_header(&self, date: &str, flurl: &FlUrl) -> String {
+    /// Table request sent with the retry policy of the connection.
+    /// POST requests (table creation, entity insert) are not idempotent
+    async fn execute_table_request<TFuture>(
+        &self,
+        idempotent: bool,
+        request: impl Fn() -> TFuture,
+    ) -> Result<AzureHttpResponse, TableStorageError>
+    where
+        TFuture: Future<Output = Result<AzureHttpRequest, TableStorageError>>,
+    {
+        execute_with_retry(
+            self.transport.as_ref(),
+            &self.retry_policy,
+            idempotent,
+            request,
+        )
+        .await
+    }
+
+    pub fn get_table_storage_auth_header(&self, date: &str, flurl: &FlUrl) -> Option<String> {
+        let account_key = self.credentials.get_account_key()?;
+
         let canonicalized_resourse = crate::sdk_azure::sign_utils::get_canonicalized_resourse(
             flurl,
             self.account_name.as_str(),
@@ -281,10 +343,9 @@ impl crate::AzureStorageConnectionData {
 
         let string_to_sign = format!("{}\n{}", date, canonicalized_resourse);
 
-        let signature = crate::sdk_azure::sign_utils::sign_transaction(
-            string_to_sign.as_str(),
-            &self.account_key,
-        );
-        format!("SharedKeyLite {}:{}", &self.account_name, signature)
+        let signature =
+            crate::sdk_azure::sign_utils::sign_transaction(string_to_sign.as_str(), account_key);
+
+        Some(format!("SharedKeyLite {}:{}", &self.account_name, signature))
     }
 }
@@ -1,4 +1,4 @@
-use flurl::FlUrlResponse;
+use crate::sdk_azure::http_transport::AzureHttpResponse;
 
 pub struct EntitiesContinuationToken {
     pub next_partition_key: Option<String>,
@@ -6,17 +6,15 @@ pub struct EntitiesContinuationToken {
 }
 
 impl EntitiesContinuationToken {
-    pub fn new(response: &FlUrlResponse) -> Option<Self> {
-        let headers = response.get_headers();
-
+    pub fn new(response: &AzureHttpResponse) -> Option<Self> {
         let next_partition_key =
-            if let Some(header) = headers.get("x-ms-continuation-nextpartitionkey") {
+            if let Some(header) = response.get_header("x-ms-continuation-nextpartitionkey") {
                 Some(header.to_string())
             } else {
                 None
             };
 
-        let next_row_key = if let Some(header) = headers.get("x-ms-continuation-nextrowkey") {
+        let next_row_key = if let Some(header) = response.get_header("x-ms-continuation-nextrowkey") {
             Some(header.to_string())
         } else {
             None
@@ -1,8 +1,10 @@
-use flurl::FlUrlResponse;
 use my_json::json_reader::array_parser::JsonArrayIterator;
 
 use crate::{
-    sdk_azure::table_storage::{EntitiesContinuationToken, TableEntitiesChunk},
+    sdk_azure::{
+        http_transport::AzureHttpResponse,
+        table_storage::{EntitiesContinuationToken, TableEntitiesChunk},
+    },
     table_storage::{TableStorageEntity, TableStorageError},
     AzureStorageConnectionData,
 };
@@ -10,11 +12,10 @@ use crate::{
 pub async fn read_entities<'s, TResult: TableStorageEntity>(
     connection_data: &'s AzureStorageConnectionData,
     table_name: &'s str,
-    response: FlUrlResponse,
+    response: AzureHttpResponse,
 ) -> Result<Option<TableEntitiesChunk<'s, TResult>>, TableStorageError> {
     let continuation_token = EntitiesContinuationToken::new(&response);
-    let body = response.receive_body().await?;
-    let payload_with_value = super::read_value_payload(&body)?;
+    let payload_with_value = super::read_value_payload(&response.body)?;
     match read_entities_items(payload_with_value) {
         Some(entities) => {
             return Ok(Some(TableEntitiesChunk::new(
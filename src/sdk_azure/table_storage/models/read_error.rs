@@ -2,21 +2,66 @@ use my_json::json_reader::JsonFirstLineReader;
 
 use crate::table_storage::TableStorageError;
 
-pub fn read_error(body: Vec<u8>) -> TableStorageError {
+pub fn read_error(status_code: u16, body: Vec<u8>) -> TableStorageError {
+    if body.is_empty() {
+        if status_code == 404 {
+            return TableStorageError::ResourceNotFound;
+        }
+
+        return TableStorageError::ServiceError {
+            code: format!("HttpStatus{}", status_code),
+            status_code,
+            message: None,
+        };
+    }
+
     for first_line in JsonFirstLineReader::new(body.as_slice()) {
-        let first_line = first_line.unwrap();
-
-        match first_line.get_name().unwrap() {
-            "odata.error" => {
-                return super::read_error_type(
-                    body.as_slice(),
-                    first_line.get_value().unwrap().as_bytes().unwrap(),
-                );
-            }
-            _ => {
-                return TableStorageError::Unknown(String::from_utf8(body.to_vec()).unwrap());
+        let first_line = match first_line {
+            Ok(first_line) => first_line,
+            Err(_) => break,
+        };
+
+        if let Ok("odata.error") = first_line.get_name() {
+            if let Ok(value) = first_line.get_value() {
+                if let Some(value) = value.as_bytes() {
+                    return super::read_error_type(status_code, body.as_slice(), value);
+                }
             }
         }
+
+        break;
+    }
+
+    TableStorageError::Unknown(String::from_utf8_lossy(body.as_slice()).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_odata_error_is_parsed() {
+        let body = r#"{"odata.error":{"code":"TableNotFound","message":{"lang":"en-US","value":"The table specified does not exist."}}}"#;
+        let err = read_error(404, body.as_bytes().to_vec());
+        assert!(matches!(err, TableStorageError::TableNotFound));
+
+        let body = r#"{"odata.error":{"code":"UpdateConditionNotSatisfied","message":{"lang":"en-US","value":"The update condition specified in the request was not satisfied."}}}"#;
+        let err = read_error(412, body.as_bytes().to_vec());
+
+        if let TableStorageError::ServiceError {
+            code,
+            status_code,
+            message,
+        } = err
+        {
+            assert_eq!("UpdateConditionNotSatisfied", code);
+            assert_eq!(412, status_code);
+            assert_eq!(
+                "The update condition specified in the request was not satisfied.",
+                message.unwrap()
+            );
+        } else {
+            panic!("ServiceError is expected");
+        }
     }
-    return TableStorageError::Unknown(String::from_utf8(body).unwrap());
 }
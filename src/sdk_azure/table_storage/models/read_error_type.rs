@@ -2,32 +2,64 @@ use my_json::json_reader::JsonFirstLineReader;
 
 use crate::table_storage::TableStorageError;
 
-pub fn read_error_type(whole_payload: &[u8], value_payload: &[u8]) -> TableStorageError {
+/// Reads {"code":"..","message":{"lang":"..","value":".."}} of an odata.error
+pub fn read_error_type(
+    status_code: u16,
+    whole_payload: &[u8],
+    value_payload: &[u8],
+) -> TableStorageError {
+    let mut code = None;
+    let mut message = None;
+
     for first_line in JsonFirstLineReader::new(value_payload) {
-        let first_line = first_line.unwrap();
+        let first_line = match first_line {
+            Ok(first_line) => first_line,
+            Err(_) => break,
+        };
 
-        if first_line.get_name().unwrap() == "code" {
-            match first_line.get_value().unwrap().as_str().unwrap() {
-                "TableNotFound" => {
-                    return TableStorageError::TableNotFound;
-                }
-                "EntityAlreadyExists" => {
-                    return TableStorageError::EntityAlreadyExists;
-                }
-                "ResourceNotFound" => {
-                    return TableStorageError::ResourceNotFound;
-                }
-                "TableAlreadyExists" => {
-                    return TableStorageError::TableAlreadyExists;
+        let value = match first_line.get_value() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        match first_line.get_name() {
+            Ok("code") => {
+                if let Some(value) = value.as_str() {
+                    code = Some(value.to_string());
                 }
-                _ => {
-                    return TableStorageError::Unknown(
-                        String::from_utf8(whole_payload.to_vec()).unwrap(),
-                    );
+            }
+            Ok("message") => {
+                if let Some(value) = value.as_bytes() {
+                    message = read_message_value(value);
                 }
             }
+            _ => {}
+        }
+    }
+
+    match code.as_deref() {
+        Some("TableNotFound") => TableStorageError::TableNotFound,
+        Some("EntityAlreadyExists") => TableStorageError::EntityAlreadyExists,
+        Some("ResourceNotFound") => TableStorageError::ResourceNotFound,
+        Some("TableAlreadyExists") => TableStorageError::TableAlreadyExists,
+        Some(code) => TableStorageError::ServiceError {
+            code: code.to_string(),
+            status_code,
+            message,
+        },
+        None => TableStorageError::Unknown(String::from_utf8_lossy(whole_payload).to_string()),
+    }
+}
+
+fn read_message_value(message_payload: &[u8]) -> Option<String> {
+    for first_line in JsonFirstLineReader::new(message_payload) {
+        let first_line = first_line.ok()?;
+
+        if let Ok("value") = first_line.get_name() {
+            let value = first_line.get_value().ok()?;
+            return Some(value.as_str()?.to_string());
         }
     }
 
-    return TableStorageError::Unknown(String::from_utf8(whole_payload.to_vec()).unwrap());
+    None
 }
@@ -1,5 +1,7 @@
 use crate::{
-    sdk_azure::flurl_ext::FlUrlAzureExtensions,
+    sdk_azure::{
+        flurl_ext::FlUrlAzureExtensions, http_transport::AzureHttpRequest, sign_utils::SignVerb,
+    },
     table_storage::{TableStorageEntity, TableStorageError},
     AzureStorageConnectionData,
 };
@@ -67,15 +69,18 @@ impl<'s, TResult: TableStorageEntity> TableEntitiesChunk<'s, TResult> {
                 fl_url = fl_url.append_query_param("NextRowKey", Some(next_row_key.as_str()));
             }
 
-            let mut fl_url = fl_url.add_table_storage_azure_headers(connection_data, None);
+            let fl_url = fl_url
+                .add_table_storage_azure_headers(connection_data, None)
+                .await?;
 
-            let mut response = fl_url.get().await?;
+            let response = connection_data
+                .transport
+                .send(AzureHttpRequest::new(SignVerb::GET, fl_url))
+                .await?;
 
             self.continuation_token = EntitiesContinuationToken::new(&response);
 
-            let body = response.receive_body().await?;
-
-            let payload = super::models::read_value_payload(&body)?;
+            let payload = super::models::read_value_payload(&response.body)?;
 
             Ok(super::models::read_entities_items(payload))
         } else {
@@ -1,5 +1,8 @@
 use crate::{
-    sdk_azure::flurl_ext::FlUrlAzureExtensions, table_storage::TableStorageError,
+    sdk_azure::{
+        flurl_ext::FlUrlAzureExtensions, http_transport::AzureHttpRequest, sign_utils::SignVerb,
+    },
+    table_storage::TableStorageError,
     AzureStorageConnectionData,
 };
 
@@ -46,22 +49,19 @@ impl<'s> TableNamesChunk<'s> {
             let fl_url = flurl::FlUrl::new(&connection_data.table_storage_api_url, None)
                 .append_path_segment("Tables")
                 .append_query_param("NextTableName", Some(continuation_token.as_str()))
-                .add_table_storage_azure_headers(connection_data, None);
+                .add_table_storage_azure_headers(connection_data, None)
+                .await?;
 
-            let mut response = fl_url.get().await?;
+            let response = connection_data
+                .transport
+                .send(AzureHttpRequest::new(SignVerb::GET, fl_url))
+                .await?;
 
-            self.continuation_token = if let Some(token) = response
-                .get_headers()
-                .get("x-ms-continuation-nexttablename")
-            {
-                Some(token.to_string())
-            } else {
-                None
-            };
+            self.continuation_token = response
+                .get_header("x-ms-continuation-nexttablename")
+                .map(|token| token.to_string());
 
-            let body = response.receive_body().await?;
-
-            let payload = super::models::read_value_payload(body.as_slice())?;
+            let payload = super::models::read_value_payload(response.body.as_slice())?;
 
             match super::models::read_table_names(payload) {
                 Some(result) => {
@@ -0,0 +1,137 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    append_blob::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions},
+    blob::{BlobAccessConditions, BlobProperties, BlobType},
+    AzureStorageError,
+};
+
+use super::{blob_sidecar::BlobSidecar, utils::FileConnectionInfo};
+
+/// The blob file is emptied and gets a sidecar of an append blob. Returns ETag of the blob
+pub async fn create<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    options: CreateAppendBlobOptions,
+) -> Result<String, AzureStorageError> {
+    super::blobs::check_write_conditions(
+        connection_data,
+        container_name,
+        blob_name,
+        &options.conditions,
+    )
+    .await?;
+
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    tokio::fs::File::create(file_name.as_str()).await?;
+    super::utils::touch_file(file_name.as_str()).await?;
+
+    BlobSidecar::new(BlobType::AppendBlob)
+        .with_http_headers(options.http_headers)
+        .with_metadata(options.metadata)
+        .write(connection_data, container_name, blob_name)
+        .await?;
+
+    // Uncommitted blocks of a block blob of the same name go, as they do with any Put Blob
+    super::blob_blocks::delete_all(connection_data, container_name, blob_name).await?;
+
+    let properties =
+        super::utils::get_blob_properties(connection_data, container_name, blob_name).await?;
+
+    Ok(properties.etag.unwrap_or_default())
+}
+
+/// The block is appended to the blob file, the sidecar counts the blocks and keeps the sealed flag
+pub async fn append_block<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    content: Vec<u8>,
+    conditions: &AppendBlockConditions,
+) -> Result<AppendBlockResult, AzureStorageError> {
+    let properties =
+        super::utils::get_blob_properties(connection_data, container_name, blob_name).await?;
+
+    check_append_blob(&properties, &conditions.access)?;
+
+    let mut sidecar = BlobSidecar::read(connection_data, container_name, blob_name).await?;
+
+    if sidecar.sealed {
+        return Err(AzureStorageError::BlobIsSealed(None));
+    }
+
+    let append_offset = properties.blob_size as u64;
+
+    conditions.check(append_offset, sidecar.committed_block_count, content.len())?;
+
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    let mut f = tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(file_name.as_str())
+        .await?;
+    tokio::io::AsyncWriteExt::write_all(&mut f, content.as_slice()).await?;
+    tokio::io::AsyncWriteExt::flush(&mut f).await?;
+    drop(f);
+
+    sidecar.committed_block_count += 1;
+    sidecar
+        .write(connection_data, container_name, blob_name)
+        .await?;
+
+    super::utils::touch_file(file_name.as_str()).await?;
+
+    let properties =
+        super::utils::get_blob_properties(connection_data, container_name, blob_name).await?;
+
+    Ok(AppendBlockResult {
+        etag: properties.etag.unwrap_or_default(),
+        append_offset,
+        committed_block_count: sidecar.committed_block_count,
+    })
+}
+
+/// Returns ETag of the blob
+pub async fn seal<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    conditions: &BlobAccessConditions,
+) -> Result<String, AzureStorageError> {
+    let properties =
+        super::utils::get_blob_properties(connection_data, container_name, blob_name).await?;
+
+    check_append_blob(&properties, conditions)?;
+
+    let mut sidecar = BlobSidecar::read(connection_data, container_name, blob_name).await?;
+    sidecar.sealed = true;
+    sidecar
+        .write(connection_data, container_name, blob_name)
+        .await?;
+
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+    super::utils::touch_file(file_name.as_str()).await?;
+
+    let properties =
+        super::utils::get_blob_properties(connection_data, container_name, blob_name).await?;
+
+    Ok(properties.etag.unwrap_or_default())
+}
+
+fn check_append_blob(
+    properties: &BlobProperties,
+    conditions: &BlobAccessConditions,
+) -> Result<(), AzureStorageError> {
+    if properties.blob_type != BlobType::AppendBlob {
+        return Err(AzureStorageError::InvalidBlobType(None));
+    }
+
+    conditions.check(Some((
+        properties.etag.as_deref().unwrap_or_default(),
+        properties
+            .last_modified
+            .unwrap_or(DateTimeAsMicroseconds::new(0)),
+    )))
+}
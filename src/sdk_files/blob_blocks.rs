@@ -0,0 +1,366 @@
+use std::io::SeekFrom;
+
+use crate::{
+    blob::BlobType,
+    block_blob::{
+        check_block_id, BlockItem, BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions,
+        MAX_COMMITTED_BLOCKS, MAX_UNCOMMITTED_BLOCKS,
+    },
+    AzureStorageError,
+};
+
+use super::{blob_sidecar::BlobSidecar, utils::FileConnectionInfo};
+
+/// Blocks put to a blob and not committed yet are files in {container}/.blob-blocks/{blob_name}/.
+/// Block ids are Base64, so the files are named by the hex of the id
+const BLOCKS_FOLDER: &str = ".blob-blocks";
+
+/// Not a hex name, so it is never taken for a staged block
+const COMMIT_FILE_NAME: &str = ".commit";
+
+/// The blob does not have to exist. Putting a block with the id of a staged one replaces it
+pub async fn put_block<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    block_id: &str,
+    content: Vec<u8>,
+) -> Result<(), AzureStorageError> {
+    super::containers::check_if_container_exists(connection_data, container_name).await?;
+
+    let staged = get_staged_blocks(connection_data, container_name, blob_name).await?;
+
+    check_block_id(block_id, staged.first().map(|itm| itm.block_id.as_str()))?;
+
+    if staged.len() >= MAX_UNCOMMITTED_BLOCKS && !staged.iter().any(|itm| itm.block_id == block_id)
+    {
+        return Err(AzureStorageError::BlockCountExceedsLimit(None));
+    }
+
+    let folder_name = compile_blocks_folder(connection_data, container_name, blob_name);
+    tokio::fs::create_dir_all(folder_name.as_str()).await?;
+
+    let file_name = compile_block_path(connection_data, container_name, blob_name, block_id);
+    tokio::fs::write(file_name.as_str(), content).await?;
+
+    Ok(())
+}
+
+/// Where the content of a block of the block list is read from
+enum BlockSource {
+    Staged(String),
+    /// Offset in the current blob file
+    Committed(u64),
+}
+
+/// Writes the blocks, in the order of the list, as the content of the blob. Returns ETag of the blob.
+/// Committed blocks are read from the current blob file, so the new content is written to a file of
+/// the staging folder and then replaces the blob
+pub async fn commit<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    blocks: Vec<BlockListItem>,
+    options: UploadBlockBlobOptions,
+) -> Result<String, AzureStorageError> {
+    super::containers::check_if_container_exists(connection_data, container_name).await?;
+
+    if blocks.len() > MAX_COMMITTED_BLOCKS {
+        return Err(AzureStorageError::BlockListTooLong(None));
+    }
+
+    let committed = get_committed_blocks(connection_data, container_name, blob_name).await?;
+    let staged = get_staged_blocks(connection_data, container_name, blob_name).await?;
+
+    let mut sources = Vec::with_capacity(blocks.len());
+    let mut block_items = Vec::with_capacity(blocks.len());
+
+    for block in &blocks {
+        let block_id = block.get_block_id();
+
+        let find_staged = || {
+            staged
+                .iter()
+                .find(|itm| itm.block_id == block_id)
+                .map(|itm| (BlockSource::Staged(block_id.to_string()), itm.size))
+        };
+
+        let find_committed = || find_committed_block(committed.as_slice(), block_id);
+
+        let source = match block {
+            BlockListItem::Committed(_) => find_committed(),
+            BlockListItem::Uncommitted(_) => find_staged(),
+            BlockListItem::Latest(_) => find_staged().or_else(find_committed),
+        };
+
+        let (source, size) = source.ok_or(AzureStorageError::InvalidBlockList(None))?;
+
+        sources.push((source, size));
+        block_items.push(BlockItem {
+            block_id: block_id.to_string(),
+            size,
+        });
+    }
+
+    super::blobs::check_write_conditions(
+        connection_data,
+        container_name,
+        blob_name,
+        &options.conditions,
+    )
+    .await?;
+
+    let folder_name = compile_blocks_folder(connection_data, container_name, blob_name);
+    tokio::fs::create_dir_all(folder_name.as_str()).await?;
+
+    let commit_file_name = format!(
+        "{folder_name}{separator}{COMMIT_FILE_NAME}",
+        separator = connection_data.get_separator(),
+    );
+
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    let mut f = tokio::fs::File::create(commit_file_name.as_str()).await?;
+
+    let mut current_blob = None;
+
+    // One block at a time is kept in memory
+    for (source, size) in sources {
+        let content = match source {
+            BlockSource::Staged(block_id) => {
+                let block_file_name = compile_block_path(
+                    connection_data,
+                    container_name,
+                    blob_name,
+                    block_id.as_str(),
+                );
+
+                tokio::fs::read(block_file_name.as_str()).await?
+            }
+            BlockSource::Committed(offset) => {
+                if current_blob.is_none() {
+                    current_blob = Some(tokio::fs::File::open(file_name.as_str()).await?);
+                }
+
+                let current_blob = current_blob.as_mut().unwrap();
+
+                let mut content = vec![0u8; size];
+                tokio::io::AsyncSeekExt::seek(current_blob, SeekFrom::Start(offset)).await?;
+                tokio::io::AsyncReadExt::read_exact(current_blob, content.as_mut_slice()).await?;
+                content
+            }
+        };
+
+        tokio::io::AsyncWriteExt::write_all(&mut f, content.as_slice()).await?;
+    }
+
+    tokio::io::AsyncWriteExt::flush(&mut f).await?;
+    drop(f);
+    drop(current_blob);
+
+    tokio::fs::rename(commit_file_name.as_str(), file_name.as_str()).await?;
+
+    super::blobs::complete_upload(
+        connection_data,
+        container_name,
+        blob_name,
+        options,
+        block_items,
+    )
+    .await
+}
+
+/// BlobNotFound if there is neither the blob nor staged blocks of it
+pub async fn get_block_list<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    list_type: BlockListType,
+) -> Result<BlockList, AzureStorageError> {
+    super::containers::check_if_container_exists(connection_data, container_name).await?;
+
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+    let blob_exists = tokio::fs::metadata(file_name.as_str()).await.is_ok();
+
+    let staged = get_staged_blocks(connection_data, container_name, blob_name).await?;
+
+    if !blob_exists && staged.is_empty() {
+        return Err(AzureStorageError::BlobNotFound(None));
+    }
+
+    let mut result = BlockList::default();
+
+    if list_type.has_committed() {
+        result.committed = get_committed_blocks(connection_data, container_name, blob_name).await?;
+    }
+
+    if list_type.has_uncommitted() {
+        result.uncommitted = staged;
+    }
+
+    Ok(result)
+}
+
+/// Missing blob or a blob without staged blocks is fine
+pub async fn delete_all<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<(), AzureStorageError> {
+    let folder_name = compile_blocks_folder(connection_data, container_name, blob_name);
+
+    match tokio::fs::remove_dir_all(folder_name.as_str()).await {
+        Ok(_) => Ok(()),
+        Err(err) => match err.kind() {
+            std::io::ErrorKind::NotFound => Ok(()),
+            _ => Err(AzureStorageError::IoError(err)),
+        },
+    }
+}
+
+/// In the order the blocks were put
+async fn get_staged_blocks<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Vec<BlockItem>, AzureStorageError> {
+    let folder_name = compile_blocks_folder(connection_data, container_name, blob_name);
+
+    let mut read_dir = match tokio::fs::read_dir(folder_name.as_str()).await {
+        Ok(read_dir) => read_dir,
+        Err(err) => match err.kind() {
+            std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            _ => return Err(AzureStorageError::IoError(err)),
+        },
+    };
+
+    let mut result = Vec::new();
+
+    while let Some(entry) = read_dir.next_entry().await? {
+        let block_id = match entry.file_name().to_str().and_then(from_file_name) {
+            Some(block_id) => block_id,
+            None => continue,
+        };
+
+        let metadata = entry.metadata().await?;
+
+        result.push((
+            metadata.modified()?,
+            BlockItem {
+                block_id,
+                size: metadata.len() as usize,
+            },
+        ));
+    }
+
+    result.sort_by(|(a_modified, a), (b_modified, b)| {
+        a_modified
+            .cmp(b_modified)
+            .then_with(|| a.block_id.cmp(&b.block_id))
+    });
+
+    Ok(result.into_iter().map(|(_, block)| block).collect())
+}
+
+/// Blocks of a blob which is not a block blob or was uploaded with a single Put Blob are not tracked
+async fn get_committed_blocks<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Vec<BlockItem>, AzureStorageError> {
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    if tokio::fs::metadata(file_name.as_str()).await.is_err() {
+        return Ok(Vec::new());
+    }
+
+    let sidecar = BlobSidecar::read(connection_data, container_name, blob_name).await?;
+
+    match sidecar.blob_type {
+        BlobType::BlockBlob => Ok(sidecar.blocks),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Source and size of the committed block
+fn find_committed_block(committed: &[BlockItem], block_id: &str) -> Option<(BlockSource, usize)> {
+    let mut offset = 0;
+
+    for block in committed {
+        if block.block_id == block_id {
+            return Some((BlockSource::Committed(offset), block.size));
+        }
+
+        offset += block.size as u64;
+    }
+
+    None
+}
+
+fn compile_blocks_folder(
+    connection: &impl FileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> String {
+    super::utils::compile_container_path(
+        connection,
+        format!(
+            "{container_name}{separator}{BLOCKS_FOLDER}{separator}{blob_name}",
+            separator = connection.get_separator(),
+        )
+        .as_str(),
+    )
+}
+
+fn compile_block_path(
+    connection: &impl FileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    block_id: &str,
+) -> String {
+    format!(
+        "{folder}{separator}{file_name}",
+        folder = compile_blocks_folder(connection, container_name, blob_name),
+        separator = connection.get_separator(),
+        file_name = to_file_name(block_id),
+    )
+}
+
+fn to_file_name(block_id: &str) -> String {
+    block_id
+        .as_bytes()
+        .iter()
+        .map(|itm| format!("{:02x}", itm))
+        .collect()
+}
+
+fn from_file_name(file_name: &str) -> Option<String> {
+    if file_name.len() % 2 != 0 {
+        return None;
+    }
+
+    let bytes = (0..file_name.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(file_name.get(index..index + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name_round_trip() {
+        let block_id = "QUJD+/==";
+
+        let file_name = to_file_name(block_id);
+
+        assert_eq!("51554a442b2f3d3d", file_name);
+        assert_eq!(
+            Some(block_id.to_string()),
+            from_file_name(file_name.as_str())
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{blob_copy::BlobCopyState, AzureStorageError};
+
+use super::{blob_sidecar::BlobSidecar, utils::FileConnectionInfo};
+
+/// Copies the blob file with its properties. The copy is a new write, so it gets an ETag of its own
+pub async fn copy<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    source_container_name: &str,
+    source_blob_name: &str,
+) -> Result<BlobCopyState, AzureStorageError> {
+    super::containers::check_if_container_exists(connection_data, container_name).await?;
+
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    let source_file_name =
+        super::utils::compile_blob_path(connection_data, source_container_name, source_blob_name);
+
+    // Copying the file onto itself would truncate it
+    if source_file_name != file_name {
+        super::utils::copy_blob(
+            connection_data,
+            source_container_name,
+            source_blob_name,
+            container_name,
+            blob_name,
+        )
+        .await?;
+    }
+
+    let metadata =
+        super::handle_error_on_file_level(tokio::fs::metadata(file_name.as_str()).await)?;
+
+    super::utils::touch_file(file_name.as_str()).await?;
+
+    let copy_state = BlobCopyState::completed(metadata.len());
+
+    BlobSidecar::read(connection_data, container_name, blob_name)
+        .await?
+        .with_copy_state(copy_state.clone())
+        .write(connection_data, container_name, blob_name)
+        .await?;
+
+    Ok(copy_state)
+}
+
+pub async fn get_copy_state<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Option<BlobCopyState>, AzureStorageError> {
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    super::handle_error_on_file_level(tokio::fs::metadata(file_name.as_str()).await)?;
+
+    let sidecar = BlobSidecar::read(connection_data, container_name, blob_name).await?;
+
+    Ok(sidecar.copy_state)
+}
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+
+use crate::{
+    blob::{BlobHttpHeaders, BlobType},
+    blob_copy::{BlobCopyState, CopyProgress, CopyStatus},
+    block_blob::BlockItem,
+    AzureStorageError,
+};
+
+use super::utils::FileConnectionInfo;
+
+/// Blob properties files can not carry are kept in a sidecar file
+/// {container}/.blob-properties/{blob_name}. Listing skips it since only files of the container folder are blobs
+const SIDECAR_FOLDER: &str = ".blob-properties";
+
+const BLOB_TYPE_KEY: &str = "x-ms-blob-type";
+const CONTENT_TYPE_KEY: &str = "x-ms-blob-content-type";
+const CONTENT_ENCODING_KEY: &str = "x-ms-blob-content-encoding";
+const CONTENT_LANGUAGE_KEY: &str = "x-ms-blob-content-language";
+const CONTENT_DISPOSITION_KEY: &str = "x-ms-blob-content-disposition";
+const CACHE_CONTROL_KEY: &str = "x-ms-blob-cache-control";
+const CONTENT_MD5_KEY: &str = "x-ms-blob-content-md5";
+const COPY_ID_KEY: &str = "x-ms-copy-id";
+const COPY_STATUS_KEY: &str = "x-ms-copy-status";
+const COPY_PROGRESS_KEY: &str = "x-ms-copy-progress";
+const COPY_STATUS_DESCRIPTION_KEY: &str = "x-ms-copy-status-description";
+const BLOCK_KEY: &str = "x-ms-block";
+const COMMITTED_BLOCK_COUNT_KEY: &str = "x-ms-blob-committed-block-count";
+const SEALED_KEY: &str = "x-ms-blob-sealed";
+const META_PREFIX: &str = "x-ms-meta-";
+
+pub struct BlobSidecar {
+    pub blob_type: BlobType,
+    pub http_headers: BlobHttpHeaders,
+    pub metadata: HashMap<String, String>,
+    /// Set if the blob is the destination of a copy. Uploads write a new sidecar, which clears it
+    pub copy_state: Option<BlobCopyState>,
+    /// Committed blocks of a blob committed with Put Block List, one `x-ms-block: {id} {size}` line each
+    pub blocks: Vec<BlockItem>,
+    /// Blocks appended to an append blob
+    pub committed_block_count: u64,
+    /// Set once an append blob is sealed
+    pub sealed: bool,
+}
+
+impl BlobSidecar {
+    pub fn new(blob_type: BlobType) -> Self {
+        Self {
+            blob_type,
+            http_headers: BlobHttpHeaders::new(),
+            metadata: HashMap::new(),
+            copy_state: None,
+            blocks: Vec::new(),
+            committed_block_count: 0,
+            sealed: false,
+        }
+    }
+
+    pub fn with_http_headers(mut self, http_headers: BlobHttpHeaders) -> Self {
+        self.http_headers = http_headers;
+        self
+    }
+
+    /// Metadata names are lowercased - the way they come back from Azure through our transport
+    pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = metadata
+            .into_iter()
+            .map(|(name, value)| (name.to_lowercase(), value))
+            .collect();
+        self
+    }
+
+    pub fn with_copy_state(mut self, copy_state: BlobCopyState) -> Self {
+        self.copy_state = Some(copy_state);
+        self
+    }
+
+    pub fn with_blocks(mut self, blocks: Vec<BlockItem>) -> Self {
+        self.blocks = blocks;
+        self
+    }
+
+    /// Blobs placed into the folder by hand have no sidecar and are treated as block blobs
+    pub async fn read(
+        connection: &impl FileConnectionInfo,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Self, AzureStorageError> {
+        let file_name = compile_sidecar_path(connection, container_name, blob_name);
+
+        match tokio::fs::read_to_string(file_name.as_str()).await {
+            Ok(content) => Ok(Self::deserialize(content.as_str())),
+            Err(err) => match err.kind() {
+                std::io::ErrorKind::NotFound => Ok(Self::new(BlobType::BlockBlob)),
+                _ => Err(AzureStorageError::IoError(err)),
+            },
+        }
+    }
+
+    pub async fn write(
+        &self,
+        connection: &impl FileConnectionInfo,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        let folder_name = format!(
+            "{container_path}{separator}{SIDECAR_FOLDER}",
+            container_path = super::utils::compile_container_path(connection, container_name),
+            separator = connection.get_separator(),
+        );
+
+        tokio::fs::create_dir_all(folder_name.as_str()).await?;
+
+        let file_name = compile_sidecar_path(connection, container_name, blob_name);
+        tokio::fs::write(file_name.as_str(), self.serialize()).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(
+        connection: &impl FileConnectionInfo,
+        container_name: &str,
+        blob_name: &str,
+    ) {
+        let file_name = compile_sidecar_path(connection, container_name, blob_name);
+        let _ = tokio::fs::remove_file(file_name.as_str()).await;
+    }
+
+    fn serialize(&self) -> String {
+        let http_headers = &self.http_headers;
+
+        let lines = [
+            (BLOB_TYPE_KEY, Some(self.blob_type.as_str())),
+            (CONTENT_TYPE_KEY, http_headers.content_type.as_deref()),
+            (
+                CONTENT_ENCODING_KEY,
+                http_headers.content_encoding.as_deref(),
+            ),
+            (
+                CONTENT_LANGUAGE_KEY,
+                http_headers.content_language.as_deref(),
+            ),
+            (
+                CONTENT_DISPOSITION_KEY,
+                http_headers.content_disposition.as_deref(),
+            ),
+            (CACHE_CONTROL_KEY, http_headers.cache_control.as_deref()),
+            (CONTENT_MD5_KEY, http_headers.content_md5.as_deref()),
+        ];
+
+        let mut result = String::new();
+
+        for (key, value) in lines {
+            if let Some(value) = value {
+                push_line(&mut result, key, value);
+            }
+        }
+
+        if let Some(copy_state) = &self.copy_state {
+            push_line(&mut result, COPY_ID_KEY, copy_state.copy_id.as_str());
+            push_line(&mut result, COPY_STATUS_KEY, copy_state.status.as_str());
+
+            if let Some(progress) = copy_state.progress {
+                push_line(
+                    &mut result,
+                    COPY_PROGRESS_KEY,
+                    progress.to_string().as_str(),
+                );
+            }
+
+            if let Some(status_description) = copy_state.status_description.as_deref() {
+                push_line(&mut result, COPY_STATUS_DESCRIPTION_KEY, status_description);
+            }
+        }
+
+        for block in &self.blocks {
+            push_line(
+                &mut result,
+                BLOCK_KEY,
+                format!("{} {}", block.block_id, block.size).as_str(),
+            );
+        }
+
+        if let BlobType::AppendBlob = self.blob_type {
+            push_line(
+                &mut result,
+                COMMITTED_BLOCK_COUNT_KEY,
+                self.committed_block_count.to_string().as_str(),
+            );
+        }
+
+        if self.sealed {
+            push_line(&mut result, SEALED_KEY, "true");
+        }
+
+        for (name, value) in &self.metadata {
+            push_line(
+                &mut result,
+                format!("{}{}", META_PREFIX, name).as_str(),
+                value,
+            );
+        }
+
+        result
+    }
+
+    fn deserialize(src: &str) -> Self {
+        let mut result = Self::new(BlobType::BlockBlob);
+
+        let mut copy_id = None;
+        let mut copy_status = None;
+        let mut copy_progress = None;
+        let mut copy_status_description = None;
+
+        for line in src.lines() {
+            let (key, value) = match line.split_once(": ") {
+                Some(key_value) => key_value,
+                None => continue,
+            };
+
+            let value = value.to_string();
+
+            match key {
+                BLOB_TYPE_KEY => {
+                    if let Some(blob_type) = BlobType::parse(value.as_str()) {
+                        result.blob_type = blob_type;
+                    }
+                }
+                CONTENT_TYPE_KEY => result.http_headers.content_type = Some(value),
+                CONTENT_ENCODING_KEY => result.http_headers.content_encoding = Some(value),
+                CONTENT_LANGUAGE_KEY => result.http_headers.content_language = Some(value),
+                CONTENT_DISPOSITION_KEY => result.http_headers.content_disposition = Some(value),
+                CACHE_CONTROL_KEY => result.http_headers.cache_control = Some(value),
+                CONTENT_MD5_KEY => result.http_headers.content_md5 = Some(value),
+                COPY_ID_KEY => copy_id = Some(value),
+                COPY_STATUS_KEY => copy_status = CopyStatus::parse(value.as_str()),
+                COPY_PROGRESS_KEY => copy_progress = CopyProgress::parse(value.as_str()),
+                COPY_STATUS_DESCRIPTION_KEY => copy_status_description = Some(value),
+                BLOCK_KEY => {
+                    if let Some((block_id, size)) = value.split_once(' ') {
+                        if let Ok(size) = size.parse() {
+                            result.blocks.push(BlockItem {
+                                block_id: block_id.to_string(),
+                                size,
+                            });
+                        }
+                    }
+                }
+                COMMITTED_BLOCK_COUNT_KEY => {
+                    if let Ok(committed_block_count) = value.parse() {
+                        result.committed_block_count = committed_block_count;
+                    }
+                }
+                SEALED_KEY => result.sealed = value == "true",
+                _ => {
+                    if let Some(name) = key.strip_prefix(META_PREFIX) {
+                        result.metadata.insert(name.to_string(), value);
+                    }
+                }
+            }
+        }
+
+        if let (Some(copy_id), Some(status)) = (copy_id, copy_status) {
+            result.copy_state = Some(BlobCopyState {
+                copy_id,
+                status,
+                progress: copy_progress,
+                status_description: copy_status_description,
+            });
+        }
+
+        result
+    }
+}
+
+fn push_line(dest: &mut String, key: &str, value: &str) {
+    dest.push_str(key);
+    dest.push_str(": ");
+    dest.push_str(value);
+    dest.push('\n');
+}
+
+pub fn compile_sidecar_path(
+    connection: &impl FileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> String {
+    format!(
+        "{container_path}{separator}{SIDECAR_FOLDER}{separator}{blob_name}",
+        container_path = super::utils::compile_container_path(connection, container_name),
+        separator = connection.get_separator(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_deserialize() {
+        let src = BlobSidecar::new(BlobType::PageBlob)
+            .with_http_headers(
+                BlobHttpHeaders::new()
+                    .with_content_type("application/json")
+                    .with_cache_control("no-cache")
+                    .with_content_md5("Q2hlY2sgSW50ZWdyaXR5IQ=="),
+            )
+            .with_metadata(HashMap::from([("Tenant".to_string(), "acme".to_string())]));
+
+        let result = BlobSidecar::deserialize(src.serialize().as_str());
+
+        assert_eq!(BlobType::PageBlob, result.blob_type);
+        assert_eq!(src.http_headers, result.http_headers);
+        assert_eq!("acme", result.metadata.get("tenant").unwrap());
+        assert_eq!(None, result.copy_state);
+    }
+
+    #[test]
+    fn test_copy_state_round_trip() {
+        let src =
+            BlobSidecar::new(BlobType::BlockBlob).with_copy_state(BlobCopyState::completed(10));
+
+        let result = BlobSidecar::deserialize(src.serialize().as_str());
+
+        assert_eq!(src.copy_state, result.copy_state);
+    }
+
+    #[test]
+    fn test_blocks_round_trip() {
+        let blocks = vec![
+            BlockItem {
+                block_id: "QUJD".to_string(),
+                size: 3,
+            },
+            BlockItem {
+                block_id: "REVG".to_string(),
+                size: 10,
+            },
+        ];
+
+        let src = BlobSidecar::new(BlobType::BlockBlob).with_blocks(blocks.clone());
+
+        let result = BlobSidecar::deserialize(src.serialize().as_str());
+
+        assert_eq!(blocks, result.blocks);
+    }
+
+    #[test]
+    fn test_append_blob_round_trip() {
+        let mut src = BlobSidecar::new(BlobType::AppendBlob);
+        src.committed_block_count = 7;
+        src.sealed = true;
+
+        let result = BlobSidecar::deserialize(src.serialize().as_str());
+
+        assert_eq!(BlobType::AppendBlob, result.blob_type);
+        assert_eq!(7, result.committed_block_count);
+        assert!(result.sealed);
+    }
+
+    #[test]
+    fn test_unknown_lines_are_skipped() {
+        let result = BlobSidecar::deserialize("garbage\nx-ms-blob-type: AppendBlob\nfoo: bar\n");
+
+        assert_eq!(BlobType::AppendBlob, result.blob_type);
+    }
+}
@@ -0,0 +1,206 @@
+use std::io::SeekFrom;
+
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::{
+    blob::{BlobAccessConditions, BlobProperties},
+    page_blob::consts::BLOB_PAGE_SIZE,
+    AzureStorageError,
+};
+
+use super::utils::FileConnectionInfo;
+
+/// Snapshots of a blob are copies of the blob file kept in {container}/.blob-snapshots/{blob_name}/.
+/// The folder is treated as a container of its own, so blob helpers (sidecar included) work for snapshots as is
+const SNAPSHOTS_FOLDER: &str = ".blob-snapshots";
+
+/// Returns the snapshot id. The copy keeps the modification time of the blob, so it keeps its ETag too
+pub async fn create<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<String, AzureStorageError> {
+    let snapshot_id =
+        crate::sdk_azure::http_date::to_azure_timestamp(crate::blob::next_write_moment());
+
+    super::utils::copy_blob(
+        connection_data,
+        container_name,
+        blob_name,
+        compile_snapshots_container(connection_data, container_name, blob_name).as_str(),
+        to_file_name(snapshot_id.as_str()).as_str(),
+    )
+    .await?;
+
+    Ok(snapshot_id)
+}
+
+/// Oldest first. A blob without snapshots (or without the blob itself) gives an empty list
+pub async fn get_list<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Vec<String>, AzureStorageError> {
+    super::containers::check_if_container_exists(connection_data, container_name).await?;
+
+    let mut result = get_snapshot_files(connection_data, container_name, blob_name).await?;
+
+    result.sort();
+
+    Ok(result
+        .iter()
+        .map(|file_name| from_file_name(file_name))
+        .collect())
+}
+
+/// Does not require the container to exist - missing container has no snapshots
+pub async fn has_snapshots<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<bool, AzureStorageError> {
+    let result = get_snapshot_files(connection_data, container_name, blob_name).await?;
+    Ok(!result.is_empty())
+}
+
+async fn get_snapshot_files<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Vec<String>, AzureStorageError> {
+    let snapshots_container =
+        compile_snapshots_container(connection_data, container_name, blob_name);
+
+    let folder_name =
+        super::utils::compile_container_path(connection_data, snapshots_container.as_str());
+
+    if tokio::fs::metadata(folder_name.as_str()).await.is_err() {
+        return Ok(vec![]);
+    }
+
+    super::blobs::get_list(connection_data, snapshots_container.as_str()).await
+}
+
+pub async fn get_properties<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    snapshot: &str,
+) -> Result<BlobProperties, AzureStorageError> {
+    super::utils::get_blob_properties(
+        connection_data,
+        compile_snapshots_container(connection_data, container_name, blob_name).as_str(),
+        to_file_name(snapshot).as_str(),
+    )
+    .await
+}
+
+pub async fn download<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    snapshot: &str,
+) -> Result<Vec<u8>, AzureStorageError> {
+    super::blobs::download(
+        connection_data,
+        compile_snapshots_container(connection_data, container_name, blob_name).as_str(),
+        to_file_name(snapshot).as_str(),
+    )
+    .await
+}
+
+/// Reads only the requested pages of the snapshot file
+pub async fn get_pages<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    snapshot: &str,
+    start_page_no: usize,
+    pages_amount: usize,
+) -> Result<Vec<u8>, AzureStorageError> {
+    let file_name = super::utils::compile_blob_path(
+        connection_data,
+        compile_snapshots_container(connection_data, container_name, blob_name).as_str(),
+        to_file_name(snapshot).as_str(),
+    );
+
+    let mut file = super::handle_error_on_file_level(tokio::fs::File::open(file_name).await)?;
+
+    file.seek(SeekFrom::Start((start_page_no * BLOB_PAGE_SIZE) as u64))
+        .await?;
+
+    let mut result = vec![0u8; pages_amount * BLOB_PAGE_SIZE];
+
+    file.read_exact(&mut result).await?;
+
+    Ok(result)
+}
+
+pub async fn delete<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    snapshot: &str,
+) -> Result<(), AzureStorageError> {
+    super::blobs::delete(
+        connection_data,
+        compile_snapshots_container(connection_data, container_name, blob_name).as_str(),
+        to_file_name(snapshot).as_str(),
+        &BlobAccessConditions::new(),
+    )
+    .await
+}
+
+pub async fn delete_all<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<(), AzureStorageError> {
+    let folder_name = super::utils::compile_container_path(
+        connection_data,
+        compile_snapshots_container(connection_data, container_name, blob_name).as_str(),
+    );
+
+    match tokio::fs::remove_dir_all(folder_name.as_str()).await {
+        Ok(_) => Ok(()),
+        Err(err) => match err.kind() {
+            std::io::ErrorKind::NotFound => Ok(()),
+            _ => Err(AzureStorageError::IoError(err)),
+        },
+    }
+}
+
+fn compile_snapshots_container(
+    connection: &impl FileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> String {
+    format!(
+        "{container_name}{separator}{SNAPSHOTS_FOLDER}{separator}{blob_name}",
+        separator = connection.get_separator(),
+    )
+}
+
+// ':' of snapshot and version ids is not allowed in file names on Windows
+pub(crate) fn to_file_name(snapshot: &str) -> String {
+    snapshot.replace(':', "_")
+}
+
+pub(crate) fn from_file_name(file_name: &str) -> String {
+    file_name.replace('_', ":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name_round_trip() {
+        let snapshot = "2011-03-09T01:42:34.9360000Z";
+
+        let file_name = to_file_name(snapshot);
+
+        assert_eq!("2011-03-09T01_42_34.9360000Z", file_name);
+        assert_eq!(snapshot, from_file_name(file_name.as_str()));
+    }
+}
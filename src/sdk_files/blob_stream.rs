@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+
+use crate::{
+    blob_stream::{BlobChunkReader, BlobDownloadStream},
+    AzureStorageError,
+};
+
+use super::utils::FileConnectionInfo;
+
+/// The file stays open while the stream is read, chunks are read from disk one by one.
+/// Chunks of a blob overwritten in the middle of the download fail with ConditionNotMet, as on Azure
+pub async fn open<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    chunk_size: usize,
+) -> Result<BlobDownloadStream, AzureStorageError> {
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    let file = tokio::fs::File::open(file_name.as_str()).await;
+
+    let file = super::handle_error_on_file_level(file)?;
+
+    let file_metadata = super::handle_error_on_file_level(file.metadata().await)?;
+
+    let reader = FileBlobChunkReader {
+        file,
+        etag: super::utils::compile_etag(&file_metadata),
+        file_name,
+        remaining: file_metadata.len(),
+        chunk_size,
+    };
+
+    Ok(BlobDownloadStream::new(
+        file_metadata.len(),
+        Box::new(reader),
+    ))
+}
+
+struct FileBlobChunkReader {
+    file: tokio::fs::File,
+    file_name: String,
+    etag: Option<String>,
+    remaining: u64,
+    chunk_size: usize,
+}
+
+#[async_trait]
+impl BlobChunkReader for FileBlobChunkReader {
+    async fn read_chunk(&mut self) -> Result<Option<Vec<u8>>, AzureStorageError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        let file_metadata = tokio::fs::metadata(self.file_name.as_str()).await;
+        let file_metadata = super::handle_error_on_file_level(file_metadata)?;
+
+        if super::utils::compile_etag(&file_metadata) != self.etag {
+            return Err(AzureStorageError::ConditionNotMet(None));
+        }
+
+        let chunk_size = (self.chunk_size as u64).min(self.remaining) as usize;
+
+        let mut result = vec![0u8; chunk_size];
+        let mut read = 0;
+
+        while read < chunk_size {
+            let read_result =
+                tokio::io::AsyncReadExt::read(&mut self.file, &mut result[read..]).await;
+
+            let read_now = super::handle_error_on_file_level(read_result)?;
+
+            // The blob was cut by a write after the stream was opened
+            if read_now == 0 {
+                break;
+            }
+
+            read += read_now;
+        }
+
+        if read == 0 {
+            self.remaining = 0;
+            return Ok(None);
+        }
+
+        result.truncate(read);
+        self.remaining -= read as u64;
+
+        Ok(Some(result))
+    }
+}
@@ -0,0 +1,535 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    blob::BlobProperties,
+    blob_version::{BlobVersionItem, DeletedBlobContainer, LocalRetentionPolicy},
+    AzureStorageError,
+};
+
+use super::{
+    blob_snapshots::{from_file_name, to_file_name},
+    utils::FileConnectionInfo,
+};
+
+/// Prior versions of a blob are copies of the blob file kept in {container}/.blob-versions/{blob_name}/,
+/// named by the version id. Version id is the Last-Modified of the blob, so the current blob has one as well
+const VERSIONS_FOLDER: &str = ".blob-versions";
+
+/// Soft-deleted blob is kept in {container}/.blob-deleted/{blob_name}/, named by the moment of deletion
+const DELETED_FOLDER: &str = ".blob-deleted";
+
+/// Soft-deleted containers are moved to {root}/.deleted-containers/{container_name}/{version}.
+/// The version is the moment of deletion, so expiry needs nothing but the folder name
+const DELETED_CONTAINERS_FOLDER: &str = ".deleted-containers";
+
+/// None if the blob does not exist
+pub async fn get_current_version_id<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Option<String>, AzureStorageError> {
+    match super::utils::get_blob_properties(connection_data, container_name, blob_name).await {
+        Ok(properties) => Ok(properties
+            .last_modified
+            .map(crate::sdk_azure::http_date::to_azure_timestamp)),
+        Err(AzureStorageError::BlobNotFound(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Copies the current blob into its versions. A missing blob has nothing to keep
+pub async fn keep_version<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<(), AzureStorageError> {
+    let version_id =
+        match get_current_version_id(connection_data, container_name, blob_name).await? {
+            Some(version_id) => version_id,
+            None => return Ok(()),
+        };
+
+    super::utils::copy_blob(
+        connection_data,
+        container_name,
+        blob_name,
+        compile_versions_container(connection_data, container_name, blob_name).as_str(),
+        to_file_name(version_id.as_str()).as_str(),
+    )
+    .await
+}
+
+/// Copies the blob about to be deleted aside. Only the latest deleted blob is kept - as the in-memory backend does
+pub async fn keep_deleted<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    deleted_at: DateTimeAsMicroseconds,
+) -> Result<(), AzureStorageError> {
+    let deleted_container = compile_deleted_container(connection_data, container_name, blob_name);
+
+    remove_folder(
+        super::utils::compile_container_path(connection_data, deleted_container.as_str()).as_str(),
+    )
+    .await?;
+
+    match super::utils::copy_blob(
+        connection_data,
+        container_name,
+        blob_name,
+        deleted_container.as_str(),
+        deleted_at.unix_microseconds.to_string().as_str(),
+    )
+    .await
+    {
+        Ok(()) => Ok(()),
+        Err(AzureStorageError::BlobNotFound(_)) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Sorted by blob name, then version id
+pub async fn get_list<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    retention: &LocalRetentionPolicy,
+    now: DateTimeAsMicroseconds,
+) -> Result<Vec<BlobVersionItem>, AzureStorageError> {
+    super::containers::check_if_container_exists(connection_data, container_name).await?;
+
+    let mut result = vec![];
+
+    for blob_name in super::blobs::get_list(connection_data, container_name).await? {
+        let version_id =
+            get_current_version_id(connection_data, container_name, blob_name.as_str()).await?;
+
+        result.push(BlobVersionItem {
+            blob_name,
+            version_id,
+            is_current_version: true,
+            deleted: false,
+        });
+    }
+
+    let container_path = super::utils::compile_container_path(connection_data, container_name);
+    let separator = connection_data.get_separator();
+
+    let versions_path = format!("{container_path}{separator}{VERSIONS_FOLDER}");
+
+    for blob_name in get_sub_folders(versions_path.as_str()).await? {
+        let versions_container =
+            compile_versions_container(connection_data, container_name, blob_name.as_str());
+
+        for file_name in
+            super::blobs::get_list(connection_data, versions_container.as_str()).await?
+        {
+            result.push(BlobVersionItem {
+                blob_name: blob_name.to_string(),
+                version_id: Some(from_file_name(file_name.as_str())),
+                is_current_version: false,
+                deleted: false,
+            });
+        }
+    }
+
+    let deleted_path = format!("{container_path}{separator}{DELETED_FOLDER}");
+
+    for blob_name in get_sub_folders(deleted_path.as_str()).await? {
+        let deleted_at =
+            get_deleted_at(connection_data, container_name, blob_name.as_str()).await?;
+
+        if let Some(deleted_at) = deleted_at {
+            if !retention.is_expired(deleted_at, now) {
+                result.push(BlobVersionItem {
+                    blob_name,
+                    version_id: None,
+                    is_current_version: false,
+                    deleted: true,
+                });
+            }
+        }
+    }
+
+    result.sort_by(|a, b| {
+        (a.blob_name.as_str(), a.version_id.as_deref())
+            .cmp(&(b.blob_name.as_str(), b.version_id.as_deref()))
+    });
+
+    Ok(result)
+}
+
+pub async fn get_properties<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    version_id: &str,
+) -> Result<BlobProperties, AzureStorageError> {
+    let (container_name, file_name) =
+        resolve_version(connection_data, container_name, blob_name, version_id).await?;
+
+    super::utils::get_blob_properties(connection_data, container_name.as_str(), file_name.as_str())
+        .await
+}
+
+pub async fn download<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    version_id: &str,
+) -> Result<Vec<u8>, AzureStorageError> {
+    let (container_name, file_name) =
+        resolve_version(connection_data, container_name, blob_name, version_id).await?;
+
+    super::blobs::download(connection_data, container_name.as_str(), file_name.as_str()).await
+}
+
+/// Overwrites the blob with the version. With versioning the overwritten blob is kept as a version
+pub async fn promote<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    version_id: &str,
+    retention: &LocalRetentionPolicy,
+) -> Result<(), AzureStorageError> {
+    let (version_container, file_name) =
+        resolve_version(connection_data, container_name, blob_name, version_id).await?;
+
+    if retention.versioning {
+        keep_version(connection_data, container_name, blob_name).await?;
+    }
+
+    if version_container != container_name {
+        super::utils::copy_blob(
+            connection_data,
+            version_container.as_str(),
+            file_name.as_str(),
+            container_name,
+            blob_name,
+        )
+        .await?;
+    }
+
+    // Promoted content is a new write, so it gets a version id of its own
+    super::utils::touch_file(
+        super::utils::compile_blob_path(connection_data, container_name, blob_name).as_str(),
+    )
+    .await
+}
+
+/// A blob which is not deleted stays as it is. With versioning a deleted blob is kept as versions
+/// and is brought back by `promote` - undelete does not change anything then
+pub async fn undelete<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    retention: &LocalRetentionPolicy,
+    now: DateTimeAsMicroseconds,
+) -> Result<(), AzureStorageError> {
+    if get_current_version_id(connection_data, container_name, blob_name)
+        .await?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    let deleted_container = compile_deleted_container(connection_data, container_name, blob_name);
+
+    if let Some(deleted_at) = get_deleted_at(connection_data, container_name, blob_name).await? {
+        if !retention.is_expired(deleted_at, now) {
+            super::utils::copy_blob(
+                connection_data,
+                deleted_container.as_str(),
+                deleted_at.unix_microseconds.to_string().as_str(),
+                container_name,
+                blob_name,
+            )
+            .await?;
+        }
+
+        remove_folder(
+            super::utils::compile_container_path(connection_data, deleted_container.as_str())
+                .as_str(),
+        )
+        .await?;
+
+        if !retention.is_expired(deleted_at, now) {
+            return Ok(());
+        }
+    }
+
+    let versions_container = compile_versions_container(connection_data, container_name, blob_name);
+
+    let versions_path =
+        super::utils::compile_container_path(connection_data, versions_container.as_str());
+
+    if tokio::fs::metadata(versions_path.as_str()).await.is_ok()
+        && !super::blobs::get_list(connection_data, versions_container.as_str())
+            .await?
+            .is_empty()
+    {
+        return Ok(());
+    }
+
+    Err(AzureStorageError::BlobNotFound(None))
+}
+
+/// Moves the container folder aside instead of removing it
+pub async fn delete_container<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    deleted_at: DateTimeAsMicroseconds,
+) -> Result<(), AzureStorageError> {
+    let container_path = super::utils::compile_container_path(connection_data, container_name);
+
+    if tokio::fs::metadata(container_path.as_str()).await.is_err() {
+        return Err(AzureStorageError::ContainerNotFound(None));
+    }
+
+    let deleted_containers_path = compile_deleted_containers_path(connection_data, container_name);
+
+    tokio::fs::create_dir_all(deleted_containers_path.as_str()).await?;
+
+    // Two deletes of the same name within a microsecond still get versions of their own
+    let mut version = deleted_at.unix_microseconds;
+
+    let mut deleted_path = compile_deleted_container_path(
+        connection_data,
+        container_name,
+        format!("{:016X}", version).as_str(),
+    );
+
+    while tokio::fs::metadata(deleted_path.as_str()).await.is_ok() {
+        version += 1;
+        deleted_path = compile_deleted_container_path(
+            connection_data,
+            container_name,
+            format!("{:016X}", version).as_str(),
+        );
+    }
+
+    tokio::fs::rename(container_path.as_str(), deleted_path.as_str()).await?;
+
+    Ok(())
+}
+
+pub async fn get_list_of_deleted_containers<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    retention: &LocalRetentionPolicy,
+    now: DateTimeAsMicroseconds,
+) -> Result<Vec<DeletedBlobContainer>, AzureStorageError> {
+    let root_path = format!(
+        "{}{}",
+        connection_data.get_root_path(),
+        DELETED_CONTAINERS_FOLDER
+    );
+
+    let mut result = vec![];
+
+    for name in get_sub_folders(root_path.as_str()).await? {
+        let deleted_containers_path = compile_deleted_containers_path(connection_data, &name);
+
+        for version in get_sub_folders(deleted_containers_path.as_str()).await? {
+            if let Some(deleted_at) = parse_container_version(version.as_str()) {
+                if !retention.is_expired(deleted_at, now) {
+                    result.push(DeletedBlobContainer {
+                        name: name.to_string(),
+                        version,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+pub async fn undelete_container<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    version: &str,
+    retention: &LocalRetentionPolicy,
+    now: DateTimeAsMicroseconds,
+) -> Result<(), AzureStorageError> {
+    let container_path = super::utils::compile_container_path(connection_data, container_name);
+
+    if tokio::fs::metadata(container_path.as_str()).await.is_ok() {
+        return Err(AzureStorageError::ContainerAlreadyExists(None));
+    }
+
+    let deleted_at = match parse_container_version(version) {
+        Some(deleted_at) => deleted_at,
+        None => return Err(AzureStorageError::ContainerNotFound(None)),
+    };
+
+    let deleted_path = compile_deleted_container_path(connection_data, container_name, version);
+
+    if retention.is_expired(deleted_at, now)
+        || tokio::fs::metadata(deleted_path.as_str()).await.is_err()
+    {
+        return Err(AzureStorageError::ContainerNotFound(None));
+    }
+
+    tokio::fs::rename(deleted_path.as_str(), container_path.as_str()).await?;
+
+    // Other versions deleted under the same name keep the folder
+    let _ = tokio::fs::remove_dir(
+        compile_deleted_containers_path(connection_data, container_name).as_str(),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Container and file name the version is read from
+async fn resolve_version<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    version_id: &str,
+) -> Result<(String, String), AzureStorageError> {
+    let current_version_id =
+        get_current_version_id(connection_data, container_name, blob_name).await?;
+
+    if current_version_id.as_deref() == Some(version_id) {
+        return Ok((container_name.to_string(), blob_name.to_string()));
+    }
+
+    let versions_container = compile_versions_container(connection_data, container_name, blob_name);
+    let file_name = to_file_name(version_id);
+
+    let file_path = super::utils::compile_blob_path(
+        connection_data,
+        versions_container.as_str(),
+        file_name.as_str(),
+    );
+
+    if tokio::fs::metadata(file_path.as_str()).await.is_err() {
+        return Err(AzureStorageError::BlobNotFound(None));
+    }
+
+    Ok((versions_container, file_name))
+}
+
+async fn get_deleted_at<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Option<DateTimeAsMicroseconds>, AzureStorageError> {
+    let deleted_container = compile_deleted_container(connection_data, container_name, blob_name);
+
+    let folder_name =
+        super::utils::compile_container_path(connection_data, deleted_container.as_str());
+
+    if tokio::fs::metadata(folder_name.as_str()).await.is_err() {
+        return Ok(None);
+    }
+
+    let result = super::blobs::get_list(connection_data, deleted_container.as_str())
+        .await?
+        .iter()
+        .filter_map(|file_name| file_name.parse::<i64>().ok())
+        .max()
+        .map(DateTimeAsMicroseconds::new);
+
+    Ok(result)
+}
+
+async fn get_sub_folders(path: &str) -> Result<Vec<String>, AzureStorageError> {
+    let mut read_dir = match tokio::fs::read_dir(path).await {
+        Ok(read_dir) => read_dir,
+        Err(err) => match err.kind() {
+            std::io::ErrorKind::NotFound => return Ok(vec![]),
+            _ => return Err(AzureStorageError::IoError(err)),
+        },
+    };
+
+    let mut result = vec![];
+
+    while let Some(entry) = read_dir.next_entry().await? {
+        if entry.file_type().await?.is_dir() {
+            result.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+
+    Ok(result)
+}
+
+async fn remove_folder(path: &str) -> Result<(), AzureStorageError> {
+    match tokio::fs::remove_dir_all(path).await {
+        Ok(_) => Ok(()),
+        Err(err) => match err.kind() {
+            std::io::ErrorKind::NotFound => Ok(()),
+            _ => Err(AzureStorageError::IoError(err)),
+        },
+    }
+}
+
+fn parse_container_version(version: &str) -> Option<DateTimeAsMicroseconds> {
+    let unix_microseconds = i64::from_str_radix(version, 16).ok()?;
+    Some(DateTimeAsMicroseconds::new(unix_microseconds))
+}
+
+fn compile_versions_container(
+    connection: &impl FileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> String {
+    format!(
+        "{container_name}{separator}{VERSIONS_FOLDER}{separator}{blob_name}",
+        separator = connection.get_separator(),
+    )
+}
+
+fn compile_deleted_container(
+    connection: &impl FileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> String {
+    format!(
+        "{container_name}{separator}{DELETED_FOLDER}{separator}{blob_name}",
+        separator = connection.get_separator(),
+    )
+}
+
+fn compile_deleted_containers_path(
+    connection: &impl FileConnectionInfo,
+    container_name: &str,
+) -> String {
+    format!(
+        "{root_path}{DELETED_CONTAINERS_FOLDER}{separator}{container_name}",
+        root_path = connection.get_root_path(),
+        separator = connection.get_separator(),
+    )
+}
+
+fn compile_deleted_container_path(
+    connection: &impl FileConnectionInfo,
+    container_name: &str,
+    version: &str,
+) -> String {
+    format!(
+        "{deleted_containers_path}{separator}{version}",
+        deleted_containers_path = compile_deleted_containers_path(connection, container_name),
+        separator = connection.get_separator(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_version_round_trip() {
+        let deleted_at = DateTimeAsMicroseconds::new(1_700_000_000_123_456);
+
+        let version = format!("{:016X}", deleted_at.unix_microseconds);
+
+        assert_eq!("00060A2418202240", version);
+        assert_eq!(
+            deleted_at.unix_microseconds,
+            parse_container_version(version.as_str())
+                .unwrap()
+                .unix_microseconds
+        );
+    }
+}
@@ -1,8 +1,14 @@
-use rust_extensions::SliceOrVec;
+use std::collections::HashMap;
 
-use crate::{blob::BlobProperties, AzureStorageError};
+use rust_extensions::{date_time::DateTimeAsMicroseconds, SliceOrVec};
 
-use super::utils::FileConnectionInfo;
+use crate::{
+    blob::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, BlobType, DownloadedBlob},
+    block_blob::{BlockItem, UploadBlockBlobOptions},
+    AzureStorageError,
+};
+
+use super::{blob_sidecar::BlobSidecar, utils::FileConnectionInfo};
 
 pub async fn get_list<TFileConnectionInfo: FileConnectionInfo>(
     connection_data: &TFileConnectionInfo,
@@ -58,23 +64,36 @@ pub async fn get_blob_properties<TFileConnectionInfo: FileConnectionInfo>(
     container_name: &str,
     blob_name: &str,
 ) -> Result<BlobProperties, AzureStorageError> {
-    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
-
-    super::utils::get_blob_properties(file_name.as_str()).await
+    super::utils::get_blob_properties(connection_data, container_name, blob_name).await
 }
 
 pub async fn delete<TFileConnectionInfo: FileConnectionInfo>(
     connection_data: &TFileConnectionInfo,
     container_name: &str,
     blob_name: &str,
+    conditions: &BlobAccessConditions,
 ) -> Result<(), AzureStorageError> {
+    if !conditions.is_empty() {
+        match super::utils::get_condition_state(connection_data, container_name, blob_name).await? {
+            Some((etag, last_modified)) => {
+                conditions.check(Some((etag.as_str(), last_modified)))?
+            }
+            None => return Err(AzureStorageError::BlobNotFound(None)),
+        }
+    }
+
     let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
 
-    match tokio::fs::remove_file(file_name).await {
+    let result = tokio::fs::remove_file(file_name).await;
+
+    BlobSidecar::delete(connection_data, container_name, blob_name).await;
+    super::blob_blocks::delete_all(connection_data, container_name, blob_name).await?;
+
+    match result {
         Ok(_) => Ok(()),
         Err(err) => match err.kind() {
             std::io::ErrorKind::NotFound => {
-                return Err(AzureStorageError::BlobNotFound);
+                return Err(AzureStorageError::BlobNotFound(None));
             }
             _ => {
                 return Err(AzureStorageError::UnknownError {
@@ -92,10 +111,12 @@ pub async fn delete_if_exists<TFileConnectionInfo: FileConnectionInfo>(
 ) -> Result<(), AzureStorageError> {
     let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
 
-    match tokio::fs::remove_file(file_name).await {
-        Ok(_) => Ok(()),
-        Err(_) => Ok(()),
-    }
+    let _ = tokio::fs::remove_file(file_name).await;
+
+    BlobSidecar::delete(connection_data, container_name, blob_name).await;
+    super::blob_blocks::delete_all(connection_data, container_name, blob_name).await?;
+
+    Ok(())
 }
 
 pub async fn download<TFileConnectionInfo: FileConnectionInfo>(
@@ -117,12 +138,78 @@ pub async fn download<TFileConnectionInfo: FileConnectionInfo>(
     return Ok(result);
 }
 
+/// Reads only the bytes of the range: seeks to the offset instead of reading the file from the start
+pub async fn download_range<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    offset: u64,
+    length: Option<u64>,
+) -> Result<Vec<u8>, AzureStorageError> {
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    let file = tokio::fs::File::open(file_name).await;
+
+    let mut file = super::handle_error_on_file_level(file)?;
+
+    let file_metadata = super::handle_error_on_file_level(file.metadata().await)?;
+
+    let range = crate::blob::resolve_range(offset, length, file_metadata.len())?;
+
+    let mut result = vec![0u8; (range.end - range.start) as usize];
+
+    if result.is_empty() {
+        return Ok(result);
+    }
+
+    let seek_result =
+        tokio::io::AsyncSeekExt::seek(&mut file, std::io::SeekFrom::Start(range.start)).await;
+    super::handle_error_on_file_level(seek_result)?;
+
+    let read_result = tokio::io::AsyncReadExt::read_exact(&mut file, &mut result).await;
+    super::handle_error_on_file_level(read_result)?;
+
+    Ok(result)
+}
+
+pub async fn download_with_conditions<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    conditions: &BlobAccessConditions,
+) -> Result<DownloadedBlob, AzureStorageError> {
+    let properties = get_blob_properties(connection_data, container_name, blob_name).await?;
+
+    conditions.check(Some((
+        properties.etag.as_deref().unwrap_or_default(),
+        properties
+            .last_modified
+            .unwrap_or(DateTimeAsMicroseconds::new(0)),
+    )))?;
+
+    let content = download(connection_data, container_name, blob_name).await?;
+
+    Ok(DownloadedBlob {
+        content,
+        properties,
+    })
+}
+
 pub async fn upload<'s, TFileConnectionInfo: FileConnectionInfo>(
     connection_data: &TFileConnectionInfo,
     container_name: &str,
     blob_name: &str,
     content: impl Into<SliceOrVec<'s, u8>>,
-) -> Result<(), AzureStorageError> {
+    options: UploadBlockBlobOptions,
+) -> Result<String, AzureStorageError> {
+    check_write_conditions(
+        connection_data,
+        container_name,
+        blob_name,
+        &options.conditions,
+    )
+    .await?;
+
     let file_name =
         crate::sdk_files::utils::compile_blob_path(connection_data, container_name, blob_name);
 
@@ -134,5 +221,111 @@ pub async fn upload<'s, TFileConnectionInfo: FileConnectionInfo>(
         .await?;
     tokio::io::AsyncWriteExt::write_all(&mut f, content.into().as_slice()).await?;
     tokio::io::AsyncWriteExt::flush(&mut f).await?;
-    Ok(())
+    drop(f);
+
+    complete_upload(
+        connection_data,
+        container_name,
+        blob_name,
+        options,
+        Vec::new(),
+    )
+    .await
+}
+
+pub(crate) async fn check_write_conditions<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    conditions: &BlobAccessConditions,
+) -> Result<(), AzureStorageError> {
+    let current =
+        super::utils::get_condition_state(connection_data, container_name, blob_name).await?;
+
+    conditions.check_before_write(
+        current
+            .as_ref()
+            .map(|(etag, last_modified)| (etag.as_str(), *last_modified)),
+    )
+}
+
+/// The content of the block blob file is written. `blocks` are the committed blocks it is made of.
+/// Returns ETag of the blob. Blocks not committed by the upload are discarded, as Azure does
+pub(crate) async fn complete_upload<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    options: UploadBlockBlobOptions,
+    blocks: Vec<BlockItem>,
+) -> Result<String, AzureStorageError> {
+    let file_name =
+        crate::sdk_files::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    super::utils::touch_file(file_name.as_str()).await?;
+
+    BlobSidecar::new(BlobType::BlockBlob)
+        .with_http_headers(options.http_headers)
+        .with_metadata(options.metadata)
+        .with_blocks(blocks)
+        .write(connection_data, container_name, blob_name)
+        .await?;
+
+    super::blob_blocks::delete_all(connection_data, container_name, blob_name).await?;
+
+    let properties = get_blob_properties(connection_data, container_name, blob_name).await?;
+
+    Ok(properties.etag.unwrap_or_default())
+}
+
+pub async fn set_metadata<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    metadata: HashMap<String, String>,
+) -> Result<(), AzureStorageError> {
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    super::handle_error_on_file_level(tokio::fs::metadata(file_name.as_str()).await)?;
+
+    BlobSidecar::read(connection_data, container_name, blob_name)
+        .await?
+        .with_metadata(metadata)
+        .write(connection_data, container_name, blob_name)
+        .await?;
+
+    // Set Blob Metadata changes ETag and Last-Modified, which the file backend derives from mtime
+    super::utils::touch_file(file_name.as_str()).await
+}
+
+pub async fn set_http_headers<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+    http_headers: BlobHttpHeaders,
+) -> Result<(), AzureStorageError> {
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    super::handle_error_on_file_level(tokio::fs::metadata(file_name.as_str()).await)?;
+
+    BlobSidecar::read(connection_data, container_name, blob_name)
+        .await?
+        .with_http_headers(http_headers)
+        .write(connection_data, container_name, blob_name)
+        .await?;
+
+    super::utils::touch_file(file_name.as_str()).await
+}
+
+pub async fn get_metadata<TFileConnectionInfo: FileConnectionInfo>(
+    connection_data: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<HashMap<String, String>, AzureStorageError> {
+    let file_name = super::utils::compile_blob_path(connection_data, container_name, blob_name);
+
+    super::handle_error_on_file_level(tokio::fs::metadata(file_name.as_str()).await)?;
+
+    let sidecar = BlobSidecar::read(connection_data, container_name, blob_name).await?;
+
+    Ok(sidecar.metadata)
 }
@@ -1,5 +1,3 @@
-use flurl::FlUrlError;
-
 use crate::AzureStorageError;
 
 use super::utils::FileConnectionInfo;
@@ -55,7 +53,7 @@ pub async fn delete_if_exists(
 
 pub async fn get_list(
     connection_data: &impl FileConnectionInfo,
-) -> Result<Vec<String>, FlUrlError> {
+) -> Result<Vec<String>, AzureStorageError> {
     let mut result = Vec::new();
 
     let root_path = connection_data.get_root_path();
@@ -70,9 +68,12 @@ pub async fn get_list(
 
                 let path = format!("{}", path.display());
 
-                result.push(
-                    super::utils::extract_file_name(path.as_str(), path_separator).to_string(),
-                );
+                let container_name = super::utils::extract_file_name(path.as_str(), path_separator);
+
+                // Container names can not start with '.' - such folders keep soft-deleted containers
+                if !container_name.starts_with('.') {
+                    result.push(container_name.to_string());
+                }
             }
         }
     }
@@ -88,7 +89,7 @@ pub async fn check_error_if_container_exists<TResult>(
     match src {
         Ok(result) => Ok(result),
         Err(err) => {
-            if let AzureStorageError::BlobNotFound = err {
+            if let AzureStorageError::BlobNotFound(_) = err {
                 check_if_container_exists(connection, container_name).await?;
             }
 
@@ -103,7 +104,7 @@ pub async fn check_if_container_exists(
 ) -> Result<(), AzureStorageError> {
     let path = super::utils::compile_container_path(connection, container_name);
     if tokio::fs::metadata(path).await.is_err() {
-        return Err(AzureStorageError::ContainerNotFound);
+        return Err(AzureStorageError::ContainerNotFound(None));
     }
 
     Ok(())
@@ -13,7 +13,7 @@ pub fn handle_error_on_file_level<TOk>(
         Ok(ok) => Ok(ok),
         Err(err) => match err.kind() {
             std::io::ErrorKind::NotFound => {
-                return Err(AzureStorageError::BlobNotFound);
+                return Err(AzureStorageError::BlobNotFound(None));
             }
             _ => {
                 return Err(AzureStorageError::UnknownError {
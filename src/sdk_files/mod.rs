@@ -1,3 +1,10 @@
+pub mod append_blobs;
+pub mod blob_blocks;
+pub mod blob_copy;
+pub mod blob_sidecar;
+pub mod blob_snapshots;
+pub mod blob_stream;
+pub mod blob_versions;
 pub mod blobs;
 pub mod containers;
 mod errors;
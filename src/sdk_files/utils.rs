@@ -1,4 +1,13 @@
-use crate::{blob::BlobProperties, AzureStorageError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    blob::{BlobProperties, BlobType},
+    AzureStorageError,
+};
+
+use super::blob_sidecar::BlobSidecar;
 
 pub trait FileConnectionInfo {
     fn get_root_path(&self) -> &str;
@@ -27,20 +36,134 @@ pub fn compile_container_path<TFileConnectionInfo: FileConnectionInfo>(
     )
 }
 
-pub async fn get_blob_properties(file_name: &str) -> Result<BlobProperties, AzureStorageError> {
-    match tokio::fs::metadata(file_name).await {
-        Ok(metadata) => {
-            return Ok(BlobProperties {
-                blob_size: metadata.len() as usize,
-            });
-        }
+pub async fn get_blob_properties<TFileConnectionInfo: FileConnectionInfo>(
+    connection: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<BlobProperties, AzureStorageError> {
+    let file_name = compile_blob_path(connection, container_name, blob_name);
+
+    let metadata = match tokio::fs::metadata(file_name).await {
+        Ok(metadata) => metadata,
         Err(err) => {
             if let std::io::ErrorKind::NotFound = err.kind() {
-                return Err(AzureStorageError::BlobNotFound);
+                return Err(AzureStorageError::BlobNotFound(None));
             }
 
             return Err(AzureStorageError::IoError(err));
         }
+    };
+
+    let sidecar = BlobSidecar::read(connection, container_name, blob_name).await?;
+
+    let mut result = BlobProperties::new(sidecar.blob_type, metadata.len() as usize);
+    result.set_http_headers(&sidecar.http_headers);
+    result.metadata = sidecar.metadata;
+
+    result.etag = compile_etag(&metadata);
+
+    if let Ok(modified) = metadata.modified() {
+        let modified = to_unix_nanoseconds(modified);
+        result.last_modified = Some(DateTimeAsMicroseconds::new((modified / 1000) as i64));
+    }
+
+    if let Ok(created) = metadata.created() {
+        result.creation_time = Some(DateTimeAsMicroseconds::new(
+            (to_unix_nanoseconds(created) / 1000) as i64,
+        ));
+    }
+
+    match result.blob_type {
+        BlobType::BlockBlob => {}
+        BlobType::PageBlob => result.sequence_number = Some(0),
+        BlobType::AppendBlob => {
+            result.committed_block_count = Some(sidecar.committed_block_count);
+            result.is_sealed = Some(sidecar.sealed);
+        }
+    }
+
+    Ok(result)
+}
+
+/// The file changes its modification time on every write, which gives ETag semantics Azure has
+pub fn compile_etag(metadata: &std::fs::Metadata) -> Option<String> {
+    let modified = to_unix_nanoseconds(metadata.modified().ok()?);
+    Some(format!("\"0x{:X}{:X}\"", modified, metadata.len()))
+}
+
+/// ETag and Last-Modified access conditions are checked against. None if the blob does not exist
+pub async fn get_condition_state<TFileConnectionInfo: FileConnectionInfo>(
+    connection: &TFileConnectionInfo,
+    container_name: &str,
+    blob_name: &str,
+) -> Result<Option<(String, DateTimeAsMicroseconds)>, AzureStorageError> {
+    match get_blob_properties(connection, container_name, blob_name).await {
+        Ok(props) => Ok(Some((
+            props.etag.unwrap_or_default(),
+            props
+                .last_modified
+                .unwrap_or(DateTimeAsMicroseconds::new(0)),
+        ))),
+        Err(AzureStorageError::BlobNotFound(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Stamps the file with a strictly increasing modification time. File system timestamps are too coarse
+/// to tell two quick writes apart, and ETag is derived from the modification time
+pub async fn touch_file(file_name: &str) -> Result<(), AzureStorageError> {
+    let file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(file_name)
+        .await?;
+
+    let moment = crate::blob::next_write_moment();
+    let modified = UNIX_EPOCH + Duration::from_micros(moment.unix_microseconds as u64);
+
+    file.into_std().await.set_modified(modified)?;
+
+    Ok(())
+}
+
+/// Copies the blob file together with its sidecar. The copy keeps the modification time of the blob,
+/// so it keeps its ETag too
+pub async fn copy_blob<TFileConnectionInfo: FileConnectionInfo>(
+    connection: &TFileConnectionInfo,
+    from_container_name: &str,
+    from_blob_name: &str,
+    to_container_name: &str,
+    to_blob_name: &str,
+) -> Result<(), AzureStorageError> {
+    let from_file_name = compile_blob_path(connection, from_container_name, from_blob_name);
+
+    let metadata =
+        super::handle_error_on_file_level(tokio::fs::metadata(from_file_name.as_str()).await)?;
+
+    tokio::fs::create_dir_all(compile_container_path(connection, to_container_name)).await?;
+
+    let to_file_name = compile_blob_path(connection, to_container_name, to_blob_name);
+
+    tokio::fs::copy(from_file_name.as_str(), to_file_name.as_str()).await?;
+
+    let file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(to_file_name.as_str())
+        .await?;
+
+    file.into_std().await.set_modified(metadata.modified()?)?;
+
+    BlobSidecar::read(connection, from_container_name, from_blob_name)
+        .await?
+        .write(connection, to_container_name, to_blob_name)
+        .await?;
+
+    Ok(())
+}
+
+fn to_unix_nanoseconds(src: SystemTime) -> u128 {
+    match src.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_nanos(),
+        Err(_) => 0,
     }
 }
 
@@ -58,6 +181,9 @@ pub fn extract_file_name(full_path: &str, separator: char) -> &str {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
+    use crate::block_blob::UploadBlockBlobOptions;
 
     use super::*;
 
@@ -67,4 +193,105 @@ mod test {
         let result = extract_file_name(src_path, '/');
         assert_eq!("FileName", result);
     }
+
+    #[tokio::test]
+    async fn test_get_blob_properties() {
+        const CONTAINER_NAME: &str = "test-blob-properties";
+        let connection_data = crate::connection::FileConnectionData::new(
+            crate::sdk_files::test_utils::get_test_folder(),
+        );
+
+        crate::sdk_files::containers::create_if_not_exists(&connection_data, CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        crate::sdk_files::blobs::upload(
+            &connection_data,
+            CONTAINER_NAME,
+            "block",
+            vec![1u8; 3],
+            UploadBlockBlobOptions::new()
+                .with_content_type("text/plain")
+                .with_metadata("Tenant", "acme"),
+        )
+        .await
+        .unwrap();
+
+        let first = get_blob_properties(&connection_data, CONTAINER_NAME, "block")
+            .await
+            .unwrap();
+
+        assert_eq!(BlobType::BlockBlob, first.blob_type);
+        assert_eq!(3, first.blob_size);
+        assert!(first.last_modified.is_some());
+        assert!(first.sequence_number.is_none());
+        assert_eq!("acme", first.metadata.get("tenant").unwrap());
+        assert_eq!(Some("text/plain".to_string()), first.content_type);
+
+        crate::sdk_files::blobs::upload(
+            &connection_data,
+            CONTAINER_NAME,
+            "block",
+            vec![1u8; 5],
+            UploadBlockBlobOptions::new(),
+        )
+        .await
+        .unwrap();
+
+        let second = get_blob_properties(&connection_data, CONTAINER_NAME, "block")
+            .await
+            .unwrap();
+
+        assert_ne!(first.etag.unwrap(), second.etag.clone().unwrap());
+        assert!(second.metadata.is_empty());
+
+        crate::sdk_files::blobs::set_metadata(
+            &connection_data,
+            CONTAINER_NAME,
+            "block",
+            HashMap::from([("schema".to_string(), "2".to_string())]),
+        )
+        .await
+        .unwrap();
+
+        let metadata =
+            crate::sdk_files::blobs::get_metadata(&connection_data, CONTAINER_NAME, "block")
+                .await
+                .unwrap();
+
+        assert_eq!("2", metadata.get("schema").unwrap());
+
+        let third = get_blob_properties(&connection_data, CONTAINER_NAME, "block")
+            .await
+            .unwrap();
+
+        assert_ne!(second.etag.unwrap(), third.etag.clone().unwrap());
+
+        crate::sdk_files::blobs::set_http_headers(
+            &connection_data,
+            CONTAINER_NAME,
+            "block",
+            crate::blob::BlobHttpHeaders::new().with_cache_control("no-cache"),
+        )
+        .await
+        .unwrap();
+
+        let fourth = get_blob_properties(&connection_data, CONTAINER_NAME, "block")
+            .await
+            .unwrap();
+
+        assert_eq!(Some("no-cache".to_string()), fourth.cache_control);
+        assert_eq!("2", fourth.metadata.get("schema").unwrap());
+        assert_ne!(third.etag.unwrap(), fourth.etag.unwrap());
+
+        let blobs = crate::sdk_files::blobs::get_list(&connection_data, CONTAINER_NAME)
+            .await
+            .unwrap();
+
+        assert_eq!(vec!["block".to_string()], blobs);
+
+        crate::sdk_files::containers::delete_if_exists(&connection_data, CONTAINER_NAME)
+            .await
+            .unwrap();
+    }
 }
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+
+use crate::{
+    append_blob::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions},
+    blob::BlobAccessConditions,
+    AzureStorageError,
+};
+
+use super::not_supported;
+
+#[async_trait]
+pub trait AppendBlobBackend {
+    /// Replaces an existing blob of any type. Returns ETag of the blob
+    async fn create_append_blob(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _options: CreateAppendBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        Err(not_supported("create_append_blob"))
+    }
+
+    async fn append_block(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _content: Vec<u8>,
+        _conditions: AppendBlockConditions,
+    ) -> Result<AppendBlockResult, AzureStorageError> {
+        Err(not_supported("append_block"))
+    }
+
+    /// Returns ETag of the blob
+    async fn seal_append_blob(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        Err(not_supported("seal_append_blob"))
+    }
+}
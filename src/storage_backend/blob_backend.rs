@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::{
+    blob::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, DownloadedBlob},
+    AzureStorageError,
+};
+
+use super::not_supported;
+
+#[async_trait]
+pub trait BlobBackend {
+    async fn get_blob_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<BlobProperties, AzureStorageError>;
+
+    async fn download_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<u8>, AzureStorageError>;
+
+    /// Open-ended if there is no length. Starting at or past the end of the blob is InvalidRange
+    async fn download_range(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _offset: u64,
+        _length: Option<u64>,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        Err(not_supported("download_range"))
+    }
+
+    /// Content together with the properties of the version which was read
+    async fn download_blob_with_conditions(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _conditions: BlobAccessConditions,
+    ) -> Result<DownloadedBlob, AzureStorageError> {
+        Err(not_supported("download_blob_with_conditions"))
+    }
+
+    async fn delete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError>;
+
+    async fn delete_blob_if_exists(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError>;
+
+    async fn set_blob_metadata(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _metadata: HashMap<String, String>,
+        _conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("set_blob_metadata"))
+    }
+
+    /// Replaces content-type, content-encoding, content-language, content-disposition and cache-control.
+    /// Headers which are None are cleared
+    async fn set_blob_http_headers(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _http_headers: BlobHttpHeaders,
+        _conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("set_blob_http_headers"))
+    }
+
+    async fn get_blob_metadata(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+    ) -> Result<HashMap<String, String>, AzureStorageError> {
+        Err(not_supported("get_blob_metadata"))
+    }
+}
@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+use crate::AzureStorageError;
+
+#[async_trait]
+pub trait BlobContainersBackend {
+    async fn create_container_if_not_exists(
+        &self,
+        container_name: &str,
+    ) -> Result<(), AzureStorageError>;
+
+    async fn delete_container(&self, container_name: &str) -> Result<(), AzureStorageError>;
+
+    async fn delete_container_if_exists(
+        &self,
+        container_name: &str,
+    ) -> Result<(), AzureStorageError>;
+
+    async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, AzureStorageError>;
+
+    async fn get_list_of_blobs(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError>;
+}
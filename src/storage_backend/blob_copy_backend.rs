@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+
+use crate::{
+    blob_copy::{BlobCopyState, CopySource},
+    AzureStorageError,
+};
+
+use super::not_supported;
+
+#[async_trait]
+pub trait BlobCopyBackend {
+    async fn start_copy_from_url(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _source: &CopySource,
+        _lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        Err(not_supported("start_copy_from_url"))
+    }
+
+    async fn copy_from_url(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _source: &CopySource,
+        _lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        Err(not_supported("copy_from_url"))
+    }
+
+    async fn get_copy_status(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+    ) -> Result<Option<BlobCopyState>, AzureStorageError> {
+        Err(not_supported("get_copy_status"))
+    }
+
+    async fn abort_copy(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _copy_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("abort_copy"))
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::{blob_lease::LeaseDuration, AzureStorageError};
+
+use super::not_supported;
+
+#[async_trait]
+pub trait BlobLeaseBackend {
+    async fn acquire_blob_lease(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _duration: LeaseDuration,
+        _proposed_lease_id: Option<&str>,
+    ) -> Result<String, AzureStorageError> {
+        Err(not_supported("acquire_blob_lease"))
+    }
+
+    async fn renew_blob_lease(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("renew_blob_lease"))
+    }
+
+    async fn release_blob_lease(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("release_blob_lease"))
+    }
+
+    async fn break_blob_lease(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _break_period: Option<Duration>,
+    ) -> Result<Duration, AzureStorageError> {
+        Err(not_supported("break_blob_lease"))
+    }
+
+    async fn change_blob_lease(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _lease_id: &str,
+        _proposed_lease_id: &str,
+    ) -> Result<String, AzureStorageError> {
+        Err(not_supported("change_blob_lease"))
+    }
+}
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+
+use crate::{blob::BlobProperties, blob_snapshot::DeleteSnapshotsOption, AzureStorageError};
+
+use super::not_supported;
+
+#[async_trait]
+pub trait BlobSnapshotBackend {
+    async fn create_blob_snapshot(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+    ) -> Result<String, AzureStorageError> {
+        Err(not_supported("create_blob_snapshot"))
+    }
+
+    async fn get_list_of_blob_snapshots(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError> {
+        Err(not_supported("get_list_of_blob_snapshots"))
+    }
+
+    async fn get_blob_snapshot_properties(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _snapshot: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        Err(not_supported("get_blob_snapshot_properties"))
+    }
+
+    async fn download_blob_snapshot(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _snapshot: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        Err(not_supported("download_blob_snapshot"))
+    }
+
+    async fn get_blob_snapshot_pages(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _snapshot: &str,
+        _start_page_no: usize,
+        _pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        Err(not_supported("get_blob_snapshot_pages"))
+    }
+
+    async fn delete_blob_snapshot(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _snapshot: &str,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("delete_blob_snapshot"))
+    }
+
+    async fn delete_blob_with_snapshots(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _option: DeleteSnapshotsOption,
+        _lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("delete_blob_with_snapshots"))
+    }
+}
@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::{blob_stream::BlobDownloadStream, AzureStorageError};
+
+use super::not_supported;
+
+#[async_trait]
+pub trait BlobStreamBackend {
+    /// Fails with BlobNotFound right away. Chunks are read as the stream is consumed
+    async fn open_blob_stream(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _chunk_size: usize,
+    ) -> Result<BlobDownloadStream, AzureStorageError> {
+        Err(not_supported("open_blob_stream"))
+    }
+}
@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+
+use crate::{
+    blob::BlobProperties,
+    blob_version::{BlobVersionItem, DeletedBlobContainer},
+    AzureStorageError,
+};
+
+use super::not_supported;
+
+#[async_trait]
+pub trait BlobVersionBackend {
+    async fn get_list_of_blob_versions(
+        &self,
+        _container_name: &str,
+    ) -> Result<Vec<BlobVersionItem>, AzureStorageError> {
+        Err(not_supported("get_list_of_blob_versions"))
+    }
+
+    async fn get_blob_version_properties(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _version_id: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        Err(not_supported("get_blob_version_properties"))
+    }
+
+    async fn download_blob_version(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _version_id: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        Err(not_supported("download_blob_version"))
+    }
+
+    async fn promote_blob_version(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _version_id: &str,
+        _lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("promote_blob_version"))
+    }
+
+    async fn undelete_blob(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("undelete_blob"))
+    }
+
+    async fn get_list_of_deleted_blob_containers(
+        &self,
+    ) -> Result<Vec<DeletedBlobContainer>, AzureStorageError> {
+        Err(not_supported("get_list_of_deleted_blob_containers"))
+    }
+
+    async fn undelete_blob_container(
+        &self,
+        _container_name: &str,
+        _version: &str,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("undelete_blob_container"))
+    }
+}
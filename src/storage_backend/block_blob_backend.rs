@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+
+use crate::{
+    block_blob::{BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions},
+    AzureStorageError,
+};
+
+use super::not_supported;
+
+#[async_trait]
+pub trait BlockBlobBackend {
+    /// Returns ETag of the uploaded blob
+    async fn upload_block_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: Vec<u8>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError>;
+
+    /// Stages a block of the blob. The blob does not change until the block is committed
+    async fn put_block(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _block_id: &str,
+        _content: Vec<u8>,
+        _lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        Err(not_supported("put_block"))
+    }
+
+    /// Commits the blocks, in the order of the list, as the content of the blob.
+    /// Blocks left out of the list are discarded, committed and uncommitted ones alike. Returns ETag of the blob
+    async fn put_block_list(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _blocks: Vec<BlockListItem>,
+        _options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        Err(not_supported("put_block_list"))
+    }
+
+    /// BlobNotFound if the blob has neither committed nor uncommitted blocks and does not exist
+    async fn get_block_list(
+        &self,
+        _container_name: &str,
+        _blob_name: &str,
+        _list_type: BlockListType,
+    ) -> Result<BlockList, AzureStorageError> {
+        Err(not_supported("get_block_list"))
+    }
+}
@@ -0,0 +1,595 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    append_blob::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions},
+    blob::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, DownloadedBlob},
+    blob_copy::{BlobCopyState, CopySource},
+    blob_lease::LeaseDuration,
+    blob_snapshot::DeleteSnapshotsOption,
+    blob_stream::BlobDownloadStream,
+    blob_version::{BlobVersionItem, DeletedBlobContainer},
+    block_blob::{BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions},
+    AzureStorageConnectionData, AzureStorageError,
+};
+
+use super::{
+    AppendBlobBackend, BlobBackend, BlobContainersBackend, BlobCopyBackend, BlobLeaseBackend,
+    BlobSnapshotBackend, BlobStreamBackend, BlobVersionBackend, BlockBlobBackend, PageBlobBackend,
+};
+
+#[async_trait]
+impl BlobContainersBackend for AzureStorageConnectionData {
+    async fn create_container_if_not_exists(
+        &self,
+        container_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::containers::create_if_not_exists(self, container_name).await
+    }
+
+    async fn delete_container(&self, container_name: &str) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::containers::delete(self, container_name).await
+    }
+
+    async fn delete_container_if_exists(
+        &self,
+        container_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::containers::delete_if_exists(self, container_name).await
+    }
+
+    async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, AzureStorageError> {
+        crate::sdk_azure::containers::get_list(self).await
+    }
+
+    async fn get_list_of_blobs(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError> {
+        crate::sdk_azure::blobs::get_list(self, container_name).await
+    }
+}
+
+#[async_trait]
+impl BlobBackend for AzureStorageConnectionData {
+    async fn get_blob_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        crate::sdk_azure::blobs::get_blob_properties(self, container_name, blob_name).await
+    }
+
+    async fn download_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        crate::sdk_azure::blobs::download(self, container_name, blob_name).await
+    }
+
+    async fn download_range(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        crate::sdk_azure::blobs::download_range(self, container_name, blob_name, offset, length)
+            .await
+    }
+
+    async fn download_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<DownloadedBlob, AzureStorageError> {
+        crate::sdk_azure::blobs::download_with_conditions(
+            self,
+            container_name,
+            blob_name,
+            &conditions,
+        )
+        .await
+    }
+
+    async fn delete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blobs::delete(self, container_name, blob_name, &conditions).await
+    }
+
+    async fn delete_blob_if_exists(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blobs::delete_if_exists(self, container_name, blob_name, lease_id).await
+    }
+
+    async fn set_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        metadata: HashMap<String, String>,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blobs::set_blob_metadata(
+            self,
+            container_name,
+            blob_name,
+            &metadata,
+            &conditions,
+        )
+        .await
+    }
+
+    async fn set_blob_http_headers(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        http_headers: BlobHttpHeaders,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blobs::set_blob_http_headers(
+            self,
+            container_name,
+            blob_name,
+            &http_headers,
+            &conditions,
+        )
+        .await
+    }
+
+    async fn get_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<HashMap<String, String>, AzureStorageError> {
+        crate::sdk_azure::blobs::get_blob_metadata(self, container_name, blob_name).await
+    }
+}
+
+#[async_trait]
+impl BlockBlobBackend for AzureStorageConnectionData {
+    async fn upload_block_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: Vec<u8>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        crate::block_blob::sdk::upload_block_blob(
+            self,
+            container_name,
+            blob_name,
+            content,
+            &options,
+        )
+        .await
+    }
+
+    async fn put_block(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        block_id: &str,
+        content: Vec<u8>,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        crate::block_blob::sdk::put_block(
+            self,
+            container_name,
+            blob_name,
+            block_id,
+            content,
+            lease_id,
+        )
+        .await
+    }
+
+    async fn put_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        blocks: Vec<BlockListItem>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        crate::block_blob::sdk::put_block_list(
+            self,
+            container_name,
+            blob_name,
+            blocks.as_slice(),
+            &options,
+        )
+        .await
+    }
+
+    async fn get_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        list_type: BlockListType,
+    ) -> Result<BlockList, AzureStorageError> {
+        crate::block_blob::sdk::get_block_list(self, container_name, blob_name, list_type).await
+    }
+}
+
+#[async_trait]
+impl PageBlobBackend for AzureStorageConnectionData {
+    async fn create_page_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        pages_amount: usize,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::page_blobs::create_page_blob(
+            self,
+            container_name,
+            blob_name,
+            pages_amount,
+            &metadata,
+        )
+        .await
+    }
+
+    async fn resize_page_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        pages_amount: usize,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::page_blobs::resize_page_blob(
+            self,
+            container_name,
+            blob_name,
+            pages_amount,
+        )
+        .await
+    }
+
+    async fn get_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        crate::sdk_azure::page_blobs::get_pages(
+            self,
+            container_name,
+            blob_name,
+            start_page_no,
+            pages_amount,
+        )
+        .await
+    }
+
+    async fn save_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        start_page_no: usize,
+        payload: Vec<u8>,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        crate::sdk_azure::page_blobs::save_pages(
+            self,
+            container_name,
+            blob_name,
+            start_page_no,
+            payload,
+            &conditions,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl AppendBlobBackend for AzureStorageConnectionData {
+    async fn create_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        options: CreateAppendBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        crate::append_blob::sdk::create(self, container_name, blob_name, &options).await
+    }
+
+    async fn append_block(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: Vec<u8>,
+        conditions: AppendBlockConditions,
+    ) -> Result<AppendBlockResult, AzureStorageError> {
+        crate::append_blob::sdk::append_block(self, container_name, blob_name, content, &conditions)
+            .await
+    }
+
+    async fn seal_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        crate::append_blob::sdk::seal(self, container_name, blob_name, &conditions).await
+    }
+}
+
+#[async_trait]
+impl BlobLeaseBackend for AzureStorageConnectionData {
+    async fn acquire_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        duration: LeaseDuration,
+        proposed_lease_id: Option<&str>,
+    ) -> Result<String, AzureStorageError> {
+        crate::sdk_azure::blob_leases::acquire(
+            self,
+            container_name,
+            blob_name,
+            duration,
+            proposed_lease_id,
+        )
+        .await
+    }
+
+    async fn renew_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blob_leases::renew(self, container_name, blob_name, lease_id).await
+    }
+
+    async fn release_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blob_leases::release(self, container_name, blob_name, lease_id).await
+    }
+
+    async fn break_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        break_period: Option<Duration>,
+    ) -> Result<Duration, AzureStorageError> {
+        crate::sdk_azure::blob_leases::break_lease(self, container_name, blob_name, break_period)
+            .await
+    }
+
+    async fn change_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+        proposed_lease_id: &str,
+    ) -> Result<String, AzureStorageError> {
+        crate::sdk_azure::blob_leases::change(
+            self,
+            container_name,
+            blob_name,
+            lease_id,
+            proposed_lease_id,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl BlobSnapshotBackend for AzureStorageConnectionData {
+    async fn create_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError> {
+        crate::sdk_azure::blob_snapshots::create(self, container_name, blob_name).await
+    }
+
+    async fn get_list_of_blob_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError> {
+        crate::sdk_azure::blob_snapshots::get_list(self, container_name, blob_name).await
+    }
+
+    async fn get_blob_snapshot_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        crate::sdk_azure::blob_snapshots::get_properties(self, container_name, blob_name, snapshot)
+            .await
+    }
+
+    async fn download_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        crate::sdk_azure::blob_snapshots::download(self, container_name, blob_name, snapshot).await
+    }
+
+    async fn get_blob_snapshot_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        crate::sdk_azure::blob_snapshots::get_pages(
+            self,
+            container_name,
+            blob_name,
+            snapshot,
+            start_page_no,
+            pages_amount,
+        )
+        .await
+    }
+
+    async fn delete_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blob_snapshots::delete(self, container_name, blob_name, snapshot).await
+    }
+
+    async fn delete_blob_with_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        option: DeleteSnapshotsOption,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blob_snapshots::delete_blob_with_snapshots(
+            self,
+            container_name,
+            blob_name,
+            option,
+            lease_id,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl BlobVersionBackend for AzureStorageConnectionData {
+    async fn get_list_of_blob_versions(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<BlobVersionItem>, AzureStorageError> {
+        crate::sdk_azure::blob_versions::get_list(self, container_name).await
+    }
+
+    async fn get_blob_version_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        crate::sdk_azure::blob_versions::get_properties(self, container_name, blob_name, version_id)
+            .await
+    }
+
+    async fn download_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        crate::sdk_azure::blob_versions::download(self, container_name, blob_name, version_id).await
+    }
+
+    async fn promote_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blob_versions::promote(
+            self,
+            container_name,
+            blob_name,
+            version_id,
+            lease_id,
+        )
+        .await
+    }
+
+    async fn undelete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blob_versions::undelete(self, container_name, blob_name).await
+    }
+
+    async fn get_list_of_deleted_blob_containers(
+        &self,
+    ) -> Result<Vec<DeletedBlobContainer>, AzureStorageError> {
+        crate::sdk_azure::blob_versions::get_list_of_deleted_containers(self).await
+    }
+
+    async fn undelete_blob_container(
+        &self,
+        container_name: &str,
+        version: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blob_versions::undelete_container(self, container_name, version).await
+    }
+}
+
+#[async_trait]
+impl BlobCopyBackend for AzureStorageConnectionData {
+    async fn start_copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        crate::sdk_azure::blob_copy::start_copy(self, container_name, blob_name, source, lease_id)
+            .await
+    }
+
+    async fn copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        crate::sdk_azure::blob_copy::copy_sync(self, container_name, blob_name, source, lease_id)
+            .await
+    }
+
+    async fn get_copy_status(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Option<BlobCopyState>, AzureStorageError> {
+        crate::sdk_azure::blob_copy::get_status(self, container_name, blob_name).await
+    }
+
+    async fn abort_copy(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        copy_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_azure::blob_copy::abort(self, container_name, blob_name, copy_id).await
+    }
+}
+
+#[async_trait]
+impl BlobStreamBackend for AzureStorageConnectionData {
+    async fn open_blob_stream(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        chunk_size: usize,
+    ) -> Result<BlobDownloadStream, AzureStorageError> {
+        crate::sdk_azure::blob_stream::open(self, container_name, blob_name, chunk_size).await
+    }
+}
@@ -0,0 +1,1073 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    append_blob::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions},
+    blob::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, DownloadedBlob},
+    blob_copy::{BlobCopyState, CopySource},
+    blob_lease::LeaseDuration,
+    blob_snapshot::DeleteSnapshotsOption,
+    blob_stream::BlobDownloadStream,
+    blob_version::{BlobVersionItem, DeletedBlobContainer},
+    block_blob::{BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions},
+    connection::FileConnectionData,
+    AzureStorageError,
+};
+
+use super::{
+    AppendBlobBackend, BlobBackend, BlobContainersBackend, BlobCopyBackend, BlobLeaseBackend,
+    BlobSnapshotBackend, BlobStreamBackend, BlobVersionBackend, BlockBlobBackend, PageBlobBackend,
+};
+
+#[async_trait]
+impl BlobContainersBackend for FileConnectionData {
+    async fn create_container_if_not_exists(
+        &self,
+        container_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_files::containers::create_if_not_exists(self, container_name).await
+    }
+
+    async fn delete_container(&self, container_name: &str) -> Result<(), AzureStorageError> {
+        self.drop_page_blob_data_of_container(container_name).await;
+        self.drop_leases_of_container(container_name).await;
+
+        if self.get_retention_policy().delete_retention.is_some() {
+            return crate::sdk_files::blob_versions::delete_container(
+                self,
+                container_name,
+                self.now(),
+            )
+            .await;
+        }
+
+        crate::sdk_files::containers::delete(self, container_name).await
+    }
+
+    async fn delete_container_if_exists(
+        &self,
+        container_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.drop_page_blob_data_of_container(container_name).await;
+        self.drop_leases_of_container(container_name).await;
+
+        if self.get_retention_policy().delete_retention.is_some() {
+            let result =
+                crate::sdk_files::blob_versions::delete_container(self, container_name, self.now())
+                    .await;
+
+            return match result {
+                Err(AzureStorageError::ContainerNotFound(_)) => Ok(()),
+                result => result,
+            };
+        }
+
+        crate::sdk_files::containers::delete_if_exists(self, container_name).await
+    }
+
+    async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, AzureStorageError> {
+        crate::sdk_files::containers::get_list(self).await
+    }
+
+    async fn get_list_of_blobs(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError> {
+        crate::sdk_files::blobs::get_list(self, container_name).await
+    }
+}
+
+#[async_trait]
+impl BlobBackend for FileConnectionData {
+    async fn get_blob_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        let result =
+            crate::sdk_files::blobs::get_blob_properties(self, container_name, blob_name).await;
+
+        let mut result = crate::sdk_files::containers::check_error_if_container_exists(
+            result,
+            self,
+            container_name,
+        )
+        .await?;
+
+        self.with_lease(container_name, blob_name, |lease, now| {
+            lease.apply_to_properties(&mut result, now);
+            Ok(())
+        })
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn download_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let result = crate::sdk_files::blobs::download(self, container_name, blob_name).await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn download_range(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let result = crate::sdk_files::blobs::download_range(
+            self,
+            container_name,
+            blob_name,
+            offset,
+            length,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn download_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<DownloadedBlob, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            conditions.lease_id.as_deref(),
+            false,
+        )
+        .await?;
+
+        let result = crate::sdk_files::blobs::download_with_conditions(
+            self,
+            container_name,
+            blob_name,
+            &conditions,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn delete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            conditions.lease_id.as_deref(),
+            true,
+        )
+        .await?;
+
+        self.check_no_snapshots(container_name, blob_name).await?;
+
+        self.keep_deleted(container_name, blob_name, &conditions)
+            .await?;
+
+        self.drop_page_blob_data(self.get_page_blob_id(container_name, blob_name))
+            .await;
+
+        let result =
+            crate::sdk_files::blobs::delete(self, container_name, blob_name, &conditions).await;
+
+        let result = crate::sdk_files::containers::check_error_if_container_exists(
+            result,
+            self,
+            container_name,
+        )
+        .await;
+
+        if result.is_ok() {
+            self.drop_lease(container_name, blob_name).await;
+        }
+
+        result
+    }
+
+    async fn delete_blob_if_exists(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(container_name, blob_name, lease_id, true)
+            .await?;
+
+        self.check_no_snapshots(container_name, blob_name).await?;
+
+        self.keep_deleted(container_name, blob_name, &BlobAccessConditions::new())
+            .await?;
+
+        self.drop_page_blob_data(self.get_page_blob_id(container_name, blob_name))
+            .await;
+
+        crate::sdk_files::blobs::delete_if_exists(self, container_name, blob_name).await?;
+
+        self.drop_lease(container_name, blob_name).await;
+
+        Ok(())
+    }
+
+    async fn set_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        metadata: HashMap<String, String>,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_conditions(container_name, blob_name, &conditions)
+            .await?;
+
+        self.keep_version(container_name, blob_name, &BlobAccessConditions::new())
+            .await?;
+
+        let result =
+            crate::sdk_files::blobs::set_metadata(self, container_name, blob_name, metadata).await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn set_blob_http_headers(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        http_headers: BlobHttpHeaders,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_conditions(container_name, blob_name, &conditions)
+            .await?;
+
+        let result = crate::sdk_files::blobs::set_http_headers(
+            self,
+            container_name,
+            blob_name,
+            http_headers,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn get_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<HashMap<String, String>, AzureStorageError> {
+        let result = crate::sdk_files::blobs::get_metadata(self, container_name, blob_name).await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+}
+
+#[async_trait]
+impl BlockBlobBackend for FileConnectionData {
+    async fn upload_block_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: Vec<u8>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            options.conditions.lease_id.as_deref(),
+            true,
+        )
+        .await?;
+
+        self.keep_version(container_name, blob_name, &options.conditions)
+            .await?;
+
+        let result =
+            crate::sdk_files::blobs::upload(self, container_name, blob_name, content, options)
+                .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn put_block(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        block_id: &str,
+        content: Vec<u8>,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(container_name, blob_name, lease_id, true)
+            .await?;
+
+        let result = crate::sdk_files::blob_blocks::put_block(
+            self,
+            container_name,
+            blob_name,
+            block_id,
+            content,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn put_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        blocks: Vec<BlockListItem>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            options.conditions.lease_id.as_deref(),
+            true,
+        )
+        .await?;
+
+        self.keep_version(container_name, blob_name, &options.conditions)
+            .await?;
+
+        let result =
+            crate::sdk_files::blob_blocks::commit(self, container_name, blob_name, blocks, options)
+                .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn get_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        list_type: BlockListType,
+    ) -> Result<BlockList, AzureStorageError> {
+        let result = crate::sdk_files::blob_blocks::get_block_list(
+            self,
+            container_name,
+            blob_name,
+            list_type,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+}
+
+#[async_trait]
+impl PageBlobBackend for FileConnectionData {
+    async fn create_page_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        pages_amount: usize,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError> {
+        self.keep_version(container_name, blob_name, &BlobAccessConditions::new())
+            .await?;
+
+        let id = self
+            .get_or_apply_page_blob_data(container_name, blob_name)
+            .await;
+
+        self.create_blob(id.as_str(), pages_amount, metadata).await
+    }
+
+    async fn resize_page_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        pages_amount: usize,
+    ) -> Result<(), AzureStorageError> {
+        let id = self
+            .get_or_apply_page_blob_data(container_name, blob_name)
+            .await;
+
+        self.resize(id.as_str(), pages_amount).await
+    }
+
+    async fn get_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let id = self
+            .get_or_apply_page_blob_data(container_name, blob_name)
+            .await;
+
+        self.get(id.as_str(), start_page_no, pages_amount).await
+    }
+
+    async fn save_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        start_page_no: usize,
+        payload: Vec<u8>,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            conditions.lease_id.as_deref(),
+            true,
+        )
+        .await?;
+
+        let id = self
+            .get_or_apply_page_blob_data(container_name, blob_name)
+            .await;
+
+        FileConnectionData::save_pages(self, id.as_str(), start_page_no, payload, &conditions).await
+    }
+}
+
+impl FileConnectionData {
+    /// Lease and access conditions of a write to an existing blob. A missing blob is left for the write to report
+    async fn check_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: &BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            conditions.lease_id.as_deref(),
+            true,
+        )
+        .await?;
+
+        if let Some((etag, last_modified)) =
+            crate::sdk_files::utils::get_condition_state(self, container_name, blob_name).await?
+        {
+            conditions.check(Some((etag.as_str(), last_modified)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Lease, snapshot and copy operations require the blob to exist
+    async fn check_blob_exists(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        let result =
+            crate::sdk_files::blobs::get_blob_properties(self, container_name, blob_name).await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await?;
+
+        Ok(())
+    }
+
+    /// A blob with snapshots is deleted only together with them (delete_blob_with_snapshots)
+    async fn check_no_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        if crate::sdk_files::blob_snapshots::has_snapshots(self, container_name, blob_name).await? {
+            return Err(AzureStorageError::SnapshotsPresent(None));
+        }
+
+        Ok(())
+    }
+
+    /// With versioning the blob about to be overwritten is kept as a version. Access conditions are checked
+    /// first, so a write which is going to fail leaves no version behind
+    async fn keep_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: &BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        if !self.get_retention_policy().versioning {
+            return Ok(());
+        }
+
+        let current =
+            crate::sdk_files::utils::get_condition_state(self, container_name, blob_name).await?;
+
+        conditions.check_before_write(
+            current
+                .as_ref()
+                .map(|(etag, last_modified)| (etag.as_str(), *last_modified)),
+        )?;
+
+        crate::sdk_files::blob_versions::keep_version(self, container_name, blob_name).await
+    }
+
+    /// The blob about to be deleted is kept as a version with versioning, as a soft-deleted blob otherwise
+    async fn keep_deleted(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: &BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let retention = self.get_retention_policy();
+
+        if !retention.versioning && retention.delete_retention.is_none() {
+            return Ok(());
+        }
+
+        if let Some((etag, last_modified)) =
+            crate::sdk_files::utils::get_condition_state(self, container_name, blob_name).await?
+        {
+            conditions.check(Some((etag.as_str(), last_modified)))?;
+        }
+
+        if retention.versioning {
+            return crate::sdk_files::blob_versions::keep_version(self, container_name, blob_name)
+                .await;
+        }
+
+        crate::sdk_files::blob_versions::keep_deleted(self, container_name, blob_name, self.now())
+            .await
+    }
+}
+
+#[async_trait]
+impl AppendBlobBackend for FileConnectionData {
+    async fn create_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        options: CreateAppendBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            options.conditions.lease_id.as_deref(),
+            true,
+        )
+        .await?;
+
+        self.keep_version(container_name, blob_name, &options.conditions)
+            .await?;
+
+        let result =
+            crate::sdk_files::append_blobs::create(self, container_name, blob_name, options).await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn append_block(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: Vec<u8>,
+        conditions: AppendBlockConditions,
+    ) -> Result<AppendBlockResult, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            conditions.access.lease_id.as_deref(),
+            true,
+        )
+        .await?;
+
+        let result = crate::sdk_files::append_blobs::append_block(
+            self,
+            container_name,
+            blob_name,
+            content,
+            &conditions,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn seal_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(
+            container_name,
+            blob_name,
+            conditions.lease_id.as_deref(),
+            true,
+        )
+        .await?;
+
+        let result =
+            crate::sdk_files::append_blobs::seal(self, container_name, blob_name, &conditions)
+                .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+}
+
+#[async_trait]
+impl BlobLeaseBackend for FileConnectionData {
+    async fn acquire_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        duration: LeaseDuration,
+        proposed_lease_id: Option<&str>,
+    ) -> Result<String, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+        self.check_blob_exists(container_name, blob_name).await?;
+
+        self.with_lease(container_name, blob_name, |lease, now| {
+            lease.acquire(duration, proposed_lease_id, now)
+        })
+        .await
+    }
+
+    async fn renew_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+        self.check_blob_exists(container_name, blob_name).await?;
+
+        self.with_lease(container_name, blob_name, |lease, now| {
+            lease.renew(lease_id, now)
+        })
+        .await
+    }
+
+    async fn release_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+        self.check_blob_exists(container_name, blob_name).await?;
+
+        self.with_lease(container_name, blob_name, |lease, now| {
+            lease.release(lease_id, now)
+        })
+        .await
+    }
+
+    async fn break_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        break_period: Option<Duration>,
+    ) -> Result<Duration, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+        self.check_blob_exists(container_name, blob_name).await?;
+
+        self.with_lease(container_name, blob_name, |lease, now| {
+            lease.break_lease(break_period, now)
+        })
+        .await
+    }
+
+    async fn change_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+        proposed_lease_id: &str,
+    ) -> Result<String, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+        self.check_blob_exists(container_name, blob_name).await?;
+
+        self.with_lease(container_name, blob_name, |lease, now| {
+            lease.change(lease_id, proposed_lease_id, now)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl BlobSnapshotBackend for FileConnectionData {
+    async fn create_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        let result =
+            crate::sdk_files::blob_snapshots::create(self, container_name, blob_name).await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn get_list_of_blob_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError> {
+        crate::sdk_files::blob_snapshots::get_list(self, container_name, blob_name).await
+    }
+
+    async fn get_blob_snapshot_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        let result = crate::sdk_files::blob_snapshots::get_properties(
+            self,
+            container_name,
+            blob_name,
+            snapshot,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn download_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let result =
+            crate::sdk_files::blob_snapshots::download(self, container_name, blob_name, snapshot)
+                .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn get_blob_snapshot_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let result = crate::sdk_files::blob_snapshots::get_pages(
+            self,
+            container_name,
+            blob_name,
+            snapshot,
+            start_page_no,
+            pages_amount,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn delete_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        let result =
+            crate::sdk_files::blob_snapshots::delete(self, container_name, blob_name, snapshot)
+                .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn delete_blob_with_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        option: DeleteSnapshotsOption,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_blob_exists(container_name, blob_name).await?;
+
+        if let DeleteSnapshotsOption::Include = option {
+            self.check_lease_access(container_name, blob_name, lease_id, true)
+                .await?;
+
+            self.keep_deleted(container_name, blob_name, &BlobAccessConditions::new())
+                .await?;
+
+            self.drop_page_blob_data(self.get_page_blob_id(container_name, blob_name))
+                .await;
+
+            crate::sdk_files::blobs::delete(
+                self,
+                container_name,
+                blob_name,
+                &BlobAccessConditions::new(),
+            )
+            .await?;
+
+            self.drop_lease(container_name, blob_name).await;
+        }
+
+        crate::sdk_files::blob_snapshots::delete_all(self, container_name, blob_name).await
+    }
+}
+
+#[async_trait]
+impl BlobVersionBackend for FileConnectionData {
+    async fn get_list_of_blob_versions(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<BlobVersionItem>, AzureStorageError> {
+        crate::sdk_files::blob_versions::get_list(
+            self,
+            container_name,
+            self.get_retention_policy(),
+            self.now(),
+        )
+        .await
+    }
+
+    async fn get_blob_version_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        let result = crate::sdk_files::blob_versions::get_properties(
+            self,
+            container_name,
+            blob_name,
+            version_id,
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn download_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let result =
+            crate::sdk_files::blob_versions::download(self, container_name, blob_name, version_id)
+                .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn promote_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(container_name, blob_name, lease_id, true)
+            .await?;
+
+        self.drop_page_blob_data(self.get_page_blob_id(container_name, blob_name))
+            .await;
+
+        let result = crate::sdk_files::blob_versions::promote(
+            self,
+            container_name,
+            blob_name,
+            version_id,
+            self.get_retention_policy(),
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn undelete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        let _write_lock = self.lock_blob_writes().await;
+
+        let result = crate::sdk_files::blob_versions::undelete(
+            self,
+            container_name,
+            blob_name,
+            self.get_retention_policy(),
+            self.now(),
+        )
+        .await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    async fn get_list_of_deleted_blob_containers(
+        &self,
+    ) -> Result<Vec<DeletedBlobContainer>, AzureStorageError> {
+        crate::sdk_files::blob_versions::get_list_of_deleted_containers(
+            self,
+            self.get_retention_policy(),
+            self.now(),
+        )
+        .await
+    }
+
+    async fn undelete_blob_container(
+        &self,
+        container_name: &str,
+        version: &str,
+    ) -> Result<(), AzureStorageError> {
+        crate::sdk_files::blob_versions::undelete_container(
+            self,
+            container_name,
+            version,
+            self.get_retention_policy(),
+            self.now(),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl BlobCopyBackend for FileConnectionData {
+    async fn start_copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        let (source_container_name, source_blob_name) = source.get_local_blob()?;
+
+        let _write_lock = self.lock_blob_writes().await;
+
+        self.check_lease_access(container_name, blob_name, lease_id, true)
+            .await?;
+
+        // The source is checked first, so a copy which fails does not keep a version of the destination
+        crate::sdk_files::blobs::get_blob_properties(self, source_container_name, source_blob_name)
+            .await?;
+
+        self.keep_version(container_name, blob_name, &BlobAccessConditions::new())
+            .await?;
+
+        self.drop_page_blob_data(self.get_page_blob_id(container_name, blob_name))
+            .await;
+
+        crate::sdk_files::blob_copy::copy(
+            self,
+            container_name,
+            blob_name,
+            source_container_name,
+            source_blob_name,
+        )
+        .await
+    }
+
+    async fn copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        self.start_copy_from_url(container_name, blob_name, source, lease_id)
+            .await
+    }
+
+    async fn get_copy_status(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Option<BlobCopyState>, AzureStorageError> {
+        let result =
+            crate::sdk_files::blob_copy::get_copy_state(self, container_name, blob_name).await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+
+    /// Copies complete at once, so there is never a pending one
+    async fn abort_copy(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        _copy_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.check_blob_exists(container_name, blob_name).await?;
+
+        Err(AzureStorageError::NoPendingCopyOperation(None))
+    }
+}
+
+#[async_trait]
+impl BlobStreamBackend for FileConnectionData {
+    async fn open_blob_stream(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        chunk_size: usize,
+    ) -> Result<BlobDownloadStream, AzureStorageError> {
+        let result =
+            crate::sdk_files::blob_stream::open(self, container_name, blob_name, chunk_size).await;
+
+        crate::sdk_files::containers::check_error_if_container_exists(result, self, container_name)
+            .await
+    }
+}
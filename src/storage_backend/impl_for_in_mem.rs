@@ -0,0 +1,580 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    append_blob::{AppendBlockConditions, AppendBlockResult, CreateAppendBlobOptions},
+    blob::{BlobAccessConditions, BlobHttpHeaders, BlobProperties, DownloadedBlob},
+    blob_copy::{BlobCopyState, CopySource},
+    blob_lease::LeaseDuration,
+    blob_snapshot::DeleteSnapshotsOption,
+    blob_stream::BlobDownloadStream,
+    blob_version::{BlobVersionItem, DeletedBlobContainer},
+    block_blob::{BlockList, BlockListItem, BlockListType, UploadBlockBlobOptions},
+    connection::in_mem::{operations::get_container, MemBlobChunkReader, MemStorageData},
+    AzureStorageError,
+};
+
+use super::{
+    AppendBlobBackend, BlobBackend, BlobContainersBackend, BlobCopyBackend, BlobLeaseBackend,
+    BlobSnapshotBackend, BlobStreamBackend, BlobVersionBackend, BlockBlobBackend, PageBlobBackend,
+};
+
+#[async_trait]
+impl BlobContainersBackend for MemStorageData {
+    async fn create_container_if_not_exists(
+        &self,
+        container_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.create(container_name).await;
+        Ok(())
+    }
+
+    async fn delete_container(&self, container_name: &str) -> Result<(), AzureStorageError> {
+        if self.delete(container_name).await {
+            Ok(())
+        } else {
+            Err(AzureStorageError::ContainerNotFound(None))
+        }
+    }
+
+    async fn delete_container_if_exists(
+        &self,
+        container_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.delete(container_name).await;
+        Ok(())
+    }
+
+    async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, AzureStorageError> {
+        Ok(self.get_list().await)
+    }
+
+    async fn get_list_of_blobs(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        Ok(container.get_list_of_blobs().await)
+    }
+}
+
+#[async_trait]
+impl BlobBackend for MemStorageData {
+    async fn get_blob_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.get_blob_properties(blob_name).await
+    }
+
+    async fn download_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.download(blob_name).await
+    }
+
+    async fn download_range(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        offset: u64,
+        length: Option<u64>,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.download_range(blob_name, offset, length).await
+    }
+
+    async fn download_blob_with_conditions(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<DownloadedBlob, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .download_with_conditions(blob_name, &conditions)
+            .await
+    }
+
+    async fn delete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .delete_with_conditions(blob_name, &conditions)
+            .await
+    }
+
+    async fn delete_blob_if_exists(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.delete(blob_name, lease_id).await
+    }
+
+    async fn set_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        metadata: HashMap<String, String>,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .set_blob_metadata(blob_name, metadata, &conditions)
+            .await
+    }
+
+    async fn set_blob_http_headers(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        http_headers: BlobHttpHeaders,
+        conditions: BlobAccessConditions,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .set_blob_http_headers(blob_name, http_headers, &conditions)
+            .await
+    }
+
+    async fn get_blob_metadata(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<HashMap<String, String>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.get_blob_metadata(blob_name).await
+    }
+}
+
+#[async_trait]
+impl BlockBlobBackend for MemStorageData {
+    async fn upload_block_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: Vec<u8>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+
+        container
+            .upload_block_blob(blob_name.to_string(), content, options)
+            .await
+    }
+
+    async fn put_block(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        block_id: &str,
+        content: Vec<u8>,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .put_block(blob_name, block_id, content, lease_id)
+            .await
+    }
+
+    async fn put_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        blocks: Vec<BlockListItem>,
+        options: UploadBlockBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+
+        container
+            .commit_block_list(blob_name.to_string(), blocks, options)
+            .await
+    }
+
+    async fn get_block_list(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        list_type: BlockListType,
+    ) -> Result<BlockList, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.get_block_list(blob_name, list_type).await
+    }
+}
+
+#[async_trait]
+impl PageBlobBackend for MemStorageData {
+    async fn create_page_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        pages_amount: usize,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .create_page_blob(blob_name, pages_amount, metadata)
+            .await
+    }
+
+    async fn resize_page_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        pages_amount: usize,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.resize_page_blob(blob_name, pages_amount).await
+    }
+
+    async fn get_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+
+        container
+            .get_page_blob_pages(blob_name, start_page_no, pages_amount)
+            .await
+    }
+
+    async fn save_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        start_page_no: usize,
+        payload: Vec<u8>,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+
+        container
+            .upload_pages_to_page_blob(blob_name, start_page_no, payload, &conditions)
+            .await
+    }
+}
+
+#[async_trait]
+impl AppendBlobBackend for MemStorageData {
+    async fn create_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        options: CreateAppendBlobOptions,
+    ) -> Result<String, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+
+        container
+            .create_append_blob(blob_name.to_string(), options)
+            .await
+    }
+
+    async fn append_block(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        content: Vec<u8>,
+        conditions: AppendBlockConditions,
+    ) -> Result<AppendBlockResult, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+
+        container
+            .append_block(blob_name, content, &conditions)
+            .await
+    }
+
+    async fn seal_append_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+
+        container.seal_append_blob(blob_name, &conditions).await
+    }
+}
+
+#[async_trait]
+impl BlobLeaseBackend for MemStorageData {
+    async fn acquire_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        duration: LeaseDuration,
+        proposed_lease_id: Option<&str>,
+    ) -> Result<String, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .acquire_lease(blob_name, duration, proposed_lease_id)
+            .await
+    }
+
+    async fn renew_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.renew_lease(blob_name, lease_id).await
+    }
+
+    async fn release_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.release_lease(blob_name, lease_id).await
+    }
+
+    async fn break_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        break_period: Option<Duration>,
+    ) -> Result<Duration, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.break_lease(blob_name, break_period).await
+    }
+
+    async fn change_blob_lease(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        lease_id: &str,
+        proposed_lease_id: &str,
+    ) -> Result<String, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .change_lease(blob_name, lease_id, proposed_lease_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl BlobSnapshotBackend for MemStorageData {
+    async fn create_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<String, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.create_snapshot(blob_name).await
+    }
+
+    async fn get_list_of_blob_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Vec<String>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        Ok(container.get_list_of_snapshots(blob_name).await)
+    }
+
+    async fn get_blob_snapshot_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.get_snapshot_properties(blob_name, snapshot).await
+    }
+
+    async fn download_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.download_snapshot(blob_name, snapshot).await
+    }
+
+    async fn get_blob_snapshot_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .get_snapshot_pages(blob_name, snapshot, start_page_no, pages_amount)
+            .await
+    }
+
+    async fn delete_blob_snapshot(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        snapshot: &str,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.delete_snapshot(blob_name, snapshot).await
+    }
+
+    async fn delete_blob_with_snapshots(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        option: DeleteSnapshotsOption,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .delete_with_snapshots(blob_name, option, lease_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl BlobVersionBackend for MemStorageData {
+    async fn get_list_of_blob_versions(
+        &self,
+        container_name: &str,
+    ) -> Result<Vec<BlobVersionItem>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        Ok(container.get_list_of_versions().await)
+    }
+
+    async fn get_blob_version_properties(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<BlobProperties, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .get_version_properties(blob_name, version_id)
+            .await
+    }
+
+    async fn download_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+    ) -> Result<Vec<u8>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.download_version(blob_name, version_id).await
+    }
+
+    async fn promote_blob_version(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        version_id: &str,
+        lease_id: Option<&str>,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container
+            .promote_version(blob_name, version_id, lease_id)
+            .await
+    }
+
+    async fn undelete_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.undelete(blob_name).await
+    }
+
+    async fn get_list_of_deleted_blob_containers(
+        &self,
+    ) -> Result<Vec<DeletedBlobContainer>, AzureStorageError> {
+        Ok(self.get_list_of_deleted().await)
+    }
+
+    async fn undelete_blob_container(
+        &self,
+        container_name: &str,
+        version: &str,
+    ) -> Result<(), AzureStorageError> {
+        self.undelete(container_name, version).await
+    }
+}
+
+#[async_trait]
+impl BlobCopyBackend for MemStorageData {
+    async fn start_copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        let (source_container_name, source_blob_name) = source.get_local_blob()?;
+
+        let source_container = get_container(self, source_container_name).await?;
+        let source = source_container.get_copy_source(source_blob_name).await?;
+
+        let container = get_container(self, container_name).await?;
+        container.copy_blob(blob_name, source, lease_id).await
+    }
+
+    async fn copy_from_url(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        source: &CopySource,
+        lease_id: Option<&str>,
+    ) -> Result<BlobCopyState, AzureStorageError> {
+        self.start_copy_from_url(container_name, blob_name, source, lease_id)
+            .await
+    }
+
+    async fn get_copy_status(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> Result<Option<BlobCopyState>, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.get_copy_state(blob_name).await
+    }
+
+    async fn abort_copy(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        _copy_id: &str,
+    ) -> Result<(), AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        container.abort_copy(blob_name).await
+    }
+}
+
+#[async_trait]
+impl BlobStreamBackend for MemStorageData {
+    async fn open_blob_stream(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        chunk_size: usize,
+    ) -> Result<BlobDownloadStream, AzureStorageError> {
+        let container = get_container(self, container_name).await?;
+        MemBlobChunkReader::open(container, blob_name, chunk_size).await
+    }
+}
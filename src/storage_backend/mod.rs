@@ -0,0 +1,25 @@
+mod append_blob_backend;
+mod blob_backend;
+mod blob_containers_backend;
+mod blob_copy_backend;
+mod blob_lease_backend;
+mod blob_snapshot_backend;
+mod blob_stream_backend;
+mod blob_version_backend;
+mod block_blob_backend;
+mod impl_for_azure_storage;
+mod impl_for_files;
+mod impl_for_in_mem;
+mod page_blob_backend;
+mod storage_backend;
+pub use append_blob_backend::*;
+pub use blob_backend::*;
+pub use blob_containers_backend::*;
+pub use blob_copy_backend::*;
+pub use blob_lease_backend::*;
+pub use blob_snapshot_backend::*;
+pub use blob_stream_backend::*;
+pub use blob_version_backend::*;
+pub use block_blob_backend::*;
+pub use page_blob_backend::*;
+pub use storage_backend::*;
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::{blob::BlobAccessConditions, AzureStorageError};
+
+#[async_trait]
+pub trait PageBlobBackend {
+    async fn create_page_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        pages_amount: usize,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), AzureStorageError>;
+
+    async fn resize_page_blob(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        pages_amount: usize,
+    ) -> Result<(), AzureStorageError>;
+
+    async fn get_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        start_page_no: usize,
+        pages_amount: usize,
+    ) -> Result<Vec<u8>, AzureStorageError>;
+
+    /// Returns ETag of the blob after the write
+    async fn save_pages(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+        start_page_no: usize,
+        payload: Vec<u8>,
+        conditions: BlobAccessConditions,
+    ) -> Result<String, AzureStorageError>;
+}
@@ -0,0 +1,299 @@
+use crate::AzureStorageError;
+
+use super::{
+    AppendBlobBackend, BlobBackend, BlobContainersBackend, BlobCopyBackend, BlobLeaseBackend,
+    BlobSnapshotBackend, BlobStreamBackend, BlobVersionBackend, BlockBlobBackend, PageBlobBackend,
+};
+
+/// Everything a blob storage has to provide to be plugged in as
+/// `AzureStorageConnection::Custom`. Implemented automatically for any type
+/// implementing the whole backend family.
+///
+/// Only containers, blob properties, download, delete, block blob upload and page blobs are required.
+/// The other operations fail with `FeatureNotSupported` unless the backend implements them.
+pub trait StorageBackend:
+    BlobContainersBackend
+    + BlobBackend
+    + BlockBlobBackend
+    + PageBlobBackend
+    + AppendBlobBackend
+    + BlobLeaseBackend
+    + BlobSnapshotBackend
+    + BlobVersionBackend
+    + BlobCopyBackend
+    + BlobStreamBackend
+    + Send
+    + Sync
+{
+}
+
+impl<T> StorageBackend for T where
+    T: BlobContainersBackend
+        + BlobBackend
+        + BlockBlobBackend
+        + PageBlobBackend
+        + AppendBlobBackend
+        + BlobLeaseBackend
+        + BlobSnapshotBackend
+        + BlobVersionBackend
+        + BlobCopyBackend
+        + BlobStreamBackend
+        + Send
+        + Sync
+{
+}
+
+pub(crate) fn not_supported(operation: &str) -> AzureStorageError {
+    AzureStorageError::FeatureNotSupported {
+        msg: format!("{} is not implemented by the storage backend", operation),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::{
+        blob::{BlobAccessConditions, BlobApi, BlobProperties},
+        blob_container::BlobContainersApi,
+        blob_lease::{BlobLeaseApi, LeaseDuration},
+        block_blob::{BlockBlobApi, UploadBlockBlobOptions},
+        connection::in_mem::MemStorageData,
+        page_blob::AzurePageBlobStorage,
+        AzureStorageConnection, AzureStorageError,
+    };
+
+    /// Custom backend implementing only the required operations
+    struct MinimalBackend(MemStorageData);
+
+    #[async_trait]
+    impl BlobContainersBackend for MinimalBackend {
+        async fn create_container_if_not_exists(
+            &self,
+            container_name: &str,
+        ) -> Result<(), AzureStorageError> {
+            self.0.create_container_if_not_exists(container_name).await
+        }
+
+        async fn delete_container(&self, container_name: &str) -> Result<(), AzureStorageError> {
+            self.0.delete_container(container_name).await
+        }
+
+        async fn delete_container_if_exists(
+            &self,
+            container_name: &str,
+        ) -> Result<(), AzureStorageError> {
+            self.0.delete_container_if_exists(container_name).await
+        }
+
+        async fn get_list_of_blob_containers(&self) -> Result<Vec<String>, AzureStorageError> {
+            self.0.get_list_of_blob_containers().await
+        }
+
+        async fn get_list_of_blobs(
+            &self,
+            container_name: &str,
+        ) -> Result<Vec<String>, AzureStorageError> {
+            self.0.get_list_of_blobs(container_name).await
+        }
+    }
+
+    #[async_trait]
+    impl BlobBackend for MinimalBackend {
+        async fn get_blob_properties(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+        ) -> Result<BlobProperties, AzureStorageError> {
+            self.0.get_blob_properties(container_name, blob_name).await
+        }
+
+        async fn download_blob(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+        ) -> Result<Vec<u8>, AzureStorageError> {
+            BlobBackend::download_blob(&self.0, container_name, blob_name).await
+        }
+
+        async fn delete_blob(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+            conditions: BlobAccessConditions,
+        ) -> Result<(), AzureStorageError> {
+            BlobBackend::delete_blob(&self.0, container_name, blob_name, conditions).await
+        }
+
+        async fn delete_blob_if_exists(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+            lease_id: Option<&str>,
+        ) -> Result<(), AzureStorageError> {
+            BlobBackend::delete_blob_if_exists(&self.0, container_name, blob_name, lease_id).await
+        }
+    }
+
+    #[async_trait]
+    impl BlockBlobBackend for MinimalBackend {
+        async fn upload_block_blob(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+            content: Vec<u8>,
+            options: UploadBlockBlobOptions,
+        ) -> Result<String, AzureStorageError> {
+            BlockBlobBackend::upload_block_blob(
+                &self.0,
+                container_name,
+                blob_name,
+                content,
+                options,
+            )
+            .await
+        }
+    }
+
+    #[async_trait]
+    impl PageBlobBackend for MinimalBackend {
+        async fn create_page_blob(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+            pages_amount: usize,
+            metadata: HashMap<String, String>,
+        ) -> Result<(), AzureStorageError> {
+            self.0
+                .create_page_blob(container_name, blob_name, pages_amount, metadata)
+                .await
+        }
+
+        async fn resize_page_blob(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+            pages_amount: usize,
+        ) -> Result<(), AzureStorageError> {
+            self.0
+                .resize_page_blob(container_name, blob_name, pages_amount)
+                .await
+        }
+
+        async fn get_pages(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+            start_page_no: usize,
+            pages_amount: usize,
+        ) -> Result<Vec<u8>, AzureStorageError> {
+            self.0
+                .get_pages(container_name, blob_name, start_page_no, pages_amount)
+                .await
+        }
+
+        async fn save_pages(
+            &self,
+            container_name: &str,
+            blob_name: &str,
+            start_page_no: usize,
+            payload: Vec<u8>,
+            conditions: BlobAccessConditions,
+        ) -> Result<String, AzureStorageError> {
+            self.0
+                .save_pages(
+                    container_name,
+                    blob_name,
+                    start_page_no,
+                    payload,
+                    conditions,
+                )
+                .await
+        }
+    }
+
+    impl AppendBlobBackend for MinimalBackend {}
+    impl BlobLeaseBackend for MinimalBackend {}
+    impl BlobSnapshotBackend for MinimalBackend {}
+    impl BlobVersionBackend for MinimalBackend {}
+    impl BlobCopyBackend for MinimalBackend {}
+    impl BlobStreamBackend for MinimalBackend {}
+
+    #[tokio::test]
+    async fn test_custom_backend_is_used_by_every_api() {
+        let connection = Arc::new(AzureStorageConnection::new_custom(Arc::new(
+            MemStorageData::new(),
+        )));
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "block-blob", vec![1u8, 2u8, 3u8])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![1u8, 2u8, 3u8],
+            connection
+                .download_blob("test", "block-blob")
+                .await
+                .unwrap()
+        );
+
+        let page_blob = AzurePageBlobStorage::new(connection.clone(), "test", "page-blob").await;
+
+        page_blob.create(2).await.unwrap();
+        page_blob.save_pages(1, vec![5u8; 512]).await.unwrap();
+
+        assert_eq!(vec![5u8; 512], page_blob.get_pages(1, 1).await.unwrap());
+        assert_eq!(
+            1024,
+            page_blob.get_blob_properties().await.unwrap().blob_size
+        );
+
+        let mut blobs = connection.get_list_of_blobs("test").await.unwrap();
+        blobs.sort();
+        assert_eq!(vec!["block-blob", "page-blob"], blobs);
+
+        connection.delete_blob("test", "block-blob").await.unwrap();
+
+        let result = connection.get_blob_properties("test", "block-blob").await;
+        assert!(matches!(result, Err(AzureStorageError::BlobNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_custom_backend_without_optional_operations() {
+        let connection = Arc::new(AzureStorageConnection::new_custom(Arc::new(
+            MinimalBackend(MemStorageData::new()),
+        )));
+
+        connection
+            .create_container_if_not_exists("test")
+            .await
+            .unwrap();
+
+        connection
+            .upload_block_blob("test", "blob", vec![1u8, 2u8, 3u8])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![1u8, 2u8, 3u8],
+            connection.download_blob("test", "blob").await.unwrap()
+        );
+
+        let result = connection
+            .acquire_blob_lease("test", "blob", LeaseDuration::Infinite, None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AzureStorageError::FeatureNotSupported { .. })
+        ));
+    }
+}
@@ -33,6 +33,7 @@ impl<TEntity: TableStorageEntity> TableStorage<TEntity> {
             AzureStorageConnection::InMemory(data) => {
                 data.create_table(self.table_name.as_str()).await
             }
+            AzureStorageConnection::Custom(_) => Err(get_custom_backend_error()),
         }
     }
 
@@ -61,6 +62,7 @@ impl<TEntity: TableStorageEntity> TableStorage<TEntity> {
                     Ok(Some(TableNamesChunk::from_list(tables)))
                 }
             }
+            AzureStorageConnection::Custom(_) => Err(get_custom_backend_error()),
         }
     }
 
@@ -90,6 +92,7 @@ impl<TEntity: TableStorageEntity> TableStorage<TEntity> {
                     Ok(None)
                 }
             }
+            AzureStorageConnection::Custom(_) => Err(get_custom_backend_error()),
         }
     }
 
@@ -128,6 +131,7 @@ impl<TEntity: TableStorageEntity> TableStorage<TEntity> {
                     None => Ok(None),
                 }
             }
+            AzureStorageConnection::Custom(_) => Err(get_custom_backend_error()),
         }
     }
 
@@ -164,6 +168,7 @@ impl<TEntity: TableStorageEntity> TableStorage<TEntity> {
                     None => Ok(None),
                 }
             }
+            AzureStorageConnection::Custom(_) => Err(get_custom_backend_error()),
         }
     }
 
@@ -191,6 +196,7 @@ impl<TEntity: TableStorageEntity> TableStorage<TEntity> {
 
                 Ok(())
             }
+            AzureStorageConnection::Custom(_) => Err(get_custom_backend_error()),
         }
     }
 
@@ -211,6 +217,7 @@ impl<TEntity: TableStorageEntity> TableStorage<TEntity> {
                     .insert(entity.get_partition_key(), entity.get_row_key(), payload)
                     .await
             }
+            AzureStorageConnection::Custom(_) => Err(get_custom_backend_error()),
         }
     }
 
@@ -235,6 +242,11 @@ impl<TEntity: TableStorageEntity> TableStorage<TEntity> {
 
                 Ok(table.delete(partition_key, row_key).await)
             }
+            AzureStorageConnection::Custom(_) => Err(get_custom_backend_error()),
         }
     }
 }
+
+fn get_custom_backend_error() -> TableStorageError {
+    TableStorageError::Unknown("Table storage is not supported by a custom backend".to_string())
+}
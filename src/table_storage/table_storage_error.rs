@@ -1,17 +1,108 @@
 use flurl::FlUrlError;
 
+use crate::{AzureStorageError, ErrorKind, StorageError};
+
 #[derive(Debug)]
 pub enum TableStorageError {
     TableNotFound,
     EntityAlreadyExists,
     ResourceNotFound,
     TableAlreadyExists,
+    /// odata.error with a code which has no own variant
+    ServiceError {
+        code: String,
+        status_code: u16,
+        message: Option<String>,
+    },
     FlUrlError(FlUrlError),
     Unknown(String),
 }
 
+impl std::fmt::Display for TableStorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableStorageError::TableNotFound => write!(f, "TableNotFound"),
+            TableStorageError::EntityAlreadyExists => write!(f, "EntityAlreadyExists"),
+            TableStorageError::ResourceNotFound => write!(f, "ResourceNotFound"),
+            TableStorageError::TableAlreadyExists => write!(f, "TableAlreadyExists"),
+            TableStorageError::ServiceError {
+                code,
+                status_code,
+                message,
+            } => match message {
+                Some(message) => write!(f, "{} (HTTP {}): {}", code, status_code, message),
+                None => write!(f, "{} (HTTP {})", code, status_code),
+            },
+            TableStorageError::FlUrlError(err) => write!(f, "HTTP transport error: {:?}", err),
+            TableStorageError::Unknown(msg) => write!(f, "Unknown error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TableStorageError {}
+
+impl StorageError for TableStorageError {
+    fn get_error_kind(&self) -> ErrorKind {
+        match self {
+            TableStorageError::TableNotFound | TableStorageError::ResourceNotFound => {
+                ErrorKind::NotFound
+            }
+            TableStorageError::EntityAlreadyExists | TableStorageError::TableAlreadyExists => {
+                ErrorKind::Conflict
+            }
+            TableStorageError::ServiceError {
+                code, status_code, ..
+            } => match code.as_str() {
+                "AuthenticationFailed" | "AuthorizationFailure" => ErrorKind::AuthFailure,
+                "ServerBusy" => ErrorKind::Throttled,
+                _ => ErrorKind::from_status_code(*status_code),
+            },
+            TableStorageError::FlUrlError(_) => ErrorKind::Transport,
+            TableStorageError::Unknown(_) => ErrorKind::Other,
+        }
+    }
+}
+
 impl From<FlUrlError> for TableStorageError {
     fn from(src: FlUrlError) -> Self {
         Self::FlUrlError(src)
     }
 }
+
+impl From<AzureStorageError> for TableStorageError {
+    fn from(src: AzureStorageError) -> Self {
+        match src {
+            AzureStorageError::FlUrlError(err) => Self::FlUrlError(err),
+            AzureStorageError::TableStorageError(err) => err,
+            src => match src.get_service_error() {
+                Some(err) => Self::ServiceError {
+                    code: err.code.to_string(),
+                    status_code: err.status_code,
+                    message: err.message.clone(),
+                },
+                None => Self::Unknown(format!("{:?}", src)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_and_blob_errors_share_kinds() {
+        assert!(TableStorageError::TableNotFound.is_not_found());
+        assert!(TableStorageError::EntityAlreadyExists.is_conflict());
+
+        let err: AzureStorageError = TableStorageError::ResourceNotFound.into();
+        assert!(err.is_not_found());
+
+        let err = TableStorageError::ServiceError {
+            code: "ServerBusy".to_string(),
+            status_code: 503,
+            message: None,
+        };
+        assert!(err.is_retryable());
+    }
+}
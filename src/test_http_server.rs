@@ -0,0 +1,145 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+pub struct TestHttpResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl TestHttpResponse {
+    pub fn new(status_code: u16) -> Self {
+        Self {
+            status_code,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn json(body: &str) -> Self {
+        Self::new(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(body.as_bytes().to_vec())
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut result = format!("HTTP/1.1 {} Status\r\n", self.status_code);
+
+        for (name, value) in &self.headers {
+            result.push_str(format!("{}: {}\r\n", name, value).as_str());
+        }
+
+        result.push_str(
+            format!(
+                "Content-Length: {}\r\nConnection: close\r\n\r\n",
+                self.body.len()
+            )
+            .as_str(),
+        );
+
+        let mut result = result.into_bytes();
+        result.extend_from_slice(self.body.as_slice());
+        result
+    }
+}
+
+/// Minimal http server standing in for Azure endpoints in tests.
+/// Responses are served in order, the last one repeats. Raw requests are kept for assertions.
+pub struct TestHttpServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestHttpServer {
+    pub async fn start(responses: Vec<TestHttpResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let requests_to_write = requests.clone();
+
+        let responses: Vec<Vec<u8>> = responses.iter().map(|itm| itm.serialize()).collect();
+
+        tokio::spawn(async move {
+            let mut response_no = 0;
+
+            loop {
+                let (mut stream, _) = match listener.accept().await {
+                    Ok(result) => result,
+                    Err(_) => return,
+                };
+
+                let request = read_request(&mut stream).await;
+                requests_to_write.lock().unwrap().push(request);
+
+                let response = &responses[response_no.min(responses.len() - 1)];
+                response_no += 1;
+
+                let _ = stream.write_all(response.as_slice()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    pub async fn start_json(body: &str) -> Self {
+        Self::start(vec![TestHttpResponse::json(body)]).await
+    }
+
+    pub fn get_requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    loop {
+        let read = stream.read(&mut chunk).await.unwrap_or(0);
+
+        if read == 0 {
+            break;
+        }
+
+        buffer.extend_from_slice(&chunk[..read]);
+
+        let request = String::from_utf8_lossy(buffer.as_slice()).to_string();
+
+        if let Some(headers_end) = request.find("\r\n\r\n") {
+            let content_length = request[..headers_end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+
+                    if name.eq_ignore_ascii_case("content-length") {
+                        value.trim().parse::<usize>().ok()
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(0);
+
+            if buffer.len() >= headers_end + 4 + content_length {
+                break;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(buffer.as_slice()).to_string()
+}
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+
+use super::{oauth, AccessToken, TokenCache, TokenCredential, TokenCredentialError};
+
+/// Service principal authenticated by a client secret
+pub struct ClientSecretCredential {
+    tenant_id: String,
+    client_id: String,
+    client_secret: String,
+    authority_host: String,
+    cache: TokenCache,
+}
+
+impl ClientSecretCredential {
+    pub fn new(
+        tenant_id: impl Into<String>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            tenant_id: tenant_id.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            authority_host: oauth::DEFAULT_AUTHORITY_HOST.to_string(),
+            cache: TokenCache::new(),
+        }
+    }
+
+    pub fn with_authority_host(mut self, authority_host: impl Into<String>) -> Self {
+        self.authority_host = authority_host.into();
+        self
+    }
+
+    pub fn with_token_cache(mut self, cache: TokenCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    async fn request_token(&self, scope: &str) -> Result<AccessToken, TokenCredentialError> {
+        oauth::request_token(
+            self.authority_host.as_str(),
+            self.tenant_id.as_str(),
+            &[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("scope", scope),
+            ],
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl TokenCredential for ClientSecretCredential {
+    async fn get_token(&self, scope: &str) -> Result<AccessToken, TokenCredentialError> {
+        self.cache
+            .get_or_fetch(scope, || self.request_token(scope))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_http_server::TestHttpServer, token_credential::STORAGE_SCOPE};
+
+    #[tokio::test]
+    async fn test_client_secret_token_is_requested_once() {
+        let server = TestHttpServer::start_json(
+            r#"{"token_type":"Bearer","expires_in":3599,"access_token":"secret-token"}"#,
+        )
+        .await;
+
+        let credential = ClientSecretCredential::new("my-tenant", "my-client", "sec/ret+")
+            .with_authority_host(server.url.as_str());
+
+        for _ in 0..2 {
+            let token = credential.get_token(STORAGE_SCOPE).await.unwrap();
+            assert_eq!("secret-token", token.token);
+        }
+
+        let requests = server.get_requests();
+        assert_eq!(1, requests.len());
+
+        let request = requests[0].as_str();
+        assert!(request.starts_with("POST /my-tenant/oauth2/v2.0/token"));
+        assert!(request.contains("grant_type=client_credentials"));
+        assert!(request.contains("client_id=my-client"));
+        assert!(request.contains("client_secret=sec%2Fret%2B"));
+        assert!(request.contains("scope=https%3A%2F%2Fstorage.azure.com%2F.default"));
+    }
+}
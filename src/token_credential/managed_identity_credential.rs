@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use flurl::FlUrl;
+
+use super::{oauth, AccessToken, TokenCache, TokenCredential, TokenCredentialError};
+
+pub const IMDS_TOKEN_ENDPOINT: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+const IMDS_API_VERSION: &str = "2018-02-01";
+
+/// Managed identity of the VM/VMSS/AKS node, resolved through the Instance Metadata Service
+pub struct ManagedIdentityCredential {
+    endpoint: String,
+    client_id: Option<String>,
+    cache: TokenCache,
+}
+
+impl ManagedIdentityCredential {
+    /// System-assigned identity
+    pub fn new() -> Self {
+        Self {
+            endpoint: IMDS_TOKEN_ENDPOINT.to_string(),
+            client_id: None,
+            cache: TokenCache::new(),
+        }
+    }
+
+    /// User-assigned identity
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn with_token_cache(mut self, cache: TokenCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    async fn request_token(&self, scope: &str) -> Result<AccessToken, TokenCredentialError> {
+        // IMDS works with resources, not with v2 scopes
+        let resource = scope.trim_end_matches(".default");
+
+        let mut fl_url = FlUrl::new(self.endpoint.as_str())
+            .append_query_param("api-version", Some(IMDS_API_VERSION))
+            .append_query_param("resource", Some(resource))
+            .with_header("Metadata", "true");
+
+        if let Some(client_id) = self.client_id.as_ref() {
+            fl_url = fl_url.append_query_param("client_id", Some(client_id.as_str()));
+        }
+
+        let response = fl_url.get().await?;
+
+        oauth::read_token_response(response).await
+    }
+}
+
+impl Default for ManagedIdentityCredential {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TokenCredential for ManagedIdentityCredential {
+    async fn get_token(&self, scope: &str) -> Result<AccessToken, TokenCredentialError> {
+        self.cache
+            .get_or_fetch(scope, || self.request_token(scope))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_http_server::TestHttpServer, token_credential::STORAGE_SCOPE};
+
+    #[tokio::test]
+    async fn test_imds_token() {
+        let server = TestHttpServer::start_json(
+            r#"{"access_token":"imds-token","expires_in":"3599","expires_on":"1893456000","resource":"https://storage.azure.com/","token_type":"Bearer"}"#,
+        )
+        .await;
+
+        let credential = ManagedIdentityCredential::new()
+            .with_client_id("user-assigned")
+            .with_endpoint(format!("{}/metadata/identity/oauth2/token", server.url));
+
+        for _ in 0..2 {
+            let token = credential.get_token(STORAGE_SCOPE).await.unwrap();
+            assert_eq!("imds-token", token.token);
+        }
+
+        let requests = server.get_requests();
+        assert_eq!(1, requests.len());
+
+        let request = requests[0].to_lowercase();
+
+        assert!(request.starts_with("get /metadata/identity/oauth2/token?api-version=2018-02-01"));
+        assert!(request.contains("client_id=user-assigned"));
+        assert!(request.contains("metadata: true"));
+    }
+}
@@ -0,0 +1,13 @@
+mod client_secret_credential;
+mod managed_identity_credential;
+mod oauth;
+mod token_cache;
+mod token_credential;
+mod token_credential_error;
+mod workload_identity_credential;
+pub use client_secret_credential::*;
+pub use managed_identity_credential::*;
+pub use token_cache::*;
+pub use token_credential::*;
+pub use token_credential_error::*;
+pub use workload_identity_credential::*;
@@ -0,0 +1,151 @@
+use flurl::{body::FlUrlBody, FlUrl};
+use my_json::json_reader::JsonFirstLineReader;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::{AccessToken, TokenCredentialError};
+
+pub const DEFAULT_AUTHORITY_HOST: &str = "https://login.microsoftonline.com";
+
+pub fn compile_form_body(params: &[(&str, &str)]) -> Vec<u8> {
+    let mut result = String::new();
+
+    for (key, value) in params {
+        if !result.is_empty() {
+            result.push('&');
+        }
+
+        result.push_str(key);
+        result.push('=');
+        result.push_str(crate::sas::utils::encode_query_value(value).as_str());
+    }
+
+    result.into_bytes()
+}
+
+/// Client credentials grant against the Entra ID v2 token endpoint
+pub async fn request_token(
+    authority_host: &str,
+    tenant_id: &str,
+    form_params: &[(&str, &str)],
+) -> Result<AccessToken, TokenCredentialError> {
+    let response = FlUrl::new(authority_host.trim_end_matches('/'))
+        .append_path_segment(tenant_id)
+        .append_path_segment("oauth2")
+        .append_path_segment("v2.0")
+        .append_path_segment("token")
+        .with_header("Content-Type", "application/x-www-form-urlencoded")
+        .post(FlUrlBody::Raw {
+            data: compile_form_body(form_params),
+            content_type: None,
+        })
+        .await?;
+
+    read_token_response(response).await
+}
+
+pub async fn read_token_response(
+    response: flurl::FlUrlResponse,
+) -> Result<AccessToken, TokenCredentialError> {
+    let status_code = response.get_status_code();
+    let body = response.receive_body().await?;
+
+    if status_code != 200 {
+        return Err(TokenCredentialError::TokenRequestFailed {
+            status_code,
+            body: String::from_utf8_lossy(body.as_slice()).to_string(),
+        });
+    }
+
+    parse_token_response(body.as_slice())
+}
+
+/// Reads access_token and expiration. Entra ID returns expires_in as a number,
+/// IMDS returns both expires_in and expires_on as strings.
+pub fn parse_token_response(body: &[u8]) -> Result<AccessToken, TokenCredentialError> {
+    let mut token = None;
+    let mut expires_in = None;
+    let mut expires_on = None;
+
+    for first_line in JsonFirstLineReader::new(body) {
+        let first_line = match first_line {
+            Ok(first_line) => first_line,
+            Err(_) => break,
+        };
+
+        let name = match first_line.get_name() {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+
+        let value = match first_line.get_value() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        let value = match value.as_bytes() {
+            Some(value) => String::from_utf8_lossy(value).trim_matches('"').to_string(),
+            None => continue,
+        };
+
+        match name {
+            "access_token" => token = Some(value),
+            "expires_in" => expires_in = value.parse::<i64>().ok(),
+            "expires_on" => expires_on = value.parse::<i64>().ok(),
+            _ => {}
+        }
+    }
+
+    let invalid_response =
+        || TokenCredentialError::InvalidTokenResponse(String::from_utf8_lossy(body).to_string());
+
+    let token = token.ok_or_else(invalid_response)?;
+
+    let expires_on = match (expires_in, expires_on) {
+        (Some(expires_in), _) => {
+            DateTimeAsMicroseconds::now().unix_microseconds + expires_in * 1_000_000
+        }
+        (None, Some(expires_on)) => expires_on * 1_000_000,
+        (None, None) => return Err(invalid_response()),
+    };
+
+    Ok(AccessToken {
+        token,
+        expires_on: DateTimeAsMicroseconds::new(expires_on),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entra_id_response() {
+        let body = br#"{"token_type":"Bearer","expires_in":3599,"ext_expires_in":3599,"access_token":"eyJ0eXAi"}"#;
+
+        let token = parse_token_response(body).unwrap();
+
+        assert_eq!("eyJ0eXAi", token.token);
+
+        let expires_in_seconds = (token.expires_on.unix_microseconds
+            - DateTimeAsMicroseconds::now().unix_microseconds)
+            / 1_000_000;
+
+        assert!(expires_in_seconds > 3590 && expires_in_seconds <= 3599);
+    }
+
+    #[test]
+    fn test_parse_imds_response() {
+        let body = br#"{"access_token":"eyJ0eXAi","expires_on":"1893456000","resource":"https://storage.azure.com/","token_type":"Bearer"}"#;
+
+        let token = parse_token_response(body).unwrap();
+
+        assert_eq!("eyJ0eXAi", token.token);
+        assert_eq!(1_893_456_000_000_000, token.expires_on.unix_microseconds);
+    }
+
+    #[test]
+    fn test_response_without_token_is_an_error() {
+        let result = parse_token_response(br#"{"error":"invalid_client"}"#);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use std::{collections::HashMap, future::Future, time::Duration};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+use super::{AccessToken, TokenCredentialError};
+
+pub const DEFAULT_REFRESH_BEFORE_EXPIRY: Duration = Duration::from_secs(300);
+
+/// Keeps tokens per scope and requests a new one when the cached token is about to expire.
+/// Concurrent callers wait for a single refresh instead of hitting the token endpoint each.
+pub struct TokenCache {
+    tokens: Mutex<HashMap<String, AccessToken>>,
+    refresh_before_expiry: Duration,
+}
+
+impl TokenCache {
+    pub fn new() -> Self {
+        Self::with_refresh_before_expiry(DEFAULT_REFRESH_BEFORE_EXPIRY)
+    }
+
+    pub fn with_refresh_before_expiry(refresh_before_expiry: Duration) -> Self {
+        Self {
+            tokens: Mutex::new(HashMap::new()),
+            refresh_before_expiry,
+        }
+    }
+
+    pub async fn get_or_fetch<TFuture: Future<Output = Result<AccessToken, TokenCredentialError>>>(
+        &self,
+        scope: &str,
+        fetch: impl FnOnce() -> TFuture,
+    ) -> Result<AccessToken, TokenCredentialError> {
+        let mut tokens = self.tokens.lock().await;
+
+        if let Some(token) = tokens.get(scope) {
+            if !self.needs_refresh(token) {
+                return Ok(token.clone());
+            }
+        }
+
+        let token = fetch().await?;
+
+        tokens.insert(scope.to_string(), token.clone());
+
+        Ok(token)
+    }
+
+    fn needs_refresh(&self, token: &AccessToken) -> bool {
+        let refresh_at = token.expires_on.unix_microseconds
+            - self.refresh_before_expiry.as_micros() as i64;
+
+        DateTimeAsMicroseconds::now().unix_microseconds >= refresh_at
+    }
+}
+
+impl Default for TokenCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn token_expiring_in(name: &str, seconds: i64) -> AccessToken {
+        AccessToken {
+            token: name.to_string(),
+            expires_on: DateTimeAsMicroseconds::new(
+                DateTimeAsMicroseconds::now().unix_microseconds + seconds * 1_000_000,
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_is_cached_until_refresh_window() {
+        let cache = TokenCache::new();
+        let fetches = AtomicUsize::new(0);
+
+        for _ in 0..3 {
+            let token = cache
+                .get_or_fetch(super::super::STORAGE_SCOPE, || async {
+                    fetches.fetch_add(1, Ordering::SeqCst);
+                    Ok(token_expiring_in("long", 3600))
+                })
+                .await
+                .unwrap();
+
+            assert_eq!("long", token.token);
+        }
+
+        assert_eq!(1, fetches.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_token_is_refreshed_before_expiry() {
+        let cache = TokenCache::new();
+        let fetches = AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            cache
+                .get_or_fetch(super::super::STORAGE_SCOPE, || async {
+                    fetches.fetch_add(1, Ordering::SeqCst);
+                    Ok(token_expiring_in("short", 60))
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(2, fetches.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::TokenCredentialError;
+
+pub const STORAGE_SCOPE: &str = "https://storage.azure.com/.default";
+
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires_on: DateTimeAsMicroseconds,
+}
+
+/// Source of OAuth bearer tokens. Implementations are expected to cache tokens,
+/// since the token is requested for every storage request.
+#[async_trait]
+pub trait TokenCredential: Send + Sync {
+    async fn get_token(&self, scope: &str) -> Result<AccessToken, TokenCredentialError>;
+}
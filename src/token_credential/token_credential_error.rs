@@ -0,0 +1,44 @@
+use flurl::FlUrlError;
+
+#[derive(Debug)]
+pub enum TokenCredentialError {
+    FlUrlError(FlUrlError),
+    IoError(std::io::Error),
+    MissingEnvVariable(&'static str),
+    TokenRequestFailed { status_code: u16, body: String },
+    InvalidTokenResponse(String),
+}
+
+impl std::fmt::Display for TokenCredentialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenCredentialError::FlUrlError(err) => write!(f, "Token request failed: {:?}", err),
+            TokenCredentialError::IoError(err) => write!(f, "Can not read token file: {}", err),
+            TokenCredentialError::MissingEnvVariable(name) => {
+                write!(f, "Environment variable {} is not set", name)
+            }
+            TokenCredentialError::TokenRequestFailed { status_code, body } => write!(
+                f,
+                "Token endpoint responded with status {}: {}",
+                status_code, body
+            ),
+            TokenCredentialError::InvalidTokenResponse(body) => {
+                write!(f, "Token endpoint response is not recognized: {}", body)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TokenCredentialError {}
+
+impl From<FlUrlError> for TokenCredentialError {
+    fn from(err: FlUrlError) -> Self {
+        Self::FlUrlError(err)
+    }
+}
+
+impl From<std::io::Error> for TokenCredentialError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
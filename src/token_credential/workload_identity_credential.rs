@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+
+use super::{oauth, AccessToken, TokenCache, TokenCredential, TokenCredentialError};
+
+const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+/// Workload identity federation (AKS and alike): a projected service account token
+/// from a file is exchanged for an Entra ID token
+pub struct WorkloadIdentityCredential {
+    tenant_id: String,
+    client_id: String,
+    token_file_path: String,
+    authority_host: String,
+    cache: TokenCache,
+}
+
+impl WorkloadIdentityCredential {
+    pub fn new(
+        tenant_id: impl Into<String>,
+        client_id: impl Into<String>,
+        token_file_path: impl Into<String>,
+    ) -> Self {
+        Self {
+            tenant_id: tenant_id.into(),
+            client_id: client_id.into(),
+            token_file_path: token_file_path.into(),
+            authority_host: oauth::DEFAULT_AUTHORITY_HOST.to_string(),
+            cache: TokenCache::new(),
+        }
+    }
+
+    /// Reads AZURE_TENANT_ID, AZURE_CLIENT_ID, AZURE_FEDERATED_TOKEN_FILE and optional AZURE_AUTHORITY_HOST
+    /// injected by the workload identity webhook
+    pub fn from_env() -> Result<Self, TokenCredentialError> {
+        let read_env = |name: &'static str| match std::env::var(name) {
+            Ok(value) => Ok(value),
+            Err(_) => Err(TokenCredentialError::MissingEnvVariable(name)),
+        };
+
+        let mut result = Self::new(
+            read_env("AZURE_TENANT_ID")?,
+            read_env("AZURE_CLIENT_ID")?,
+            read_env("AZURE_FEDERATED_TOKEN_FILE")?,
+        );
+
+        if let Ok(authority_host) = std::env::var("AZURE_AUTHORITY_HOST") {
+            result.authority_host = authority_host;
+        }
+
+        Ok(result)
+    }
+
+    pub fn with_authority_host(mut self, authority_host: impl Into<String>) -> Self {
+        self.authority_host = authority_host.into();
+        self
+    }
+
+    pub fn with_token_cache(mut self, cache: TokenCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    async fn request_token(&self, scope: &str) -> Result<AccessToken, TokenCredentialError> {
+        // The file is rotated by kubelet, so it is read for every token request
+        let client_assertion = tokio::fs::read_to_string(self.token_file_path.as_str()).await?;
+
+        oauth::request_token(
+            self.authority_host.as_str(),
+            self.tenant_id.as_str(),
+            &[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.client_id.as_str()),
+                ("client_assertion_type", CLIENT_ASSERTION_TYPE),
+                ("client_assertion", client_assertion.trim()),
+                ("scope", scope),
+            ],
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl TokenCredential for WorkloadIdentityCredential {
+    async fn get_token(&self, scope: &str) -> Result<AccessToken, TokenCredentialError> {
+        self.cache
+            .get_or_fetch(scope, || self.request_token(scope))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_http_server::TestHttpServer, token_credential::STORAGE_SCOPE};
+
+    #[tokio::test]
+    async fn test_federated_token_is_sent_as_client_assertion() {
+        let server = TestHttpServer::start_json(
+            r#"{"token_type":"Bearer","expires_in":3599,"access_token":"federated-token"}"#,
+        )
+        .await;
+
+        let token_file = std::env::temp_dir().join(format!("{}.jwt", uuid::Uuid::new_v4()));
+        std::fs::write(&token_file, "header.payload.signature\n").unwrap();
+
+        let credential = WorkloadIdentityCredential::new(
+            "my-tenant",
+            "my-client",
+            token_file.to_str().unwrap(),
+        )
+        .with_authority_host(server.url.as_str());
+
+        let token = credential.get_token(STORAGE_SCOPE).await.unwrap();
+
+        std::fs::remove_file(&token_file).unwrap();
+
+        assert_eq!("federated-token", token.token);
+
+        let requests = server.get_requests();
+        let request = requests[0].as_str();
+
+        assert!(request.starts_with("POST /my-tenant/oauth2/v2.0/token"));
+        assert!(request.contains("client_assertion=header.payload.signature&"));
+        assert!(request.contains(
+            "client_assertion_type=urn%3Aietf%3Aparams%3Aoauth%3Aclient-assertion-type%3Ajwt-bearer"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_file_is_an_error() {
+        let credential = WorkloadIdentityCredential::new(
+            "my-tenant",
+            "my-client",
+            "/not-existing/azure-identity-token",
+        );
+
+        let result = credential.get_token(STORAGE_SCOPE).await;
+
+        assert!(matches!(result, Err(TokenCredentialError::IoError(_))));
+    }
+}
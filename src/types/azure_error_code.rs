@@ -0,0 +1,402 @@
+/// Error codes of the Blob service (x-ms-error-code header or <Code> of the error body)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AzureErrorCode {
+    // Common storage codes
+    AccountAlreadyExists,
+    AccountBeingCreated,
+    AccountIsDisabled,
+    AuthenticationFailed,
+    AuthorizationFailure,
+    AuthorizationPermissionMismatch,
+    AuthorizationProtocolMismatch,
+    AuthorizationResourceTypeMismatch,
+    AuthorizationServiceMismatch,
+    AuthorizationSourceIPMismatch,
+    ConditionHeadersNotSupported,
+    ConditionNotMet,
+    EmptyMetadataKey,
+    InsufficientAccountPermissions,
+    InternalError,
+    InvalidAuthenticationInfo,
+    InvalidHeaderValue,
+    InvalidHttpVerb,
+    InvalidInput,
+    InvalidMd5,
+    InvalidMetadata,
+    InvalidQueryParameterValue,
+    InvalidRange,
+    InvalidResourceName,
+    InvalidUri,
+    InvalidXmlDocument,
+    InvalidXmlNodeValue,
+    Md5Mismatch,
+    MetadataTooLarge,
+    MissingContentLengthHeader,
+    MissingRequiredHeader,
+    MissingRequiredQueryParameter,
+    MissingRequiredXmlNode,
+    MultipleConditionHeadersNotSupported,
+    NoAuthenticationInformation,
+    OperationTimedOut,
+    OutOfRangeInput,
+    OutOfRangeQueryParameterValue,
+    RequestBodyTooLarge,
+    RequestUrlFailedToParse,
+    ResourceAlreadyExists,
+    ResourceNotFound,
+    ResourceTypeMismatch,
+    ServerBusy,
+    UnsupportedHeader,
+    UnsupportedHttpVerb,
+    UnsupportedQueryParameter,
+    UnsupportedXmlNode,
+    // Blob service codes
+    AppendPositionConditionNotMet,
+    BlobAlreadyExists,
+    BlobArchived,
+    BlobBeingRehydrated,
+    BlobImmutableDueToPolicy,
+    BlobIsSealed,
+    BlobNotArchived,
+    BlobNotFound,
+    BlobOverwritten,
+    BlobTierInadequateForContentLength,
+    BlobUsesCustomerSpecifiedEncryption,
+    BlockCountExceedsLimit,
+    BlockListTooLong,
+    CannotChangeToLowerTier,
+    CannotVerifyCopySource,
+    ContainerAlreadyExists,
+    ContainerBeingDeleted,
+    ContainerDisabled,
+    ContainerNotFound,
+    ContentLengthLargerThanTierLimit,
+    CopyAcrossAccountsNotSupported,
+    CopyIdMismatch,
+    FeatureVersionMismatch,
+    IncrementalCopyBlobMismatch,
+    IncrementalCopyOfEarlierVersionSnapshotNotAllowed,
+    IncrementalCopySourceMustBeSnapshot,
+    InfiniteLeaseDurationRequired,
+    InvalidBlobOrBlock,
+    InvalidBlobTier,
+    InvalidBlobType,
+    InvalidBlockId,
+    InvalidBlockList,
+    InvalidOperation,
+    InvalidPageRange,
+    InvalidSourceBlobType,
+    InvalidSourceBlobUrl,
+    InvalidVersionForPageBlobOperation,
+    LeaseAlreadyBroken,
+    LeaseAlreadyPresent,
+    LeaseIdMismatchWithBlobOperation,
+    LeaseIdMismatchWithContainerOperation,
+    LeaseIdMismatchWithLeaseOperation,
+    LeaseIdMissing,
+    LeaseIsBreakingAndCannotBeAcquired,
+    LeaseIsBreakingAndCannotBeChanged,
+    LeaseIsBrokenAndCannotBeRenewed,
+    LeaseLost,
+    LeaseNotPresentWithBlobOperation,
+    LeaseNotPresentWithContainerOperation,
+    LeaseNotPresentWithLeaseOperation,
+    MaxBlobSizeConditionNotMet,
+    NoPendingCopyOperation,
+    OperationNotAllowedOnIncrementalCopyBlob,
+    PendingCopyOperation,
+    PreviousSnapshotCannotBeNewer,
+    PreviousSnapshotNotFound,
+    PreviousSnapshotOperationNotSupported,
+    SequenceNumberConditionNotMet,
+    SequenceNumberIncrementTooLarge,
+    SnapshotCountExceeded,
+    SnapshotOperationRateExceeded,
+    SnapshotsPresent,
+    SourceConditionNotMet,
+    SystemInUse,
+    TargetConditionNotMet,
+    UnauthorizedBlobOverwrite,
+    /// Code which is not in the catalogue
+    Other(String),
+}
+
+impl AzureErrorCode {
+    pub fn parse(src: &str) -> Self {
+        match src {
+            "AccountAlreadyExists" => AzureErrorCode::AccountAlreadyExists,
+            "AccountBeingCreated" => AzureErrorCode::AccountBeingCreated,
+            "AccountIsDisabled" => AzureErrorCode::AccountIsDisabled,
+            "AuthenticationFailed" => AzureErrorCode::AuthenticationFailed,
+            "AuthorizationFailure" => AzureErrorCode::AuthorizationFailure,
+            "AuthorizationPermissionMismatch" => AzureErrorCode::AuthorizationPermissionMismatch,
+            "AuthorizationProtocolMismatch" => AzureErrorCode::AuthorizationProtocolMismatch,
+            "AuthorizationResourceTypeMismatch" => AzureErrorCode::AuthorizationResourceTypeMismatch,
+            "AuthorizationServiceMismatch" => AzureErrorCode::AuthorizationServiceMismatch,
+            "AuthorizationSourceIPMismatch" => AzureErrorCode::AuthorizationSourceIPMismatch,
+            "ConditionHeadersNotSupported" => AzureErrorCode::ConditionHeadersNotSupported,
+            "ConditionNotMet" => AzureErrorCode::ConditionNotMet,
+            "EmptyMetadataKey" => AzureErrorCode::EmptyMetadataKey,
+            "InsufficientAccountPermissions" => AzureErrorCode::InsufficientAccountPermissions,
+            "InternalError" => AzureErrorCode::InternalError,
+            "InvalidAuthenticationInfo" => AzureErrorCode::InvalidAuthenticationInfo,
+            "InvalidHeaderValue" => AzureErrorCode::InvalidHeaderValue,
+            "InvalidHttpVerb" => AzureErrorCode::InvalidHttpVerb,
+            "InvalidInput" => AzureErrorCode::InvalidInput,
+            "InvalidMd5" => AzureErrorCode::InvalidMd5,
+            "InvalidMetadata" => AzureErrorCode::InvalidMetadata,
+            "InvalidQueryParameterValue" => AzureErrorCode::InvalidQueryParameterValue,
+            "InvalidRange" => AzureErrorCode::InvalidRange,
+            "InvalidResourceName" => AzureErrorCode::InvalidResourceName,
+            "InvalidUri" => AzureErrorCode::InvalidUri,
+            "InvalidXmlDocument" => AzureErrorCode::InvalidXmlDocument,
+            "InvalidXmlNodeValue" => AzureErrorCode::InvalidXmlNodeValue,
+            "Md5Mismatch" => AzureErrorCode::Md5Mismatch,
+            "MetadataTooLarge" => AzureErrorCode::MetadataTooLarge,
+            "MissingContentLengthHeader" => AzureErrorCode::MissingContentLengthHeader,
+            "MissingRequiredHeader" => AzureErrorCode::MissingRequiredHeader,
+            "MissingRequiredQueryParameter" => AzureErrorCode::MissingRequiredQueryParameter,
+            "MissingRequiredXmlNode" => AzureErrorCode::MissingRequiredXmlNode,
+            "MultipleConditionHeadersNotSupported" => AzureErrorCode::MultipleConditionHeadersNotSupported,
+            "NoAuthenticationInformation" => AzureErrorCode::NoAuthenticationInformation,
+            "OperationTimedOut" => AzureErrorCode::OperationTimedOut,
+            "OutOfRangeInput" => AzureErrorCode::OutOfRangeInput,
+            "OutOfRangeQueryParameterValue" => AzureErrorCode::OutOfRangeQueryParameterValue,
+            "RequestBodyTooLarge" => AzureErrorCode::RequestBodyTooLarge,
+            "RequestUrlFailedToParse" => AzureErrorCode::RequestUrlFailedToParse,
+            "ResourceAlreadyExists" => AzureErrorCode::ResourceAlreadyExists,
+            "ResourceNotFound" => AzureErrorCode::ResourceNotFound,
+            "ResourceTypeMismatch" => AzureErrorCode::ResourceTypeMismatch,
+            "ServerBusy" => AzureErrorCode::ServerBusy,
+            "UnsupportedHeader" => AzureErrorCode::UnsupportedHeader,
+            "UnsupportedHttpVerb" => AzureErrorCode::UnsupportedHttpVerb,
+            "UnsupportedQueryParameter" => AzureErrorCode::UnsupportedQueryParameter,
+            "UnsupportedXmlNode" => AzureErrorCode::UnsupportedXmlNode,
+            "AppendPositionConditionNotMet" => AzureErrorCode::AppendPositionConditionNotMet,
+            "BlobAlreadyExists" => AzureErrorCode::BlobAlreadyExists,
+            "BlobArchived" => AzureErrorCode::BlobArchived,
+            "BlobBeingRehydrated" => AzureErrorCode::BlobBeingRehydrated,
+            "BlobImmutableDueToPolicy" => AzureErrorCode::BlobImmutableDueToPolicy,
+            "BlobIsSealed" => AzureErrorCode::BlobIsSealed,
+            "BlobNotArchived" => AzureErrorCode::BlobNotArchived,
+            "BlobNotFound" => AzureErrorCode::BlobNotFound,
+            "BlobOverwritten" => AzureErrorCode::BlobOverwritten,
+            "BlobTierInadequateForContentLength" => AzureErrorCode::BlobTierInadequateForContentLength,
+            "BlobUsesCustomerSpecifiedEncryption" => AzureErrorCode::BlobUsesCustomerSpecifiedEncryption,
+            "BlockCountExceedsLimit" => AzureErrorCode::BlockCountExceedsLimit,
+            "BlockListTooLong" => AzureErrorCode::BlockListTooLong,
+            "CannotChangeToLowerTier" => AzureErrorCode::CannotChangeToLowerTier,
+            "CannotVerifyCopySource" => AzureErrorCode::CannotVerifyCopySource,
+            "ContainerAlreadyExists" => AzureErrorCode::ContainerAlreadyExists,
+            "ContainerBeingDeleted" => AzureErrorCode::ContainerBeingDeleted,
+            "ContainerDisabled" => AzureErrorCode::ContainerDisabled,
+            "ContainerNotFound" => AzureErrorCode::ContainerNotFound,
+            "ContentLengthLargerThanTierLimit" => AzureErrorCode::ContentLengthLargerThanTierLimit,
+            "CopyAcrossAccountsNotSupported" => AzureErrorCode::CopyAcrossAccountsNotSupported,
+            "CopyIdMismatch" => AzureErrorCode::CopyIdMismatch,
+            "FeatureVersionMismatch" => AzureErrorCode::FeatureVersionMismatch,
+            "IncrementalCopyBlobMismatch" => AzureErrorCode::IncrementalCopyBlobMismatch,
+            "IncrementalCopyOfEarlierVersionSnapshotNotAllowed" => AzureErrorCode::IncrementalCopyOfEarlierVersionSnapshotNotAllowed,
+            "IncrementalCopySourceMustBeSnapshot" => AzureErrorCode::IncrementalCopySourceMustBeSnapshot,
+            "InfiniteLeaseDurationRequired" => AzureErrorCode::InfiniteLeaseDurationRequired,
+            "InvalidBlobOrBlock" => AzureErrorCode::InvalidBlobOrBlock,
+            "InvalidBlobTier" => AzureErrorCode::InvalidBlobTier,
+            "InvalidBlobType" => AzureErrorCode::InvalidBlobType,
+            "InvalidBlockId" => AzureErrorCode::InvalidBlockId,
+            "InvalidBlockList" => AzureErrorCode::InvalidBlockList,
+            "InvalidOperation" => AzureErrorCode::InvalidOperation,
+            "InvalidPageRange" => AzureErrorCode::InvalidPageRange,
+            "InvalidSourceBlobType" => AzureErrorCode::InvalidSourceBlobType,
+            "InvalidSourceBlobUrl" => AzureErrorCode::InvalidSourceBlobUrl,
+            "InvalidVersionForPageBlobOperation" => AzureErrorCode::InvalidVersionForPageBlobOperation,
+            "LeaseAlreadyBroken" => AzureErrorCode::LeaseAlreadyBroken,
+            "LeaseAlreadyPresent" => AzureErrorCode::LeaseAlreadyPresent,
+            "LeaseIdMismatchWithBlobOperation" => AzureErrorCode::LeaseIdMismatchWithBlobOperation,
+            "LeaseIdMismatchWithContainerOperation" => AzureErrorCode::LeaseIdMismatchWithContainerOperation,
+            "LeaseIdMismatchWithLeaseOperation" => AzureErrorCode::LeaseIdMismatchWithLeaseOperation,
+            "LeaseIdMissing" => AzureErrorCode::LeaseIdMissing,
+            "LeaseIsBreakingAndCannotBeAcquired" => AzureErrorCode::LeaseIsBreakingAndCannotBeAcquired,
+            "LeaseIsBreakingAndCannotBeChanged" => AzureErrorCode::LeaseIsBreakingAndCannotBeChanged,
+            "LeaseIsBrokenAndCannotBeRenewed" => AzureErrorCode::LeaseIsBrokenAndCannotBeRenewed,
+            "LeaseLost" => AzureErrorCode::LeaseLost,
+            "LeaseNotPresentWithBlobOperation" => AzureErrorCode::LeaseNotPresentWithBlobOperation,
+            "LeaseNotPresentWithContainerOperation" => AzureErrorCode::LeaseNotPresentWithContainerOperation,
+            "LeaseNotPresentWithLeaseOperation" => AzureErrorCode::LeaseNotPresentWithLeaseOperation,
+            "MaxBlobSizeConditionNotMet" => AzureErrorCode::MaxBlobSizeConditionNotMet,
+            "NoPendingCopyOperation" => AzureErrorCode::NoPendingCopyOperation,
+            "OperationNotAllowedOnIncrementalCopyBlob" => AzureErrorCode::OperationNotAllowedOnIncrementalCopyBlob,
+            "PendingCopyOperation" => AzureErrorCode::PendingCopyOperation,
+            "PreviousSnapshotCannotBeNewer" => AzureErrorCode::PreviousSnapshotCannotBeNewer,
+            "PreviousSnapshotNotFound" => AzureErrorCode::PreviousSnapshotNotFound,
+            "PreviousSnapshotOperationNotSupported" => AzureErrorCode::PreviousSnapshotOperationNotSupported,
+            "SequenceNumberConditionNotMet" => AzureErrorCode::SequenceNumberConditionNotMet,
+            "SequenceNumberIncrementTooLarge" => AzureErrorCode::SequenceNumberIncrementTooLarge,
+            "SnapshotCountExceeded" => AzureErrorCode::SnapshotCountExceeded,
+            "SnapshotOperationRateExceeded" => AzureErrorCode::SnapshotOperationRateExceeded,
+            "SnapshotsPresent" => AzureErrorCode::SnapshotsPresent,
+            "SourceConditionNotMet" => AzureErrorCode::SourceConditionNotMet,
+            "SystemInUse" => AzureErrorCode::SystemInUse,
+            "TargetConditionNotMet" => AzureErrorCode::TargetConditionNotMet,
+            "UnauthorizedBlobOverwrite" => AzureErrorCode::UnauthorizedBlobOverwrite,
+            _ => AzureErrorCode::Other(src.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            AzureErrorCode::AccountAlreadyExists => "AccountAlreadyExists",
+            AzureErrorCode::AccountBeingCreated => "AccountBeingCreated",
+            AzureErrorCode::AccountIsDisabled => "AccountIsDisabled",
+            AzureErrorCode::AuthenticationFailed => "AuthenticationFailed",
+            AzureErrorCode::AuthorizationFailure => "AuthorizationFailure",
+            AzureErrorCode::AuthorizationPermissionMismatch => "AuthorizationPermissionMismatch",
+            AzureErrorCode::AuthorizationProtocolMismatch => "AuthorizationProtocolMismatch",
+            AzureErrorCode::AuthorizationResourceTypeMismatch => "AuthorizationResourceTypeMismatch",
+            AzureErrorCode::AuthorizationServiceMismatch => "AuthorizationServiceMismatch",
+            AzureErrorCode::AuthorizationSourceIPMismatch => "AuthorizationSourceIPMismatch",
+            AzureErrorCode::ConditionHeadersNotSupported => "ConditionHeadersNotSupported",
+            AzureErrorCode::ConditionNotMet => "ConditionNotMet",
+            AzureErrorCode::EmptyMetadataKey => "EmptyMetadataKey",
+            AzureErrorCode::InsufficientAccountPermissions => "InsufficientAccountPermissions",
+            AzureErrorCode::InternalError => "InternalError",
+            AzureErrorCode::InvalidAuthenticationInfo => "InvalidAuthenticationInfo",
+            AzureErrorCode::InvalidHeaderValue => "InvalidHeaderValue",
+            AzureErrorCode::InvalidHttpVerb => "InvalidHttpVerb",
+            AzureErrorCode::InvalidInput => "InvalidInput",
+            AzureErrorCode::InvalidMd5 => "InvalidMd5",
+            AzureErrorCode::InvalidMetadata => "InvalidMetadata",
+            AzureErrorCode::InvalidQueryParameterValue => "InvalidQueryParameterValue",
+            AzureErrorCode::InvalidRange => "InvalidRange",
+            AzureErrorCode::InvalidResourceName => "InvalidResourceName",
+            AzureErrorCode::InvalidUri => "InvalidUri",
+            AzureErrorCode::InvalidXmlDocument => "InvalidXmlDocument",
+            AzureErrorCode::InvalidXmlNodeValue => "InvalidXmlNodeValue",
+            AzureErrorCode::Md5Mismatch => "Md5Mismatch",
+            AzureErrorCode::MetadataTooLarge => "MetadataTooLarge",
+            AzureErrorCode::MissingContentLengthHeader => "MissingContentLengthHeader",
+            AzureErrorCode::MissingRequiredHeader => "MissingRequiredHeader",
+            AzureErrorCode::MissingRequiredQueryParameter => "MissingRequiredQueryParameter",
+            AzureErrorCode::MissingRequiredXmlNode => "MissingRequiredXmlNode",
+            AzureErrorCode::MultipleConditionHeadersNotSupported => "MultipleConditionHeadersNotSupported",
+            AzureErrorCode::NoAuthenticationInformation => "NoAuthenticationInformation",
+            AzureErrorCode::OperationTimedOut => "OperationTimedOut",
+            AzureErrorCode::OutOfRangeInput => "OutOfRangeInput",
+            AzureErrorCode::OutOfRangeQueryParameterValue => "OutOfRangeQueryParameterValue",
+            AzureErrorCode::RequestBodyTooLarge => "RequestBodyTooLarge",
+            AzureErrorCode::RequestUrlFailedToParse => "RequestUrlFailedToParse",
+            AzureErrorCode::ResourceAlreadyExists => "ResourceAlreadyExists",
+            AzureErrorCode::ResourceNotFound => "ResourceNotFound",
+            AzureErrorCode::ResourceTypeMismatch => "ResourceTypeMismatch",
+            AzureErrorCode::ServerBusy => "ServerBusy",
+            AzureErrorCode::UnsupportedHeader => "UnsupportedHeader",
+            AzureErrorCode::UnsupportedHttpVerb => "UnsupportedHttpVerb",
+            AzureErrorCode::UnsupportedQueryParameter => "UnsupportedQueryParameter",
+            AzureErrorCode::UnsupportedXmlNode => "UnsupportedXmlNode",
+            AzureErrorCode::AppendPositionConditionNotMet => "AppendPositionConditionNotMet",
+            AzureErrorCode::BlobAlreadyExists => "BlobAlreadyExists",
+            AzureErrorCode::BlobArchived => "BlobArchived",
+            AzureErrorCode::BlobBeingRehydrated => "BlobBeingRehydrated",
+            AzureErrorCode::BlobImmutableDueToPolicy => "BlobImmutableDueToPolicy",
+            AzureErrorCode::BlobIsSealed => "BlobIsSealed",
+            AzureErrorCode::BlobNotArchived => "BlobNotArchived",
+            AzureErrorCode::BlobNotFound => "BlobNotFound",
+            AzureErrorCode::BlobOverwritten => "BlobOverwritten",
+            AzureErrorCode::BlobTierInadequateForContentLength => "BlobTierInadequateForContentLength",
+            AzureErrorCode::BlobUsesCustomerSpecifiedEncryption => "BlobUsesCustomerSpecifiedEncryption",
+            AzureErrorCode::BlockCountExceedsLimit => "BlockCountExceedsLimit",
+            AzureErrorCode::BlockListTooLong => "BlockListTooLong",
+            AzureErrorCode::CannotChangeToLowerTier => "CannotChangeToLowerTier",
+            AzureErrorCode::CannotVerifyCopySource => "CannotVerifyCopySource",
+            AzureErrorCode::ContainerAlreadyExists => "ContainerAlreadyExists",
+            AzureErrorCode::ContainerBeingDeleted => "ContainerBeingDeleted",
+            AzureErrorCode::ContainerDisabled => "ContainerDisabled",
+            AzureErrorCode::ContainerNotFound => "ContainerNotFound",
+            AzureErrorCode::ContentLengthLargerThanTierLimit => "ContentLengthLargerThanTierLimit",
+            AzureErrorCode::CopyAcrossAccountsNotSupported => "CopyAcrossAccountsNotSupported",
+            AzureErrorCode::CopyIdMismatch => "CopyIdMismatch",
+            AzureErrorCode::FeatureVersionMismatch => "FeatureVersionMismatch",
+            AzureErrorCode::IncrementalCopyBlobMismatch => "IncrementalCopyBlobMismatch",
+            AzureErrorCode::IncrementalCopyOfEarlierVersionSnapshotNotAllowed => "IncrementalCopyOfEarlierVersionSnapshotNotAllowed",
+            AzureErrorCode::IncrementalCopySourceMustBeSnapshot => "IncrementalCopySourceMustBeSnapshot",
+            AzureErrorCode::InfiniteLeaseDurationRequired => "InfiniteLeaseDurationRequired",
+            AzureErrorCode::InvalidBlobOrBlock => "InvalidBlobOrBlock",
+            AzureErrorCode::InvalidBlobTier => "InvalidBlobTier",
+            AzureErrorCode::InvalidBlobType => "InvalidBlobType",
+            AzureErrorCode::InvalidBlockId => "InvalidBlockId",
+            AzureErrorCode::InvalidBlockList => "InvalidBlockList",
+            AzureErrorCode::InvalidOperation => "InvalidOperation",
+            AzureErrorCode::InvalidPageRange => "InvalidPageRange",
+            AzureErrorCode::InvalidSourceBlobType => "InvalidSourceBlobType",
+            AzureErrorCode::InvalidSourceBlobUrl => "InvalidSourceBlobUrl",
+            AzureErrorCode::InvalidVersionForPageBlobOperation => "InvalidVersionForPageBlobOperation",
+            AzureErrorCode::LeaseAlreadyBroken => "LeaseAlreadyBroken",
+            AzureErrorCode::LeaseAlreadyPresent => "LeaseAlreadyPresent",
+            AzureErrorCode::LeaseIdMismatchWithBlobOperation => "LeaseIdMismatchWithBlobOperation",
+            AzureErrorCode::LeaseIdMismatchWithContainerOperation => "LeaseIdMismatchWithContainerOperation",
+            AzureErrorCode::LeaseIdMismatchWithLeaseOperation => "LeaseIdMismatchWithLeaseOperation",
+            AzureErrorCode::LeaseIdMissing => "LeaseIdMissing",
+            AzureErrorCode::LeaseIsBreakingAndCannotBeAcquired => "LeaseIsBreakingAndCannotBeAcquired",
+            AzureErrorCode::LeaseIsBreakingAndCannotBeChanged => "LeaseIsBreakingAndCannotBeChanged",
+            AzureErrorCode::LeaseIsBrokenAndCannotBeRenewed => "LeaseIsBrokenAndCannotBeRenewed",
+            AzureErrorCode::LeaseLost => "LeaseLost",
+            AzureErrorCode::LeaseNotPresentWithBlobOperation => "LeaseNotPresentWithBlobOperation",
+            AzureErrorCode::LeaseNotPresentWithContainerOperation => "LeaseNotPresentWithContainerOperation",
+            AzureErrorCode::LeaseNotPresentWithLeaseOperation => "LeaseNotPresentWithLeaseOperation",
+            AzureErrorCode::MaxBlobSizeConditionNotMet => "MaxBlobSizeConditionNotMet",
+            AzureErrorCode::NoPendingCopyOperation => "NoPendingCopyOperation",
+            AzureErrorCode::OperationNotAllowedOnIncrementalCopyBlob => "OperationNotAllowedOnIncrementalCopyBlob",
+            AzureErrorCode::PendingCopyOperation => "PendingCopyOperation",
+            AzureErrorCode::PreviousSnapshotCannotBeNewer => "PreviousSnapshotCannotBeNewer",
+            AzureErrorCode::PreviousSnapshotNotFound => "PreviousSnapshotNotFound",
+            AzureErrorCode::PreviousSnapshotOperationNotSupported => "PreviousSnapshotOperationNotSupported",
+            AzureErrorCode::SequenceNumberConditionNotMet => "SequenceNumberConditionNotMet",
+            AzureErrorCode::SequenceNumberIncrementTooLarge => "SequenceNumberIncrementTooLarge",
+            AzureErrorCode::SnapshotCountExceeded => "SnapshotCountExceeded",
+            AzureErrorCode::SnapshotOperationRateExceeded => "SnapshotOperationRateExceeded",
+            AzureErrorCode::SnapshotsPresent => "SnapshotsPresent",
+            AzureErrorCode::SourceConditionNotMet => "SourceConditionNotMet",
+            AzureErrorCode::SystemInUse => "SystemInUse",
+            AzureErrorCode::TargetConditionNotMet => "TargetConditionNotMet",
+            AzureErrorCode::UnauthorizedBlobOverwrite => "UnauthorizedBlobOverwrite",
+            AzureErrorCode::Other(src) => src.as_str(),
+        }
+    }
+
+    /// Code for error responses without x-ms-error-code and without a body (HEAD requests, 304)
+    pub fn from_status_code(status_code: u16) -> Self {
+        match status_code {
+            304 | 412 => AzureErrorCode::ConditionNotMet,
+            403 => AzureErrorCode::AuthenticationFailed,
+            404 => AzureErrorCode::ResourceNotFound,
+            409 => AzureErrorCode::ResourceAlreadyExists,
+            413 => AzureErrorCode::RequestBodyTooLarge,
+            416 => AzureErrorCode::InvalidRange,
+            500 => AzureErrorCode::InternalError,
+            503 => AzureErrorCode::ServerBusy,
+            _ => AzureErrorCode::Other(format!("HttpStatus{}", status_code)),
+        }
+    }
+}
+
+impl std::fmt::Display for AzureErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trip() {
+        for code in ["BlobNotFound", "LeaseIdMissing", "ServerBusy", "AuthenticationFailed"] {
+            assert_eq!(code, AzureErrorCode::parse(code).as_str());
+        }
+
+        assert_eq!(
+            AzureErrorCode::Other("SomethingNew".to_string()),
+            AzureErrorCode::parse("SomethingNew")
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use super::{AzureErrorCode, ErrorKind, StorageError};
+
+/// Error response of the storage service
+#[derive(Debug, Clone)]
+pub struct AzureServiceError {
+    pub code: AzureErrorCode,
+    pub status_code: u16,
+    /// x-ms-request-id. Needed when a failure is escalated to Azure support
+    pub request_id: Option<String>,
+    /// <Message> of the error body
+    pub message: Option<String>,
+}
+
+impl AzureServiceError {
+    pub fn new(code: AzureErrorCode, status_code: u16) -> Self {
+        Self {
+            code,
+            status_code,
+            request_id: None,
+            message: None,
+        }
+    }
+
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+impl std::fmt::Display for AzureServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (HTTP {})", self.code, self.status_code)?;
+
+        if let Some(message) = self.message.as_ref() {
+            write!(f, ": {}", message)?;
+        }
+
+        if let Some(request_id) = self.request_id.as_ref() {
+            write!(f, " [request id: {}]", request_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for AzureServiceError {}
+
+impl StorageError for AzureServiceError {
+    fn get_error_kind(&self) -> ErrorKind {
+        match self.code {
+            AzureErrorCode::ServerBusy => ErrorKind::Throttled,
+            AzureErrorCode::OperationTimedOut => ErrorKind::Timeout,
+            AzureErrorCode::AuthenticationFailed
+            | AzureErrorCode::AuthorizationFailure
+            | AzureErrorCode::AuthorizationPermissionMismatch
+            | AzureErrorCode::AuthorizationProtocolMismatch
+            | AzureErrorCode::AuthorizationResourceTypeMismatch
+            | AzureErrorCode::AuthorizationServiceMismatch
+            | AzureErrorCode::AuthorizationSourceIPMismatch
+            | AzureErrorCode::InsufficientAccountPermissions
+            | AzureErrorCode::InvalidAuthenticationInfo
+            | AzureErrorCode::NoAuthenticationInformation => ErrorKind::AuthFailure,
+            _ => ErrorKind::from_status_code(self.status_code),
+        }
+    }
+}
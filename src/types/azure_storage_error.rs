@@ -0,0 +1,500 @@
+use super::{AzureErrorCode, AzureServiceError, ErrorKind, StorageError};
+
+/// Variants named after a storage error code mean the same on every backend.
+/// From the Azure backend they carry the details of the error response, local backends leave them None
+#[derive(Debug)]
+pub enum AzureStorageError {
+    ContainerNotFound(Option<AzureServiceError>),
+    BlobNotFound(Option<AzureServiceError>),
+    BlobAlreadyExists(Option<AzureServiceError>),
+    ContainerBeingDeleted(Option<AzureServiceError>),
+    ContainerAlreadyExists(Option<AzureServiceError>),
+    InvalidPageRange(Option<AzureServiceError>),
+    /// Range of a download starts at or past the end of the blob (416)
+    InvalidRange(Option<AzureServiceError>),
+    RequestBodyTooLarge(Option<AzureServiceError>),
+    InvalidResourceName(Option<AzureServiceError>),
+    /// If-Match, If-None-Match or If-Modified-Since of the request is not met
+    ConditionNotMet(Option<AzureServiceError>),
+    /// The blob has an active lease and the request does not pass its id
+    LeaseIdMissing(Option<AzureServiceError>),
+    /// The lease id of the request differs from the active lease of the blob.
+    /// Local backends use it for blob and lease operations alike
+    LeaseIdMismatch(Option<AzureServiceError>),
+    LeaseAlreadyPresent(Option<AzureServiceError>),
+    /// The request passes a lease id, but the blob has no active lease
+    LeaseNotPresent(Option<AzureServiceError>),
+    /// Delete of a blob which has snapshots, without `DeleteSnapshotsOption`
+    SnapshotsPresent(Option<AzureServiceError>),
+    /// Abort of a copy which is not pending. Local backends complete copies before returning
+    NoPendingCopyOperation(Option<AzureServiceError>),
+    /// The copy source can not be read. Local backends read only blobs of their own storage
+    CannotVerifyCopySource(Option<AzureServiceError>),
+    /// Block id which is not Base64, longer than 64 bytes or of another length than the other blocks of the blob
+    InvalidBlockId(Option<AzureServiceError>),
+    /// Put Block List names a block which is not put (or committed) for the blob
+    InvalidBlockList(Option<AzureServiceError>),
+    /// Put Block List of more than 50,000 blocks
+    BlockListTooLong(Option<AzureServiceError>),
+    /// More than 100,000 uncommitted blocks on the blob
+    BlockCountExceedsLimit(Option<AzureServiceError>),
+    /// The operation is not supported by the type of the blob, e.g. Append Block to a block blob
+    InvalidBlobType(Option<AzureServiceError>),
+    /// The length of the append blob differs from x-ms-blob-condition-appendpos
+    AppendPositionConditionNotMet(Option<AzureServiceError>),
+    /// Append Block would make the append blob longer than x-ms-blob-condition-maxsize
+    MaxBlobSizeConditionNotMet(Option<AzureServiceError>),
+    /// Append Block to an append blob which is sealed
+    BlobIsSealed(Option<AzureServiceError>),
+    /// A header value is out of its range, e.g. a lease duration other than -1 or 15..=60 seconds
+    InvalidHeaderValue(Option<AzureServiceError>),
+    /// The transfer was stopped by its cancellation token. A transfer manager resumes it from its journal
+    TransferCancelled,
+    /// The committed blob is not made of the blocks of the uploaded file,
+    /// or the downloaded file differs from the size or the MD5 of the blob
+    TransferVerificationFailed,
+    /// Options of the call can not be used, e.g. a chunk size of 0. Nothing is sent
+    InvalidOptions { msg: String },
+    /// The storage backend does not implement the operation, e.g. a custom backend written before it was added
+    FeatureNotSupported { msg: String },
+    /// Error response of the Azure storage service
+    ServiceError(AzureServiceError),
+    IoError(std::io::Error),
+    FlUrlError(flurl::FlUrlError),
+    TokenCredentialError(crate::token_credential::TokenCredentialError),
+    Timeout,
+    #[cfg(feature = "table-storage")]
+    TableStorageError(crate::table_storage::TableStorageError),
+    UnknownError { msg: String },
+}
+
+impl AzureStorageError {
+    pub fn parse(str: &str) -> Self {
+        match str {
+            "ContainerNotFound" => AzureStorageError::ContainerNotFound(None),
+            "ContainerBeingDeleted" => AzureStorageError::ContainerBeingDeleted(None),
+            "BlobNotFound" => AzureStorageError::BlobNotFound(None),
+            "BlobAlreadyExists" => AzureStorageError::BlobAlreadyExists(None),
+            "ContainerAlreadyExists" => AzureStorageError::ContainerAlreadyExists(None),
+            "InvalidPageRange" => AzureStorageError::InvalidPageRange(None),
+            "InvalidRange" => AzureStorageError::InvalidRange(None),
+            "RequestBodyTooLarge" => AzureStorageError::RequestBodyTooLarge(None),
+            "InvalidResourceName" => AzureStorageError::InvalidResourceName(None),
+            "ConditionNotMet" => AzureStorageError::ConditionNotMet(None),
+            "LeaseIdMissing" => AzureStorageError::LeaseIdMissing(None),
+            "LeaseIdMismatchWithBlobOperation" => AzureStorageError::LeaseIdMismatch(None),
+            "LeaseAlreadyPresent" => AzureStorageError::LeaseAlreadyPresent(None),
+            "LeaseNotPresentWithBlobOperation" => AzureStorageError::LeaseNotPresent(None),
+            "SnapshotsPresent" => AzureStorageError::SnapshotsPresent(None),
+            "NoPendingCopyOperation" => AzureStorageError::NoPendingCopyOperation(None),
+            "CannotVerifyCopySource" => AzureStorageError::CannotVerifyCopySource(None),
+            "InvalidBlockId" => AzureStorageError::InvalidBlockId(None),
+            "InvalidBlockList" => AzureStorageError::InvalidBlockList(None),
+            "BlockListTooLong" => AzureStorageError::BlockListTooLong(None),
+            "BlockCountExceedsLimit" => AzureStorageError::BlockCountExceedsLimit(None),
+            "InvalidBlobType" => AzureStorageError::InvalidBlobType(None),
+            "AppendPositionConditionNotMet" => {
+                AzureStorageError::AppendPositionConditionNotMet(None)
+            }
+            "MaxBlobSizeConditionNotMet" => AzureStorageError::MaxBlobSizeConditionNotMet(None),
+            "BlobIsSealed" => AzureStorageError::BlobIsSealed(None),
+            "InvalidHeaderValue" => AzureStorageError::InvalidHeaderValue(None),
+            _ => AzureStorageError::UnknownError {
+                msg: str.to_string(),
+            },
+        }
+    }
+
+    /// Error of an Azure response. A known code becomes the variant the local backends return,
+    /// with the response details attached. Other codes stay a `ServiceError`
+    pub fn from_service_error(err: AzureServiceError) -> Self {
+        match err.code {
+            AzureErrorCode::ContainerNotFound => AzureStorageError::ContainerNotFound(Some(err)),
+            AzureErrorCode::BlobNotFound => AzureStorageError::BlobNotFound(Some(err)),
+            AzureErrorCode::BlobAlreadyExists => AzureStorageError::BlobAlreadyExists(Some(err)),
+            AzureErrorCode::ContainerBeingDeleted => {
+                AzureStorageError::ContainerBeingDeleted(Some(err))
+            }
+            AzureErrorCode::ContainerAlreadyExists => {
+                AzureStorageError::ContainerAlreadyExists(Some(err))
+            }
+            AzureErrorCode::InvalidPageRange => AzureStorageError::InvalidPageRange(Some(err)),
+            AzureErrorCode::InvalidRange => AzureStorageError::InvalidRange(Some(err)),
+            AzureErrorCode::RequestBodyTooLarge => {
+                AzureStorageError::RequestBodyTooLarge(Some(err))
+            }
+            AzureErrorCode::InvalidResourceName => {
+                AzureStorageError::InvalidResourceName(Some(err))
+            }
+            AzureErrorCode::ConditionNotMet => AzureStorageError::ConditionNotMet(Some(err)),
+            AzureErrorCode::LeaseIdMissing => AzureStorageError::LeaseIdMissing(Some(err)),
+            AzureErrorCode::LeaseIdMismatchWithBlobOperation
+            | AzureErrorCode::LeaseIdMismatchWithLeaseOperation => {
+                AzureStorageError::LeaseIdMismatch(Some(err))
+            }
+            AzureErrorCode::LeaseAlreadyPresent => {
+                AzureStorageError::LeaseAlreadyPresent(Some(err))
+            }
+            AzureErrorCode::LeaseNotPresentWithBlobOperation
+            | AzureErrorCode::LeaseNotPresentWithLeaseOperation => {
+                AzureStorageError::LeaseNotPresent(Some(err))
+            }
+            AzureErrorCode::SnapshotsPresent => AzureStorageError::SnapshotsPresent(Some(err)),
+            AzureErrorCode::NoPendingCopyOperation => {
+                AzureStorageError::NoPendingCopyOperation(Some(err))
+            }
+            AzureErrorCode::CannotVerifyCopySource => {
+                AzureStorageError::CannotVerifyCopySource(Some(err))
+            }
+            AzureErrorCode::InvalidBlockId => AzureStorageError::InvalidBlockId(Some(err)),
+            AzureErrorCode::InvalidBlockList => AzureStorageError::InvalidBlockList(Some(err)),
+            AzureErrorCode::BlockListTooLong => AzureStorageError::BlockListTooLong(Some(err)),
+            AzureErrorCode::BlockCountExceedsLimit => {
+                AzureStorageError::BlockCountExceedsLimit(Some(err))
+            }
+            AzureErrorCode::InvalidBlobType => AzureStorageError::InvalidBlobType(Some(err)),
+            AzureErrorCode::AppendPositionConditionNotMet => {
+                AzureStorageError::AppendPositionConditionNotMet(Some(err))
+            }
+            AzureErrorCode::MaxBlobSizeConditionNotMet => {
+                AzureStorageError::MaxBlobSizeConditionNotMet(Some(err))
+            }
+            AzureErrorCode::BlobIsSealed => AzureStorageError::BlobIsSealed(Some(err)),
+            AzureErrorCode::InvalidHeaderValue => AzureStorageError::InvalidHeaderValue(Some(err)),
+            _ => AzureStorageError::ServiceError(err),
+        }
+    }
+
+    /// Details of the Azure error response. None for errors of the local backends
+    pub fn get_service_error(&self) -> Option<&AzureServiceError> {
+        match self {
+            AzureStorageError::ContainerNotFound(err)
+            | AzureStorageError::BlobNotFound(err)
+            | AzureStorageError::BlobAlreadyExists(err)
+            | AzureStorageError::ContainerBeingDeleted(err)
+            | AzureStorageError::ContainerAlreadyExists(err)
+            | AzureStorageError::InvalidPageRange(err)
+            | AzureStorageError::InvalidRange(err)
+            | AzureStorageError::RequestBodyTooLarge(err)
+            | AzureStorageError::InvalidResourceName(err)
+            | AzureStorageError::ConditionNotMet(err)
+            | AzureStorageError::LeaseIdMissing(err)
+            | AzureStorageError::LeaseIdMismatch(err)
+            | AzureStorageError::LeaseAlreadyPresent(err)
+            | AzureStorageError::LeaseNotPresent(err)
+            | AzureStorageError::SnapshotsPresent(err)
+            | AzureStorageError::NoPendingCopyOperation(err)
+            | AzureStorageError::CannotVerifyCopySource(err)
+            | AzureStorageError::InvalidBlockId(err)
+            | AzureStorageError::InvalidBlockList(err)
+            | AzureStorageError::BlockListTooLong(err)
+            | AzureStorageError::BlockCountExceedsLimit(err)
+            | AzureStorageError::InvalidBlobType(err)
+            | AzureStorageError::AppendPositionConditionNotMet(err)
+            | AzureStorageError::MaxBlobSizeConditionNotMet(err)
+            | AzureStorageError::BlobIsSealed(err)
+            | AzureStorageError::InvalidHeaderValue(err) => err.as_ref(),
+            AzureStorageError::ServiceError(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Storage error code regardless of the backend the error came from.
+    /// The one of the Azure response wins, e.g. LeaseIdMismatchWithLeaseOperation for `LeaseIdMismatch`
+    pub fn get_error_code(&self) -> Option<AzureErrorCode> {
+        if let Some(err) = self.get_service_error() {
+            return Some(err.code.clone());
+        }
+
+        match self {
+            AzureStorageError::ContainerNotFound(_) => Some(AzureErrorCode::ContainerNotFound),
+            AzureStorageError::BlobNotFound(_) => Some(AzureErrorCode::BlobNotFound),
+            AzureStorageError::BlobAlreadyExists(_) => Some(AzureErrorCode::BlobAlreadyExists),
+            AzureStorageError::ContainerBeingDeleted(_) => {
+                Some(AzureErrorCode::ContainerBeingDeleted)
+            }
+            AzureStorageError::ContainerAlreadyExists(_) => {
+                Some(AzureErrorCode::ContainerAlreadyExists)
+            }
+            AzureStorageError::InvalidPageRange(_) => Some(AzureErrorCode::InvalidPageRange),
+            AzureStorageError::InvalidRange(_) => Some(AzureErrorCode::InvalidRange),
+            AzureStorageError::RequestBodyTooLarge(_) => Some(AzureErrorCode::RequestBodyTooLarge),
+            AzureStorageError::InvalidResourceName(_) => Some(AzureErrorCode::InvalidResourceName),
+            AzureStorageError::ConditionNotMet(_) => Some(AzureErrorCode::ConditionNotMet),
+            AzureStorageError::LeaseIdMissing(_) => Some(AzureErrorCode::LeaseIdMissing),
+            AzureStorageError::LeaseIdMismatch(_) => {
+                Some(AzureErrorCode::LeaseIdMismatchWithBlobOperation)
+            }
+            AzureStorageError::LeaseAlreadyPresent(_) => Some(AzureErrorCode::LeaseAlreadyPresent),
+            AzureStorageError::LeaseNotPresent(_) => {
+                Some(AzureErrorCode::LeaseNotPresentWithBlobOperation)
+            }
+            AzureStorageError::SnapshotsPresent(_) => Some(AzureErrorCode::SnapshotsPresent),
+            AzureStorageError::NoPendingCopyOperation(_) => {
+                Some(AzureErrorCode::NoPendingCopyOperation)
+            }
+            AzureStorageError::CannotVerifyCopySource(_) => {
+                Some(AzureErrorCode::CannotVerifyCopySource)
+            }
+            AzureStorageError::InvalidBlockId(_) => Some(AzureErrorCode::InvalidBlockId),
+            AzureStorageError::InvalidBlockList(_) => Some(AzureErrorCode::InvalidBlockList),
+            AzureStorageError::BlockListTooLong(_) => Some(AzureErrorCode::BlockListTooLong),
+            AzureStorageError::BlockCountExceedsLimit(_) => {
+                Some(AzureErrorCode::BlockCountExceedsLimit)
+            }
+            AzureStorageError::InvalidBlobType(_) => Some(AzureErrorCode::InvalidBlobType),
+            AzureStorageError::AppendPositionConditionNotMet(_) => {
+                Some(AzureErrorCode::AppendPositionConditionNotMet)
+            }
+            AzureStorageError::MaxBlobSizeConditionNotMet(_) => {
+                Some(AzureErrorCode::MaxBlobSizeConditionNotMet)
+            }
+            AzureStorageError::BlobIsSealed(_) => Some(AzureErrorCode::BlobIsSealed),
+            AzureStorageError::InvalidHeaderValue(_) => Some(AzureErrorCode::InvalidHeaderValue),
+            _ => None,
+        }
+    }
+
+    pub fn get_status_code(&self) -> Option<u16> {
+        Some(self.get_service_error()?.status_code)
+    }
+
+    pub fn get_request_id(&self) -> Option<&str> {
+        self.get_service_error()?.request_id.as_deref()
+    }
+}
+
+impl std::fmt::Display for AzureStorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(err) = self.get_service_error() {
+            return write!(f, "{}", err);
+        }
+
+        match self {
+            AzureStorageError::IoError(err) => write!(f, "IO error: {}", err),
+            AzureStorageError::FlUrlError(err) => write!(f, "HTTP transport error: {:?}", err),
+            AzureStorageError::TokenCredentialError(err) => write!(f, "{}", err),
+            AzureStorageError::Timeout => write!(f, "Request timed out"),
+            #[cfg(feature = "table-storage")]
+            AzureStorageError::TableStorageError(err) => write!(f, "{}", err),
+            AzureStorageError::InvalidOptions { msg } => write!(f, "Invalid options: {}", msg),
+            AzureStorageError::FeatureNotSupported { msg } => write!(f, "Not supported: {}", msg),
+            AzureStorageError::UnknownError { msg } => write!(f, "Unknown error: {}", msg),
+            _ => match self.get_error_code() {
+                Some(code) => write!(f, "{}", code),
+                None => write!(f, "{:?}", self),
+            },
+        }
+    }
+}
+
+impl std::error::Error for AzureStorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        if let Some(err) = self.get_service_error() {
+            return Some(err);
+        }
+
+        match self {
+            AzureStorageError::IoError(err) => Some(err),
+            AzureStorageError::TokenCredentialError(err) => Some(err),
+            #[cfg(feature = "table-storage")]
+            AzureStorageError::TableStorageError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<flurl::FlUrlError> for AzureStorageError {
+    fn from(err: flurl::FlUrlError) -> Self {
+        Self::FlUrlError(err)
+    }
+}
+
+impl From<crate::token_credential::TokenCredentialError> for AzureStorageError {
+    fn from(err: crate::token_credential::TokenCredentialError) -> Self {
+        Self::TokenCredentialError(err)
+    }
+}
+
+impl StorageError for AzureStorageError {
+    fn get_error_kind(&self) -> ErrorKind {
+        match self {
+            AzureStorageError::ContainerNotFound(_) | AzureStorageError::BlobNotFound(_) => {
+                ErrorKind::NotFound
+            }
+            AzureStorageError::BlobAlreadyExists(_)
+            | AzureStorageError::ContainerBeingDeleted(_)
+            | AzureStorageError::ContainerAlreadyExists(_)
+            | AzureStorageError::LeaseAlreadyPresent(_)
+            | AzureStorageError::SnapshotsPresent(_)
+            | AzureStorageError::NoPendingCopyOperation(_)
+            | AzureStorageError::BlockCountExceedsLimit(_)
+            | AzureStorageError::InvalidBlobType(_)
+            | AzureStorageError::BlobIsSealed(_) => ErrorKind::Conflict,
+            AzureStorageError::InvalidPageRange(_)
+            | AzureStorageError::InvalidRange(_)
+            | AzureStorageError::RequestBodyTooLarge(_)
+            | AzureStorageError::InvalidResourceName(_)
+            | AzureStorageError::CannotVerifyCopySource(_)
+            | AzureStorageError::InvalidBlockId(_)
+            | AzureStorageError::InvalidBlockList(_)
+            | AzureStorageError::BlockListTooLong(_)
+            | AzureStorageError::InvalidHeaderValue(_)
+            | AzureStorageError::InvalidOptions { .. } => ErrorKind::InvalidInput,
+            AzureStorageError::ConditionNotMet(_)
+            | AzureStorageError::LeaseIdMissing(_)
+            | AzureStorageError::LeaseIdMismatch(_)
+            | AzureStorageError::LeaseNotPresent(_)
+            | AzureStorageError::AppendPositionConditionNotMet(_)
+            | AzureStorageError::MaxBlobSizeConditionNotMet(_) => ErrorKind::ConditionNotMet,
+            AzureStorageError::ServiceError(err) => err.get_error_kind(),
+            AzureStorageError::IoError(err) => match err.kind() {
+                std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+                std::io::ErrorKind::AlreadyExists => ErrorKind::Conflict,
+                std::io::ErrorKind::PermissionDenied => ErrorKind::AuthFailure,
+                _ => ErrorKind::Io,
+            },
+            AzureStorageError::FlUrlError(_) => ErrorKind::Transport,
+            AzureStorageError::TokenCredentialError(_) => ErrorKind::AuthFailure,
+            AzureStorageError::Timeout => ErrorKind::Timeout,
+            #[cfg(feature = "table-storage")]
+            AzureStorageError::TableStorageError(err) => err.get_error_kind(),
+            AzureStorageError::TransferCancelled
+            | AzureStorageError::TransferVerificationFailed
+            | AzureStorageError::FeatureNotSupported { .. }
+            | AzureStorageError::UnknownError { .. } => ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(feature = "table-storage")]
+impl From<crate::table_storage::TableStorageError> for AzureStorageError {
+    fn from(err: crate::table_storage::TableStorageError) -> Self {
+        Self::TableStorageError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_and_service_errors_are_the_same_variant() {
+        let service_error = AzureStorageError::from_service_error(
+            AzureServiceError::new(AzureErrorCode::BlobNotFound, 404)
+                .with_request_id("req-1")
+                .with_message("The specified blob does not exist."),
+        );
+
+        assert!(matches!(
+            service_error,
+            AzureStorageError::BlobNotFound(Some(_))
+        ));
+        assert!(service_error.is_not_found());
+        assert!(AzureStorageError::BlobNotFound(None).is_not_found());
+        assert_eq!(
+            AzureStorageError::BlobNotFound(None).get_error_code(),
+            service_error.get_error_code()
+        );
+        assert_eq!(Some(404), service_error.get_status_code());
+        assert_eq!(Some("req-1"), service_error.get_request_id());
+        assert_eq!(
+            "BlobNotFound (HTTP 404): The specified blob does not exist. [request id: req-1]",
+            service_error.to_string()
+        );
+
+        assert!(AzureStorageError::BlobNotFound(None)
+            .get_service_error()
+            .is_none());
+        assert!(AzureStorageError::BlobNotFound(None)
+            .get_status_code()
+            .is_none());
+
+        let service_error = AzureStorageError::from_service_error(AzureServiceError::new(
+            AzureErrorCode::ContainerAlreadyExists,
+            409,
+        ));
+
+        assert!(matches!(
+            service_error,
+            AzureStorageError::ContainerAlreadyExists(Some(_))
+        ));
+        assert!(service_error.is_conflict());
+        assert!(AzureStorageError::ContainerAlreadyExists(None).is_conflict());
+    }
+
+    #[test]
+    fn test_retryable_and_auth_errors() {
+        let server_busy = AzureStorageError::from_service_error(AzureServiceError::new(
+            AzureErrorCode::ServerBusy,
+            503,
+        ));
+        assert!(matches!(server_busy, AzureStorageError::ServiceError(_)));
+        assert!(server_busy.is_retryable());
+        assert!(AzureStorageError::Timeout.is_retryable());
+
+        let auth_failed = AzureStorageError::from_service_error(AzureServiceError::new(
+            AzureErrorCode::AuthorizationPermissionMismatch,
+            403,
+        ));
+        assert!(auth_failed.is_auth_failure());
+        assert!(!auth_failed.is_retryable());
+    }
+
+    #[test]
+    fn test_condition_not_met() {
+        let service_error = AzureStorageError::from_service_error(AzureServiceError::new(
+            AzureErrorCode::from_status_code(412),
+            412,
+        ));
+
+        assert!(matches!(
+            service_error,
+            AzureStorageError::ConditionNotMet(Some(_))
+        ));
+        assert!(service_error.is_condition_not_met());
+        assert!(AzureStorageError::ConditionNotMet(None).is_condition_not_met());
+        assert_eq!(
+            AzureStorageError::ConditionNotMet(None).get_error_code(),
+            service_error.get_error_code()
+        );
+    }
+
+    #[test]
+    fn test_lease_errors() {
+        let service_error = AzureStorageError::from_service_error(AzureServiceError::new(
+            AzureErrorCode::LeaseIdMissing,
+            412,
+        ));
+
+        assert!(matches!(
+            service_error,
+            AzureStorageError::LeaseIdMissing(Some(_))
+        ));
+        assert_eq!(
+            AzureStorageError::LeaseIdMissing(None).get_error_code(),
+            service_error.get_error_code()
+        );
+        assert!(AzureStorageError::LeaseIdMissing(None).is_condition_not_met());
+        assert!(AzureStorageError::LeaseAlreadyPresent(None).is_conflict());
+        assert!(matches!(
+            AzureStorageError::parse("LeaseIdMismatchWithBlobOperation"),
+            AzureStorageError::LeaseIdMismatch(None)
+        ));
+
+        // Lease operations of the local backends fail with the same variant
+        let service_error = AzureStorageError::from_service_error(AzureServiceError::new(
+            AzureErrorCode::LeaseIdMismatchWithLeaseOperation,
+            409,
+        ));
+
+        assert!(matches!(
+            service_error,
+            AzureStorageError::LeaseIdMismatch(Some(_))
+        ));
+        assert_eq!(
+            Some(AzureErrorCode::LeaseIdMismatchWithLeaseOperation),
+            service_error.get_error_code()
+        );
+    }
+}
@@ -0,0 +1,83 @@
+/// Backend-independent class of an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    NotFound,
+    /// Resource already exists, is being deleted or is locked by a lease or a pending copy
+    Conflict,
+    /// If-Match/If-None-Match/If-Modified-Since or a lease condition is not met
+    ConditionNotMet,
+    AuthFailure,
+    InvalidInput,
+    /// ServerBusy, 429 and 503 responses
+    Throttled,
+    ServerError,
+    Timeout,
+    Transport,
+    Io,
+    Other,
+}
+
+impl ErrorKind {
+    pub fn from_status_code(status_code: u16) -> Self {
+        match status_code {
+            304 | 412 => ErrorKind::ConditionNotMet,
+            401 | 403 => ErrorKind::AuthFailure,
+            404 => ErrorKind::NotFound,
+            408 => ErrorKind::Timeout,
+            409 => ErrorKind::Conflict,
+            429 | 503 => ErrorKind::Throttled,
+            400..=499 => ErrorKind::InvalidInput,
+            500..=599 => ErrorKind::ServerError,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorKind::Throttled | ErrorKind::ServerError | ErrorKind::Timeout | ErrorKind::Transport
+        )
+    }
+}
+
+/// Classification shared by the blob and table errors of every backend
+pub trait StorageError {
+    fn get_error_kind(&self) -> ErrorKind;
+
+    fn is_not_found(&self) -> bool {
+        self.get_error_kind() == ErrorKind::NotFound
+    }
+
+    fn is_conflict(&self) -> bool {
+        self.get_error_kind() == ErrorKind::Conflict
+    }
+
+    fn is_condition_not_met(&self) -> bool {
+        self.get_error_kind() == ErrorKind::ConditionNotMet
+    }
+
+    fn is_retryable(&self) -> bool {
+        self.get_error_kind().is_retryable()
+    }
+
+    fn is_auth_failure(&self) -> bool {
+        self.get_error_kind() == ErrorKind::AuthFailure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_from_status_code() {
+        assert_eq!(ErrorKind::NotFound, ErrorKind::from_status_code(404));
+        assert_eq!(ErrorKind::Conflict, ErrorKind::from_status_code(409));
+        assert_eq!(ErrorKind::ConditionNotMet, ErrorKind::from_status_code(412));
+        assert_eq!(ErrorKind::AuthFailure, ErrorKind::from_status_code(403));
+        assert_eq!(ErrorKind::InvalidInput, ErrorKind::from_status_code(400));
+        assert!(ErrorKind::from_status_code(503).is_retryable());
+        assert!(ErrorKind::from_status_code(500).is_retryable());
+        assert!(!ErrorKind::from_status_code(404).is_retryable());
+    }
+}
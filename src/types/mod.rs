@@ -0,0 +1,8 @@
+mod azure_error_code;
+mod azure_service_error;
+mod azure_storage_error;
+mod error_kind;
+pub use azure_error_code::*;
+pub use azure_service_error::*;
+pub use azure_storage_error::*;
+pub use error_kind::*;